@@ -23,12 +23,18 @@ impl<T: IsAtomicAccumulativeHashType> Clone for AtomicAccumulativeHash<T> {
     }
 }
 
+impl<T: IsAtomicAccumulativeHashType> Default for AtomicAccumulativeHash<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: IsAtomicAccumulativeHashType> AtomicAccumulativeHash<T> {
     /// Create a new empty accumulative hash.
     ///
     /// The initial state is equivalent to hashing no values.
     pub fn new() -> Self {
-        Self::with_state(T::UnderlyingType::zero().into())
+        Self::with_state(T::UnderlyingType::zero())
     }
 
     /// Internal method to add a hashed value to the current state atomically.
@@ -48,15 +54,15 @@ impl<T: IsAtomicAccumulativeHashType> AtomicAccumulativeHash<T> {
             };
 
             match self.state.compare_exchange(
-                current_state.into(),
-                new_state.into(),
+                current_state,
+                new_state,
                 success,
                 failure,
             ) {
                 // If the exchange was successful, return the new state, and discard the previous state.
                 Ok(_) => return new_state,
                 // If the exchange failed, update current_state and retry
-                Err(actual) => current_state = actual.into(),
+                Err(actual) => current_state = actual,
             }
         }
     }
@@ -224,10 +230,10 @@ mod tests {
     #[cfg(any(target_pointer_width = "64", target_pointer_width = "32"))]
     use std::sync::atomic::AtomicUsize;
 
-    const SEQUENCE_TO_ADD_1: &'static [u8] = &[1, 2, 4, 8, 16, 32, 64, 128];
-    const SEQUENCE_TO_REMOVE_1: &'static [u8] = &[1, 4, 8, 64];
-    const SEQUENCE_TO_ADD_2: &'static [u8] = &[3, 6, 9, 12, 15];
-    const SEQUENCE_TO_REMOVE_2: &'static [u8] = &[2, 6, 12];
+    const SEQUENCE_TO_ADD_1: &[u8] = &[1, 2, 4, 8, 16, 32, 64, 128];
+    const SEQUENCE_TO_REMOVE_1: &[u8] = &[1, 4, 8, 64];
+    const SEQUENCE_TO_ADD_2: &[u8] = &[3, 6, 9, 12, 15];
+    const SEQUENCE_TO_REMOVE_2: &[u8] = &[2, 6, 12];
 
     const LOAD_ORDER: Ordering = Ordering::Acquire;
     const STORE_ORDER: Ordering = Ordering::Release;
@@ -13,6 +13,12 @@ pub struct AccumulativeHash<T: IsAccumulativeHashType> {
     state: T,
 }
 
+impl<T: IsAccumulativeHashType> Default for AccumulativeHash<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: IsAccumulativeHashType> AccumulativeHash<T> {
     /// Create a new empty accumulative hash.
     ///
@@ -126,10 +132,10 @@ where
 mod tests {
     use super::*;
 
-    const SEQUENCE_TO_ADD_1: &'static [u8] = &[1, 2, 4, 8, 16, 32, 64, 128];
-    const SEQUENCE_TO_REMOVE_1: &'static [u8] = &[1, 4, 8, 64];
-    const SEQUENCE_TO_ADD_2: &'static [u8] = &[3, 6, 9, 12, 15];
-    const SEQUENCE_TO_REMOVE_2: &'static [u8] = &[2, 6, 12];
+    const SEQUENCE_TO_ADD_1: &[u8] = &[1, 2, 4, 8, 16, 32, 64, 128];
+    const SEQUENCE_TO_REMOVE_1: &[u8] = &[1, 4, 8, 64];
+    const SEQUENCE_TO_ADD_2: &[u8] = &[3, 6, 9, 12, 15];
+    const SEQUENCE_TO_REMOVE_2: &[u8] = &[2, 6, 12];
 
     macro_rules! test_type {
         ($name:ident::<$typ:ident>(add_1=$add_1:literal, remove_1=$remove_1:literal, add_2=$add_2:literal, remove_2=$remove_2:literal)) => {
@@ -0,0 +1,22 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum InstructionError {
+    #[error("invalid direction {direction:?} at line {line}, column {column}; expected L or R")]
+    InvalidDirection {
+        line: usize,
+        column: usize,
+        direction: char,
+    },
+
+    #[error("invalid amount {amount:?} at line {line}, column {column}")]
+    InvalidAmount {
+        line: usize,
+        column: usize,
+        amount: String,
+    },
+
+    #[error("failed to read instructions: {0}")]
+    Io(#[from] io::Error),
+}
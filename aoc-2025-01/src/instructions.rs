@@ -0,0 +1,120 @@
+//! Parsing the rotations of the dial.
+
+use crate::InstructionError;
+
+/// Parse the whitespace separated rotations on ``line``, such as ``L68``, into their
+/// direction and amount; ``line_number`` is only used to report errors, counting from 1.
+///
+/// Columns are counted in characters from 1, at the start of the offending rotation.
+pub fn parse_line(
+    line: &str,
+    line_number: usize,
+) -> impl Iterator<Item = Result<(char, u64), InstructionError>> + '_ {
+    line.split_whitespace().map(move |token| {
+        let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+        let column = line[..offset].chars().count() + 1;
+
+        let mut chars = token.chars();
+        let direction = chars.next().expect("Unreachable; tokens are never empty");
+        if direction != 'L' && direction != 'R' {
+            return Err(InstructionError::InvalidDirection {
+                line: line_number,
+                column,
+                direction,
+            });
+        }
+
+        let amount = chars.as_str();
+        amount
+            .parse::<u64>()
+            .map(|amount| (direction, amount))
+            .map_err(|_| InstructionError::InvalidAmount {
+                line: line_number,
+                column,
+                amount: amount.to_owned(),
+            })
+    })
+}
+
+/// Parse every rotation in ``s``, line by line; see [`parse_line`].
+pub fn parse_instructions(
+    s: &str,
+) -> impl Iterator<Item = Result<(char, u64), InstructionError>> + '_ {
+    s.lines()
+        .enumerate()
+        .flat_map(|(index, line)| parse_line(line, index + 1))
+}
+
+/// Parse whitespace separated rotations such as ``L68``, into their direction and amount.
+///
+/// Panics on any malformed rotation; see [`parse_instructions`] to handle them instead.
+pub fn instructions_from_string(s: &str) -> impl Iterator<Item = (char, u64)> + '_ {
+    parse_instructions(s).map(|instruction| instruction.unwrap_or_else(|error| panic!("{error}")))
+}
+
+#[cfg(test)]
+mod tests_parse_instructions {
+    use super::*;
+
+    #[test]
+    fn test_valid() {
+        let instructions = parse_instructions("L68 L30\n  R48\n\nL5")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to parse");
+
+        assert_eq!(
+            instructions,
+            vec![('L', 68), ('L', 30), ('R', 48), ('L', 5)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_direction() {
+        let error = parse_instructions("L68\nR1 X30")
+            .find_map(Result::err)
+            .expect("No error");
+
+        assert!(matches!(
+            error,
+            InstructionError::InvalidDirection {
+                line: 2,
+                column: 4,
+                direction: 'X',
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "invalid direction 'X' at line 2, column 4; expected L or R"
+        );
+    }
+
+    #[test]
+    fn test_invalid_amount() {
+        let errors = parse_instructions("L68 Rx2\nL99999999999999999999 R")
+            .filter_map(Result::err)
+            .map(|error| match error {
+                InstructionError::InvalidAmount {
+                    line,
+                    column,
+                    amount,
+                } => (line, column, amount),
+                error => panic!("Unexpected error {error:?}"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            vec![
+                (1, 5, "x2".to_owned()),
+                (2, 1, "99999999999999999999".to_owned()),
+                (2, 23, String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid amount \"x\" at line 1, column 1")]
+    fn test_instructions_from_string_panics() {
+        instructions_from_string("Lx").for_each(drop);
+    }
+}
@@ -0,0 +1,11 @@
+//! The dial of the safe from Day 1 of 2025, as a library so that it can be reused and
+//! benchmarked outside of the puzzle.
+
+mod errors;
+pub use errors::*;
+
+mod instructions;
+pub use instructions::*;
+
+mod wheel;
+pub use wheel::*;
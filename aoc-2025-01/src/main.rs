@@ -111,279 +111,87 @@
 mod input;
 use input::INPUT;
 
-fn instructions_from_string(s: &str) -> impl Iterator<Item = (char, u16)> + '_ {
-    s.split_whitespace().map(|s| {
-        let (dir, amt) = s.split_at(1);
-        (
-            dir.chars().next().expect("Invalid direction"),
-            amt.parse::<u16>().expect("Invalid amount"),
-        )
-    })
-}
-
-/// A wheel (or dial) that can be rotated left or right, tracking how many times it
-/// passes through and ends at position 0.
-/// 
-/// This implementation uses a generic constant parameter `S` to define the size of the
-/// wheel, defaulting to ``100`` if not specified.
-#[derive(Debug, PartialEq, Eq)]
-pub struct Wheel<const S: u16 = 100> {
-    pub position: u16,
-    pub ends_at_zero_count: usize,
-    pub pass_through_zero_count: usize,
-}
-
-impl<const S: u16> Wheel<S> {
-    pub fn new(position: u16) -> Self {
-        Self {
-            position,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 0,
-        }
-    }
-
-    pub fn set_position(&mut self, position: i32, direction: char) {
-        let size = S as i32;
-
-        let mut raw_position = position % size;
-        let mut revolutions = (position / size).unsigned_abs() as u16;
-
-        if raw_position <= 0 && (self.position > 0 && direction == 'L') {
-            revolutions += 1;
-        }
-        if raw_position < 0 {
-            raw_position += size;
-        }
-
-        assert!(raw_position >= 0);
-
-        self.position = raw_position as u16;
-        self.pass_through_zero_count += revolutions as usize;
-
-        if self.position == 0 {
-            self.ends_at_zero_count += 1;
-        }
-    }
-
-    pub fn rotate(&mut self, direction: char, amount: u16) {
-        let current_passes_through_zero = self.pass_through_zero_count;
-        match direction {
-            'L' => {
-                self.set_position(self.position as i32 - amount as i32, direction);
-            }
-            'R' => {
-                self.set_position(self.position as i32 + amount as i32, direction);
-            }
-            _ => {
-                panic!("Invalid direction {:?}", direction);
-            }
-        }
-        let suffix = if self.pass_through_zero_count > current_passes_through_zero {
-            &format!(
-                "; during this rotation, it points at 0 {} times(s).",
-                self.pass_through_zero_count - current_passes_through_zero
-            )
-        } else {
-            ""
-        };
-        eprintln!(
-            "The dial is rotated {direction}{amount} to point at {position}{suffix}",
-            position = self.position,
-        );
-    }
-
-    pub fn execute(&mut self, instructions: impl Iterator<Item = (char, u16)>) {
-        eprintln!("The dial starts by pointing at {}", self.position);
-        for (direction, amount) in instructions {
-            self.rotate(direction, amount);
-        }
+use aoc_2025_01::{Dial, Verbosity, Wheel};
+use std::{
+    fs::File,
+    io::{self, Read},
+};
+
+/// Where to read the rotations from, according to the positional command line ``args``:
+/// a path to a file, ``-`` for stdin, or the puzzle input baked into [`INPUT`] if no
+/// argument is given.
+fn open_input(mut args: impl Iterator<Item = String>) -> io::Result<Box<dyn Read>> {
+    match args.next().as_deref() {
+        None => Ok(Box::new(INPUT.as_bytes())),
+        Some("-") => Ok(Box::new(io::stdin().lock())),
+        Some(path) => Ok(Box::new(File::open(path)?)),
     }
 }
 
 fn main() {
-    let mut wheel = Wheel::<100>::new(50);
+    // ``-v`` or ``--verbose`` anywhere narrates every rotation, as the puzzle does.
+    let (flags, args): (Vec<_>, Vec<_>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg == "-v" || arg == "--verbose");
+    let verbosity = if flags.is_empty() {
+        Verbosity::Quiet
+    } else {
+        Verbosity::Narrate
+    };
+
+    let input = match open_input(args.into_iter()) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("Failed to read the rotations: {error}");
+            std::process::exit(1);
+        }
+    };
 
-    let instructions = instructions_from_string(INPUT);
+    let mut wheel = Wheel::<100>::new(50);
 
-    wheel.execute(instructions);
+    let summary = match wheel.execute_reader(input, verbosity) {
+        Ok(summary) => summary,
+        Err(error) => {
+            eprintln!("Failed to execute the rotations: {error}");
+            std::process::exit(1);
+        }
+    };
 
     println!(
         "The dial ends pointing at {} having passed through zero {} times and ended at zero {} times.",
-        wheel.position, wheel.pass_through_zero_count, wheel.ends_at_zero_count
+        summary.final_position, summary.passes_through_zero, summary.ends_at_zero
     );
 }
 
 #[cfg(test)]
-mod tests_set_position {
+mod tests_open_input {
     use super::*;
 
-    macro_rules! create_test {
-        ($name:ident(size=$size:literal, initial=$initial:literal, position=$position:literal, direction=$direction:literal, expected=$expected:expr)) => {
-            #[test]
-            fn $name() {
-                let mut wheel: Wheel<$size> = Wheel::new($initial);
-
-                wheel.set_position($position, $direction);
-
-                assert_eq!(wheel, $expected);
-            }
-        };
+    fn read_to_string(mut input: Box<dyn Read>) -> String {
+        let mut buffer = String::new();
+        input
+            .read_to_string(&mut buffer)
+            .expect("Failed to read input");
+        buffer
     }
 
-    create_test!(test1(
-        size = 100,
-        initial = 0,
-        position = 249,
-        direction = 'R',
-        expected = Wheel::<100> {
-            position: 49,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 2,
-        }
-    ));
-    create_test!(test2(
-        size = 100,
-        initial = 0,
-        position = -249,
-        direction = 'L',
-        expected = Wheel::<100> {
-            position: 51,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 2,
-        }
-    ));
-    create_test!(test3(
-        size = 100,
-        initial = 1,
-        position = -249,
-        direction = 'L',
-        expected = Wheel::<100> {
-            position: 51,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 3,
-        }
-    ));
-    create_test!(test4(
-        size = 100,
-        initial = 0,
-        position = 200,
-        direction = 'R',
-        expected = Wheel::<100> {
-            position: 0,
-            ends_at_zero_count: 1,
-            pass_through_zero_count: 2,
-        }
-    ));
-    create_test!(test5(
-        size = 100,
-        initial = 50,
-        position = -100,
-        direction = 'L',
-        expected = Wheel::<100> {
-            position: 0,
-            ends_at_zero_count: 1,
-            pass_through_zero_count: 2,
-        }
-    ));
-}
-
-#[cfg(test)]
-mod tests_rotate {
-    use super::*;
-
-    macro_rules! create_test {
-        ($name:ident(size=$size:literal, initial=$initial:literal, direction=$direction:literal, amount=$amount:literal, expected=$expected:expr)) => {
-            #[test]
-            fn $name() {
-                let mut wheel: Wheel<$size> = Wheel::new($initial);
-
-                wheel.rotate($direction, $amount);
-
-                assert_eq!(wheel, $expected);
-            }
-        };
+    #[test]
+    fn test_default() {
+        let input = open_input(std::iter::empty()).expect("Failed to open input");
+        assert_eq!(read_to_string(input), INPUT);
     }
 
-    create_test!(test1(
-        size = 100,
-        initial = 0,
-        direction = 'R',
-        amount = 250,
-        expected = Wheel::<100> {
-            position: 50,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 2,
-        }
-    ));
-
-    create_test!(test2(
-        size = 100,
-        initial = 0,
-        direction = 'L',
-        amount = 249,
-        expected = Wheel::<100> {
-            position: 51,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 2,
-        }
-    ));
-
-    create_test!(test3(
-        size = 100,
-        initial = 1,
-        direction = 'L',
-        amount = 251,
-        expected = Wheel::<100> {
-            position: 50,
-            ends_at_zero_count: 0,
-            pass_through_zero_count: 3,
-        }
-    ));
-
-    create_test!(test4(
-        size = 100,
-        initial = 50,
-        direction = 'R',
-        amount = 150,
-        expected = Wheel::<100> {
-            position: 0,
-            ends_at_zero_count: 1,
-            pass_through_zero_count: 2,
-        }
-    ));
-
-    create_test!(test5(
-        size = 100,
-        initial = 50,
-        direction = 'L',
-        amount = 150,
-        expected = Wheel::<100> {
-            position: 0,
-            ends_at_zero_count: 1,
-            pass_through_zero_count: 2,
-        }
-    ));
-}
-
-#[cfg(test)]
-mod tests_execute {
-    use super::*;
-
     #[test]
-    fn test1() {
-        let mut wheel = Wheel::<100>::new(50);
-
-        let instructions = instructions_from_string("L68 L30 R48 L5 R60 L55 L1 L99 R14 L82");
+    fn test_path() {
+        let path = std::env::temp_dir().join("aoc-2025-01-test-path.txt");
+        std::fs::write(&path, "L68 L30 R48").expect("Failed to write input");
 
-        wheel.execute(instructions);
+        let input = open_input([path.to_string_lossy().into_owned()].into_iter())
+            .expect("Failed to open input");
+        let contents = read_to_string(input);
+        std::fs::remove_file(&path).expect("Failed to remove input");
 
-        assert_eq!(
-            wheel,
-            Wheel::<100> {
-                position: 32,
-                ends_at_zero_count: 3,
-                pass_through_zero_count: 6,
-            }
-        );
+        assert_eq!(contents, "L68 L30 R48");
+        assert!(open_input(["/nonexistent/rotations.txt".to_owned()].into_iter()).is_err());
     }
 }
@@ -0,0 +1,762 @@
+//! The dial of the safe, and how it counts passing through zero.
+
+use crate::{InstructionError, parse_line};
+use std::io::{self, BufRead};
+
+/// A wheel (or dial) that can be rotated left or right, tracking how many times it
+/// passes through and ends at position 0.
+///
+/// This implementation uses a generic constant parameter `S` to define the size of the
+/// wheel, defaulting to ``100`` if not specified. Wheels whose size is only known at
+/// runtime are [`SizedWheel`]s instead, created with [`Wheel::with_size`]; both are turned
+/// through the [`Dial`] trait.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Wheel<const S: u16 = 100> {
+    pub position: u16,
+    pub ends_at_zero_count: usize,
+    pub pass_through_zero_count: usize,
+}
+
+/// A wheel like [`Wheel`], but whose size is chosen at runtime rather than by a generic
+/// constant parameter.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SizedWheel {
+    pub position: u16,
+    pub ends_at_zero_count: usize,
+    pub pass_through_zero_count: usize,
+    size: u16,
+}
+
+/// Whether executing rotations describes each of them on stderr, as the puzzle does.
+///
+/// Describing every rotation of a real input prints thousands of lines, and takes far
+/// longer than the rotations themselves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    #[default]
+    Quiet,
+    Narrate,
+}
+
+/// Where a wheel ended up after executing some rotations, and how often it pointed at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WheelSummary {
+    pub final_position: u16,
+    /// The number of rotations that left the wheel pointing at 0; the answer to part 1.
+    pub ends_at_zero: usize,
+    /// The number of clicks that left the wheel pointing at 0, whether at the end of a
+    /// rotation or during it; the answer to part 2.
+    pub passes_through_zero: usize,
+}
+
+/// Where a wheel points after one rotation, as yielded by [`Dial::iter_states`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WheelState {
+    /// Where the wheel pointed before the rotation.
+    pub from: u16,
+    pub direction: char,
+    pub amount: u64,
+    /// Where the wheel points after the rotation.
+    pub position: u16,
+    /// The number of times the wheel pointed at 0 during the rotation, including at its
+    /// end.
+    pub passes_through_zero: usize,
+    size: u16,
+}
+
+impl WheelState {
+    /// The clicks into the rotation at which the wheel pointed at 0, counting from 1, found
+    /// without turning the wheel a click at a time.
+    pub fn zero_crossings(&self) -> impl Iterator<Item = u64> + use<> {
+        let size = self.size as u64;
+        let first = match (self.direction, self.from) {
+            (_, 0) => size,
+            ('L', from) => from as u64,
+            (_, from) => size - from as u64,
+        };
+
+        (0..self.passes_through_zero as u64).map(move |index| first + index * size)
+    }
+}
+
+/// Rotating a wheel and counting how often it points at 0.
+///
+/// A [`Wheel`] and a [`SizedWheel`] only differ in where their size comes from, so each
+/// of them just tells its size and where its counts are kept; every rotation is then
+/// counted the same way for both.
+pub trait Dial {
+    /// The number of positions on the wheel.
+    fn size(&self) -> u16;
+
+    /// The counts so far, and where the wheel points now.
+    fn summary(&self) -> WheelSummary;
+
+    /// Where the wheel points, the number of rotations that ended at 0, and the number of
+    /// clicks that pointed at 0; in that order.
+    fn counts_mut(&mut self) -> (&mut u16, &mut usize, &mut usize);
+
+    /// Move the wheel to ``position``, counted without wrapping around from where it was
+    /// before it was turned in ``direction``.
+    ///
+    /// How many times the wheel passes through ``0`` is found from the number of whole
+    /// revolutions alone, never click by click, so a turn of any size takes the same time.
+    fn set_position(&mut self, position: i128, direction: char) {
+        let size = self.size() as i128;
+        let (current, ends_at_zero_count, pass_through_zero_count) = self.counts_mut();
+
+        let mut raw_position = position % size;
+        let mut revolutions = (position / size).unsigned_abs();
+
+        if raw_position <= 0 && (*current > 0 && direction == 'L') {
+            revolutions += 1;
+        }
+        if raw_position < 0 {
+            raw_position += size;
+        }
+
+        assert!(raw_position >= 0);
+
+        *current = raw_position as u16;
+        *pass_through_zero_count += revolutions as usize;
+
+        if *current == 0 {
+            *ends_at_zero_count += 1;
+        }
+    }
+
+    /// Rotate the wheel ``amount`` clicks in ``direction``, returning how many times it
+    /// pointed at 0 during the rotation.
+    fn rotate(&mut self, direction: char, amount: u64) -> usize {
+        let WheelSummary {
+            final_position: position,
+            passes_through_zero: current_passes_through_zero,
+            ..
+        } = self.summary();
+        match direction {
+            'L' => {
+                self.set_position(position as i128 - amount as i128, direction);
+            }
+            'R' => {
+                self.set_position(position as i128 + amount as i128, direction);
+            }
+            _ => {
+                panic!("Invalid direction {:?}", direction);
+            }
+        }
+
+        self.summary().passes_through_zero - current_passes_through_zero
+    }
+
+    fn execute(
+        &mut self,
+        instructions: impl Iterator<Item = (char, u64)>,
+        verbosity: Verbosity,
+    ) -> WheelSummary {
+        if verbosity == Verbosity::Narrate {
+            eprintln!(
+                "The dial starts by pointing at {}",
+                self.summary().final_position
+            );
+        }
+        for (direction, amount) in instructions {
+            rotate_with(self, direction, amount, verbosity);
+        }
+
+        self.summary()
+    }
+
+    /// Lazily execute ``instructions``, yielding where the wheel points after each of
+    /// them, so that the trajectory of the wheel can be followed without parsing stderr.
+    ///
+    /// Only the rotations consumed from the iterator are executed.
+    fn iter_states(
+        &mut self,
+        instructions: impl IntoIterator<Item = (char, u64)>,
+    ) -> impl Iterator<Item = WheelState> {
+        instructions.into_iter().map(move |(direction, amount)| {
+            let from = self.summary().final_position;
+            let passes_through_zero = self.rotate(direction, amount);
+
+            WheelState {
+                from,
+                direction,
+                amount,
+                position: self.summary().final_position,
+                passes_through_zero,
+                size: self.size(),
+            }
+        })
+    }
+
+    /// Execute the rotations read from ``reader`` a line at a time, so that the whole input
+    /// is never held in memory.
+    ///
+    /// Stops at the first rotation that cannot be read or parsed, leaving the wheel as it
+    /// was after the rotations before it.
+    fn execute_reader(
+        &mut self,
+        reader: impl io::Read,
+        verbosity: Verbosity,
+    ) -> Result<WheelSummary, InstructionError> {
+        if verbosity == Verbosity::Narrate {
+            eprintln!(
+                "The dial starts by pointing at {}",
+                self.summary().final_position
+            );
+        }
+        for (index, line) in io::BufReader::new(reader).lines().enumerate() {
+            for instruction in parse_line(&line?, index + 1) {
+                let (direction, amount) = instruction?;
+                rotate_with(self, direction, amount, verbosity);
+            }
+        }
+
+        Ok(self.summary())
+    }
+}
+
+/// Rotate ``wheel``, describing the rotation on stderr if ``verbosity`` asks for it.
+fn rotate_with<D: Dial + ?Sized>(
+    wheel: &mut D,
+    direction: char,
+    amount: u64,
+    verbosity: Verbosity,
+) {
+    let passes = wheel.rotate(direction, amount);
+    if verbosity == Verbosity::Quiet {
+        return;
+    }
+
+    let suffix = if passes > 0 {
+        &format!("; during this rotation, it points at 0 {passes} times(s).")
+    } else {
+        ""
+    };
+    eprintln!(
+        "The dial is rotated {direction}{amount} to point at {position}{suffix}",
+        position = wheel.summary().final_position,
+    );
+}
+
+impl Wheel {
+    /// Create a wheel of ``size`` positions, pointing at ``start``, for when the size is
+    /// only known at runtime.
+    ///
+    /// Panics if ``start`` is not a position on the wheel, i.e. not less than ``size``.
+    pub fn with_size(size: u16, start: u16) -> SizedWheel {
+        assert!(
+            start < size,
+            "Start position {start} is not on a wheel of size {size}"
+        );
+
+        SizedWheel {
+            position: start,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 0,
+            size,
+        }
+    }
+}
+
+impl<const S: u16> Wheel<S> {
+    pub fn new(position: u16) -> Self {
+        Self {
+            position,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 0,
+        }
+    }
+}
+
+impl<const S: u16> Dial for Wheel<S> {
+    fn size(&self) -> u16 {
+        S
+    }
+
+    fn summary(&self) -> WheelSummary {
+        WheelSummary {
+            final_position: self.position,
+            ends_at_zero: self.ends_at_zero_count,
+            passes_through_zero: self.pass_through_zero_count,
+        }
+    }
+
+    fn counts_mut(&mut self) -> (&mut u16, &mut usize, &mut usize) {
+        (
+            &mut self.position,
+            &mut self.ends_at_zero_count,
+            &mut self.pass_through_zero_count,
+        )
+    }
+}
+
+impl Dial for SizedWheel {
+    fn size(&self) -> u16 {
+        self.size
+    }
+
+    fn summary(&self) -> WheelSummary {
+        WheelSummary {
+            final_position: self.position,
+            ends_at_zero: self.ends_at_zero_count,
+            passes_through_zero: self.pass_through_zero_count,
+        }
+    }
+
+    fn counts_mut(&mut self) -> (&mut u16, &mut usize, &mut usize) {
+        (
+            &mut self.position,
+            &mut self.ends_at_zero_count,
+            &mut self.pass_through_zero_count,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests_set_position {
+    use super::*;
+
+    macro_rules! create_test {
+        ($name:ident(size=$size:literal, initial=$initial:literal, position=$position:literal, direction=$direction:literal, expected=$expected:expr)) => {
+            #[test]
+            fn $name() {
+                let mut wheel: Wheel<$size> = Wheel::new($initial);
+
+                wheel.set_position($position, $direction);
+
+                assert_eq!(wheel, $expected);
+            }
+        };
+    }
+
+    create_test!(test1(
+        size = 100,
+        initial = 0,
+        position = 249,
+        direction = 'R',
+        expected = Wheel::<100> {
+            position: 49,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 2,
+        }
+    ));
+    create_test!(test2(
+        size = 100,
+        initial = 0,
+        position = -249,
+        direction = 'L',
+        expected = Wheel::<100> {
+            position: 51,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 2,
+        }
+    ));
+    create_test!(test3(
+        size = 100,
+        initial = 1,
+        position = -249,
+        direction = 'L',
+        expected = Wheel::<100> {
+            position: 51,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 3,
+        }
+    ));
+    create_test!(test4(
+        size = 100,
+        initial = 0,
+        position = 200,
+        direction = 'R',
+        expected = Wheel::<100> {
+            position: 0,
+            ends_at_zero_count: 1,
+            pass_through_zero_count: 2,
+        }
+    ));
+    create_test!(test5(
+        size = 100,
+        initial = 50,
+        position = -100,
+        direction = 'L',
+        expected = Wheel::<100> {
+            position: 0,
+            ends_at_zero_count: 1,
+            pass_through_zero_count: 2,
+        }
+    ));
+}
+
+#[cfg(test)]
+mod tests_rotate {
+    use super::*;
+
+    macro_rules! create_test {
+        ($name:ident(size=$size:literal, initial=$initial:literal, direction=$direction:literal, amount=$amount:literal, expected=$expected:expr)) => {
+            #[test]
+            fn $name() {
+                let mut wheel: Wheel<$size> = Wheel::new($initial);
+
+                wheel.rotate($direction, $amount);
+
+                assert_eq!(wheel, $expected);
+            }
+        };
+    }
+
+    create_test!(test1(
+        size = 100,
+        initial = 0,
+        direction = 'R',
+        amount = 250,
+        expected = Wheel::<100> {
+            position: 50,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 2,
+        }
+    ));
+
+    create_test!(test2(
+        size = 100,
+        initial = 0,
+        direction = 'L',
+        amount = 249,
+        expected = Wheel::<100> {
+            position: 51,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 2,
+        }
+    ));
+
+    create_test!(test3(
+        size = 100,
+        initial = 1,
+        direction = 'L',
+        amount = 251,
+        expected = Wheel::<100> {
+            position: 50,
+            ends_at_zero_count: 0,
+            pass_through_zero_count: 3,
+        }
+    ));
+
+    create_test!(test4(
+        size = 100,
+        initial = 50,
+        direction = 'R',
+        amount = 150,
+        expected = Wheel::<100> {
+            position: 0,
+            ends_at_zero_count: 1,
+            pass_through_zero_count: 2,
+        }
+    ));
+
+    create_test!(test5(
+        size = 100,
+        initial = 50,
+        direction = 'L',
+        amount = 150,
+        expected = Wheel::<100> {
+            position: 0,
+            ends_at_zero_count: 1,
+            pass_through_zero_count: 2,
+        }
+    ));
+}
+
+#[cfg(test)]
+mod tests_execute {
+    use super::*;
+    use crate::instructions_from_string;
+
+    #[test]
+    fn test1() {
+        let mut wheel = Wheel::<100>::new(50);
+
+        let instructions = instructions_from_string("L68 L30 R48 L5 R60 L55 L1 L99 R14 L82");
+
+        wheel.execute(instructions, Verbosity::Quiet);
+
+        assert_eq!(
+            wheel,
+            Wheel::<100> {
+                position: 32,
+                ends_at_zero_count: 3,
+                pass_through_zero_count: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let instructions = "L68 L30 R48 L5 R60 L55 L1 L99 R14 L82";
+        let expected = WheelSummary {
+            final_position: 32,
+            ends_at_zero: 3,
+            passes_through_zero: 6,
+        };
+
+        for verbosity in [Verbosity::Quiet, Verbosity::Narrate] {
+            let mut wheel = Wheel::<100>::new(50);
+            let summary = wheel.execute(instructions_from_string(instructions), verbosity);
+
+            assert_eq!(summary, expected);
+            assert_eq!(wheel.summary(), expected);
+        }
+    }
+
+    #[test]
+    fn test_rotate_returns_passes() {
+        let mut wheel = Wheel::<100>::new(50);
+        assert_eq!(wheel.rotate('R', 1000), 10);
+        assert_eq!(wheel.rotate('L', 49), 0);
+        assert_eq!(wheel.rotate('L', 1), 1);
+    }
+}
+
+#[cfg(test)]
+mod tests_with_size {
+    use super::*;
+    use crate::instructions_from_string;
+
+    #[test]
+    fn test_matches_const_size() {
+        let mut wheel = Wheel::with_size(100, 50);
+        wheel.execute(
+            instructions_from_string("L68 L30 R48 L5 R60 L55 L1 L99 R14 L82"),
+            Verbosity::Quiet,
+        );
+
+        let mut expected = Wheel::<100>::new(50);
+        expected.execute(
+            instructions_from_string("L68 L30 R48 L5 R60 L55 L1 L99 R14 L82"),
+            Verbosity::Quiet,
+        );
+
+        assert_eq!(wheel.summary(), expected.summary());
+    }
+
+    #[test]
+    fn test_small_wheel() {
+        let mut wheel = Wheel::with_size(10, 3);
+        assert_eq!(wheel.size(), 10);
+
+        wheel.rotate('R', 27);
+        assert_eq!((wheel.position, wheel.pass_through_zero_count), (0, 3));
+        wheel.rotate('L', 11);
+        assert_eq!((wheel.position, wheel.pass_through_zero_count), (9, 4));
+        assert_eq!(wheel.ends_at_zero_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "not on a wheel of size 10")]
+    fn test_start_out_of_range() {
+        Wheel::with_size(10, 10);
+    }
+}
+
+#[cfg(test)]
+mod tests_execute_reader {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let mut wheel = Wheel::<100>::new(50);
+        wheel
+            .execute_reader(
+                "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n".as_bytes(),
+                Verbosity::Quiet,
+            )
+            .expect("Failed to execute");
+
+        assert_eq!(
+            (
+                wheel.position,
+                wheel.ends_at_zero_count,
+                wheel.pass_through_zero_count
+            ),
+            (32, 3, 6)
+        );
+    }
+
+    #[test]
+    fn test_stops_at_error() {
+        let mut wheel = Wheel::<100>::new(50);
+        let result = wheel.execute_reader("R10\nL5 R\nR20\n".as_bytes(), Verbosity::Quiet);
+
+        assert!(matches!(
+            result,
+            Err(InstructionError::InvalidAmount {
+                line: 2,
+                column: 4,
+                ..
+            })
+        ));
+        assert_eq!(wheel.position, 55);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut wheel = Wheel::<100>::new(50);
+        let result = wheel.execute_reader(&b"R10\n\xff\n"[..], Verbosity::Quiet);
+
+        assert!(matches!(result, Err(InstructionError::Io(_))));
+        assert_eq!(wheel.position, 60);
+    }
+}
+
+#[cfg(test)]
+mod tests_large_amounts {
+    use super::*;
+
+    /// Turn the wheel a click at a time, counting every click that lands on ``0``.
+    fn click_by_click(size: u16, start: u16, direction: char, amount: u64) -> (u16, usize) {
+        let (mut position, mut passes) = (start, 0);
+        for _ in 0..amount {
+            position = match direction {
+                'L' => (position + size - 1) % size,
+                _ => (position + 1) % size,
+            };
+            passes += (position == 0) as usize;
+        }
+
+        (position, passes)
+    }
+
+    #[test]
+    fn test_matches_click_by_click() {
+        for size in 1..=7 {
+            for start in 0..size {
+                for direction in ['L', 'R'] {
+                    for amount in 0..=30 {
+                        let mut wheel = Wheel::with_size(size, start);
+                        wheel.rotate(direction, amount);
+
+                        assert_eq!(
+                            (wheel.position, wheel.pass_through_zero_count),
+                            click_by_click(size, start, direction, amount),
+                            "{direction}{amount} from {start} on a wheel of size {size}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_billions() {
+        let mut wheel = Wheel::<100>::new(50);
+
+        wheel.rotate('R', 5_000_000_000);
+        assert_eq!(
+            (wheel.position, wheel.pass_through_zero_count),
+            (50, 50_000_000)
+        );
+
+        wheel.rotate('L', 7_000_000_050);
+        assert_eq!(
+            (wheel.position, wheel.pass_through_zero_count),
+            (0, 50_000_000 + 70_000_001)
+        );
+        assert_eq!(wheel.ends_at_zero_count, 1);
+
+        // Starting at zero does not count it again.
+        wheel.rotate('L', 3_000_000_001);
+        assert_eq!(
+            (wheel.position, wheel.pass_through_zero_count),
+            (99, 50_000_000 + 70_000_001 + 30_000_000)
+        );
+    }
+
+    #[test]
+    fn test_largest_amount() {
+        let mut wheel = Wheel::<100>::new(0);
+        wheel.rotate('R', u64::MAX);
+        assert_eq!(
+            (wheel.position, wheel.pass_through_zero_count as u64),
+            (15, u64::MAX / 100)
+        );
+
+        let mut wheel = Wheel::<100>::new(1);
+        wheel.rotate('L', u64::MAX);
+        assert_eq!(
+            (wheel.position, wheel.pass_through_zero_count as u64),
+            (86, u64::MAX / 100 + 1)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_iter_states {
+    use super::*;
+    use crate::instructions_from_string;
+
+    #[test]
+    fn test_example() {
+        let mut wheel = Wheel::<100>::new(50);
+        let states = wheel
+            .iter_states(instructions_from_string(
+                "L68 L30 R48 L5 R60 L55 L1 L99 R14 L82",
+            ))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            states
+                .iter()
+                .map(|state| state.position)
+                .collect::<Vec<_>>(),
+            vec![82, 52, 0, 95, 55, 0, 99, 0, 14, 32]
+        );
+        assert_eq!(
+            states
+                .iter()
+                .map(|state| state.passes_through_zero)
+                .sum::<usize>(),
+            6
+        );
+        assert_eq!(states[0].zero_crossings().collect::<Vec<_>>(), vec![50]);
+        assert_eq!(states[4].zero_crossings().collect::<Vec<_>>(), vec![5]);
+        assert_eq!(wheel.summary().final_position, 32);
+    }
+
+    #[test]
+    fn test_lazy() {
+        let mut wheel = Wheel::<100>::new(50);
+        let first = wheel.iter_states([('R', 10), ('R', 20)]).next();
+
+        assert_eq!(first.map(|state| state.position), Some(60));
+        assert_eq!(wheel.position, 60);
+    }
+
+    #[test]
+    fn test_zero_crossings_match_click_by_click() {
+        for start in 0..5 {
+            for direction in ['L', 'R'] {
+                for amount in 0..=12 {
+                    let mut wheel = Wheel::with_size(5, start);
+                    let state = wheel
+                        .iter_states([(direction, amount)])
+                        .next()
+                        .expect("No state");
+
+                    let mut position = start;
+                    let expected = (1..=amount)
+                        .filter(|_| {
+                            position = match direction {
+                                'L' => (position + 4) % 5,
+                                _ => (position + 1) % 5,
+                            };
+                            position == 0
+                        })
+                        .collect::<Vec<_>>();
+
+                    assert_eq!(
+                        state.zero_crossings().collect::<Vec<_>>(),
+                        expected,
+                        "{direction}{amount} from {start}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -66,6 +66,13 @@
 //! 
 //! Both parts of this puzzle are complete! They provide two gold stars: **
 
+// The same code handles IDs of both ``u128`` and ``BigUint``, so it clones and borrows
+// them as the latter needs, which is needless for the former.
+#![cfg_attr(
+    not(feature = "num-bigint"),
+    allow(clippy::clone_on_copy, clippy::op_ref)
+)]
+
 #[cfg(feature = "jemalloc")]
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
@@ -73,38 +80,128 @@ static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 mod input;
 use input::INPUT;
 
+mod output;
+use output::output_format;
+#[cfg(not(feature = "sum-only"))]
+use output::write_found;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 #[cfg(feature = "profile")]
 use std::time::Instant;
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// The integer type of IDs, which holds IDs of up to 38 digits.
+#[cfg(not(feature = "num-bigint"))]
+type Id = u128;
+
+/// The integer type of IDs, of arbitrary precision with the ``num-bigint`` feature.
+#[cfg(feature = "num-bigint")]
+type Id = num_bigint::BigUint;
+
+/// The number of decimal digits in ``value``, counting ``0`` as one digit.
+///
+/// This is exact for every [`Id`], unlike going through a floating point ``log10``,
+/// which rounds values such as ``999_999_999`` up to the next power of ten.
+#[cfg(not(feature = "num-bigint"))]
+fn digit_count(value: &Id) -> usize {
+    value.checked_ilog10().map_or(1, |log| log as usize + 1)
+}
+
+/// The number of decimal digits in ``value``, counting ``0`` as one digit.
+#[cfg(feature = "num-bigint")]
+fn digit_count(value: &Id) -> usize {
+    value.to_str_radix(10).len()
+}
 
-const PRIMES: [usize; 10] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+/// ``10`` to the power of ``exponent``; ``None`` if it does not fit in an [`Id`].
+#[cfg(not(feature = "num-bigint"))]
+fn power_of_ten(exponent: usize) -> Option<Id> {
+    Id::checked_pow(10, exponent as u32)
+}
+
+/// ``10`` to the power of ``exponent``, which always fits.
+#[cfg(feature = "num-bigint")]
+fn power_of_ten(exponent: usize) -> Option<Id> {
+    Some(Id::from(10u8).pow(exponent as u32))
+}
+
+/// Parse a single range, such as ``11-22``, from ``section``.
+///
+/// Returns an error naming ``section`` if either bound is not an integer, or if the
+/// range ends before it starts.
+fn parse_range(section: &str) -> Result<RangeInclusive<Id>, anyhow::Error> {
+    let (start, end) = section
+        .trim()
+        .split_once('-')
+        .ok_or_else(|| anyhow::anyhow!("Range {:?} is not of the form start-end", section))?;
+    let parse = |bound: &str| {
+        bound
+            .parse::<Id>()
+            .map_err(|error| anyhow::anyhow!("Range {:?} has an invalid bound: {}", section, error))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+
+    if start > end {
+        return Err(anyhow::anyhow!("Range {:?} ends before it starts", section));
+    }
+
+    Ok(start..=end)
+}
 
 fn split_input_into_iterables(
     input: &str,
-) -> impl Iterator<Item = impl Iterator<Item = u64> + '_> + '_ {
-    input.split(',').map(|section| {
-        let mut bounds = section
-            .split('-')
-            .map(|num_str| num_str.parse::<u64>().unwrap());
-        let start = bounds.next().unwrap();
-        let end = bounds.next().unwrap();
-        start..=end
-    })
+) -> impl Iterator<Item = Result<RangeInclusive<Id>, anyhow::Error>> + '_ {
+    input.split(',').map(parse_range)
+}
+
+/// Merge the ``ranges`` that overlap or are adjacent, so that no ID is counted twice,
+/// returning the merged ranges in ascending order.
+#[cfg(any(test, feature = "merge-ranges"))]
+fn merge_ranges(mut ranges: Vec<RangeInclusive<Id>>) -> Vec<RangeInclusive<Id>> {
+    ranges.sort_unstable_by(|a, b| a.start().cmp(b.start()));
+
+    let mut merged: Vec<RangeInclusive<Id>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last)
+                if range.start() <= last.end()
+                    || range.start().clone() - last.end() == Id::from(1u8) =>
+            {
+                *last = last.start().clone()..=last.end().max(range.end()).clone();
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Every number of times a pattern can be repeated to make up ``digit_count`` digits, i.e.
+/// every divisor of ``digit_count`` from ``2`` upwards, in ascending order.
+///
+/// Trying only the prime divisors would find the same values, since a pattern repeated
+/// ``4`` times is also a longer pattern repeated twice; but listing every divisor does not
+/// rely on that, and lets each of them be tried explicitly.
+fn repeat_counts(digit_count: usize) -> impl Iterator<Item = usize> {
+    (2..=digit_count).filter(move |&repeats| digit_count.is_multiple_of(repeats))
 }
 
-fn generate_mask(pattern_length: usize, repeats: usize) -> u64 {
-    (0..repeats).fold(0u64, |acc, i| acc + 10u64.pow((i * pattern_length) as u32))
+fn generate_mask(pattern_length: usize, repeats: usize) -> Id {
+    (0..repeats).fold(Id::from(0u8), |acc, i| {
+        acc + power_of_ten(i * pattern_length).expect("A mask is no longer than an ID")
+    })
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct RepeatedPatternInteger {
-    pub value: u64,
-    pub pattern: u64,
+    pub value: Id,
+    pub pattern: Id,
     pub repeats: usize,
 }
 
 impl RepeatedPatternInteger {
-    pub fn try_from_value_and_repeats(value: u64, repeats: usize) -> Result<Self, anyhow::Error> {
-        let digit_count = (value as f32).log10().floor() as usize + 1;
+    pub fn try_from_value_and_repeats(value: Id, repeats: usize) -> Result<Self, anyhow::Error> {
+        let digit_count = digit_count(&value);
         if !digit_count.is_multiple_of(repeats) {
             return Err(anyhow::anyhow!(
                 "Value {} does not have a divisible digit count for pattern length {}",
@@ -118,7 +215,7 @@ impl RepeatedPatternInteger {
         // Special thanks to Mr Kushagra Raina for suggesting the use of a mask.
         let mask = generate_mask(pattern_length, repeats);
 
-        if !value.is_multiple_of(mask) {
+        if &value % &mask != Id::from(0u8) {
             return Err(anyhow::anyhow!(
                 "Value {} is not a repeated pattern integer for repeats {}",
                 value,
@@ -127,36 +224,101 @@ impl RepeatedPatternInteger {
         }
 
         Ok(Self {
+            pattern: &value / &mask,
             value,
-            pattern: value / mask,
             repeats,
         })
     }
 
-    pub fn try_from_value(value: u64) -> Result<Self, anyhow::Error> {
-        let digit_count = (value as f32).log10().floor() as usize + 1;
+    pub fn try_from_value(value: Id) -> Result<Self, anyhow::Error> {
+        let digit_count = digit_count(&value);
 
-        PRIMES
-            .iter()
-            .filter(|&&r| r <= digit_count)
-            .find_map(|&r| Self::try_from_value_and_repeats(value, r).ok())
+        repeat_counts(digit_count)
+            .find_map(|r| Self::try_from_value_and_repeats(value.clone(), r).ok())
             .ok_or_else(|| anyhow::anyhow!("Value {} is not a repeated pattern integer", value))
     }
 }
 
-impl TryFrom<u64> for RepeatedPatternInteger {
+/// Every repeated pattern integer within ``range``, in ascending order, built directly
+/// from their patterns rather than by checking every integer in ``range``.
+///
+/// For each digit count ``E``, and each number of repeats ``R`` dividing it, the values are
+/// exactly the patterns of ``E / R`` digits times the mask of ``R`` repeats; so only the
+/// patterns whose multiples fall within ``range`` are generated. A value made of several
+/// repeats, such as ``777777``, is reported with the fewest, as
+/// [`RepeatedPatternInteger::try_from_value`] would.
+fn repeated_pattern_integers(
+    range: RangeInclusive<Id>,
+) -> impl Iterator<Item = RepeatedPatternInteger> {
+    let mut found = BTreeMap::new();
+    let (zero, one) = (Id::from(0u8), Id::from(1u8));
+
+    for digit_count in digit_count(range.start())..=digit_count(range.end()) {
+        let lowest = power_of_ten(digit_count - 1).expect("Shorter than the end of the range");
+        let start = range.start().max(&lowest).clone();
+        let end = match power_of_ten(digit_count) {
+            Some(next) => range.end().min(&(next - &one)).clone(),
+            None => range.end().clone(),
+        };
+        if start > end {
+            continue;
+        }
+
+        for repeats in repeat_counts(digit_count) {
+            let pattern_length = digit_count / repeats;
+            let mask = generate_mask(pattern_length, repeats);
+            // The patterns of ``pattern_length`` digits whose multiples are within range.
+            let smallest_pattern =
+                power_of_ten(pattern_length - 1).expect("Shorter than the end of the range");
+            let largest_pattern =
+                power_of_ten(pattern_length).expect("Shorter than the end of the range") - &one;
+            let first = if &start % &mask == zero {
+                &start / &mask
+            } else {
+                &start / &mask + &one
+            }
+            .max(smallest_pattern);
+            let last = (&end / &mask).min(largest_pattern);
+
+            let mut pattern = first;
+            while pattern <= last {
+                let value = &pattern * &mask;
+                found
+                    .entry(value.clone())
+                    .or_insert_with(|| RepeatedPatternInteger {
+                        value,
+                        pattern: pattern.clone(),
+                        repeats,
+                    });
+                pattern += &one;
+            }
+        }
+    }
+
+    found.into_values()
+}
+
+impl TryFrom<Id> for RepeatedPatternInteger {
     type Error = anyhow::Error;
 
-    fn try_from(value: u64) -> Result<Self, Self::Error> {
+    fn try_from(value: Id) -> Result<Self, Self::Error> {
         Self::try_from_value(value)
     }
 }
 
+/// A repeated pattern integer, and the range of the input it was found in.
+#[cfg(not(feature = "sum-only"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FoundInteger {
+    pub rpi: RepeatedPatternInteger,
+    pub range: RangeInclusive<Id>,
+}
+
 struct RepeatedPatternIntegerCounter {
     #[cfg(not(feature = "sum-only"))]
-    pub found: Vec<RepeatedPatternInteger>,
+    pub found: Vec<FoundInteger>,
     #[cfg(feature = "sum-only")]
-    pub sum: u64,
+    pub sum: Id,
 }
 
 impl RepeatedPatternIntegerCounter {
@@ -166,40 +328,104 @@ impl RepeatedPatternIntegerCounter {
     }
     #[cfg(feature = "sum-only")]
     pub fn new() -> Self {
-        Self { sum: 0 }
+        Self { sum: Id::from(0u8) }
     }
 
-    pub fn search_iterable_and_add(&mut self, iterable: impl Iterator<Item = u64>) {
-        for item in iterable {
-            // Currently only supports R=2
-            RepeatedPatternInteger::try_from(item)
-                .map(|rpi| {
-                    #[cfg(feature = "sum-only")]
-                    {
-                        self.sum += rpi.value;
-                    }
-                    #[cfg(not(feature = "sum-only"))]
-                    {
-                        self.found.push(rpi);
-                    }
-                })
+    #[cfg_attr(feature = "sum-only", allow(unused_variables))]
+    pub fn add(&mut self, rpi: RepeatedPatternInteger, range: &RangeInclusive<Id>) {
+        #[cfg(feature = "sum-only")]
+        {
+            self.sum += rpi.value;
+        }
+        #[cfg(not(feature = "sum-only"))]
+        {
+            self.found.push(FoundInteger {
+                rpi,
+                range: range.clone(),
+            });
+        }
+    }
+
+    /// Combine the integers found by two counters, such as those of two threads.
+    #[cfg(all(any(test, feature = "rayon"), not(feature = "sum-only")))]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.found.extend(other.found);
+        self
+    }
+    #[cfg(all(any(test, feature = "rayon"), feature = "sum-only"))]
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            sum: self.sum + other.sum,
+        }
+    }
+
+    /// Add every repeated pattern integer in ``range``, generated directly from their
+    /// patterns; see [`repeated_pattern_integers`].
+    pub fn generate_range_and_add(&mut self, range: RangeInclusive<Id>) {
+        repeated_pattern_integers(range.clone()).for_each(|rpi| self.add(rpi, &range));
+    }
+
+    /// Check every integer of ``range`` in turn; only kept to test
+    /// [`generate_range_and_add`](Self::generate_range_and_add) against.
+    #[cfg(test)]
+    pub fn search_iterable_and_add(&mut self, range: RangeInclusive<Id>) {
+        let mut item = range.start().clone();
+        while &item <= range.end() {
+            RepeatedPatternInteger::try_from(item.clone())
+                .map(|rpi| self.add(rpi, &range))
                 .unwrap_or_default();
+            item += Id::from(1u8);
         }
     }
 
-    pub fn sum(&self) -> u64 {
+    pub fn sum(&self) -> Id {
         #[cfg(feature = "sum-only")]
         {
-            self.sum
+            self.sum.clone()
         }
 
         #[cfg(not(feature = "sum-only"))]
         {
-            self.found.iter().map(|rpi| rpi.value).sum()
+            self.found.iter().map(|item| &item.rpi.value).sum()
         }
     }
 }
 
+/// Add the repeated pattern integers of a single ``range`` to ``counter``.
+fn count_range(
+    mut counter: RepeatedPatternIntegerCounter,
+    range: RangeInclusive<Id>,
+) -> RepeatedPatternIntegerCounter {
+    #[cfg(feature = "profile-per-loop")]
+    let iteration_time = Instant::now();
+    counter.generate_range_and_add(range);
+    #[cfg(feature = "profile-per-loop")]
+    {
+        eprintln!("Time taken for iteration: {:?}", iteration_time.elapsed());
+    }
+    counter
+}
+
+/// Count the repeated pattern integers of every range, one after another.
+#[cfg(not(feature = "rayon"))]
+fn count_ranges(ranges: impl Iterator<Item = RangeInclusive<Id>>) -> RepeatedPatternIntegerCounter {
+    ranges.fold(RepeatedPatternIntegerCounter::new(), count_range)
+}
+
+/// Count the repeated pattern integers of every range in parallel, each thread into its
+/// own counter, which are then merged.
+#[cfg(feature = "rayon")]
+fn count_ranges(ranges: impl Iterator<Item = RangeInclusive<Id>>) -> RepeatedPatternIntegerCounter {
+    ranges
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .fold(RepeatedPatternIntegerCounter::new, count_range)
+        .reduce(
+            RepeatedPatternIntegerCounter::new,
+            RepeatedPatternIntegerCounter::merge,
+        )
+}
+
 fn main() {
     #[cfg(feature = "jemalloc")]
     {
@@ -210,23 +436,46 @@ fn main() {
         eprintln!("Using the default global allocator");
     }
 
+    let format = match output_format(std::env::args().skip(1)) {
+        Ok(format) => format,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+    #[cfg(feature = "sum-only")]
+    if let Some(format) = format {
+        eprintln!("--format {format} needs the invalid IDs, which the sum-only feature discards");
+        std::process::exit(1);
+    }
+
     #[cfg(feature = "profile")]
     let start_time = Instant::now();
 
-    let iterables = split_input_into_iterables(INPUT);
-
-    let mut counter = RepeatedPatternIntegerCounter::new();
-    for iterable in iterables {
-        #[cfg(feature = "profile-per-loop")]
-        let iteration_time = Instant::now();
-        counter.search_iterable_and_add(iterable);
-        #[cfg(feature = "profile-per-loop")]
-        {
-            eprintln!("Time taken for iteration: {:?}", iteration_time.elapsed());
+    let ranges = match split_input_into_iterables(INPUT).collect::<Result<Vec<_>, _>>() {
+        Ok(ranges) => ranges,
+        Err(error) => {
+            eprintln!("Failed to parse the input: {error}");
+            std::process::exit(1);
         }
-    }
+    };
+    #[cfg(feature = "merge-ranges")]
+    let ranges = merge_ranges(ranges);
+
+    let counter = count_ranges(ranges.into_iter());
 
     let sum = counter.sum();
+    #[cfg(not(feature = "sum-only"))]
+    if let Some(format) = format {
+        if let Err(error) = write_found(std::io::stdout().lock(), format, &counter.found) {
+            eprintln!("Failed to write the invalid IDs: {error}");
+            std::process::exit(1);
+        }
+        eprintln!("Sum of all repeated pattern integers: {}", sum);
+    } else {
+        println!("Sum of all repeated pattern integers: {}", sum);
+    }
+    #[cfg(feature = "sum-only")]
     println!("Sum of all repeated pattern integers: {}", sum);
 
     #[cfg(feature = "profile")]
@@ -235,7 +484,7 @@ fn main() {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "num-bigint")))]
 mod test_repeated_pattern_integer {
     use super::*;
 
@@ -300,28 +549,268 @@ mod test_repeated_pattern_integer {
     );
 }
 
+#[cfg(all(test, not(feature = "num-bigint")))]
+mod test_repeat_counts {
+    use super::*;
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(repeat_counts(1).collect::<Vec<_>>(), Vec::<usize>::new());
+        assert_eq!(repeat_counts(7).collect::<Vec<_>>(), vec![7]);
+        assert_eq!(repeat_counts(12).collect::<Vec<_>>(), vec![2, 3, 4, 6, 12]);
+        assert_eq!(repeat_counts(18).collect::<Vec<_>>(), vec![2, 3, 6, 9, 18]);
+    }
+
+    #[test]
+    fn test_composite_repeats() {
+        // 4-, 6- and 9-fold repeats, each also found as the shortest repeat that fits.
+        for (value, pattern, repeats, shortest) in [
+            (12121212, 12, 4, (2, 1212)),
+            (121212121212, 12, 6, (2, 121212)),
+            (123123123123123123, 123, 6, (2, 123123123)),
+            (121212121212121212, 12, 9, (3, 121212)),
+            (777777777, 7, 9, (3, 777)),
+        ] {
+            let rpi = RepeatedPatternInteger::try_from_value_and_repeats(value, repeats)
+                .expect("Expected Ok result");
+            assert_eq!((rpi.pattern, rpi.repeats), (pattern, repeats));
+
+            let rpi = RepeatedPatternInteger::try_from_value(value).expect("Expected Ok result");
+            assert_eq!((rpi.repeats, rpi.pattern), shortest);
+        }
+
+        // A 4-fold mask only matches a pattern repeated exactly 4 times.
+        assert!(RepeatedPatternInteger::try_from_value_and_repeats(12341234, 4).is_err());
+        assert!(RepeatedPatternInteger::try_from_value(1234567).is_err());
+    }
+}
+
 #[cfg(test)]
 mod test_repeated_pattern_integer_counter {
     use super::*;
 
-    const INPUT: &'static str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+    /// The sum of the example, as an [`Id`] with or without the ``num-bigint`` feature.
+    fn expected_sum() -> Id {
+        Id::from(4_174_379_265u64)
+    }
+
+    const INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+
+    fn ranges(input: &str) -> Vec<RangeInclusive<Id>> {
+        split_input_into_iterables(input)
+            .collect::<Result<_, _>>()
+            .expect("Invalid input")
+    }
 
     #[test]
     fn test_search_iterable_and_add() {
         let mut counter = RepeatedPatternIntegerCounter::new();
-        let iterables = split_input_into_iterables(INPUT);
-
-        for iterable in iterables {
+        for iterable in ranges(INPUT) {
             counter.search_iterable_and_add(iterable);
         }
 
         let sum = counter.sum();
 
-        assert_eq!(sum, 4174379265);
+        assert_eq!(sum, expected_sum());
+    }
+
+    #[test]
+    fn test_generate_range_and_add() {
+        let mut counter = RepeatedPatternIntegerCounter::new();
+        for range in ranges(INPUT) {
+            counter.generate_range_and_add(range);
+        }
+
+        assert_eq!(counter.sum(), expected_sum());
+    }
+
+    #[test]
+    fn test_count_ranges() {
+        let counter = count_ranges(ranges(INPUT).into_iter());
+        assert_eq!(counter.sum(), expected_sum());
+
+        let counter = RepeatedPatternIntegerCounter::new().merge(counter);
+        assert_eq!(counter.sum(), expected_sum());
+        #[cfg(not(feature = "sum-only"))]
+        {
+            let mut values = counter
+                .found
+                .iter()
+                .map(|item| item.rpi.value.clone())
+                .collect::<Vec<_>>();
+            values.sort_unstable();
+            assert_eq!(values.first(), Some(&Id::from(11u8)));
+            assert_eq!(values.len(), 13);
+        }
+    }
+
+    #[test]
+    fn test_merge_parsed_ranges() {
+        assert_eq!(
+            merge_ranges(ranges("95-115,11-22,20-30,31-40,100-105,998-1012")),
+            ranges("11-40,95-115,998-1012")
+        );
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "num-bigint")))]
+mod test_split_input_into_iterables {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            split_input_into_iterables("11-22,95-115\n")
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Invalid input"),
+            vec![11..=22, 95..=115]
+        );
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        for (input, token) in [
+            ("11-22,95", "\"95\""),
+            ("11-22,9x-115", "\"9x-115\""),
+            ("11-22,-115", "\"-115\""),
+            ("22-11", "\"22-11\""),
+            ("11-22,,95-115", "\"\""),
+        ] {
+            let error = split_input_into_iterables(input)
+                .collect::<Result<Vec<_>, _>>()
+                .expect_err("Parsed an invalid input");
+            assert!(error.to_string().contains(token), "{input:?}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        assert_eq!(
+            merge_ranges(vec![
+                95..=115,
+                11..=22,
+                20..=30,
+                31..=40,
+                100..=105,
+                998..=1012
+            ]),
+            vec![11..=40, 95..=115, 998..=1012]
+        );
+        assert_eq!(merge_ranges(vec![0..=Id::MAX, 5..=6]), vec![0..=Id::MAX]);
+        assert_eq!(merge_ranges(vec![]), vec![]);
+
+        // Overlapping ranges would otherwise count the same IDs twice.
+        let overlapping = vec![11..=22, 11..=1012, 95..=115];
+        assert_eq!(
+            count_ranges(merge_ranges(overlapping.clone()).into_iter()).sum(),
+            count_ranges([11..=1012].into_iter()).sum()
+        );
+        assert!(
+            count_ranges(overlapping.into_iter()).sum()
+                > count_ranges([11..=1012].into_iter()).sum()
+        );
+    }
+}
+
+#[cfg(all(test, not(feature = "num-bigint")))]
+mod test_repeated_pattern_integers {
+    use super::*;
+
+    fn scan(range: RangeInclusive<Id>) -> Vec<RepeatedPatternInteger> {
+        range
+            .filter_map(|value| RepeatedPatternInteger::try_from(value).ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_scanning() {
+        for range in [
+            1..=20_000,
+            95..=115,
+            998..=1012,
+            222_220..=222_224,
+            565_653..=565_659,
+            1_188_511_880..=1_188_511_890,
+            12_341_234..=12_351_235,
+        ] {
+            assert_eq!(
+                repeated_pattern_integers(range.clone()).collect::<Vec<_>>(),
+                scan(range.clone()),
+                "{range:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_and_extreme_ranges() {
+        assert_eq!(repeated_pattern_integers(13..=20).count(), 0);
+        assert_eq!(repeated_pattern_integers(5..=5).count(), 0);
+        assert_eq!(
+            repeated_pattern_integers(0..=11)
+                .map(|rpi| rpi.value)
+                .collect::<Vec<_>>(),
+            vec![11]
+        );
+
+        // The largest values of a ``u64``, which are no longer a limit.
+        let largest = repeated_pattern_integers(18_446_744_000_000_000_000..=u64::MAX as Id)
+            .map(|rpi| (rpi.pattern, rpi.repeats))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            largest,
+            (1_844_674_400..=1_844_674_407)
+                .map(|pattern| (pattern, 2))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            repeated_pattern_integers(18_441_844_184_418_441_844..=18_441_844_184_418_441_844)
+                .map(|rpi| (rpi.pattern, rpi.repeats))
+                .collect::<Vec<_>>(),
+            vec![(1844, 5)]
+        );
+
+        // The largest values, where the next power of ten does not fit.
+        assert_eq!(
+            repeated_pattern_integers(
+                340_282_366_920_930_000_000_000_000_000_000_000_000..=Id::MAX
+            )
+            .map(|rpi| (rpi.pattern, rpi.repeats))
+            .collect::<Vec<_>>(),
+            vec![(3_402_823_669_209, 3)]
+        );
+    }
+}
+
+#[cfg(all(test, not(feature = "num-bigint")))]
+mod test_digit_count {
+    use super::*;
+
+    #[test]
+    fn test_digit_count() {
+        assert_eq!(digit_count(&0), 1);
+        assert_eq!(digit_count(&9), 1);
+        assert_eq!(digit_count(&10), 2);
+        assert_eq!(digit_count(&999_999_999), 9);
+        assert_eq!(digit_count(&1_000_000_000), 10);
+        assert_eq!(digit_count(&(u64::MAX as Id)), 20);
+        assert_eq!(digit_count(&Id::MAX), 39);
+    }
+
+    #[test]
+    fn test_near_powers_of_ten() {
+        // Each of these rounds up to the next power of ten as an ``f32``.
+        for (value, pattern, repeats) in [
+            (999_999_999, 999, 3),
+            (9_999_999_999, 99_999, 2),
+            (99_999_999_999_999_999, 9, 17),
+        ] {
+            let rpi = RepeatedPatternInteger::try_from(value).expect("Not a repeated pattern");
+            assert_eq!((rpi.pattern, rpi.repeats), (pattern, repeats), "{value}");
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "num-bigint")))]
 mod test_generate_mask {
     use super::*;
 
@@ -340,3 +829,33 @@ mod test_generate_mask {
     create_test!(test_mask_2x3(pattern_length = 2, repeats = 3) = 10101);
     create_test!(test_mask_1x5(pattern_length = 1, repeats = 5) = 11111);
 }
+
+#[cfg(all(test, feature = "num-bigint"))]
+mod test_num_bigint {
+    use super::*;
+
+    fn id(digits: &str) -> Id {
+        digits.parse().expect("Not an ID")
+    }
+
+    #[test]
+    fn test_past_u128() {
+        // 40 and 60 digits, longer than any ``u128``.
+        let pattern = "12345678901234567891";
+        for repeats in [2, 3] {
+            let value = id(&pattern.repeat(repeats));
+            assert_eq!(digit_count(&value), 20 * repeats);
+
+            let rpi =
+                RepeatedPatternInteger::try_from(value.clone()).expect("Not a repeated pattern");
+            assert_eq!((rpi.pattern, rpi.repeats), (id(pattern), repeats));
+
+            assert_eq!(
+                repeated_pattern_integers(value.clone()..=value.clone() + Id::from(1u8))
+                    .map(|rpi| rpi.value)
+                    .collect::<Vec<_>>(),
+                vec![value]
+            );
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Structured output of the invalid IDs found, for post-processing elsewhere.
+
+use std::{fmt, str::FromStr};
+
+#[cfg(not(feature = "sum-only"))]
+use super::FoundInteger;
+#[cfg(not(feature = "sum-only"))]
+use std::io;
+
+/// The format to write each invalid ID in, chosen with ``--format``.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A JSON array of objects, one per line.
+    Json,
+    /// A header row, then one row per ID.
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(anyhow::anyhow!(
+                "Unknown output format {:?}; expected json or csv",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// The format given by ``--format <format>`` or ``--format=<format>`` in ``args``, if any.
+pub fn output_format(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<OutputFormat>, anyhow::Error> {
+    let Some(arg) = args.next() else {
+        return Ok(None);
+    };
+
+    let format = if arg == "--format" {
+        args.next()
+            .ok_or_else(|| anyhow::anyhow!("--format needs a value; expected json or csv"))?
+    } else if let Some(format) = arg.strip_prefix("--format=") {
+        format.to_owned()
+    } else {
+        return Err(anyhow::anyhow!("Unexpected argument {:?}", arg));
+    };
+
+    match args.next() {
+        Some(arg) => Err(anyhow::anyhow!("Unexpected argument {:?}", arg)),
+        None => format.parse().map(Some),
+    }
+}
+
+/// Write each of ``found`` to ``writer`` in ``format``, with its pattern, repeat count and
+/// the range it was found in.
+#[cfg(not(feature = "sum-only"))]
+pub fn write_found(
+    mut writer: impl io::Write,
+    format: OutputFormat,
+    found: &[FoundInteger],
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            writeln!(writer, "[")?;
+            for (index, item) in found.iter().enumerate() {
+                let separator = if index + 1 < found.len() { "," } else { "" };
+                writeln!(
+                    writer,
+                    "  {{\"value\": {}, \"pattern\": {}, \"repeats\": {}, \"range_start\": {}, \"range_end\": {}}}{}",
+                    item.rpi.value,
+                    item.rpi.pattern,
+                    item.rpi.repeats,
+                    item.range.start(),
+                    item.range.end(),
+                    separator
+                )?;
+            }
+            writeln!(writer, "]")
+        }
+        OutputFormat::Csv => {
+            writeln!(writer, "value,pattern,repeats,range_start,range_end")?;
+            for item in found {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{}",
+                    item.rpi.value,
+                    item.rpi.pattern,
+                    item.rpi.repeats,
+                    item.range.start(),
+                    item.range.end()
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_output_format {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(output_format(args(&[])).unwrap(), None);
+        assert_eq!(
+            output_format(args(&["--format", "json"])).unwrap(),
+            Some(OutputFormat::Json)
+        );
+        assert_eq!(
+            output_format(args(&["--format=CSV"])).unwrap(),
+            Some(OutputFormat::Csv)
+        );
+        assert!(output_format(args(&["--format", "xml"])).is_err());
+        assert!(output_format(args(&["--format"])).is_err());
+        assert!(output_format(args(&["json"])).is_err());
+        assert!(output_format(args(&["--format", "csv", "json"])).is_err());
+    }
+}
+
+#[cfg(all(test, not(feature = "sum-only")))]
+mod test_write_found {
+    use super::*;
+    use crate::{RepeatedPatternIntegerCounter, split_input_into_iterables};
+
+    fn write(format: OutputFormat) -> String {
+        let mut counter = RepeatedPatternIntegerCounter::new();
+        for range in split_input_into_iterables("11-22,998-1012") {
+            counter.generate_range_and_add(range.expect("Invalid input"));
+        }
+
+        let mut buffer = Vec::new();
+        write_found(&mut buffer, format, &counter.found).expect("Failed to write");
+        String::from_utf8(buffer).expect("Invalid UTF-8")
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            write(OutputFormat::Csv),
+            "value,pattern,repeats,range_start,range_end\n\
+             11,1,2,11,22\n\
+             22,2,2,11,22\n\
+             999,9,3,998,1012\n\
+             1010,10,2,998,1012\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            write(OutputFormat::Json),
+            "[\n\
+             \x20 {\"value\": 11, \"pattern\": 1, \"repeats\": 2, \"range_start\": 11, \"range_end\": 22},\n\
+             \x20 {\"value\": 22, \"pattern\": 2, \"repeats\": 2, \"range_start\": 11, \"range_end\": 22},\n\
+             \x20 {\"value\": 999, \"pattern\": 9, \"repeats\": 3, \"range_start\": 998, \"range_end\": 1012},\n\
+             \x20 {\"value\": 1010, \"pattern\": 10, \"repeats\": 2, \"range_start\": 998, \"range_end\": 1012}\n\
+             ]\n"
+        );
+    }
+}
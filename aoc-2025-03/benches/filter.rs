@@ -0,0 +1,72 @@
+//! Benchmarks of the monotonic stack in [`filter_highest_by`] against the removal it
+//! replaced, [`filter_highest_by_removal`].
+//!
+//! The ``worst`` banks are a run of ``9``s followed by rising digits, keeping half of
+//! them: every removal is then from the middle of the bank, so the removal shifts half of
+//! it each time, while the stack still visits each digit once.
+//!
+//! Run with ``cargo bench -p aoc-2025-03 --features legacy-filter``.
+
+use aoc_2025_03::{filter_highest_by, filter_highest_by_removal};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::{collections::VecDeque, hint::black_box};
+
+/// ``len`` pseudo-random digits, from a linear congruential generator.
+fn random(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u64;
+    (0..len)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1);
+            ((state >> 33) % 10) as u8
+        })
+        .collect()
+}
+
+/// ``len / 2`` nines followed by ``0`` to ``9`` over and over.
+fn worst(len: usize) -> Vec<u8> {
+    (0..len)
+        .map(|index| {
+            if index < len / 2 {
+                9
+            } else {
+                (index % 10) as u8
+            }
+        })
+        .collect()
+}
+
+fn bench_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("filter");
+    group.sample_size(10);
+
+    for (name, bank, keep) in [
+        ("random/100", random(100), 12),
+        ("random/100000", random(100_000), 12),
+        ("worst/10000", worst(10_000), 5_000),
+        ("worst/100000", worst(100_000), 50_000),
+    ] {
+        let sequence = bank.iter().copied().collect::<VecDeque<_>>();
+
+        group.bench_function(BenchmarkId::new("stack", name), |b| {
+            b.iter(|| {
+                let mut sequence = sequence.clone();
+                filter_highest_by(black_box(&mut sequence), keep, |&digit| digit);
+                sequence
+            })
+        });
+        group.bench_function(BenchmarkId::new("removal", name), |b| {
+            b.iter(|| {
+                let mut sequence = sequence.clone();
+                filter_highest_by_removal(black_box(&mut sequence), keep, |&digit| digit);
+                sequence
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_filter);
+criterion_main!(benches);
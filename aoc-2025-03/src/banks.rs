@@ -0,0 +1,157 @@
+//! Parsing banks of batteries, and choosing a fixed number of them.
+
+use crate::{JoltageError, filter_highest_by};
+use std::{collections::VecDeque, io::BufRead};
+
+/// The digits of ``s``.
+///
+/// Panics if ``s`` contains anything but digits; see [`parse_line`] to handle them instead.
+pub fn build_vec_deque_from_string(s: &str) -> VecDeque<u8> {
+    s.chars()
+        .map(|c| {
+            c.to_digit(10)
+                .unwrap_or_else(|| panic!("Invalid battery {c:?}; expected a digit"))
+                as u8
+        })
+        .collect()
+}
+
+/// Parse the whitespace separated banks on ``line`` into their digits; ``line_number`` is
+/// only used to report errors, counting from 1.
+///
+/// Columns are counted in characters from 1, at the offending battery.
+pub fn parse_line(
+    line: &str,
+    line_number: usize,
+) -> impl Iterator<Item = Result<VecDeque<u8>, JoltageError>> + '_ {
+    line.split_whitespace().map(move |bank| {
+        let offset = bank.as_ptr() as usize - line.as_ptr() as usize;
+        let column = line[..offset].chars().count() + 1;
+
+        bank.chars()
+            .enumerate()
+            .map(|(index, battery)| {
+                battery
+                    .to_digit(10)
+                    .map(|digit| digit as u8)
+                    .ok_or(JoltageError::InvalidBattery {
+                        line: line_number,
+                        column: column + index,
+                        battery,
+                    })
+            })
+            .collect()
+    })
+}
+
+/// Read every bank from ``reader``, line by line, as it is needed; see [`parse_line`].
+pub fn read_banks(
+    reader: impl BufRead,
+) -> impl Iterator<Item = Result<VecDeque<u8>, JoltageError>> {
+    reader
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| match line {
+            Ok(line) => parse_line(&line, index + 1).collect::<Vec<_>>(),
+            Err(error) => vec![Err(JoltageError::from(error))],
+        })
+}
+
+pub trait HighestSequentialCombination {
+    fn filter_to_highest_sequential_combination<const N: usize>(&mut self);
+    fn build_int(&self) -> u64;
+}
+
+impl HighestSequentialCombination for VecDeque<u8> {
+    fn filter_to_highest_sequential_combination<const N: usize>(&mut self) {
+        filter_highest_by(self, N, |&digit| digit);
+    }
+
+    fn build_int(&self) -> u64 {
+        self.iter()
+            .fold(0u64, |acc, &digit| acc * 10u64 + digit as u64)
+    }
+}
+
+#[cfg(test)]
+mod test_highest_sequential_combination {
+    use super::*;
+
+    macro_rules! create_test {
+        ($name:ident::<$size:literal>($values:expr) = $expected:expr) => {
+            #[test]
+            fn $name() {
+                let mut vec_deque: VecDeque<u8> = build_vec_deque_from_string($values);
+                vec_deque.filter_to_highest_sequential_combination::<$size>();
+                let result: Vec<u8> = vec_deque.into();
+                assert_eq!(result, $expected);
+            }
+        };
+    }
+
+    create_test! {
+        test1::<3>("987654321111111") = vec![9, 8, 7]
+    }
+    create_test! {
+        test2::<2>("123456789") = vec![8, 9]
+    }
+    create_test! {
+        test3::<4>("543216789") = vec![6, 7, 8, 9]
+    }
+    create_test! {test4::<5>("1111122222333334444455555") = vec![5, 5, 5, 5, 5]}
+    create_test! {test5::<1>("987654321") = vec![9]}
+    create_test! {
+        test6::<2>("811111111111119") = vec![8, 9]
+    }
+}
+
+#[cfg(test)]
+mod test_build_int {
+    use super::*;
+
+    #[test]
+    fn test_build_int() {
+        let vec_deque: VecDeque<u8> = build_vec_deque_from_string("123456789");
+        let result = vec_deque.build_int();
+        assert_eq!(result, 123456789u64);
+    }
+}
+
+#[cfg(test)]
+mod test_read_banks {
+    use super::*;
+
+    #[test]
+    fn test_valid() {
+        let banks = read_banks("123 45\n\n  678\n".as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to read banks");
+        assert_eq!(
+            banks,
+            vec![
+                VecDeque::from([1, 2, 3]),
+                VecDeque::from([4, 5]),
+                VecDeque::from([6, 7, 8]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = read_banks("123\n45 6x8".as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .expect_err("Read an invalid bank");
+        assert!(matches!(
+            error,
+            JoltageError::InvalidBattery {
+                line: 2,
+                column: 5,
+                battery: 'x',
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "invalid battery 'x' at line 2, column 5; expected a digit"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Turning the digits of a joltage into a number, however many there are.
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+
+/// The number made by ``digits``, or [`None`] if it does not fit in a ``u128``, i.e. if
+/// it is more than ``38`` digits long, or ``39`` with a low enough first digit.
+pub fn digits_to_u128(digits: &[u8]) -> Option<u128> {
+    digits.iter().try_fold(0u128, |acc, &digit| {
+        acc.checked_mul(10)?.checked_add(digit as u128)
+    })
+}
+
+/// The number made by ``digits``, as a string of any length.
+pub fn digits_to_string(digits: &[u8]) -> String {
+    digits
+        .iter()
+        .map(|&digit| char::from(b'0' + digit))
+        .collect()
+}
+
+/// The number made by ``digits``, of any length.
+#[cfg(feature = "num-bigint")]
+pub fn digits_to_biguint(digits: &[u8]) -> BigUint {
+    BigUint::from_radix_be(digits, 10).expect("Unreachable; every digit is below 10")
+}
+
+/// The sum of the numbers made by each of ``numbers``, as a string of any length, added
+/// up column by column as on paper.
+pub fn sum_digits<'a>(numbers: impl IntoIterator<Item = &'a [u8]>) -> String {
+    // The columns of the sum, least significant first, each of which may carry over.
+    let mut columns: Vec<u64> = Vec::new();
+    for digits in numbers {
+        if columns.len() < digits.len() {
+            columns.resize(digits.len(), 0);
+        }
+        for (column, &digit) in columns.iter_mut().zip(digits.iter().rev()) {
+            *column += digit as u64;
+        }
+    }
+
+    let mut carry = 0;
+    let mut sum = Vec::with_capacity(columns.len() + 1);
+    for column in columns {
+        let total = column + carry;
+        sum.push((total % 10) as u8);
+        carry = total / 10;
+    }
+    while carry > 0 {
+        sum.push((carry % 10) as u8);
+        carry /= 10;
+    }
+    while sum.len() > 1 && sum.last() == Some(&0) {
+        sum.pop();
+    }
+    if sum.is_empty() {
+        sum.push(0);
+    }
+
+    sum.reverse();
+    digits_to_string(&sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits_to_u128() {
+        assert_eq!(digits_to_u128(&[]), Some(0));
+        assert_eq!(digits_to_u128(&[0, 4, 2]), Some(42));
+        assert_eq!(digits_to_u128(&[9; 38]), Some(10u128.pow(38) - 1));
+        assert_eq!(
+            digits_to_u128(&[1; 39]),
+            Some((0..39).map(|power| 10u128.pow(power)).sum())
+        );
+        assert_eq!(digits_to_u128(&[9; 39]), None);
+    }
+
+    #[test]
+    fn test_sum_digits() {
+        assert_eq!(sum_digits([]), "0");
+        assert_eq!(sum_digits([&[0, 0][..]]), "0");
+        assert_eq!(sum_digits([&[9, 8][..], &[1, 2][..], &[5][..]]), "115");
+
+        // Beyond the range of a ``u128``.
+        let nines = [9; 50];
+        assert_eq!(
+            sum_digits([&nines[..], &nines[..]]),
+            format!("1{}8", "9".repeat(49))
+        );
+
+        let numbers = [[3, 1, 4, 1, 5, 9], [2, 6, 5, 3, 5, 8], [9, 7, 9, 3, 2, 3]];
+        assert_eq!(
+            sum_digits(numbers.iter().map(|digits| &digits[..])),
+            numbers
+                .iter()
+                .map(|digits| digits_to_u128(digits).expect("Too long"))
+                .sum::<u128>()
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_digits_to_biguint() {
+        let digits = [7; 60];
+        assert_eq!(
+            digits_to_biguint(&digits).to_string(),
+            digits_to_string(&digits)
+        );
+        assert_eq!(digits_to_biguint(&[]), BigUint::default());
+    }
+}
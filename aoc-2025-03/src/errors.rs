@@ -0,0 +1,15 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum JoltageError {
+    #[error("invalid battery {battery:?} at line {line}, column {column}; expected a digit")]
+    InvalidBattery {
+        line: usize,
+        column: usize,
+        battery: char,
+    },
+
+    #[error("failed to read banks: {0}")]
+    Io(#[from] io::Error),
+}
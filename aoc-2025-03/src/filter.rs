@@ -0,0 +1,100 @@
+//! Choosing which digits of a sequence to keep, so that they make the highest number.
+
+use std::collections::VecDeque;
+
+/// Discard elements of ``sequence`` until at most ``keep`` remain, leaving the ones whose
+/// ``digit``s, in order, make the highest number.
+///
+/// This is a single pass over a monotonic stack: each element pops the smaller ones
+/// before it, for as long as there are still elements to spare, i.e. up to
+/// ``len - keep`` in all. Whatever is left over at the end is cut off.
+pub fn filter_highest_by<T>(sequence: &mut VecDeque<T>, keep: usize, digit: impl Fn(&T) -> u8) {
+    let mut budget = sequence.len().saturating_sub(keep);
+    let mut stack = Vec::with_capacity(sequence.len());
+
+    for item in sequence.drain(..) {
+        while budget > 0 && stack.last().is_some_and(|last| digit(last) < digit(&item)) {
+            stack.pop();
+            budget -= 1;
+        }
+        stack.push(item);
+    }
+
+    stack.truncate(keep);
+    *sequence = stack.into();
+}
+
+/// The same as [`filter_highest_by`], by sliding a window of two along ``sequence`` and
+/// removing the left of the two whenever it is smaller, then backtracking; see the
+/// README.
+///
+/// Each removal shifts the rest of ``sequence``, so this is quadratic in the worst case.
+/// It is only kept to test and benchmark [`filter_highest_by`] against.
+#[cfg(feature = "legacy-filter")]
+pub fn filter_highest_by_removal<T>(
+    sequence: &mut VecDeque<T>,
+    keep: usize,
+    digit: impl Fn(&T) -> u8,
+) {
+    let mut pos: usize = 0;
+    loop {
+        if pos + 1 >= sequence.len() || sequence.len() <= keep {
+            break;
+        }
+        if digit(&sequence[pos]) < digit(&sequence[pos + 1]) {
+            sequence.remove(pos);
+            pos = pos.saturating_sub(1);
+        } else {
+            pos += 1;
+        }
+    }
+
+    sequence.truncate(keep);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(digits: &str, keep: usize) -> Vec<u8> {
+        let mut sequence = digits.bytes().map(|c| c - b'0').collect::<VecDeque<_>>();
+        filter_highest_by(&mut sequence, keep, |&digit| digit);
+        sequence.into()
+    }
+
+    #[test]
+    fn test_filter_highest_by() {
+        assert_eq!(filter("987654321111111", 3), vec![9, 8, 7]);
+        assert_eq!(filter("123456789", 2), vec![8, 9]);
+        assert_eq!(
+            filter("818181911112111", 12),
+            vec![8, 8, 8, 9, 1, 1, 1, 1, 2, 1, 1, 1]
+        );
+        assert_eq!(filter("12", 3), vec![1, 2]);
+        assert_eq!(filter("", 2), vec![]);
+        assert_eq!(filter("5", 0), vec![]);
+    }
+
+    /// Compare against the removal on every bank of up to ``6`` digits from ``0`` to ``3``,
+    /// keeping every possible number of them.
+    #[cfg(feature = "legacy-filter")]
+    #[test]
+    fn test_matches_removal() {
+        for len in 0..=6u32 {
+            for code in 0..4usize.pow(len) {
+                let bank = (0..len)
+                    .map(|position| (code / 4usize.pow(position) % 4) as u8)
+                    .collect::<Vec<_>>();
+
+                for keep in 0..=len as usize + 1 {
+                    let mut stack = (0..bank.len()).collect::<VecDeque<_>>();
+                    filter_highest_by(&mut stack, keep, |&index| bank[index]);
+                    let mut removal = (0..bank.len()).collect::<VecDeque<_>>();
+                    filter_highest_by_removal(&mut removal, keep, |&index| bank[index]);
+
+                    assert_eq!(stack, removal, "{bank:?} keeping {keep}");
+                }
+            }
+        }
+    }
+}
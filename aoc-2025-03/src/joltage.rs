@@ -0,0 +1,224 @@
+//! The joltage of the batteries chosen from each bank.
+
+#[cfg(feature = "num-bigint")]
+use crate::digits_to_biguint;
+use crate::{
+    HighestSequentialCombination, JoltageError, build_vec_deque_from_string, digits_to_string,
+    digits_to_u128, filter_highest_by, read_banks, sum_digits,
+};
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigUint;
+use std::{collections::VecDeque, io::BufRead};
+
+/// The batteries turned on in a single bank.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankJoltage {
+    /// The positions of the chosen batteries within the bank, in ascending order.
+    pub indices: Vec<usize>,
+    /// The joltage of each chosen battery, in the same order.
+    pub digits: Vec<u8>,
+}
+
+impl BankJoltage {
+    /// Choose the ``keep`` batteries of ``bank``, given as its digits, that make the
+    /// highest joltage, or every battery if it has no more than ``keep``.
+    pub fn from_digits(bank: &VecDeque<u8>, keep: usize) -> Self {
+        let mut indices = (0..bank.len()).collect::<VecDeque<_>>();
+        filter_highest_by(&mut indices, keep, |&index| bank[index]);
+
+        Self {
+            digits: indices.iter().map(|&index| bank[index]).collect(),
+            indices: indices.into(),
+        }
+    }
+
+    /// The joltage the chosen batteries make together, or [`None`] if there are too many
+    /// of them for a ``u128``; see [`joltage_string`](Self::joltage_string).
+    pub fn joltage(&self) -> Option<u128> {
+        digits_to_u128(&self.digits)
+    }
+
+    /// The joltage the chosen batteries make together, however many there are.
+    pub fn joltage_string(&self) -> String {
+        digits_to_string(&self.digits)
+    }
+
+    /// The joltage the chosen batteries make together, however many there are.
+    #[cfg(feature = "num-bigint")]
+    pub fn joltage_biguint(&self) -> BigUint {
+        digits_to_biguint(&self.digits)
+    }
+}
+
+/// Choose the ``keep`` batteries of ``bank`` that make the highest joltage, or every
+/// battery if it has no more than ``keep``.
+///
+/// Panics if ``bank`` contains anything but digits; see [`BankJoltage::from_digits`]
+/// and [`read_banks`] to handle them instead.
+pub fn max_joltage(bank: &str, keep: usize) -> BankJoltage {
+    BankJoltage::from_digits(&build_vec_deque_from_string(bank), keep)
+}
+
+/// The batteries turned on in every bank, one per line of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TotalJoltage {
+    pub banks: Vec<BankJoltage>,
+}
+
+impl TotalJoltage {
+    /// Choose the ``keep`` batteries of each bank read from ``reader``; see
+    /// [`read_banks`].
+    pub fn from_reader(reader: impl BufRead, keep: usize) -> Result<Self, JoltageError> {
+        Ok(Self {
+            banks: read_banks(reader)
+                .map(|bank| bank.map(|bank| BankJoltage::from_digits(&bank, keep)))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The sum of the joltages of every bank, or [`None`] if it does not fit in a
+    /// ``u128``; see [`total_string`](Self::total_string).
+    pub fn total(&self) -> Option<u128> {
+        self.banks
+            .iter()
+            .try_fold(0u128, |acc, bank| acc.checked_add(bank.joltage()?))
+    }
+
+    /// The sum of the joltages of every bank, however large.
+    pub fn total_string(&self) -> String {
+        sum_digits(self.banks.iter().map(|bank| bank.digits.as_slice()))
+    }
+
+    /// The sum of the joltages of every bank, however large.
+    #[cfg(feature = "num-bigint")]
+    pub fn total_biguint(&self) -> BigUint {
+        self.banks.iter().map(BankJoltage::joltage_biguint).sum()
+    }
+}
+
+/// Choose the ``keep`` batteries of each bank in ``input``; see [`max_joltage`].
+///
+/// Panics if any bank contains anything but digits; see [`TotalJoltage::from_reader`] to
+/// handle them instead.
+pub fn total_joltage(input: &str, keep: usize) -> TotalJoltage {
+    TotalJoltage::from_reader(input.as_bytes(), keep).unwrap_or_else(|error| panic!("{error}"))
+}
+
+/// The answers to both parts of the puzzle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Answers {
+    /// The total joltage keeping ``2`` batteries of each bank.
+    pub part_1: u64,
+    /// The total joltage keeping ``12`` batteries of each bank.
+    pub part_2: u64,
+}
+
+/// Solve both parts in a single pass over the banks read from ``reader``, so that it can
+/// be a stream that is only read once.
+pub fn solve(reader: impl BufRead) -> Result<Answers, JoltageError> {
+    read_banks(reader).try_fold(Answers::default(), |answers, bank| {
+        let mut two = bank?;
+        let mut twelve = two.clone();
+        two.filter_to_highest_sequential_combination::<2>();
+        twelve.filter_to_highest_sequential_combination::<12>();
+
+        Ok(Answers {
+            part_1: answers.part_1 + two.build_int(),
+            part_2: answers.part_2 + twelve.build_int(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "987654321111111\n811111111111119\n234234234234278\n818181911112111";
+
+    #[test]
+    fn test_indices() {
+        let bank = max_joltage("811111111111119", 2);
+        assert_eq!(bank.indices, vec![0, 14]);
+        assert_eq!(bank.digits, vec![8, 9]);
+        assert_eq!(bank.joltage(), Some(89));
+
+        let bank = max_joltage("234234234234278", 12);
+        assert_eq!(bank.indices, vec![2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(bank.joltage(), Some(434234234278));
+
+        // A bank with too few batteries keeps them all.
+        assert_eq!(max_joltage("12", 3).indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_matches_const_generic() {
+        for line in EXAMPLE.lines() {
+            let mut vec_deque = build_vec_deque_from_string(line);
+            vec_deque.filter_to_highest_sequential_combination::<12>();
+            assert_eq!(
+                max_joltage(line, 12).joltage(),
+                Some(vec_deque.build_int() as u128)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid battery 'x'")]
+    fn test_max_joltage_of_non_digits() {
+        max_joltage("81x9", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid battery 'x' at line 2, column 3")]
+    fn test_total_joltage_of_non_digits() {
+        total_joltage("811\n81x9", 2);
+    }
+
+    #[test]
+    fn test_total_joltage() {
+        assert_eq!(total_joltage(EXAMPLE, 2).total(), Some(357));
+        assert_eq!(total_joltage(EXAMPLE, 12).total(), Some(3121910778619));
+        assert_eq!(total_joltage(EXAMPLE, 12).total_string(), "3121910778619");
+        assert_eq!(total_joltage(EXAMPLE, 12).banks.len(), 4);
+
+        assert!(matches!(
+            TotalJoltage::from_reader("12\n3a".as_bytes(), 1),
+            Err(JoltageError::InvalidBattery { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            solve(EXAMPLE.as_bytes()).expect("Failed to solve"),
+            Answers {
+                part_1: 357,
+                part_2: 3121910778619,
+            }
+        );
+    }
+
+    #[test]
+    fn test_longer_than_u128() {
+        let input = ["9876543210", "0123456789", "5555566666"]
+            .map(|digits| digits.repeat(10))
+            .join("\n");
+
+        let result = total_joltage(&input, 50);
+        assert_eq!(result.total(), None);
+        assert_eq!(result.banks[0].joltage(), None);
+        assert_eq!(result.banks[0].joltage_string().len(), 50);
+
+        let total = result.total_string();
+        assert_eq!(total.len(), 51);
+        #[cfg(feature = "num-bigint")]
+        assert_eq!(result.total_biguint().to_string(), total);
+
+        // Below the limit, the two agree.
+        let result = total_joltage(&input, 30);
+        assert_eq!(
+            result.total().map(|total| total.to_string()),
+            Some(result.total_string())
+        );
+    }
+}
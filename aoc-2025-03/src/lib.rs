@@ -0,0 +1,17 @@
+//! Choosing the batteries of each bank that make the highest joltage, from Day 3 of 2025,
+//! as a library so that it can be tested and reused outside of the puzzle.
+
+mod errors;
+pub use errors::*;
+
+mod filter;
+pub use filter::*;
+
+mod digits;
+pub use digits::*;
+
+mod banks;
+pub use banks::*;
+
+mod joltage;
+pub use joltage::*;
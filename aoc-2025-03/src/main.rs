@@ -1,174 +1,248 @@
 //! ## Day 3: Lobby
-//! 
+//!
 //! You descend a short staircase, enter the surprisingly vast lobby, and are quickly cleared by the security checkpoint. When you get to the main elevators, however, you discover that each one has a red light above it: they're all offline.
-//! 
+//!
 //! "Sorry about that," an Elf apologizes as she tinkers with a nearby control panel. "Some kind of electrical surge seems to have fried them. I'll try to get them online soon."
-//! 
+//!
 //! You explain your need to get further underground. "Well, you could at least take the escalator down to the printing department, not that you'd get much further than that without the elevators working. That is, you could if the escalator weren't also offline."
-//! 
+//!
 //! "But, don't worry! It's not fried; it just needs power. Maybe you can get it running while I keep working on the elevators."
-//! 
+//!
 //! There are batteries nearby that can supply emergency power to the escalator for just such an occasion. The batteries are each labeled with their joltage rating, a value from 1 to 9. You make a note of their joltage ratings (your puzzle input). For example:
-//! 
+//!
 //! ```text
 //! 987654321111111
 //! 811111111111119
 //! 234234234234278
 //! 818181911112111
 //! ```
-//! 
+//!
 //! The batteries are arranged into banks; each line of digits in your input corresponds to a single bank of batteries. Within each bank, you need to turn on exactly two batteries; the joltage that the bank produces is equal to the number formed by the digits on the batteries you've turned on. For example, if you have a bank like 12345 and you turn on batteries 2 and 4, the bank would produce 24 jolts. (You cannot rearrange batteries.)
-//! 
+//!
 //! You'll need to find the largest possible joltage each bank can produce. In the above example:
-//! 
+//!
 //! ```text
 //!     In 987654321111111, you can make the largest joltage possible, 98, by turning on the first two batteries.
 //!     In 811111111111119, you can make the largest joltage possible by turning on the batteries labeled 8 and 9, producing 89 jolts.
 //!     In 234234234234278, you can make 78 by turning on the last two batteries (marked 7 and 8).
 //!     In 818181911112111, the largest joltage you can produce is 92.
 //! ```
-//! 
+//!
 //! The total output joltage is the sum of the maximum joltage from each bank, so in this example, the total output joltage is 98 + 89 + 78 + 92 = 357.
-//! 
+//!
 //! There are many batteries in front of you. Find the maximum joltage possible from each bank; what is the total output joltage?
-//! 
+//!
 //! Your puzzle answer was 17694.
-//! 
+//!
 //! ## Part Two
-//! 
+//!
 //! The escalator doesn't move. The Elf explains that it probably needs more joltage to overcome the static friction of the system and hits the big red "joltage limit safety override" button. You lose count of the number of times she needs to confirm "yes, I'm sure" and decorate the lobby a bit while you wait.
-//! 
+//!
 //! Now, you need to make the largest joltage by turning on exactly twelve batteries within each bank.
-//! 
+//!
 //! The joltage output for the bank is still the number formed by the digits of the batteries you've turned on; the only difference is that now there will be 12 digits in each bank's joltage output instead of two.
-//! 
+//!
 //! Consider again the example from before:
-//! 
+//!
 //! ```text
 //! 987654321111111
 //! 811111111111119
 //! 234234234234278
 //! 818181911112111
 //! ```
-//! 
+//!
 //! Now, the joltages are much larger:
-//! 
+//!
 //! ```text
 //!     In 987654321111111, the largest joltage can be found by turning on everything except some 1s at the end to produce 987654321111.
 //!     In the digit sequence 811111111111119, the largest joltage can be found by turning on everything except some 1s, producing 811111111119.
 //!     In 234234234234278, the largest joltage can be found by turning on everything except a 2 battery, a 3 battery, and another 2 battery near the start to produce 434234234278.
 //!     In 818181911112111, the joltage 888911112111 is produced by turning on everything except some 1s near the front.
 //! ```
-//! 
+//!
 //! The total output joltage is now much larger: 987654321111 + 811111111119 + 434234234278 + 888911112111 = 3121910778619.
-//! 
+//!
 //! What is the new total output joltage?
-//! 
+//!
 //! Your puzzle answer was 175659236361660.
-//! 
+//!
 //! Both parts of this puzzle are complete! They provide two gold stars: **
 
-
-use std::collections::VecDeque;
 mod input;
 use input::INPUT;
 
-fn build_vec_deque_from_string(s: &str) -> VecDeque<u8> {
-    VecDeque::from(s.chars().map(|c| c as u8 - b'0').collect::<Vec<u8>>())
-}
-trait HighestSequentialCombination {
-    fn filter_to_highest_sequential_combination<const N: usize>(&mut self);
-    fn build_int(&self) -> u64;
+use aoc_2025_03::{TotalJoltage, solve};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+};
+
+/// The command line options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Args {
+    /// Where to read the banks from: a path to a file, ``-`` for stdin, or the puzzle
+    /// input baked into [`INPUT`] if not given.
+    input: Option<String>,
+    /// The number of batteries to keep, from ``--keep <N>`` or ``--keep=<N>``, instead of
+    /// solving both parts.
+    keep: Option<usize>,
+    /// List the batteries of every bank, from ``-v`` or ``--verbose``.
+    verbose: bool,
+    /// Add up the joltages as strings, from ``--string``, for totals too large for a
+    /// ``u128``.
+    string: bool,
 }
 
-impl HighestSequentialCombination for VecDeque<u8> {
-    fn filter_to_highest_sequential_combination<const N: usize>(&mut self) {
-        let mut pos: usize = 0;
-        loop {
-            if pos == self.len() - 1 || self.len() <= N {
-                break;
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let parse = |value: &str| {
+        value
+            .parse::<usize>()
+            .map_err(|error| format!("Invalid number of batteries {value:?}: {error}"))
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-v" | "--verbose" => parsed.verbose = true,
+            "--string" => parsed.string = true,
+            "--keep" => {
+                let value = args.next().ok_or("--keep needs a number of batteries")?;
+                parsed.keep = Some(parse(&value)?);
             }
-            if self[pos] < self[pos + 1] {
-                self.remove(pos);
-                pos = pos.saturating_sub(1);
-            } else {
-                pos += 1;
+            _ => {
+                if let Some(value) = arg.strip_prefix("--keep=") {
+                    parsed.keep = Some(parse(value)?);
+                } else if (arg == "-" || !arg.starts_with('-')) && parsed.input.is_none() {
+                    parsed.input = Some(arg);
+                } else {
+                    return Err(format!("Unexpected argument {arg:?}"));
+                }
             }
         }
-
-        if self.len() > N {
-            drop(self.split_off(N));
-        }
     }
 
-    fn build_int(&self) -> u64 {
-        self.iter()
-            .fold(0u64, |acc, &digit| acc * 10u64 + digit as u64)
+    Ok(parsed)
+}
+
+/// Open ``input``, as given in [`Args::input`], to read the banks from line by line.
+fn open_input(input: Option<&str>) -> io::Result<Box<dyn BufRead>> {
+    match input {
+        None => Ok(Box::new(INPUT.as_bytes())),
+        Some("-") => Ok(Box::new(io::stdin().lock())),
+        Some(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
     }
 }
 
 fn main() {
-    let sum2: u64 = INPUT
-        .split_whitespace()
-        .map(|line| {
-            let mut vec_deque = build_vec_deque_from_string(line);
-            vec_deque.filter_to_highest_sequential_combination::<2>();
-            vec_deque.build_int()
-        })
-        .sum();
-    let sum12: u64 = INPUT
-        .split_whitespace()
-        .map(|line| {
-            let mut vec_deque = build_vec_deque_from_string(line);
-            vec_deque.filter_to_highest_sequential_combination::<12>();
-            vec_deque.build_int()
-        })
-        .sum();
-
-    println!("Part 1 Total Joltage: \x1b[32m{}\x1b[0m", sum2);
-    println!("Part 2 Total Joltage: \x1b[32m{}\x1b[0m", sum12);
-}
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
 
-#[cfg(test)]
-mod test_highest_sequential_combination {
-    use super::*;
+    let reader = match open_input(args.input.as_deref()) {
+        Ok(reader) => reader,
+        Err(error) => {
+            eprintln!("Failed to open the banks: {error}");
+            std::process::exit(1);
+        }
+    };
 
-    macro_rules! create_test {
-        ($name:ident::<$size:literal>($values:expr) = $expected:expr) => {
-            #[test]
-            fn $name() {
-                let mut vec_deque: VecDeque<u8> = build_vec_deque_from_string($values);
-                vec_deque.filter_to_highest_sequential_combination::<$size>();
-                let result: Vec<u8> = vec_deque.into();
-                assert_eq!(result, $expected);
+    if let Some(keep) = args.keep {
+        let result = match TotalJoltage::from_reader(reader, keep) {
+            Ok(result) => result,
+            Err(error) => {
+                eprintln!("{error}");
+                std::process::exit(1);
             }
         };
-    }
+        if args.verbose {
+            for (line, bank) in result.banks.iter().enumerate() {
+                println!(
+                    "Bank {}: {} from batteries {:?}",
+                    line + 1,
+                    bank.joltage_string(),
+                    bank.indices
+                );
+            }
+        }
 
-    create_test! {
-        test1::<3>("987654321111111") = vec![9, 8, 7]
-    }
-    create_test! {
-        test2::<2>("123456789") = vec![8, 9]
-    }
-    create_test! {
-        test3::<4>("543216789") = vec![6, 7, 8, 9]
-    }
-    create_test! {test4::<5>("1111122222333334444455555") = vec![5, 5, 5, 5, 5]}
-    create_test! {test5::<1>("987654321") = vec![9]}
-    create_test! {
-        test6::<2>("811111111111119") = vec![8, 9]
+        let total = if args.string {
+            result.total_string()
+        } else if let Some(total) = result.total() {
+            total.to_string()
+        } else {
+            eprintln!("The total joltage does not fit in a u128; add it up with --string instead");
+            std::process::exit(1);
+        };
+        println!(
+            "Total Joltage keeping {} batteries: \x1b[32m{}\x1b[0m",
+            keep, total
+        );
+        return;
     }
+
+    let answers = match solve(reader) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("Part 1 Total Joltage: \x1b[32m{}\x1b[0m", answers.part_1);
+    println!("Part 2 Total Joltage: \x1b[32m{}\x1b[0m", answers.part_2);
 }
 
 #[cfg(test)]
-mod test_build_int {
+mod test_args {
     use super::*;
 
+    fn args(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
     #[test]
-    fn test_build_int() {
-        let vec_deque: VecDeque<u8> = build_vec_deque_from_string("123456789");
-        let result = vec_deque.build_int();
-        assert_eq!(result, 123456789u64);
+    fn test_parse_args() {
+        assert_eq!(args(&[]), Ok(Args::default()));
+        assert_eq!(
+            args(&["--keep", "3", "-v", "banks.txt"]),
+            Ok(Args {
+                input: Some("banks.txt".to_owned()),
+                keep: Some(3),
+                verbose: true,
+                string: false,
+            })
+        );
+        assert_eq!(
+            args(&["-", "--keep=12", "--string"]),
+            Ok(Args {
+                input: Some("-".to_owned()),
+                keep: Some(12),
+                verbose: false,
+                string: true,
+            })
+        );
+        assert!(args(&["--keep"]).is_err());
+        assert!(args(&["--keep", "x"]).is_err());
+        assert!(args(&["--unknown"]).is_err());
+        assert!(args(&["a.txt", "b.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_open_input() {
+        let answers =
+            solve(open_input(None).expect("Failed to open input")).expect("Failed to solve");
+        assert_eq!(answers.part_1, 17694);
+        assert_eq!(answers.part_2, 175659236361660);
+
+        let path = std::env::temp_dir().join("aoc-2025-03-test-open-input.txt");
+        std::fs::write(&path, "987654321111111\n811111111111119\n").expect("Failed to write input");
+        let input = open_input(Some(&path.to_string_lossy())).expect("Failed to open input");
+        let result = TotalJoltage::from_reader(input, 2).expect("Failed to read banks");
+        std::fs::remove_file(&path).expect("Failed to remove input");
+        assert_eq!(result.total(), Some(98 + 89));
     }
 }
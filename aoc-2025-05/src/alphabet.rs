@@ -0,0 +1,195 @@
+use std::cmp::Ordering;
+
+/// The symbols that the values of a [`StringRange`](crate::StringRange) are written in,
+/// as the digits of a number in base [`radix`](Self::radix): the first symbol is zero,
+/// and values are ordered by the positions of their symbols in the alphabet, not by
+/// their bytes.
+///
+/// Values are padded with the zero symbol to compare values of different lengths, so
+/// that, as with numbers, a longer value without leading zeros is always the larger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: &'static [u8],
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::DECIMAL
+    }
+}
+
+impl Alphabet {
+    /// The digits ``0`` to ``9``.
+    pub const DECIMAL: Self = Self::new(b"0123456789");
+    /// The digits ``0`` to ``9`` then the lowercase letters ``a`` to ``f``.
+    pub const HEX: Self = Self::new(b"0123456789abcdef");
+    /// The digits, then the uppercase letters, then the lowercase letters, i.e. base 62
+    /// in ASCII order.
+    pub const ALPHANUMERIC: Self =
+        Self::new(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+
+    /// An alphabet of ``symbols``, in ascending order; they must be distinct ASCII
+    /// characters, and there must be at least two of them.
+    pub const fn new(symbols: &'static [u8]) -> Self {
+        assert!(symbols.len() >= 2, "An alphabet needs at least two symbols");
+        let mut index = 0;
+        while index < symbols.len() {
+            assert!(symbols[index].is_ascii(), "Symbols must be ASCII");
+            let mut other = index + 1;
+            while other < symbols.len() {
+                assert!(symbols[index] != symbols[other], "Symbols must be distinct");
+                other += 1;
+            }
+            index += 1;
+        }
+
+        Self { symbols }
+    }
+
+    /// The symbols, in ascending order.
+    pub const fn symbols(&self) -> &'static [u8] {
+        self.symbols
+    }
+
+    /// The number of symbols.
+    pub const fn radix(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// The first symbol, which values are padded with.
+    pub const fn zero(&self) -> u8 {
+        self.symbols[0]
+    }
+
+    /// The position of ``symbol`` in the alphabet, if it is in it.
+    pub fn rank(&self, symbol: u8) -> Option<usize> {
+        self.symbols.iter().position(|&other| other == symbol)
+    }
+
+    /// Whether every symbol of ``value`` is in the alphabet.
+    pub fn is_valid(&self, value: &str) -> bool {
+        value.bytes().all(|symbol| self.rank(symbol).is_some())
+    }
+
+    /// ``value`` padded with zeros to ``width``.
+    pub fn pad(&self, value: &str, width: usize) -> String {
+        self.padded_bytes(value, width).map(char::from).collect()
+    }
+
+    /// The bytes of ``value`` padded with zeros to ``width``, without allocating.
+    fn padded_bytes<'v>(&self, value: &'v str, width: usize) -> impl Iterator<Item = u8> + 'v {
+        std::iter::repeat_n(self.zero(), width.saturating_sub(value.len())).chain(value.bytes())
+    }
+
+    /// Compare ``a`` and ``b`` as if both were padded with zeros to the same length, by
+    /// the ranks of their symbols; symbols outside the alphabet rank after all others.
+    pub fn cmp_padded(&self, a: &str, b: &str) -> Ordering {
+        let width = a.len().max(b.len());
+        let ranks = |value| {
+            self.padded_bytes(value, width)
+                .map(|symbol| self.rank(symbol).unwrap_or(usize::MAX))
+        };
+        ranks(a).cmp(ranks(b))
+    }
+
+    /// ``value`` as it would be written, without its leading zeros; all zeros become a
+    /// single zero.
+    pub fn unpadded<'v>(&self, value: &'v str) -> &'v str {
+        let trimmed = value.trim_start_matches(char::from(self.zero()));
+        if trimmed.is_empty() && !value.is_empty() {
+            &value[value.len() - 1..]
+        } else {
+            trimmed
+        }
+    }
+
+    /// ``value`` plus one, which may be a symbol longer.
+    ///
+    /// Panics if ``value`` has symbols outside the alphabet.
+    pub fn increment(&self, value: &str) -> String {
+        let mut symbols = value.as_bytes().to_vec();
+        for symbol in symbols.iter_mut().rev() {
+            let rank = self.rank(*symbol).expect("Symbol not in the alphabet");
+            if rank + 1 == self.radix() {
+                *symbol = self.zero();
+            } else {
+                *symbol = self.symbols[rank + 1];
+                return symbols.into_iter().map(char::from).collect();
+            }
+        }
+
+        std::iter::once(self.symbols[1])
+            .chain(symbols)
+            .map(char::from)
+            .collect()
+    }
+
+    /// ``value`` minus one, or [`None`] if it is zero.
+    ///
+    /// Panics if ``value`` has symbols outside the alphabet.
+    pub fn decrement(&self, value: &str) -> Option<String> {
+        let mut symbols = value.as_bytes().to_vec();
+        for symbol in symbols.iter_mut().rev() {
+            let rank = self.rank(*symbol).expect("Symbol not in the alphabet");
+            if rank == 0 {
+                *symbol = self.symbols[self.radix() - 1];
+            } else {
+                *symbol = self.symbols[rank - 1];
+                return Some(symbols.into_iter().map(char::from).collect());
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test_alphabet {
+    use super::*;
+
+    #[test]
+    fn test_cmp_padded() {
+        let decimal = Alphabet::DECIMAL;
+        assert_eq!(decimal.cmp_padded("9", "10"), Ordering::Less);
+        assert_eq!(decimal.cmp_padded("010", "10"), Ordering::Equal);
+        assert_eq!(decimal.cmp_padded("200", "0199"), Ordering::Greater);
+        assert_eq!(decimal.cmp_padded("", "0"), Ordering::Equal);
+
+        assert_eq!(Alphabet::HEX.cmp_padded("f", "10"), Ordering::Less);
+        assert_eq!(Alphabet::HEX.cmp_padded("a", "9"), Ordering::Greater);
+
+        // Ordered by rank rather than by byte.
+        let reversed = Alphabet::new(b"zyx");
+        assert_eq!(reversed.cmp_padded("x", "y"), Ordering::Greater);
+        assert_eq!(reversed.cmp_padded("x", "yz"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_step() {
+        let decimal = Alphabet::DECIMAL;
+        assert_eq!(decimal.increment("199"), "200");
+        assert_eq!(decimal.increment("999"), "1000");
+        assert_eq!(decimal.decrement("100"), Some("099".to_owned()));
+        assert_eq!(decimal.decrement("0"), None);
+        assert_eq!(decimal.unpadded("000"), "0");
+
+        assert_eq!(Alphabet::HEX.increment("ff"), "100");
+        assert_eq!(Alphabet::HEX.increment("9f"), "a0");
+        assert_eq!(Alphabet::HEX.decrement("a0"), Some("9f".to_owned()));
+        assert_eq!(Alphabet::ALPHANUMERIC.increment("Zz"), "a0");
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(Alphabet::HEX.is_valid("deadbeef"));
+        assert!(!Alphabet::HEX.is_valid("DEADBEEF"));
+        assert!(!Alphabet::DECIMAL.is_valid("12a"));
+        assert!(Alphabet::ALPHANUMERIC.is_valid("Hello123"));
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct")]
+    fn test_duplicate_symbols() {
+        Alphabet::new(b"0120");
+    }
+}
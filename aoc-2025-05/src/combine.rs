@@ -27,12 +27,39 @@ pub fn combine_ranges<'r>(ranges: impl Iterator<Item = &'r StringRange>) -> Vec<
     combined_ranges
 }
 
+/// The ranges of values not covered between the first and the last of ``ranges``, which
+/// must be sorted, as [`combine_ranges`] expects; they may overlap.
+pub fn gaps_between<'r>(ranges: impl Iterator<Item = &'r StringRange>) -> Vec<StringRange> {
+    let mut gaps = Vec::new();
+    let mut covered: Option<StringRange> = None;
+
+    for range in ranges {
+        covered = Some(match covered {
+            None => range.clone(),
+            Some(covered) => match StringRange::combine(&covered, range) {
+                Ok(merged) => merged,
+                Err(_) => {
+                    let start = covered.next_after();
+                    let end = range
+                        .last_before()
+                        .expect("Unreachable; a range after another cannot start at zero");
+                    // Ranges that are only contiguous leave no gap between them.
+                    gaps.extend(StringRange::new(&start, &end));
+                    range.clone()
+                }
+            },
+        });
+    }
+
+    gaps
+}
+
 #[cfg(test)]
 mod test_combine_ranges {
     use super::*;
     use crate::traits::HasStringRanges;
 
-    const RANGES: &'static [(&'static str, &'static str)] = &[
+    const RANGES: &[(&str, &str)] = &[
         ("3", "5"),
         ("6", "9"),
         ("8", "11"),
@@ -43,8 +70,7 @@ mod test_combine_ranges {
         ("25", "27"),
     ];
 
-    const EXPECTED: &'static [(&'static str, &'static str)] =
-        &[("3", "5"), ("6", "15"), ("16", "30")];
+    const EXPECTED: &[(&str, &str)] = &[("3", "5"), ("6", "15"), ("16", "30")];
 
     #[test]
     fn test_combine_ranges() {
@@ -70,3 +96,33 @@ mod test_combine_ranges {
         assert_eq!(actual, expected);
     }
 }
+
+#[cfg(test)]
+mod test_gaps_between {
+    use super::*;
+    use crate::traits::HasStringRanges;
+
+    #[test]
+    fn test_gaps_between() {
+        let mut ranges: Vec<StringRange> = [
+            ("3", "5"),
+            ("10", "14"),
+            ("16", "20"),
+            ("12", "18"),
+            ("21", "30"),
+            ("95", "105"),
+        ]
+        .iter()
+        .map(|(min, max)| StringRange::new(min, max).expect("Invalid range"))
+        .collect();
+        ranges.sort();
+
+        let gaps = gaps_between(ranges.iter_ranges());
+        assert_eq!(
+            gaps.iter().map(|r| r.get_print_range()).collect::<Vec<_>>(),
+            vec![("6", "9"), ("31", "94")]
+        );
+
+        assert!(gaps_between(std::iter::empty()).is_empty());
+    }
+}
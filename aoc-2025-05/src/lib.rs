@@ -0,0 +1,18 @@
+//! Ranges of IDs written as strings of any length, from Day 5 of 2025, as a library so
+//! that their set operations can be reused outside of the puzzle.
+
+mod alphabet;
+pub use alphabet::Alphabet;
+
+mod range;
+pub use range::StringRange;
+
+pub mod combine;
+
+mod range_set;
+pub use range_set::RangeSet;
+
+pub mod traits;
+
+mod parser;
+pub use parser::{ParsedInput, parse_input, read_ranges};
@@ -70,47 +70,50 @@ static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 #[cfg(feature = "profile")]
 use std::time::Instant;
 
-mod range;
-use range::StringRange;
-
-mod combine;
-
-mod traits;
-use traits::*;
-
-mod parser;
-use parser::{ParsedInput, parse_input};
+use aoc_2025_05::{Alphabet, read_ranges, traits::*};
+use std::io::BufRead;
 
 mod input;
 use input::INPUT;
 
-fn main() {
-    let ParsedInput { ranges, values } = parse_input(INPUT);
+fn main() -> anyhow::Result<()> {
+    let mut reader = INPUT.as_bytes();
 
     #[cfg(feature = "profile")]
-    let count_start_time = Instant::now();
-    let count = values.iter().filter(|value| ranges.contains(value)).count();
+    let combine_start_time = Instant::now();
+    let range_set = read_ranges(&mut reader, Alphabet::DECIMAL)?;
     #[cfg(feature = "profile")]
     {
-        let duration = Instant::now() - count_start_time;
-        println!("Count time: {:?}", duration);
+        let duration = Instant::now() - combine_start_time;
+        println!("Combine time: {:?}", duration);
     }
 
     #[cfg(feature = "profile")]
-    let combine_start_time = Instant::now();
-    let total_range_size = combine::combine_ranges(ranges.iter())
-        .iter()
-        .map(|range| range.get_size())
-        .sum::<u128>();
-
+    let count_start_time = Instant::now();
+    let (mut count, mut total) = (0, 0);
+    for line in reader.lines() {
+        let line = line?;
+        let value = line.trim();
+        if value.is_empty() {
+            continue;
+        }
+        total += 1;
+        if range_set.contains(value) {
+            count += 1;
+        }
+    }
     #[cfg(feature = "profile")]
     {
-        let duration = Instant::now() - combine_start_time;
-        println!("Combine time: {:?}", duration);
+        let duration = Instant::now() - count_start_time;
+        println!("Count time: {:?}", duration);
     }
 
+    let total_range_size = range_set.size();
+
     println!("Number of values within ranges: {}", count);
-    println!("Number of values outside ranges: {}", values.len() - count);
+    println!("Number of values outside ranges: {}", total - count);
 
     println!("Sizes of ranges: {:?}", total_range_size);
+
+    Ok(())
 }
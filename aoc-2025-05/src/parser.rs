@@ -1,4 +1,5 @@
-use super::StringRange;
+use super::{Alphabet, RangeSet, StringRange};
+use std::io::BufRead;
 
 pub struct ParsedInput {
     pub ranges: Vec<StringRange>,
@@ -24,11 +25,48 @@ pub fn parse_input(input: &str) -> ParsedInput {
     ParsedInput { ranges, values }
 }
 
+/// Read ranges of values written in ``alphabet`` from ``reader``, one per line, merging
+/// each into a [`RangeSet`] as soon as it is read rather than collecting them first.
+///
+/// Blank lines before the first range are skipped, and reading stops at the first blank
+/// line after it, so that ``reader`` is left at the values that follow in the puzzle
+/// input.
+pub fn read_ranges(reader: &mut impl BufRead, alphabet: Alphabet) -> anyhow::Result<RangeSet> {
+    let mut range_set = RangeSet::default();
+    let (mut line, mut line_number, mut started) = (String::new(), 0, false);
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if started {
+                break;
+            }
+            continue;
+        }
+        started = true;
+
+        let range = trimmed
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("{trimmed:?} is not a range"))
+            .and_then(|(min, max)| StringRange::new_in(min.trim(), max.trim(), alphabet))
+            .map_err(|error| anyhow::anyhow!("Invalid range on line {line_number}: {error}"))?;
+        range_set.insert(range);
+    }
+
+    Ok(range_set)
+}
+
 #[cfg(test)]
 mod test_parse_input {
     use super::*;
 
-    const TEST_INPUT: &'static str = "
+    const TEST_INPUT: &str = "
     3-5
     10-14
     16-20
@@ -65,3 +103,43 @@ mod test_parse_input {
         );
     }
 }
+
+#[cfg(test)]
+mod test_read_ranges {
+    use super::*;
+    use crate::traits::HasStringRanges;
+
+    #[test]
+    fn test_read_ranges() {
+        let mut reader = "\n3-5\n10-14\n16-20\n12-18\n\n1\n5\n".as_bytes();
+        let range_set = read_ranges(&mut reader, Alphabet::DECIMAL).expect("Failed to read");
+
+        assert_eq!(
+            range_set,
+            RangeSet::new(parse_input("3-5\n10-14\n16-20\n12-18").ranges)
+        );
+        assert!(range_set.contains("17"));
+        // The values are left to be read.
+        assert_eq!(
+            std::io::read_to_string(reader).expect("Failed to read"),
+            "1\n5\n"
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let error = read_ranges(&mut "3-5\n10-x\n".as_bytes(), Alphabet::DECIMAL)
+            .expect_err("Read an invalid range");
+        assert!(error.to_string().contains("line 2"), "{error}");
+
+        let error = read_ranges(&mut "3-5\n10\n".as_bytes(), Alphabet::DECIMAL)
+            .expect_err("Read an invalid range");
+        assert!(
+            error.to_string().contains("\"10\" is not a range"),
+            "{error}"
+        );
+
+        let hex = read_ranges(&mut "a-ff\n".as_bytes(), Alphabet::HEX).expect("Failed to read");
+        assert_eq!(hex.size(), 0xff - 0xa + 1);
+    }
+}
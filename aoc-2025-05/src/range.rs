@@ -1,16 +1,20 @@
-fn pad_string(value: &str, width: usize) -> String {
-    format!("{:0>width$}", value, width = width)
-}
+use crate::Alphabet;
+use std::cmp::Ordering;
 
 /// A range of strings, defined by a start and end string (inclusive).
 ///
 /// For the purposes of this challenge, all values are well within the [`u64`] range,
 /// so we could have done this whole challenge with [`std::ops::RangeInclusive`], but this
 /// implementation is more general and can handle infinitely large strings (provided that
-/// each of their range size is less than or equal to [`i64::MAX`]) as well as
-/// non-numeric strings (though untested).
+/// each of their range size is less than or equal to [`i128::MAX`]), written in any
+/// [`Alphabet`]: decimal by default, or hexadecimal, alphanumeric and so on with
+/// [`new_in`](Self::new_in).
+///
+/// Ranges of different alphabets cannot be combined, and are not meaningfully ordered
+/// against each other.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StringRange {
+    alphabet: Alphabet,
     min_len: usize,
     max_len: usize,
     start: String,
@@ -19,14 +23,27 @@ pub struct StringRange {
 
 impl StringRange {
     pub fn new(start: &str, end: &str) -> anyhow::Result<Self> {
+        Self::new_in(start, end, Alphabet::DECIMAL)
+    }
+
+    /// A range of the values from ``start`` to ``end`` written in ``alphabet``.
+    pub fn new_in(start: &str, end: &str, alphabet: Alphabet) -> anyhow::Result<Self> {
+        if let Some(invalid) = [start, end]
+            .into_iter()
+            .find(|bound| !alphabet.is_valid(bound))
+        {
+            anyhow::bail!("{invalid:?} has symbols outside the alphabet");
+        }
+
         let min_len = start.len().min(end.len());
         let max_len = start.len().max(end.len());
-        let start_padded = pad_string(start, max_len);
-        let end_padded = pad_string(end, max_len);
-        if end_padded < start_padded {
+        let start_padded = alphabet.pad(start, max_len);
+        let end_padded = alphabet.pad(end, max_len);
+        if alphabet.cmp_padded(&end_padded, &start_padded) == Ordering::Less {
             anyhow::bail!("end must be greater than or equal to start");
         }
         Ok(Self {
+            alphabet,
             min_len,
             max_len,
             start: start_padded,
@@ -34,6 +51,21 @@ impl StringRange {
         })
     }
 
+    /// The alphabet the values of the range are written in.
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    /// The first value of the range, padded to the length of the longer bound.
+    pub fn start(&self) -> &str {
+        &self.start
+    }
+
+    /// The last value of the range, padded to the length of the longer bound.
+    pub fn end(&self) -> &str {
+        &self.end
+    }
+
     /// Check if the length of the value is within the min and max length of the range;
     /// if its not, there is no point in checking further.
     pub fn in_range(&self, value: &str) -> bool {
@@ -52,7 +84,10 @@ impl StringRange {
             }
             return false;
         }
-        let value_padded = format!("{:0>width$}", value, width = self.max_len);
+        if !self.alphabet.is_valid(value) {
+            return false;
+        }
+        let value_padded = self.alphabet.pad(value, self.max_len);
 
         #[cfg(feature = "trace")]
         {
@@ -62,22 +97,31 @@ impl StringRange {
             );
         }
         // Inclusive range check
-        value_padded >= self.start && value_padded <= self.end
+        self.alphabet.cmp_padded(&value_padded, &self.start) != Ordering::Less
+            && self.alphabet.cmp_padded(&value_padded, &self.end) != Ordering::Greater
     }
 
     pub fn get_size(&self) -> u128 {
+        let radix = self.alphabet.radix() as i128;
+        let rank = |symbol: u8| {
+            self.alphabet
+                .rank(symbol)
+                .expect("Unreachable; bounds are checked against the alphabet") as i128
+        };
         let unchecked = self
             .start
-            .chars()
+            .bytes()
             .rev()
-            .zip(self.end.chars().rev())
+            .zip(self.end.bytes().rev())
             .enumerate()
-            .fold(0_i64, |acc, (idx, (s_char, e_char))| {
-                // This tolerates non-digit characters, but this is currently untested.
-                let diff = e_char as i64 - s_char as i64;
+            .fold(0_i128, |acc, (idx, (s_symbol, e_symbol))| {
+                let diff = rank(e_symbol) - rank(s_symbol);
                 if diff != 0 {
-                    acc.checked_add(diff * 10_i64.pow(idx as u32))
-                        .expect("Range size overflowed i64; range too large to compute size")
+                    radix
+                        .checked_pow(idx as u32)
+                        .and_then(|place| diff.checked_mul(place))
+                        .and_then(|value| acc.checked_add(value))
+                        .expect("Range size overflowed i128; range too large to compute size")
                 } else {
                     acc
                 }
@@ -87,26 +131,89 @@ impl StringRange {
         (unchecked + 1) as u128
     }
 
+    /// The range between two bounds that may be padded with leading zeros, which are
+    /// trimmed so that the lengths of the range are those of the values it covers.
+    fn from_padded(start: &str, end: &str, alphabet: Alphabet) -> Self {
+        Self::new_in(alphabet.unpadded(start), alphabet.unpadded(end), alphabet)
+            .expect("Unreachable; bounds are only ever taken from ordered ranges")
+    }
+
+    /// The values covered by both ``self`` and ``other``, if any; ranges of different
+    /// alphabets have none in common.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        if self.alphabet != other.alphabet {
+            return None;
+        }
+
+        let cmp = |a: &&str, b: &&str| self.alphabet.cmp_padded(a, b);
+        let start = std::cmp::max_by(self.start.as_str(), other.start.as_str(), cmp);
+        let end = std::cmp::min_by(self.end.as_str(), other.end.as_str(), cmp);
+
+        (self.alphabet.cmp_padded(start, end) != Ordering::Greater)
+            .then(|| Self::from_padded(start, end, self.alphabet))
+    }
+
+    /// The values covered by ``self`` but not by ``other``: none if ``other`` covers all
+    /// of ``self``, two ranges if ``other`` lies strictly inside it, or otherwise one.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersect(other) else {
+            return vec![self.clone()];
+        };
+
+        let alphabet = self.alphabet;
+        let mut remainder = Vec::with_capacity(2);
+        if alphabet.cmp_padded(&self.start, &overlap.start) == Ordering::Less {
+            let below = alphabet
+                .decrement(&overlap.start)
+                .expect("Unreachable; the overlap starts after the start of the range");
+            remainder.push(Self::from_padded(&self.start, &below, alphabet));
+        }
+        if alphabet.cmp_padded(&overlap.end, &self.end) == Ordering::Less {
+            let above = alphabet.increment(&overlap.end);
+            remainder.push(Self::from_padded(&above, &self.end, alphabet));
+        }
+
+        remainder
+    }
+
+    /// The first value after the range.
+    pub fn next_after(&self) -> String {
+        self.alphabet.increment(self.alphabet.unpadded(&self.end))
+    }
+
+    /// The last value before the range, or [`None`] if it starts at zero.
+    pub fn last_before(&self) -> Option<String> {
+        self.alphabet
+            .decrement(&self.start)
+            .map(|value| self.alphabet.unpadded(&value).to_owned())
+    }
+
     /// Static method to combine two ranges into one encompassing range if possible.
     pub fn combine(this: &Self, that: &Self) -> anyhow::Result<Self> {
+        if this.alphabet != that.alphabet {
+            anyhow::bail!("Ranges of different alphabets cannot be combined");
+        }
+
         let sorted = if this < that {
             (this, that)
         } else {
             (that, this)
         };
 
-        let max_len = sorted.0.max_len.max(sorted.1.max_len);
+        let alphabet = this.alphabet;
+        let at_least = |a: &str, b: &str| alphabet.cmp_padded(a, b) != Ordering::Less;
         match (
-            pad_string(&sorted.0.start, max_len),
-            pad_string(&sorted.0.end, max_len),
-            pad_string(&sorted.1.start, max_len),
-            pad_string(&sorted.1.end, max_len),
+            &sorted.0.start,
+            &sorted.0.end,
+            &sorted.1.start,
+            &sorted.1.end,
         ) {
-            (start_a, end_a, start_b, end_b) if start_b >= start_a && end_a >= start_b => {
+            (start_a, end_a, start_b, end_b)
+                if at_least(start_b, start_a) && at_least(end_a, start_b) =>
+            {
                 // Ranges overlap or are contiguous
-                let new_start = start_a;
-                let new_end = end_a.max(end_b);
-                StringRange::new(&new_start, &new_end)
+                let new_end = if at_least(end_a, end_b) { end_a } else { end_b };
+                Ok(Self::from_padded(start_a, new_end, alphabet))
             }
             // Welp turns out there are no other cases here, clippy be mad lol
             _ => anyhow::bail!("Ranges do not overlap and cannot be combined"),
@@ -115,9 +222,10 @@ impl StringRange {
 
     #[cfg(test)]
     pub fn get_print_range(&self) -> (&str, &str) {
+        let zero = char::from(self.alphabet.zero());
         (
-            self.start.trim_start_matches("0"),
-            self.end.trim_start_matches("0"),
+            self.start.trim_start_matches(zero),
+            self.end.trim_start_matches(zero),
         )
     }
 }
@@ -130,21 +238,22 @@ impl PartialOrd for StringRange {
 
 impl Ord for StringRange {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let max_len = self.max_len.max(other.max_len);
+        let alphabet = self.alphabet;
         self.min_len
             .cmp(&other.min_len)
             .then_with(
                 // We know that the min lengths are equal here, but one of them
                 // could still be padded to reach the max length, so we need to
                 // compare the starts up to the min length.
-                || pad_string(&self.start, max_len).cmp(&pad_string(&other.start, max_len)),
+                || alphabet.cmp_padded(&self.start, &other.start),
             )
             .then_with(
                 // Now that we know the starts are equal, and the max lengths are equal,
                 // we can compare the ends lexicographically.
                 || self.max_len.cmp(&other.max_len),
             )
-            .then_with(|| self.end.cmp(&other.end))
+            .then_with(|| alphabet.cmp_padded(&self.end, &other.end))
+            .then_with(|| self.alphabet.symbols().cmp(other.alphabet.symbols()))
     }
 }
 
@@ -178,11 +287,71 @@ mod test_struct_contains {
     create_test!(test_larger_than_u128_range_above("130000000000000000000000000000000000000000" in ("0", "110000000000000000000000000000000000000000")) = false);
 }
 
+#[cfg(test)]
+mod test_set_operations {
+    use super::*;
+
+    fn range(start: &str, end: &str) -> StringRange {
+        StringRange::new(start, end).expect("Invalid range")
+    }
+
+    fn print(ranges: &[StringRange]) -> Vec<(&str, &str)> {
+        ranges.iter().map(StringRange::get_print_range).collect()
+    }
+
+    #[test]
+    fn test_intersect() {
+        assert_eq!(
+            range("10", "20").intersect(&range("15", "125")),
+            Some(range("15", "20"))
+        );
+        assert_eq!(
+            range("95", "105").intersect(&range("100", "200")),
+            Some(range("100", "105"))
+        );
+        assert_eq!(
+            range("10", "20").intersect(&range("20", "30")),
+            Some(range("20", "20"))
+        );
+        assert_eq!(range("10", "20").intersect(&range("21", "30")), None);
+        assert_eq!(
+            range("5", "9").intersect(&range("1", "100")),
+            Some(range("5", "9"))
+        );
+    }
+
+    #[test]
+    fn test_subtract() {
+        assert_eq!(
+            print(&range("1", "100").subtract(&range("10", "19"))),
+            vec![("1", "9"), ("20", "100")]
+        );
+        assert_eq!(
+            print(&range("95", "105").subtract(&range("100", "200"))),
+            vec![("95", "99")]
+        );
+        assert_eq!(
+            print(&range("95", "105").subtract(&range("0", "99"))),
+            vec![("100", "105")]
+        );
+        assert_eq!(
+            print(&range("10", "20").subtract(&range("30", "40"))),
+            vec![("10", "20")]
+        );
+        assert!(range("10", "20").subtract(&range("5", "25")).is_empty());
+
+        // The remainders cover values of their own lengths.
+        let remainder = range("1", "100").subtract(&range("10", "99"));
+        assert!(remainder[0].contains("9"));
+        assert!(remainder[1].contains("100"));
+    }
+}
+
 #[cfg(test)]
 mod test_struct_sort {
     use super::*;
 
-    const RANGES: &'static [(&'static str, &'static str)] = &[
+    const RANGES: &[(&str, &str)] = &[
         ("1", "10"),
         ("100", "200"),
         ("20", "30"),
@@ -295,3 +464,82 @@ mod test_struct_combine {
     create_test!(test_full_contained_with_overlapping_end(("15", "30"), ("10", "30") => TestResult::Ok(("10", "30"))));
     create_test!(test_no_overlap(("10", "15"), ("20", "25") => TestResult::Err(())));
 }
+
+#[cfg(test)]
+mod test_alphabets {
+    use super::*;
+
+    fn hex(start: &str, end: &str) -> StringRange {
+        StringRange::new_in(start, end, Alphabet::HEX).expect("Invalid range")
+    }
+
+    #[test]
+    fn test_hex_contains() {
+        let range = hex("f0", "10a");
+        assert!(range.contains("f0"));
+        assert!(range.contains("ff"));
+        assert!(range.contains("100"));
+        assert!(range.contains("10a"));
+        assert!(!range.contains("ef"));
+        assert!(!range.contains("10b"));
+        // A decimal digit beyond ``9`` in hex.
+        assert!(!hex("9", "a").contains("10"));
+        assert!(!range.contains("fg"));
+    }
+
+    #[test]
+    fn test_hex_size() {
+        assert_eq!(hex("0", "ff").get_size(), 256);
+        assert_eq!(hex("f0", "10a").get_size(), 0x10a - 0xf0 + 1);
+        assert_eq!(hex("9", "a").get_size(), 2);
+        // Beyond the range of a ``u64`` either side of ``16^30``.
+        let below = "f".repeat(30);
+        let above = format!("1{}f", "0".repeat(29));
+        assert_eq!(hex(&below, &above).get_size(), 17);
+    }
+
+    #[test]
+    fn test_hex_order_and_combine() {
+        let mut ranges = [
+            hex("a0", "af"),
+            hex("9", "f"),
+            hex("10", "1f"),
+            hex("b0", "b1"),
+        ];
+        ranges.sort();
+        assert_eq!(
+            ranges
+                .iter()
+                .map(StringRange::get_print_range)
+                .collect::<Vec<_>>(),
+            vec![("9", "f"), ("10", "1f"), ("a0", "af"), ("b0", "b1")]
+        );
+
+        let combined =
+            StringRange::combine(&hex("9", "1f"), &hex("a", "2a")).expect("Failed to combine");
+        assert_eq!(combined.get_print_range(), ("9", "2a"));
+        assert!(
+            StringRange::combine(&hex("9", "f"), &StringRange::new("9", "15").unwrap()).is_err()
+        );
+
+        assert_eq!(
+            hex("0", "ff")
+                .subtract(&hex("a", "ef"))
+                .iter()
+                .map(StringRange::get_print_range)
+                .collect::<Vec<_>>(),
+            vec![("", "9"), ("f0", "ff")]
+        );
+    }
+
+    #[test]
+    fn test_alphanumeric() {
+        let range = StringRange::new_in("Zz", "a5", Alphabet::ALPHANUMERIC).expect("Invalid range");
+        assert!(range.contains("a0"));
+        assert!(!range.contains("a6"));
+        assert_eq!(range.get_size(), 7);
+
+        assert!(StringRange::new_in("a", "Z", Alphabet::ALPHANUMERIC).is_err());
+        assert!(StringRange::new_in("g", "h", Alphabet::HEX).is_err());
+    }
+}
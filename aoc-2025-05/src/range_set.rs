@@ -0,0 +1,153 @@
+use crate::{StringRange, combine::combine_ranges, traits::HasStringRanges};
+use std::cmp::Ordering;
+
+/// A set of values covered by any of a list of [`StringRange`]s.
+///
+/// The ranges are sorted and merged once, when the set is built, into disjoint ranges in
+/// ascending order; so checking whether a value is covered only needs a binary search for
+/// the last range starting at or before it, rather than a scan over every range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<StringRange>,
+}
+
+impl RangeSet {
+    pub fn new(ranges: impl IntoIterator<Item = StringRange>) -> Self {
+        let mut ranges = ranges.into_iter().collect::<Vec<_>>();
+        ranges.sort();
+
+        Self {
+            ranges: combine_ranges(ranges.iter()),
+        }
+    }
+
+    /// Merge ``range`` into the set, combining it with every range it overlaps.
+    ///
+    /// Only the merged ranges are kept, so a set built up one range at a time holds no
+    /// more than the disjoint ranges so far, however many ranges it is fed.
+    pub fn insert(&mut self, range: StringRange) {
+        let alphabet = range.alphabet();
+        // The first range that does not end before ``range`` starts, and the first after
+        // that which starts after it ends; everything in between overlaps it.
+        let first = self.ranges.partition_point(|other| {
+            alphabet.cmp_padded(other.end(), range.start()) == Ordering::Less
+        });
+        let last = first
+            + self.ranges[first..].partition_point(|other| {
+                alphabet.cmp_padded(other.start(), range.end()) != Ordering::Greater
+            });
+
+        let merged = self.ranges[first..last]
+            .iter()
+            .fold(range, |merged, other| {
+                StringRange::combine(&merged, other)
+                    .expect("Unreachable; only overlapping ranges are combined")
+            });
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// The total number of values covered, counting each value once however many of the
+    /// original ranges cover it.
+    pub fn size(&self) -> u128 {
+        self.ranges.iter().map(StringRange::get_size).sum()
+    }
+}
+
+impl Extend<StringRange> for RangeSet {
+    fn extend<I: IntoIterator<Item = StringRange>>(&mut self, ranges: I) {
+        ranges.into_iter().for_each(|range| self.insert(range));
+    }
+}
+
+impl HasStringRanges for RangeSet {
+    /// The merged ranges, disjoint and in ascending order.
+    fn iter_ranges(&self) -> impl Iterator<Item = &StringRange> + '_ {
+        self.ranges.iter()
+    }
+
+    /// Binary search for the only range that could contain ``value``.
+    fn contains(&self, value: &str) -> bool {
+        let after = self.ranges.partition_point(|range| {
+            range.alphabet().cmp_padded(range.start(), value) != Ordering::Greater
+        });
+
+        after
+            .checked_sub(1)
+            .is_some_and(|index| self.ranges[index].contains(value))
+    }
+}
+
+#[cfg(test)]
+mod test_range_set {
+    use super::*;
+
+    const RANGES: &[(&str, &str)] = &[
+        ("3", "5"),
+        ("10", "14"),
+        ("16", "20"),
+        ("12", "18"),
+        ("95", "105"),
+        ("1000", "1000"),
+    ];
+
+    fn ranges() -> Vec<StringRange> {
+        RANGES
+            .iter()
+            .map(|(min, max)| StringRange::new(min, max).expect("Invalid range"))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_linear_scan() {
+        let range_set = RangeSet::new(ranges());
+        let mut sorted = ranges();
+        sorted.sort();
+
+        for value in (0..1100).map(|value| value.to_string()) {
+            assert_eq!(
+                range_set.contains(&value),
+                sorted.contains(&value),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merged() {
+        let range_set = RangeSet::new(ranges());
+        assert_eq!(
+            range_set
+                .iter_ranges()
+                .map(|range| range.get_print_range())
+                .collect::<Vec<_>>(),
+            vec![("3", "5"), ("10", "20"), ("95", "105"), ("1000", "1000")]
+        );
+        assert_eq!(range_set.size(), 3 + 11 + 11 + 1);
+    }
+
+    #[test]
+    fn test_empty() {
+        let range_set = RangeSet::new([]);
+        assert!(!range_set.contains("0"));
+        assert_eq!(range_set.size(), 0);
+    }
+
+    #[test]
+    fn test_insert_matches_new() {
+        let mut range_set = RangeSet::default();
+        range_set.extend(ranges());
+        assert_eq!(range_set, RangeSet::new(ranges()));
+
+        // In every order, including ranges that bridge several others.
+        let mut reversed = RangeSet::default();
+        reversed.extend(ranges().into_iter().rev());
+        reversed.insert(StringRange::new("4", "96").expect("Invalid range"));
+        assert_eq!(
+            reversed
+                .iter_ranges()
+                .map(|range| range.get_print_range())
+                .collect::<Vec<_>>(),
+            vec![("3", "105"), ("1000", "1000")]
+        );
+    }
+}
@@ -22,8 +22,7 @@ impl HasStringRanges for Vec<StringRange> {
 mod test_has_string_ranges {
     use super::*;
 
-    const RANGES: &'static [(&'static str, &'static str)] =
-        &[("3", "5"), ("10", "14"), ("16", "20"), ("12", "18")];
+    const RANGES: &[(&str, &str)] = &[("3", "5"), ("10", "14"), ("16", "20"), ("12", "18")];
 
     macro_rules! create_test {
         ($name:ident($value:literal) == $expected:expr) => {
@@ -0,0 +1,86 @@
+//! Benchmarks of evaluating the problems of a worksheet in parallel, through
+//! [`SegmentIndex::par_process`], against the sequential [`Orchestrator::process`].
+//!
+//! The worksheets are generated rather than read from the puzzle input, with ``rows``
+//! numeric lines of ``problems`` problems each, so that there are enough problems for the
+//! parallel path to spread across threads.
+//!
+//! Run with ``cargo bench -p aoc-2025-06 --features rayon``.
+
+use aoc_2025_06::{Direction, Orchestrator, SegmentIndex};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// A worksheet of ``problems`` problems of ``rows`` pseudo-random numbers of up to 3
+/// digits, from a linear congruential generator, cycling through ``operators``.
+fn worksheet(rows: usize, problems: usize, operators: &[char]) -> String {
+    let mut state = 0x2545_f491_u64;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        (state >> 33) % 1000
+    };
+
+    let mut text = (0..rows)
+        .map(|_| {
+            (0..problems)
+                .map(|_| format!("{:>3}", next()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.push('\n');
+    text.push_str(
+        &(0..problems)
+            .map(|problem| format!("{:<3}", operators[problem % operators.len()]))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    text
+}
+
+fn bench_segments(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segments");
+    group.sample_size(10);
+
+    // Products of more than a few rows would overflow, so the tall worksheet takes the
+    // largest of each problem instead; and its columns are too long to read vertically.
+    for (rows, problems, operators, directions) in [
+        (4, 1_000, &['+', '*'][..], &Direction::ALL[..]),
+        (4, 100_000, &['+', '*'], &Direction::ALL),
+        (100, 10_000, &['+', '>'], &[Direction::Horizontal]),
+    ] {
+        let text = worksheet(rows, problems, operators);
+        let name = format!("{}x{}", rows, problems);
+
+        for &direction in directions {
+            group.bench_function(
+                BenchmarkId::new(format!("sequential/{}", direction), &name),
+                |b| {
+                    b.iter(|| {
+                        Orchestrator::from_text(black_box(&text))
+                            .and_then(|orchestrator| orchestrator.process(&direction))
+                            .expect("Failed to process worksheet")
+                    })
+                },
+            );
+            group.bench_function(
+                BenchmarkId::new(format!("parallel/{}", direction), &name),
+                |b| {
+                    b.iter(|| {
+                        SegmentIndex::from_text(black_box(&text))
+                            .and_then(|index| index.par_process(&direction))
+                            .expect("Failed to process worksheet")
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_segments);
+criterion_main!(benches);
@@ -1,26 +1,147 @@
-use std::str::Chars;
+use std::{
+    collections::VecDeque,
+    io::{self, Read},
+    str::Chars,
+};
 
 use super::AddToBuffer;
 
-pub struct BufferedLineReader<'s, T: AddToBuffer> {
-    chars: Chars<'s>,
+/// The size of the chunks in which a stream is read.
+pub const STREAM_CHUNK_SIZE: usize = 4096;
+
+/// The characters of a single line, one at a time.
+pub trait LineSource {
+    /// The next character of the line, or [`None`] at its end.
+    fn next_char(&mut self) -> anyhow::Result<Option<char>>;
+}
+
+impl LineSource for Chars<'_> {
+    fn next_char(&mut self) -> anyhow::Result<Option<char>> {
+        Ok(self.next())
+    }
+}
+
+/// One line of a stream, kept as the pieces of the chunks of [`STREAM_CHUNK_SIZE`] bytes
+/// that it was read in, up to its line break.
+///
+/// A piece is dropped as soon as its last character is read, so a line only holds the
+/// part of it that is still ahead of the reader.
+///
+/// The input is expected to be ASCII, like the puzzle input.
+#[derive(Debug, Default)]
+pub struct StreamedLine {
+    pieces: VecDeque<Vec<u8>>,
+    /// The next byte of the first piece to read.
+    index: usize,
+    ended: bool,
+}
+
+impl StreamedLine {
+    /// Read ``reader`` through once, a chunk at a time, splitting it into its lines.
+    ///
+    /// Nothing is ever sought, so ``reader`` can be a pipe or stdin as well as a file.
+    pub fn read_lines(mut reader: impl Read) -> io::Result<Vec<Self>> {
+        let mut lines = vec![Self::default()];
+        let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+
+            let mut pieces = chunk[..read].split(|&byte| byte == b'\n');
+            // The chunk carries on with the line the last one ended in; each line break in
+            // it starts another.
+            if let Some(piece) = pieces.next() {
+                lines.last_mut().expect("Unreachable").push(piece);
+            }
+            for piece in pieces {
+                let mut line = Self::default();
+                line.push(piece);
+                lines.push(line);
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Add ``bytes`` to the end of the line.
+    fn push(&mut self, bytes: &[u8]) {
+        if !bytes.is_empty() {
+            self.pieces.push_back(bytes.to_vec());
+        }
+    }
+
+    /// The first character of the line that is yet to be read, without reading it.
+    pub fn peek(&self) -> Option<char> {
+        self.pieces
+            .front()
+            .map(|piece| piece[self.index] as char)
+            .filter(|_| !self.ended)
+    }
+
+    /// The next byte of the line, dropping its piece if it was the last of it.
+    fn next_byte(&mut self) -> Option<u8> {
+        let piece = self.pieces.front()?;
+        let byte = piece[self.index];
+
+        self.index += 1;
+        if self.index == piece.len() {
+            self.pieces.pop_front();
+            self.index = 0;
+        }
+        Some(byte)
+    }
+}
+
+impl LineSource for StreamedLine {
+    fn next_char(&mut self) -> anyhow::Result<Option<char>> {
+        if self.ended {
+            return Ok(None);
+        }
+
+        match self.next_byte() {
+            Some(b'\r') | None => {
+                self.ended = true;
+                // Whatever follows a carriage return is not part of the line.
+                self.pieces.clear();
+                Ok(None)
+            }
+            Some(byte) if byte.is_ascii() => Ok(Some(byte as char)),
+            Some(byte) => anyhow::bail!("Non-ASCII byte {:#04x} in input", byte),
+        }
+    }
+}
+
+pub struct BufferedLineReader<T: AddToBuffer, L: LineSource> {
+    chars: L,
     pub buffer: T,
 }
 
-impl<'s, T: AddToBuffer> BufferedLineReader<'s, T> {
+impl<'s, T: AddToBuffer> BufferedLineReader<T, Chars<'s>> {
     /// Creates a new BufferedLineReader from the given line.
     ///
     /// The input string needs to remain in scope for the lifetime of the reader.
     pub fn new(line: &'s str) -> Self {
+        Self::from_source(line.chars())
+    }
+}
+
+impl<T: AddToBuffer, L: LineSource> BufferedLineReader<T, L> {
+    /// Creates a new BufferedLineReader reading the characters of ``source``.
+    pub fn from_source(source: L) -> Self {
         Self {
-            chars: line.chars(),
+            chars: source,
             buffer: T::default(),
         }
     }
 
     /// Advances the reader by one character, adding it to the buffer.
     pub fn advance(&mut self) -> anyhow::Result<Option<char>> {
-        if let Some(ch) = self.chars.next() {
+        if let Some(ch) = self.chars.next_char()? {
             self.buffer.add_to_buffer(ch)?;
             Ok(Some(ch))
         } else {
@@ -37,7 +158,7 @@ impl<'s, T: AddToBuffer> BufferedLineReader<'s, T> {
     }
 }
 
-impl<'s, T: AddToBuffer> Iterator for BufferedLineReader<'s, T> {
+impl<T: AddToBuffer, L: LineSource> Iterator for BufferedLineReader<T, L> {
     type Item = anyhow::Result<char>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -61,7 +182,7 @@ mod test_reader {
             #[test]
             fn $name() {
                 let line = $line;
-                let mut reader = BufferedLineReader::<$type>::new(line);
+                let mut reader = BufferedLineReader::<$type, _>::new(line);
 
                 while let Some(result) = reader.next() {
                     result.expect("Failed to read character");
@@ -78,4 +199,87 @@ mod test_reader {
     create_test!(test_simple_op::<Operator>("+  ") = Operator::Add);
     create_test!(test_empty_op::<Operator>("") = Operator::default());
     create_test!(test_number_trailing_spaces::<u16>("12345       ") = 12345);
+
+    fn read_lines(text: &str) -> Vec<StreamedLine> {
+        StreamedLine::read_lines(text.as_bytes()).expect("Failed to read lines")
+    }
+
+    #[test]
+    fn test_streamed_line() {
+        let text = "123 4\r\n+   *\n\n 56";
+        let lines = read_lines(text)
+            .into_iter()
+            .map(|line| {
+                let mut reader = BufferedLineReader::<Vec<Option<u8>>, _>::from_source(line);
+                for result in &mut reader {
+                    result?;
+                }
+                anyhow::Ok(reader.yield_buffer())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0].as_ref().unwrap(),
+            &vec![Some(1), Some(2), Some(3), None, Some(4)]
+        );
+        assert!(lines[1].is_err());
+        assert_eq!(lines[2].as_ref().unwrap(), &vec![]);
+        assert_eq!(lines[3].as_ref().unwrap(), &vec![None, Some(5), Some(6)]);
+
+        let mut line = read_lines("1é").remove(0);
+        assert_eq!(line.next_char().unwrap(), Some('1'));
+        assert!(line.next_char().is_err());
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut lines = read_lines("+ 1\n\n");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].peek(), Some('+'));
+        assert_eq!(lines[1].peek(), None);
+        assert_eq!(lines[2].peek(), None);
+
+        lines[0].next_char().unwrap();
+        assert_eq!(lines[0].peek(), Some(' '));
+    }
+
+    #[test]
+    fn test_lines_longer_than_a_chunk() {
+        // Two lines, split across chunks, read a character at a time in turns.
+        let first = "1".repeat(STREAM_CHUNK_SIZE * 2 + 1);
+        let second = "2".repeat(STREAM_CHUNK_SIZE + 3);
+        let text = format!("{}\n{}", first, second);
+
+        let mut lines = read_lines(&text);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line.pieces.len())
+                .collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+
+        let mut read = [String::new(), String::new()];
+        loop {
+            let chars = lines
+                .iter_mut()
+                .map(|line| line.next_char().unwrap())
+                .collect::<Vec<_>>();
+            if chars.iter().all(Option::is_none) {
+                break;
+            }
+            for (read, char) in read.iter_mut().zip(chars) {
+                read.extend(char);
+            }
+
+            // Every piece read to its end has been dropped.
+            if read[0].len() == STREAM_CHUNK_SIZE {
+                assert_eq!(lines[0].pieces.len(), 2);
+            }
+        }
+
+        assert_eq!(read, [first, second]);
+        assert!(lines.iter().all(|line| line.pieces.is_empty()));
+    }
 }
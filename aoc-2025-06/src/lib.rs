@@ -0,0 +1,23 @@
+//! The worksheet of Day 6 of 2025, as a library so that its operators can be extended
+//! outside of the puzzle.
+
+mod operator;
+pub use operator::*;
+
+mod buffer;
+pub use buffer::*;
+
+mod traits;
+pub use traits::*;
+
+mod orchestrator;
+pub use orchestrator::*;
+
+mod processor;
+pub use processor::*;
+
+mod validate;
+pub use validate::*;
+
+mod segments;
+pub use segments::*;
@@ -74,44 +74,130 @@
 mod input;
 use input::INPUT;
 
-mod operator;
-use operator::*;
+use aoc_2025_06::{Direction, Orchestrator, validate_alignment};
+use std::{fs::File, io::BufReader};
 
-mod buffer;
-use buffer::*;
+/// The command line arguments.
+#[derive(Debug, Default)]
+struct Args {
+    /// From ``--direction <horizontal|vertical>``, which may be repeated; both parts if
+    /// none is given.
+    directions: Vec<Direction>,
+    /// From ``--input <path>``, to stream the input from a file rather than using the
+    /// one built in.
+    input: Option<String>,
+    /// From ``--strict``, to check that the columns of the input line up before
+    /// processing it.
+    strict: bool,
+    /// From ``--parallel``, to index the problems up front and evaluate them in parallel,
+    /// holding the whole input in memory.
+    #[cfg(feature = "rayon")]
+    parallel: bool,
+}
 
-mod traits;
-use traits::*;
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
 
-mod orchestrator;
-use orchestrator::*;
+        while let Some(arg) = args.next() {
+            if arg == "--strict" {
+                parsed.strict = true;
+                continue;
+            }
+            #[cfg(feature = "rayon")]
+            if arg == "--parallel" {
+                parsed.parallel = true;
+                continue;
+            }
 
-fn main() -> anyhow::Result<()> {
-    let orchestrator_u16 = Orchestrator::<'_, u16>::from_text(INPUT)?;
-    let orchestrator_vec_option_u8 = Orchestrator::<'_, Vec<Option<u8>>>::from_text(INPUT)?;
-
-    #[cfg(feature = "profile")]
-    let start = std::time::Instant::now();
-    {
-        let result = orchestrator_u16.horizontal_process()?;
-        println!("Part 1 accumulated result: {}", result);
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                    (arg, value)
+                }
+            };
+
+            match name.as_str() {
+                "--direction" => parsed.directions.push(value.parse()?),
+                "--input" => parsed.input = Some(value),
+                _ => anyhow::bail!("Unknown argument {:?}", name),
+            }
+        }
+
+        if parsed.directions.is_empty() {
+            parsed.directions.extend(Direction::ALL);
+        }
+        Ok(parsed)
     }
-    #[cfg(feature = "profile")]
-    {
-        let duration = start.elapsed();
-        println!("Part 1 processing time: {:?}", duration);
+}
+
+/// The part of the puzzle that reads the numbers in ``direction``.
+fn part(direction: Direction) -> u8 {
+    match direction {
+        Direction::Horizontal => 1,
+        Direction::Vertical => 2,
     }
+}
 
-    #[cfg(feature = "profile")]
-    let start = std::time::Instant::now();
-    {
-        let result = orchestrator_vec_option_u8.vertical_process()?;
-        println!("Part 2 accumulated result: {}", result);
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    if args.strict {
+        let validated = match &args.input {
+            Some(path) => File::open(path)
+                .map_err(Into::into)
+                .and_then(|file| validate_alignment(BufReader::new(file))),
+            None => validate_alignment(INPUT.as_bytes()),
+        };
+        if let Err(err) = validated {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
     }
-    #[cfg(feature = "profile")]
-    {
-        let duration = start.elapsed();
-        println!("Part 2 processing time: {:?}", duration);
+
+    #[cfg(feature = "rayon")]
+    let text = match (&args.input, args.parallel) {
+        (Some(path), true) => Some(std::fs::read_to_string(path)?),
+        (None, true) => Some(INPUT.to_owned()),
+        (_, false) => None,
+    };
+    #[cfg(feature = "rayon")]
+    let index = text
+        .as_deref()
+        .map(aoc_2025_06::SegmentIndex::from_text)
+        .transpose()?;
+
+    for direction in args.directions {
+        #[cfg(feature = "profile")]
+        let start = std::time::Instant::now();
+
+        #[cfg(feature = "rayon")]
+        let parallel = index
+            .as_ref()
+            .map(|index| index.par_process(&direction))
+            .transpose()?;
+        #[cfg(not(feature = "rayon"))]
+        let parallel = None;
+
+        let result = match (parallel, &args.input) {
+            (Some(result), _) => result,
+            (None, Some(path)) => {
+                Orchestrator::from_reader(File::open(path)?)?.process(&direction)?
+            }
+            (None, None) => Orchestrator::from_text(INPUT)?.process(&direction)?,
+        };
+        println!("Part {} accumulated result: {}", part(direction), result);
+        #[cfg(feature = "profile")]
+        {
+            let duration = start.elapsed();
+            println!("Part {} processing time: {:?}", part(direction), duration);
+        }
     }
 
     Ok(())
@@ -1,31 +1,110 @@
 use super::AddToBuffer;
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+/// How a registered operator combines the result so far with the next item; returns
+/// [`None`] if the result would overflow.
+pub type Fold = fn(u128, u128) -> Option<u128>;
+
+/// The operators registered with [`Operator::register`], by their symbols.
+fn registry() -> &'static RwLock<HashMap<char, Fold>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<char, Fold>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Operator {
+    /// ``+``
     Add,
+    /// ``*``
     Multiply,
+    /// ``-``: the first item, minus every item after it.
+    Subtract,
+    /// ``<``: the smallest item.
+    Min,
+    /// ``>``: the largest item.
+    Max,
+    /// Any other symbol, folded with the function it was registered with.
+    Custom(char),
     #[default]
     Undefined,
 }
 
 impl Operator {
+    /// The operator written as ``symbol``, if there is one.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '+' => Some(Operator::Add),
+            '*' => Some(Operator::Multiply),
+            '-' => Some(Operator::Subtract),
+            '<' => Some(Operator::Min),
+            '>' => Some(Operator::Max),
+            _ => registry()
+                .read()
+                .expect("Operator registry poisoned")
+                .contains_key(&symbol)
+                .then_some(Operator::Custom(symbol)),
+        }
+    }
+
+    /// Register ``symbol`` as an operator that folds its items with ``fold``, starting
+    /// from the first item; there is no result for no items, so it is ``0`` as for the
+    /// other operators.
+    ///
+    /// Symbols that are already taken, digits and whitespace cannot be registered.
+    pub fn register(symbol: char, fold: Fold) -> anyhow::Result<()> {
+        if symbol.is_whitespace() || symbol.is_ascii_digit() {
+            anyhow::bail!("Cannot register {:?} as an operator", symbol);
+        }
+        if let Some(existing) = Self::from_symbol(symbol) {
+            anyhow::bail!("{:?} is already the operator {:?}", symbol, existing);
+        }
+
+        registry()
+            .write()
+            .expect("Operator registry poisoned")
+            .insert(symbol, fold);
+        Ok(())
+    }
+
     pub fn operate_on(&self, mut items: impl Iterator<Item = u16>) -> anyhow::Result<u128> {
         items
             .try_fold(None, |acc, item| -> anyhow::Result<Option<u128>> {
                 #[cfg(feature = "trace")]
                 eprintln!("Operating: {:?} with acc={:?} and item={}", self, acc, item);
-                match self {
-                    Operator::Add => acc
-                        .or(Some(0_u128))
-                        .map(|acc| acc.checked_add(item as u128))
-                        .ok_or_else(|| anyhow::anyhow!("Overflow in addition")),
-                    Operator::Multiply => acc
-                        .or(Some(1_u128))
-                        .map(|acc| acc.checked_mul(item as u128))
-                        .ok_or_else(|| anyhow::anyhow!("Overflow in multiplication")),
-                    Operator::Undefined => {
+                let item = item as u128;
+                let result = match (self, acc) {
+                    (Operator::Undefined, _) => {
                         anyhow::bail!("Cannot operate with undefined operator")
                     }
-                }
+                    (Operator::Add, acc) => acc
+                        .unwrap_or(0)
+                        .checked_add(item)
+                        .ok_or_else(|| anyhow::anyhow!("Overflow in addition"))?,
+                    (Operator::Multiply, acc) => acc
+                        .unwrap_or(1)
+                        .checked_mul(item)
+                        .ok_or_else(|| anyhow::anyhow!("Overflow in multiplication"))?,
+                    // The rest start from the first item.
+                    (_, None) => item,
+                    (Operator::Subtract, Some(acc)) => acc
+                        .checked_sub(item)
+                        .ok_or_else(|| anyhow::anyhow!("Underflow in subtraction"))?,
+                    (Operator::Min, Some(acc)) => acc.min(item),
+                    (Operator::Max, Some(acc)) => acc.max(item),
+                    (Operator::Custom(symbol), Some(acc)) => {
+                        let fold = *registry()
+                            .read()
+                            .expect("Operator registry poisoned")
+                            .get(symbol)
+                            .ok_or_else(|| anyhow::anyhow!("Unregistered operator {:?}", symbol))?;
+                        fold(acc, item)
+                            .ok_or_else(|| anyhow::anyhow!("Overflow in operator {:?}", symbol))?
+                    }
+                };
+                Ok(Some(result))
             })
             .map(|result_opt| result_opt.unwrap_or_default())
     }
@@ -45,14 +124,11 @@ impl AddToBuffer for Operator {
             );
         }
 
-        match input {
-            '+' => {
-                *self = Operator::Add;
-            }
-            '*' => {
-                *self = Operator::Multiply;
+        match Operator::from_symbol(input) {
+            Some(operator) => {
+                *self = operator;
             }
-            _ => {
+            None => {
                 anyhow::bail!("Invalid operator character: {:?}", input);
             }
         }
@@ -92,4 +168,51 @@ mod test_add_operator {
     create_test!(test_new_space(Operator::default(), ' ') = Ok(Operator::Undefined));
     create_test!(test_existing_operator(Operator::Add, '*') = Err(anyhow::Error::msg("")));
     create_test!(test_invalid_char(Operator::default(), 'x') = Err(anyhow::Error::msg("")));
+    create_test!(test_new_subtract(Operator::default(), '-') = Ok(Operator::Subtract));
+    create_test!(test_new_min(Operator::default(), '<') = Ok(Operator::Min));
+    create_test!(test_new_max(Operator::default(), '>') = Ok(Operator::Max));
+}
+
+#[cfg(test)]
+mod test_operate_on {
+    use super::*;
+
+    fn operate(operator: Operator, items: &[u16]) -> anyhow::Result<u128> {
+        operator.operate_on(items.iter().copied())
+    }
+
+    #[test]
+    fn test_builtin() {
+        assert_eq!(operate(Operator::Add, &[1, 2, 3]).unwrap(), 6);
+        assert_eq!(operate(Operator::Multiply, &[4, 5, 6]).unwrap(), 120);
+        assert_eq!(operate(Operator::Subtract, &[100, 30, 7]).unwrap(), 63);
+        assert_eq!(operate(Operator::Min, &[8, 3, 9]).unwrap(), 3);
+        assert_eq!(operate(Operator::Max, &[8, 3, 9]).unwrap(), 9);
+
+        for operator in [Operator::Add, Operator::Subtract, Operator::Min] {
+            assert_eq!(operate(operator, &[]).unwrap(), 0);
+        }
+        assert!(operate(Operator::Subtract, &[3, 4]).is_err());
+        assert!(operate(Operator::Undefined, &[1]).is_err());
+        assert!(operate(Operator::Multiply, &[u16::MAX; 9]).is_err());
+    }
+
+    #[test]
+    fn test_register() {
+        // Each test registers its own symbols, as the registry is shared.
+        Operator::register('^', |acc, item| acc.checked_pow(item as u32)).unwrap();
+        assert_eq!(Operator::from_symbol('^'), Some(Operator::Custom('^')));
+        assert_eq!(operate(Operator::Custom('^'), &[2, 3, 2]).unwrap(), 64);
+        assert!(operate(Operator::Custom('^'), &[10, 100]).is_err());
+
+        let mut operator = Operator::default();
+        operator.add_to_buffer('^').unwrap();
+        assert_eq!(operator, Operator::Custom('^'));
+
+        assert!(Operator::register('^', |acc, _| Some(acc)).is_err());
+        assert!(Operator::register('+', |acc, _| Some(acc)).is_err());
+        assert!(Operator::register('7', |acc, _| Some(acc)).is_err());
+        assert!(Operator::register(' ', |acc, _| Some(acc)).is_err());
+        assert!(operate(Operator::Custom('~'), &[1, 2]).is_err());
+    }
 }
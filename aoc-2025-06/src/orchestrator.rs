@@ -1,43 +1,71 @@
-use anyhow::anyhow;
+use std::{io::Read, str::Chars};
 
-use crate::{AddToBuffer, BufferedLineReader, Operator};
+use crate::{
+    AddToBuffer, BufferedLineReader, LineSource, Operator, SegmentProcessor, StreamedLine,
+};
 
-pub struct Orchestrator<'s, B: AddToBuffer> {
-    numeric_buffers: Vec<BufferedLineReader<'s, B>>,
-    operator_buffer: BufferedLineReader<'s, Operator>,
+/// What a line of the input holds, going by its first character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineKind {
+    Numeric,
+    Operator,
 }
 
-impl<'s, B: AddToBuffer> Orchestrator<'s, B> {
-    /// Create an Orchestrator from an iterator over lines of text.
-    pub fn from_lines(lines: impl Iterator<Item = &'s str>) -> anyhow::Result<Self> {
-        let mut numeric_lines = Vec::new();
-        let mut operator_lines = Vec::new();
+impl LineKind {
+    pub(crate) fn of(first: Option<char>) -> Option<Self> {
+        match first {
+            Some('0'..='9') | Some(' ') => Some(LineKind::Numeric),
+            Some(symbol) if Operator::from_symbol(symbol).is_some() => Some(LineKind::Operator),
+            _ => None,
+        }
+    }
+}
+
+pub struct Orchestrator<B: AddToBuffer, L: LineSource> {
+    numeric_buffers: Vec<BufferedLineReader<B, L>>,
+    operator_buffer: BufferedLineReader<Operator, L>,
+}
 
-        lines.for_each(|line| match line.chars().next() {
-            Some('0'..='9') => numeric_lines.push(line),
-            Some(' ') => numeric_lines.push(line),
-            Some('+') | Some('*') => operator_lines.push(line),
-            _ => {}
-        });
+impl<B: AddToBuffer, L: LineSource> Orchestrator<B, L> {
+    /// Create an Orchestrator from the sources of each line, by what they hold.
+    fn from_sources(sources: Vec<(LineKind, L)>) -> anyhow::Result<Self> {
+        let mut numeric_buffers = Vec::new();
+        let mut operator_buffers = Vec::new();
+
+        for (kind, source) in sources {
+            match kind {
+                LineKind::Numeric => numeric_buffers.push(BufferedLineReader::from_source(source)),
+                LineKind::Operator => {
+                    operator_buffers.push(BufferedLineReader::from_source(source))
+                }
+            }
+        }
 
-        if operator_lines.len() != 1 {
+        if operator_buffers.len() != 1 {
             anyhow::bail!(
                 "Expected exactly one operator line, found {}",
-                operator_lines.len()
+                operator_buffers.len()
             );
         }
 
-        let numeric_buffers = numeric_lines
-            .into_iter()
-            .map(|line| BufferedLineReader::new(line))
-            .collect::<Vec<_>>();
-        let operator_buffer = BufferedLineReader::new(operator_lines[0]);
-
         Ok(Self {
             numeric_buffers,
-            operator_buffer,
+            operator_buffer: operator_buffers.pop().expect("Unreachable"),
         })
     }
+}
+
+impl<'s, B: AddToBuffer> Orchestrator<B, Chars<'s>> {
+    /// Create an Orchestrator from an iterator over lines of text.
+    pub fn from_lines(lines: impl Iterator<Item = &'s str>) -> anyhow::Result<Self> {
+        Self::from_sources(
+            lines
+                .filter_map(|line| {
+                    LineKind::of(line.chars().next()).map(|kind| (kind, line.chars()))
+                })
+                .collect(),
+        )
+    }
 
     /// Create an Orchestrator from a block of text.
     pub fn from_text(text: &'s str) -> anyhow::Result<Self> {
@@ -45,59 +73,27 @@ impl<'s, B: AddToBuffer> Orchestrator<'s, B> {
     }
 }
 
-impl<'s> Orchestrator<'s, u16> {
-    /// Parse each segment horizonally as a number, then operate on them vertically.
-    pub fn horizontal_process(mut self) -> anyhow::Result<u128> {
-        // We can't use `try_fold` because we need to &mut operator_buffer twice.
-        let mut acc = 0_u128;
-        loop {
-            let operator_char = self.operator_buffer.advance()?;
-            let all_digits: Vec<Option<char>> = {
-                self.numeric_buffers
-                    .iter_mut()
-                    .map(|buf| buf.advance())
-                    .collect::<anyhow::Result<Vec<Option<char>>>>()
-            }?;
-
-            let is_exhausted = operator_char.is_none() && all_digits.iter().all(|c| c.is_none());
-            if is_exhausted
-                || operator_char.is_some_and(|c| c.is_whitespace())
-                    && all_digits
-                        .iter()
-                        .all(|oc| oc.is_some_and(|c| c.is_whitespace()))
-            {
-                #[cfg(feature = "trace")]
-                eprintln!(
-                    "Processing segment with all whitespaces, currently accumulated: {}",
-                    acc
-                );
-
-                // If everything yielded a whitespace, then we know that we have got the columns we
-                // needed. Let's start processing.
-                let numbers = self
-                    .numeric_buffers
-                    .iter_mut()
-                    .map(|buf| buf.yield_buffer());
-
-                let operator = self.operator_buffer.yield_buffer();
-
-                acc = acc
-                    .checked_add(operator.operate_on(numbers)?)
-                    .ok_or_else(|| anyhow::anyhow!("Overflow occurred during accumulation"))?;
-
-                if is_exhausted {
-                    break;
-                }
-            }
-        }
-
-        Ok(acc)
+impl<B: AddToBuffer> Orchestrator<B, StreamedLine> {
+    /// Create an Orchestrator streaming the input from ``reader``, which is read through
+    /// once, a chunk at a time, and never sought.
+    ///
+    /// Columns are walked across every line at once, and the last line can only be reached
+    /// by reading every line before it; so each line keeps the chunks it was read in, and
+    /// drops each of them once its columns have been walked. See [`StreamedLine`].
+    pub fn from_reader(reader: impl Read) -> anyhow::Result<Self> {
+        Self::from_sources(
+            StreamedLine::read_lines(reader)?
+                .into_iter()
+                .filter_map(|line| LineKind::of(line.peek()).map(|kind| (kind, line)))
+                .collect(),
+        )
     }
 }
 
-impl<'s> Orchestrator<'s, Vec<Option<u8>>> {
-    /// Parse each segment horizonally as a number, then operate on them vertically.
-    pub fn vertical_process(mut self) -> anyhow::Result<u128> {
+impl<L: LineSource> Orchestrator<Vec<Option<u8>>, L> {
+    /// Walk the columns until every line is blank, then read the numbers of the segment
+    /// with ``processor`` and operate on them; the results of all segments are summed.
+    pub fn process(mut self, processor: &impl SegmentProcessor) -> anyhow::Result<u128> {
         // We can't use `try_fold` because we need to &mut operator_buffer twice.
         let mut acc = 0_u128;
         loop {
@@ -122,45 +118,24 @@ impl<'s> Orchestrator<'s, Vec<Option<u8>>> {
                     acc
                 );
 
-                let numbers = self
+                // If everything yielded a whitespace, then we know that we have got the columns we
+                // needed. Let's start processing.
+                let rows = self
                     .numeric_buffers
                     .iter_mut()
-                    .try_fold(None, |mut vec, buf| {
+                    .map(|buf| {
                         let mut digits = buf.yield_buffer();
 
-                        // If the line is not exhausted, we must have inserted a trailing None for teh
+                        // If the line is not exhausted, we must have inserted a trailing None for the
                         // separator (i.e. the whitespace we were checking for above). We need to pop it
                         // off to avoid messing up multiplication.
                         if !is_exhausted {
                             digits.pop_if(|d| d.is_none());
                         }
-
-                        // Lazy init because we don't know how many numbers there are yet.
-                        if vec.is_none() {
-                            vec = Some(vec![0_u16; digits.len()]);
-                        }
-
-                        digits.iter().enumerate().try_for_each(|(idx, digit)| {
-                            if digit.is_none() {
-                                return Ok(());
-                            }
-
-                            let existing_number = vec.as_ref().expect("Unreachable")[idx];
-                            vec.as_mut().expect("Unreachable")[idx] = existing_number
-                                .checked_mul(10)
-                                .and_then(|v| v.checked_add(digit.unwrap() as u16))
-                                .ok_or_else(|| {
-                                    anyhow!(
-                                        "Overflow when shifting number during vertical processing"
-                                    )
-                                })?;
-
-                            Ok::<_, anyhow::Error>(())
-                        })?;
-
-                        Ok::<_, anyhow::Error>(vec)
-                    })?
-                    .expect("Unreachable: vec should be initialized");
+                        digits
+                    })
+                    .collect::<Vec<_>>();
+                let numbers = processor.numbers(&rows)?;
 
                 let operator = self.operator_buffer.yield_buffer();
 
@@ -181,6 +156,8 @@ impl<'s> Orchestrator<'s, Vec<Option<u8>>> {
 #[cfg(test)]
 mod test_orchestrator {
     use super::*;
+    use crate::{Direction, STREAM_CHUNK_SIZE};
+    use std::{cell::RefCell, io, rc::Rc};
 
     const TEST_INPUT: &str = "123 328  51 64 
  45 64  387 23 
@@ -193,7 +170,7 @@ mod test_orchestrator {
             .expect("Failed to create orchestrator from test input");
 
         let result = orchestrator
-            .horizontal_process()
+            .process(&Direction::Horizontal)
             .expect("Failed to process horizontally");
 
         assert_eq!(result, 4277556);
@@ -205,9 +182,113 @@ mod test_orchestrator {
             .expect("Failed to create orchestrator from test input");
 
         let result = orchestrator
-            .vertical_process()
+            .process(&Direction::Vertical)
             .expect("Failed to process vertically");
 
         assert_eq!(result, 3263827);
     }
+
+    #[test]
+    fn test_other_operators() {
+        let input = "123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+-   <   >   +  ";
+        let result = Orchestrator::from_text(input)
+            .expect("Failed to create orchestrator from test input")
+            .process(&Direction::Horizontal)
+            .expect("Failed to process horizontally");
+
+        assert_eq!(result, (123 - 45 - 6) + 64 + 387 + (64 + 23 + 314));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        for direction in Direction::ALL {
+            let streamed = Orchestrator::from_reader(io::Cursor::new(TEST_INPUT))
+                .expect("Failed to create orchestrator from test input")
+                .process(&direction)
+                .expect("Failed to process streamed input");
+            let resident = Orchestrator::from_text(TEST_INPUT)
+                .expect("Failed to create orchestrator from test input")
+                .process(&direction)
+                .expect("Failed to process input");
+
+            assert_eq!(streamed, resident);
+        }
+
+        // CRLF line breaks, and blank lines around the worksheet.
+        let input = format!("\r\n{}\r\n\r\n", TEST_INPUT.replace('\n', "\r\n"));
+        let result = Orchestrator::from_reader(io::Cursor::new(input))
+            .expect("Failed to create orchestrator from test input")
+            .process(&Direction::Vertical)
+            .expect("Failed to process streamed input");
+        assert_eq!(result, 3263827);
+    }
+
+    /// A reader that counts the bytes read through it, and cannot seek.
+    struct Counted<R> {
+        inner: R,
+        bytes_read: Rc<RefCell<usize>>,
+    }
+
+    impl<R: Read> Read for Counted<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let read = self.inner.read(buf)?;
+            *self.bytes_read.borrow_mut() += read;
+            Ok(read)
+        }
+    }
+
+    /// A worksheet of ``lines`` numeric lines, each as wide as ``columns`` problems.
+    fn tall_worksheet(lines: usize, columns: usize) -> String {
+        let mut input = (0..lines)
+            .map(|line| vec![format!("{:>3}", line % 1000); columns].join(" ") + "\n")
+            .collect::<String>();
+        input.push_str(&vec!["+  "; columns].join(" "));
+        input
+    }
+
+    #[test]
+    fn test_from_reader_many_lines() {
+        // More lines than the 1024 files a process can usually have open at once.
+        let input = tall_worksheet(2000, 2);
+        let path = std::env::temp_dir().join("aoc-2025-06-test-many-lines.txt");
+        std::fs::write(&path, &input).expect("Failed to write test input");
+
+        let file = std::fs::File::open(&path).expect("Failed to open test input");
+        let streamed = Orchestrator::from_reader(file)
+            .expect("Failed to create orchestrator from test input")
+            .process(&Direction::Horizontal)
+            .expect("Failed to process streamed input");
+        std::fs::remove_file(&path).expect("Failed to remove test input");
+
+        let expected = 2 * (0..2000).map(|line| line % 1000).sum::<u128>();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_from_reader_reads_once() {
+        // Lines several chunks long, so that each is split across chunks.
+        let input = tall_worksheet(20, STREAM_CHUNK_SIZE);
+        let bytes_read = Rc::new(RefCell::new(0));
+        let reader = Counted {
+            inner: input.as_bytes(),
+            bytes_read: Rc::clone(&bytes_read),
+        };
+
+        let streamed = Orchestrator::from_reader(reader)
+            .expect("Failed to create orchestrator from test input")
+            .process(&Direction::Horizontal)
+            .expect("Failed to process streamed input");
+
+        assert_eq!(*bytes_read.borrow(), input.len());
+        assert_eq!(
+            streamed,
+            Orchestrator::from_text(&input)
+                .expect("Failed to create orchestrator from test input")
+                .process(&Direction::Horizontal)
+                .expect("Failed to process input")
+        );
+    }
 }
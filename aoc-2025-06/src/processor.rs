@@ -0,0 +1,138 @@
+use std::{fmt, str::FromStr};
+
+/// Reads the numbers to operate on out of the digits of one segment.
+///
+/// Each row is one numeric line of the segment, as buffered by
+/// [`AddToBuffer`](crate::AddToBuffer) for [`Vec<Option<u8>>`], with [`None`] wherever
+/// the line had a space; how the digits are put together into numbers is up to the
+/// implementor.
+pub trait SegmentProcessor {
+    fn numbers(&self, rows: &[Vec<Option<u8>>]) -> anyhow::Result<Vec<u16>>;
+}
+
+/// The direction in which the digits of a segment are read as numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Part 1: each line is a number.
+    #[default]
+    Horizontal,
+    /// Part 2: each column is a number, with its most significant digit at the top.
+    Vertical,
+}
+
+impl Direction {
+    /// Every direction, in the order of the parts of the puzzle.
+    pub const ALL: [Direction; 2] = [Direction::Horizontal, Direction::Vertical];
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "horizontal" => Ok(Direction::Horizontal),
+            "vertical" => Ok(Direction::Vertical),
+            _ => anyhow::bail!(
+                "Unknown direction {:?}; expected \"horizontal\" or \"vertical\"",
+                s
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Horizontal => write!(f, "horizontal"),
+            Direction::Vertical => write!(f, "vertical"),
+        }
+    }
+}
+
+/// Shift ``digit`` onto the end of ``number``, failing if it no longer fits.
+fn push_digit(number: u16, digit: u8) -> anyhow::Result<u16> {
+    number
+        .checked_mul(10)
+        .and_then(|v| v.checked_add(digit as u16))
+        .ok_or_else(|| anyhow::anyhow!("Overflow when adding digit {} to {}", digit, number))
+}
+
+impl SegmentProcessor for Direction {
+    fn numbers(&self, rows: &[Vec<Option<u8>>]) -> anyhow::Result<Vec<u16>> {
+        match self {
+            Direction::Horizontal => rows
+                .iter()
+                .map(|row| row.iter().flatten().try_fold(0, |n, &d| push_digit(n, d)))
+                .collect(),
+            Direction::Vertical => {
+                let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+                (0..width)
+                    .map(|column| {
+                        rows.iter()
+                            .filter_map(|row| row.get(column).copied().flatten())
+                            .try_fold(0, push_digit)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_direction {
+    use super::*;
+
+    fn rows(lines: &[&str]) -> Vec<Vec<Option<u8>>> {
+        lines
+            .iter()
+            .map(|line| {
+                line.chars()
+                    .map(|c| c.to_digit(10).map(|d| d as u8))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_horizontal() {
+        let segment = rows(&["123", " 45", "  6"]);
+        assert_eq!(
+            Direction::Horizontal.numbers(&segment).unwrap(),
+            vec![123, 45, 6]
+        );
+    }
+
+    #[test]
+    fn test_vertical() {
+        let segment = rows(&["123", " 45", "  6"]);
+        assert_eq!(
+            Direction::Vertical.numbers(&segment).unwrap(),
+            vec![1, 24, 356]
+        );
+
+        // Lines of different lengths.
+        let segment = rows(&["64", "23", "314"]);
+        assert_eq!(
+            Direction::Vertical.numbers(&segment).unwrap(),
+            vec![623, 431, 4]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let segment = rows(&["99999"]);
+        assert!(Direction::Horizontal.numbers(&segment).is_err());
+        assert!(Direction::Vertical.numbers(&segment).is_ok());
+    }
+
+    #[test]
+    fn test_from_str() {
+        for direction in Direction::ALL {
+            assert_eq!(
+                direction.to_string().parse::<Direction>().unwrap(),
+                direction
+            );
+        }
+        assert!("diagonal".parse::<Direction>().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{AddToBuffer, Operator, SegmentProcessor, orchestrator::LineKind};
+
+/// The worksheet held in memory, with the columns of each problem indexed up front so
+/// that problems can be evaluated independently of one another, in any order.
+///
+/// A column separates two problems where every line either has a space or has already
+/// ended; for a worksheet whose lines are all the same width, these are exactly the
+/// segments that [`Orchestrator::process`](crate::Orchestrator::process) walks to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentIndex<'s> {
+    numeric_lines: Vec<&'s str>,
+    operator_line: &'s str,
+    segments: Vec<Range<usize>>,
+}
+
+impl<'s> SegmentIndex<'s> {
+    /// Index the problems of ``text``, which must be ASCII.
+    pub fn from_text(text: &'s str) -> anyhow::Result<Self> {
+        if !text.is_ascii() {
+            anyhow::bail!("Cannot index a worksheet that is not ASCII");
+        }
+
+        let mut numeric_lines = Vec::new();
+        let mut operator_lines = Vec::new();
+        text.lines()
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .for_each(|line| match LineKind::of(line.chars().next()) {
+                Some(LineKind::Numeric) => numeric_lines.push(line),
+                Some(LineKind::Operator) => operator_lines.push(line),
+                None => {}
+            });
+
+        let [operator_line] = operator_lines[..] else {
+            anyhow::bail!(
+                "Expected exactly one operator line, found {}",
+                operator_lines.len()
+            );
+        };
+
+        let lines = || numeric_lines.iter().chain([&operator_line]);
+        let width = lines().map(|line| line.len()).max().unwrap_or_default();
+        let is_separator = |column: usize| {
+            lines().all(|line| line.as_bytes().get(column).is_none_or(|&byte| byte == b' '))
+        };
+
+        let mut segments = Vec::new();
+        let mut start = None;
+        for column in 0..=width {
+            match (start, column == width || is_separator(column)) {
+                (None, false) => start = Some(column),
+                (Some(from), true) => {
+                    segments.push(from..column);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            numeric_lines,
+            operator_line,
+            segments,
+        })
+    }
+
+    /// The columns of each problem, from left to right.
+    pub fn segments(&self) -> &[Range<usize>] {
+        &self.segments
+    }
+
+    /// Buffer the characters of ``columns`` in ``line``, which may end before them.
+    fn buffer<B: AddToBuffer>(line: &str, columns: &Range<usize>) -> anyhow::Result<B> {
+        let end = columns.end.min(line.len());
+        line.get(columns.start.min(end)..end)
+            .unwrap_or_default()
+            .chars()
+            .try_fold(B::default(), |mut buffer, ch| {
+                buffer.add_to_buffer(ch)?;
+                Ok(buffer)
+            })
+    }
+
+    /// Evaluate the problem in ``columns``, reading its numbers with ``processor``.
+    pub fn evaluate(
+        &self,
+        columns: &Range<usize>,
+        processor: &impl SegmentProcessor,
+    ) -> anyhow::Result<u128> {
+        let rows = self
+            .numeric_lines
+            .iter()
+            .map(|line| Self::buffer::<Vec<Option<u8>>>(line, columns))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let operator = Self::buffer::<Operator>(self.operator_line, columns)?;
+
+        operator.operate_on(processor.numbers(&rows)?.into_iter())
+    }
+
+    /// Evaluate every problem in turn, and sum the results.
+    pub fn process(&self, processor: &impl SegmentProcessor) -> anyhow::Result<u128> {
+        self.segments.iter().try_fold(0_u128, |acc, columns| {
+            acc.checked_add(self.evaluate(columns, processor)?)
+                .ok_or_else(|| anyhow::anyhow!("Overflow occurred during accumulation"))
+        })
+    }
+
+    /// Evaluate the problems in parallel, and sum the results.
+    #[cfg(feature = "rayon")]
+    pub fn par_process(&self, processor: &(impl SegmentProcessor + Sync)) -> anyhow::Result<u128> {
+        self.segments
+            .par_iter()
+            .map(|columns| self.evaluate(columns, processor))
+            .try_reduce(
+                || 0,
+                |a, b| {
+                    a.checked_add(b)
+                        .ok_or_else(|| anyhow::anyhow!("Overflow occurred during accumulation"))
+                },
+            )
+    }
+}
+
+#[cfg(test)]
+mod test_segment_index {
+    use super::*;
+    use crate::{Direction, Orchestrator};
+
+    const TEST_INPUT: &str = "123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+*   +   *   +  ";
+
+    #[test]
+    fn test_segments() {
+        let index = SegmentIndex::from_text(TEST_INPUT).expect("Failed to index test input");
+        assert_eq!(index.segments(), &[0..3, 4..7, 8..11, 12..15]);
+
+        // A ragged worksheet, whose last problem is only as wide as its longest line.
+        let index = SegmentIndex::from_text("1  22\n33 4\n+  *").expect("Failed to index");
+        assert_eq!(index.segments(), &[0..2, 3..5]);
+        assert_eq!(index.evaluate(&(0..2), &Direction::Horizontal).unwrap(), 34);
+        assert_eq!(index.process(&Direction::Horizontal).unwrap(), 34 + 88);
+    }
+
+    #[test]
+    fn test_matches_orchestrator() {
+        let index = SegmentIndex::from_text(TEST_INPUT).expect("Failed to index test input");
+
+        for direction in Direction::ALL {
+            let expected = Orchestrator::from_text(TEST_INPUT)
+                .expect("Failed to create orchestrator from test input")
+                .process(&direction)
+                .expect("Failed to process input");
+
+            assert_eq!(index.process(&direction).unwrap(), expected);
+            #[cfg(feature = "rayon")]
+            assert_eq!(index.par_process(&direction).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(SegmentIndex::from_text("12\n34").is_err());
+        assert!(SegmentIndex::from_text("1é\n+ ").is_err());
+
+        let index = SegmentIndex::from_text("1x\n+ ").expect("Failed to index");
+        assert!(index.process(&Direction::Horizontal).is_err());
+    }
+}
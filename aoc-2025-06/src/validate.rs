@@ -0,0 +1,244 @@
+use std::io::{self, BufRead};
+use thiserror::Error;
+
+use crate::{Operator, orchestrator::LineKind};
+
+/// Where the columns of a worksheet fail to line up; lines and columns count from 1, as
+/// in an editor.
+#[derive(Error, Debug)]
+pub enum AlignmentError {
+    #[error("line {line} ends at column {column}, but the worksheet is {width} columns wide")]
+    RaggedLine {
+        line: usize,
+        column: usize,
+        width: usize,
+    },
+
+    #[error("invalid character {character:?} at line {line}, column {column}")]
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+
+    #[error("expected exactly one operator line, found {found}")]
+    OperatorLines { found: usize },
+
+    #[error(
+        "operator {symbol:?} at line {line}, column {column} is not at the first column of a problem"
+    )]
+    MisalignedOperator {
+        line: usize,
+        column: usize,
+        symbol: char,
+    },
+
+    #[error("no operator at line {line}, column {column} for the problem starting there")]
+    MissingOperator { line: usize, column: usize },
+
+    #[error("failed to read worksheet: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Check that the worksheet read from ``reader`` lines up: every line is as wide as the
+/// first, and each problem has exactly one operator, under its first column.
+///
+/// Lines are read one at a time, keeping only whether each column holds any digits, so
+/// this can run ahead of [`Orchestrator::from_reader`](crate::Orchestrator::from_reader)
+/// without holding the whole input.
+pub fn validate_alignment(reader: impl BufRead) -> Result<(), AlignmentError> {
+    let mut width = None;
+    let mut has_digits = Vec::<bool>::new();
+    let mut operator_lines = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        let number = index + 1;
+
+        let invalid = |column: usize, character| AlignmentError::InvalidCharacter {
+            line: number,
+            column: column + 1,
+            character,
+        };
+
+        // Lines that are neither are skipped by the orchestrator, so anything in them is
+        // lost from the sums.
+        let Some(kind) = LineKind::of(line.chars().next()) else {
+            match line
+                .char_indices()
+                .find(|(_, character)| !character.is_whitespace())
+            {
+                Some((column, character)) => return Err(invalid(column, character)),
+                None => continue,
+            }
+        };
+
+        let length = line.chars().count();
+        let width = *width.get_or_insert(length);
+        if length != width {
+            return Err(AlignmentError::RaggedLine {
+                line: number,
+                column: length.min(width) + 1,
+                width,
+            });
+        }
+        has_digits.resize(width, false);
+
+        match kind {
+            LineKind::Numeric => {
+                for (column, character) in line.chars().enumerate() {
+                    match character {
+                        '0'..='9' => has_digits[column] = true,
+                        ' ' => {}
+                        _ => return Err(invalid(column, character)),
+                    }
+                }
+            }
+            LineKind::Operator => {
+                let symbols = line
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, character)| *character != ' ')
+                    .map(
+                        |(column, character)| match Operator::from_symbol(character) {
+                            Some(_) => Ok((column, character)),
+                            None => Err(invalid(column, character)),
+                        },
+                    )
+                    .collect::<Result<Vec<_>, _>>()?;
+                operator_lines.push((number, symbols));
+            }
+        }
+    }
+
+    let [(line, symbols)] = operator_lines.as_slice() else {
+        return Err(AlignmentError::OperatorLines {
+            found: operator_lines.len(),
+        });
+    };
+    let starts_problem =
+        |column: usize| has_digits[column] && (column == 0 || !has_digits[column - 1]);
+
+    for &(column, symbol) in symbols {
+        if !starts_problem(column) {
+            return Err(AlignmentError::MisalignedOperator {
+                line: *line,
+                column: column + 1,
+                symbol,
+            });
+        }
+    }
+
+    match (0..has_digits.len())
+        .filter(|&column| starts_problem(column))
+        .find(|column| {
+            !symbols
+                .iter()
+                .any(|(symbol_column, _)| symbol_column == column)
+        }) {
+        Some(column) => Err(AlignmentError::MissingOperator {
+            line: *line,
+            column: column + 1,
+        }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test_validate {
+    use super::*;
+
+    const TEST_INPUT: &str = "123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+*   +   *   +  ";
+
+    fn validate(input: &str) -> Result<(), AlignmentError> {
+        validate_alignment(input.as_bytes())
+    }
+
+    #[test]
+    fn test_aligned() {
+        validate(TEST_INPUT).expect("Example should be aligned");
+        validate(&format!("\n{}\n\n", TEST_INPUT.replace('\n', "\r\n")))
+            .expect("Example should be aligned with blank lines and CRLF");
+    }
+
+    #[test]
+    fn test_ragged_line() {
+        let input = TEST_INPUT.replace(" 45 64  387 23 ", " 45 64  387 23");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::RaggedLine {
+                line: 2,
+                column: 15,
+                width: 15
+            })
+        ));
+
+        let input = TEST_INPUT.replace("*   +   *   +  ", "*   +   *   +");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::RaggedLine {
+                line: 4,
+                column: 14,
+                width: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn test_misaligned_operator() {
+        let input = TEST_INPUT.replace("*   +   *   +  ", "*   +    *  +  ");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::MisalignedOperator {
+                line: 4,
+                column: 10,
+                symbol: '*'
+            })
+        ));
+    }
+
+    #[test]
+    fn test_missing_operator() {
+        let input = TEST_INPUT.replace("*   +   *   +  ", "*   +       +  ");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::MissingOperator { line: 4, column: 9 })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_character() {
+        let input = TEST_INPUT.replace("  6 98", "x 6 98");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::InvalidCharacter {
+                line: 3,
+                column: 1,
+                character: 'x'
+            })
+        ));
+
+        let input = TEST_INPUT.replace("387", "3x7");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::InvalidCharacter {
+                line: 2,
+                column: 10,
+                character: 'x'
+            })
+        ));
+    }
+
+    #[test]
+    fn test_operator_lines() {
+        let input = TEST_INPUT.lines().take(3).collect::<Vec<_>>().join("\n");
+        assert!(matches!(
+            validate(&input),
+            Err(AlignmentError::OperatorLines { found: 0 })
+        ));
+    }
+}
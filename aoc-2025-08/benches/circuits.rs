@@ -0,0 +1,75 @@
+//! Benchmarks of the union-find [`CircuitTracker`] against the two-map
+//! [`LegacyCircuitTracker`] it replaced.
+//!
+//! The ``input`` joins are those of the real input, in order of distance until every
+//! junction box is in one circuit, found once up front so that only the trackers are
+//! timed. The ``worst`` joins always join a new node with the growing circuit as
+//! ``node_b``, so that the legacy tracker relabels the whole circuit every time.
+//!
+//! Run with ``cargo bench -p aoc-2025-08 --features legacy-tracker``.
+
+use aoc_2025_08::{
+    INPUT,
+    models::{CircuitTracker, LegacyCircuitTracker, NodesList, Relation},
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// The joins made for ``input`` until every node is in one circuit.
+fn input_joins(input: &str) -> (usize, Vec<(usize, usize)>) {
+    let nodes_list: NodesList =
+        NodesList::build_from_text(input).expect("failed to build nodes list");
+    let mut tracker = CircuitTracker::with_capacity(nodes_list.len());
+
+    let joins = nodes_list
+        .iter_closest_neighbours()
+        .expect("failed to build nodes heap")
+        .map(|Relation { node_a, node_b, .. }| (node_a, node_b))
+        .take_while(|&(node_a, node_b)| {
+            let more = tracker.total_circuits() > 1;
+            tracker.join(node_a, node_b);
+            more
+        })
+        .collect();
+
+    (nodes_list.len(), joins)
+}
+
+/// Join each of ``len`` nodes in turn to the circuit of node ``0``.
+fn worst_joins(len: usize) -> (usize, Vec<(usize, usize)>) {
+    (len, (1..len).map(|node| (node, 0)).collect())
+}
+
+fn bench_circuits(c: &mut Criterion) {
+    let mut group = c.benchmark_group("circuits");
+    group.sample_size(10);
+
+    for (name, (len, joins)) in [
+        ("input", input_joins(INPUT)),
+        ("worst/10000", worst_joins(10_000)),
+    ] {
+        group.bench_function(BenchmarkId::new("union-find", name), |b| {
+            b.iter(|| {
+                let mut tracker = CircuitTracker::with_capacity(len);
+                for &(node_a, node_b) in black_box(&joins) {
+                    tracker.join(node_a, node_b);
+                }
+                tracker.total_circuits()
+            })
+        });
+        group.bench_function(BenchmarkId::new("legacy", name), |b| {
+            b.iter(|| {
+                let mut tracker = LegacyCircuitTracker::with_capacity(len);
+                for &(node_a, node_b) in black_box(&joins) {
+                    tracker.join(node_a, node_b);
+                }
+                tracker.total_circuits()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_circuits);
+criterion_main!(benches);
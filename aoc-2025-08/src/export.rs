@@ -0,0 +1,387 @@
+//! Exporting junction boxes and the connections between them as a 3D scene, to see how
+//! the circuits grow in a 3D viewer.
+//!
+//! Three formats are written, all as plain text:
+//!
+//! - [PLY], with a vertex per junction box coloured by its circuit, and an edge per
+//!   connection;
+//! - [OBJ], with a vertex per junction box and a line per connection, grouped by
+//!   circuit; and
+//! - a simple JSON scene, with the nodes, connections and circuits as lists of objects.
+//!
+//! [PLY]: https://paulbourke.net/dataformats/ply/
+//! [OBJ]: https://paulbourke.net/dataformats/obj/
+
+use crate::models::{CircuitEvent, CircuitTracker, Node, NodeDistanceType, NodesList};
+use serde::Serialize;
+use std::{io, path::Path, str::FromStr};
+
+/// A format to export a [`Scene`] to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    Ply,
+    Obj,
+    Json,
+}
+
+impl SceneFormat {
+    /// The format of ``path``, by its extension.
+    pub fn from_path(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or_else(|| anyhow::anyhow!("No extension to tell the format of {:?}", path))?
+            .parse()
+    }
+}
+
+impl FromStr for SceneFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "ply" => Ok(Self::Ply),
+            "obj" => Ok(Self::Obj),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!(
+                "Unknown scene format {:?}; expected ply, obj or json",
+                value
+            ),
+        }
+    }
+}
+
+/// A connection in a [`Scene`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SceneConnection {
+    pub node_a: usize,
+    pub node_b: usize,
+    pub distance: NodeDistanceType,
+}
+
+/// The junction boxes of a [`NodesList`], with the connections that joined them into
+/// circuits so far.
+#[derive(Debug, Clone)]
+pub struct Scene<'a> {
+    nodes: &'a [Node],
+    connections: Vec<SceneConnection>,
+    /// The circuit ID of each node.
+    circuits: Vec<usize>,
+    /// The size of the circuit of each node.
+    circuit_sizes: Vec<usize>,
+}
+
+/// The JSON form of a node of a [`Scene`].
+#[derive(Serialize)]
+struct JsonNode {
+    id: usize,
+    position: Node,
+    circuit: usize,
+}
+
+/// The JSON form of a circuit of a [`Scene`].
+#[derive(Serialize)]
+struct JsonCircuit {
+    id: usize,
+    size: usize,
+    colour: [u8; 3],
+}
+
+/// The JSON form of a whole [`Scene`].
+#[derive(Serialize)]
+struct JsonScene<'s> {
+    nodes: Vec<JsonNode>,
+    connections: &'s [SceneConnection],
+    circuits: Vec<JsonCircuit>,
+}
+
+impl<'a> Scene<'a> {
+    /// Build a scene of the nodes of ``list``, connected by each of ``events`` that
+    /// joined two circuits; connections within a circuit are left out.
+    pub fn from_events(
+        list: &'a NodesList,
+        events: impl IntoIterator<Item = CircuitEvent>,
+    ) -> Self {
+        let connections = events
+            .into_iter()
+            .filter(CircuitEvent::is_join)
+            .map(|event| SceneConnection {
+                node_a: event.node_a,
+                node_b: event.node_b,
+                distance: event.distance,
+            })
+            .collect::<Vec<_>>();
+
+        let mut tracker = CircuitTracker::with_capacity(list.len());
+        for connection in &connections {
+            tracker.join(connection.node_a, connection.node_b);
+        }
+
+        let circuits = (0..list.len())
+            .map(|node| tracker.get_circuit_of(node))
+            .collect::<Vec<_>>();
+        let circuit_sizes = circuits
+            .iter()
+            .map(|&circuit_id| tracker.get_circuit_size(circuit_id))
+            .collect();
+
+        Self {
+            nodes: &list.nodes,
+            connections,
+            circuits,
+            circuit_sizes,
+        }
+    }
+
+    /// The connections in the scene, in the order they were made.
+    pub fn connections(&self) -> &[SceneConnection] {
+        &self.connections
+    }
+
+    /// The colour of ``node``, by its circuit; grey if it is in a circuit of its own.
+    ///
+    /// Circuit IDs are spread around the hue circle by the golden angle, so that circuits
+    /// with close IDs still get distinct colours.
+    pub fn colour_of(&self, node: usize) -> [u8; 3] {
+        if self.circuit_sizes[node] <= 1 {
+            return [0x80, 0x80, 0x80];
+        }
+        hue_to_rgb((self.circuits[node] as f32 * 137.507_77) % 360.0)
+    }
+
+    /// Write the scene to ``writer`` as ``format``.
+    pub fn write(&self, format: SceneFormat, writer: impl io::Write) -> io::Result<()> {
+        match format {
+            SceneFormat::Ply => self.write_ply(writer),
+            SceneFormat::Obj => self.write_obj(writer),
+            SceneFormat::Json => self.write_json(writer),
+        }
+    }
+
+    /// Write the scene to ``path``, in the format its extension names.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let format = SceneFormat::from_path(&path)?;
+        let file = std::fs::File::create(&path)?;
+        self.write(format, io::BufWriter::new(file))?;
+        Ok(())
+    }
+
+    /// Write the scene as an ASCII PLY file, with a coloured vertex per node and an edge
+    /// per connection.
+    pub fn write_ply(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "element vertex {}", self.nodes.len())?;
+        for property in ["float x", "float y", "float z"] {
+            writeln!(writer, "property {}", property)?;
+        }
+        for property in ["uchar red", "uchar green", "uchar blue"] {
+            writeln!(writer, "property {}", property)?;
+        }
+        writeln!(writer, "element edge {}", self.connections.len())?;
+        writeln!(writer, "property int vertex1")?;
+        writeln!(writer, "property int vertex2")?;
+        writeln!(writer, "end_header")?;
+
+        for (node, [x, y, z]) in self.nodes.iter().enumerate() {
+            let [red, green, blue] = self.colour_of(node);
+            writeln!(writer, "{} {} {} {} {} {}", x, y, z, red, green, blue)?;
+        }
+        for connection in &self.connections {
+            writeln!(writer, "{} {}", connection.node_a, connection.node_b)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the scene as a Wavefront OBJ file, with a vertex per node and a line per
+    /// connection, each circuit of more than one node as a group of its own.
+    pub fn write_obj(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for [x, y, z] in self.nodes {
+            writeln!(writer, "v {} {} {}", x, y, z)?;
+        }
+
+        let mut connections = self.connections.iter().collect::<Vec<_>>();
+        connections.sort_by_key(|connection| self.circuits[connection.node_a]);
+        let mut group = None;
+        for connection in connections {
+            let circuit_id = self.circuits[connection.node_a];
+            if group != Some(circuit_id) {
+                writeln!(writer, "g circuit_{}", circuit_id)?;
+                group = Some(circuit_id);
+            }
+            // OBJ counts vertices from 1.
+            writeln!(
+                writer,
+                "l {} {}",
+                connection.node_a + 1,
+                connection.node_b + 1
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the scene as JSON, with lists of ``nodes``, ``connections`` and
+    /// ``circuits``.
+    pub fn write_json(&self, writer: impl io::Write) -> io::Result<()> {
+        let nodes = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(id, &position)| JsonNode {
+                id,
+                position,
+                circuit: self.circuits[id],
+            })
+            .collect();
+
+        // Circuit IDs are inherited from one of their members, so each circuit is listed
+        // once by the node sharing its ID.
+        let circuits = (0..self.nodes.len())
+            .filter(|&node| self.circuits[node] == node)
+            .map(|id| JsonCircuit {
+                id,
+                size: self.circuit_sizes[id],
+                colour: self.colour_of(id),
+            })
+            .collect();
+
+        serde_json::to_writer(
+            writer,
+            &JsonScene {
+                nodes,
+                connections: &self.connections,
+                circuits,
+            },
+        )
+        .map_err(io::Error::from)
+    }
+}
+
+/// The fully saturated colour at ``hue`` degrees.
+fn hue_to_rgb(hue: f32) -> [u8; 3] {
+    let channel = |offset: f32| {
+        let k = (offset + hue / 60.0) % 6.0;
+        let value = 1.0 - k.min(4.0 - k).clamp(0.0, 1.0);
+        (value * 255.0).round() as u8
+    };
+    [channel(5.0), channel(3.0), channel(1.0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "162,817,812
+                              57,618,57
+                              906,360,560
+                              592,479,940
+                              352,342,300
+                              466,668,158
+                              542,29,236
+                              431,825,988
+                              739,650,466
+                              52,470,668
+                              216,146,977
+                              819,987,18
+                              117,168,530
+                              805,96,715
+                              346,949,466
+                              970,615,88
+                              941,993,340
+                              862,61,35
+                              984,92,344
+                              425,690,689";
+
+    /// The example after its first ten connections, of which nine join two circuits.
+    fn with_scene(callback: impl FnOnce(&Scene)) {
+        let nodes_list: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
+        let events = nodes_list
+            .iter_closest_neighbours()
+            .expect("failed to build nodes heap")
+            .circuit_events()
+            .take(10);
+        callback(&Scene::from_events(&nodes_list, events));
+    }
+
+    fn export(scene: &Scene, format: SceneFormat) -> String {
+        let mut buffer = Vec::new();
+        scene
+            .write(format, &mut buffer)
+            .expect("failed to write scene");
+        String::from_utf8(buffer).expect("scene is not UTF-8")
+    }
+
+    #[test]
+    fn test_scene_format() {
+        assert_eq!(
+            SceneFormat::from_path("out/scene.PLY").ok(),
+            Some(SceneFormat::Ply)
+        );
+        assert_eq!(
+            SceneFormat::from_path("scene.obj").ok(),
+            Some(SceneFormat::Obj)
+        );
+        assert_eq!("json".parse::<SceneFormat>().ok(), Some(SceneFormat::Json));
+        assert!(SceneFormat::from_path("scene.stl").is_err());
+        assert!(SceneFormat::from_path("scene").is_err());
+    }
+
+    #[test]
+    fn test_write_ply() {
+        with_scene(|scene| {
+            assert_eq!(scene.connections().len(), 9);
+
+            let ply = export(scene, SceneFormat::Ply);
+            let (header, body) = ply.split_once("end_header\n").expect("no PLY header");
+            assert!(header.contains("element vertex 20\n"));
+            assert!(header.contains("element edge 9\n"));
+
+            let lines = body.lines().collect::<Vec<_>>();
+            assert_eq!(lines.len(), 29);
+            assert!(lines[0].starts_with("162 817 812 "));
+            assert_eq!(lines[1], "57 618 57 128 128 128");
+        });
+    }
+
+    #[test]
+    fn test_write_obj() {
+        with_scene(|scene| {
+            let obj = export(scene, SceneFormat::Obj);
+
+            assert_eq!(
+                obj.lines().filter(|line| line.starts_with("v ")).count(),
+                20
+            );
+            assert_eq!(obj.lines().filter(|line| line.starts_with("l ")).count(), 9);
+            // One group per circuit of more than one junction box.
+            assert_eq!(obj.lines().filter(|line| line.starts_with("g ")).count(), 4);
+            assert!(obj.contains("\nl 20 1\n"));
+        });
+    }
+
+    #[test]
+    fn test_write_json() {
+        with_scene(|scene| {
+            let json: serde_json::Value =
+                serde_json::from_str(&export(scene, SceneFormat::Json)).expect("invalid JSON");
+
+            assert_eq!(json["nodes"].as_array().map(Vec::len), Some(20));
+            assert_eq!(json["connections"].as_array().map(Vec::len), Some(9));
+            assert_eq!(
+                json["nodes"][0]["position"],
+                serde_json::json!([162.0, 817.0, 812.0])
+            );
+
+            let mut sizes = json["circuits"]
+                .as_array()
+                .expect("no circuits")
+                .iter()
+                .map(|circuit| circuit["size"].as_u64().expect("no size"))
+                .collect::<Vec<_>>();
+            sizes.sort_unstable_by(|a, b| b.cmp(a));
+            assert_eq!(sizes, vec![5, 4, 2, 2, 1, 1, 1, 1, 1, 1, 1]);
+        });
+    }
+}
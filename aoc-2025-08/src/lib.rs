@@ -0,0 +1,10 @@
+//! Joining junction boxes into circuits by distance, from Day 8 of 2025, as a library so
+//! that the models can be benchmarked and reused outside of the puzzle.
+
+pub mod models;
+
+mod input;
+pub use input::INPUT;
+
+#[cfg(feature = "export")]
+pub mod export;
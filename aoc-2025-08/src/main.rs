@@ -109,105 +109,222 @@
 //!
 //! [straight-line distance]: https://en.wikipedia.org/wiki/Euclidean_distance
 
-mod input;
-pub mod models;
-use input::INPUT;
+#[cfg(feature = "export")]
+use aoc_2025_08::export::Scene;
+use aoc_2025_08::{INPUT, models};
 
-#[cfg(feature = "profile")]
-use std::time::Instant;
+/// The number of connections to make for part 1 of the puzzle.
+const DEFAULT_CONNECTIONS: usize = 1000;
 
-fn generate_circuit_map<'a>(
-    iter_closest_neighbours: models::ClosestNeighboursIterator<'a>,
-    steps: Option<usize>,
-) -> anyhow::Result<(models::CircuitTracker, Vec<models::CircuitOperation>)> {
-    let length = iter_closest_neighbours.nodes_list_len();
-    let mut circuit_tracker = models::CircuitTracker::with_capacity(length);
+/// The command line arguments.
+///
+/// If neither ``--connections`` nor ``--until-single-circuit`` is given, both parts are
+/// answered, with [`DEFAULT_CONNECTIONS`] for part 1.
+#[derive(Debug, Default)]
+struct Args {
+    /// From ``--input <path>``, to read the junction boxes from a file rather than using
+    /// the ones built in.
+    input: Option<String>,
+    /// From ``--connections <N>``, to answer part 1 after the ``N`` closest pairs are
+    /// connected.
+    connections: Option<usize>,
+    /// From ``--until-single-circuit``, to answer part 2 by connecting pairs until every
+    /// junction box is in one circuit.
+    until_single_circuit: bool,
+    /// From ``--cross-check``, to check the lazy heap of relations against sorting every
+    /// pair up front before answering.
+    #[cfg(feature = "cross-check")]
+    cross_check: bool,
+    /// From ``--export <path>``, to write the junction boxes and the connections made to
+    /// a PLY, OBJ or JSON scene, by the extension of ``path``; as of the connections of
+    /// part 1 if it is answered, otherwise once every junction box is in one circuit.
+    #[cfg(feature = "export")]
+    export: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            if arg == "--until-single-circuit" {
+                parsed.until_single_circuit = true;
+                continue;
+            }
+            #[cfg(feature = "cross-check")]
+            if arg == "--cross-check" {
+                parsed.cross_check = true;
+                continue;
+            }
+
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                    (arg, value)
+                }
+            };
+
+            match name.as_str() {
+                "--input" => parsed.input = Some(value),
+                #[cfg(feature = "export")]
+                "--export" => parsed.export = Some(value),
+                "--connections" => {
+                    parsed.connections = Some(value.parse().map_err(|e| {
+                        anyhow::anyhow!("Invalid number of connections {:?}: {}", value, e)
+                    })?)
+                }
+                _ => anyhow::bail!("Unknown argument {:?}", name),
+            }
+        }
 
-    let mut op_history = Vec::with_capacity(steps.unwrap_or(length));
-    let mut step_count = 0;
-    for models::Relation { node_a, node_b, .. } in iter_closest_neighbours {
-        let op = circuit_tracker.join(node_a, node_b);
+        if parsed.connections.is_none() && !parsed.until_single_circuit {
+            parsed.connections = Some(DEFAULT_CONNECTIONS);
+            parsed.until_single_circuit = true;
+        }
+        Ok(parsed)
+    }
+}
 
-        op_history.push(op);
+/// The answers found in one pass over the relations; each is [`None`] if it was not
+/// asked for.
+#[derive(Debug, Default, PartialEq)]
+struct Answers {
+    /// The product of the sizes of the three largest circuits after the connections of
+    /// part 1.
+    largest_circuits_product: Option<usize>,
+    /// The two nodes whose connection first joined every node into a single circuit.
+    last_join: Option<(usize, usize)>,
+}
 
-        step_count += 1;
+/// Connect the closest pairs of ``iter_closest_neighbours`` in turn, answering part 1
+/// after ``connections`` pairs if given, and part 2 if ``until_single_circuit``;
+/// stopping as soon as both are answered.
+///
+/// If the relations run out before ``connections`` pairs are connected, part 1 is
+/// answered with the circuits as they are; part 2 is left unanswered if the nodes never
+/// end up in a single circuit.
+fn solve(
+    iter_closest_neighbours: models::ClosestNeighboursIterator<'_>,
+    connections: Option<usize>,
+    until_single_circuit: bool,
+) -> Answers {
+    let mut events = iter_closest_neighbours.circuit_events();
+    let mut answers = Answers::default();
 
-        if let Some(max_steps) = steps
-            && step_count >= max_steps
+    for event in &mut events {
+        if connections == Some(event.step + 1) {
+            answers.largest_circuits_product = Some(event.top_three_product);
+        }
+        if until_single_circuit
+            && answers.last_join.is_none()
+            && event.is_join()
+            && event.total_circuits <= 1
         {
-            break;
+            answers.last_join = Some((event.node_a, event.node_b));
         }
 
-        if circuit_tracker.total_circuits() <= 1 {
+        let part1_done = connections.is_none() || answers.largest_circuits_product.is_some();
+        let part2_done = !until_single_circuit || answers.last_join.is_some();
+        if part1_done && part2_done {
             break;
         }
     }
 
-    Ok((circuit_tracker, op_history))
+    if connections.is_some() && answers.largest_circuits_product.is_none() {
+        answers.largest_circuits_product = Some(events.top_three_product());
+    }
+
+    answers
 }
 
 fn main() {
-    let nodes_list =
-        models::NodesList::build_from_text(INPUT).expect("failed to build nodes list from input");
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
 
-    #[cfg(feature = "profile")]
-    let start_time = Instant::now();
-    '_part1: {
-        let iter_closest_neighbours = nodes_list
-            .iter_closest_neighbours()
-            .expect("failed to build nodes heap");
+    let text = match &args.input {
+        Some(path) => std::fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("Failed to read {}: {}", path, err);
+            std::process::exit(1);
+        }),
+        None => INPUT.to_owned(),
+    };
+    let nodes_list: models::NodesList =
+        models::NodesList::build_from_text(&text).expect("failed to build nodes list from input");
 
-        let (circuit_tracker, _) = generate_circuit_map(iter_closest_neighbours, Some(1000))
-            .expect("failed to generate circuit map");
-
-        println!(
-            "Part 1: {}",
-            circuit_tracker
-                .circuits_by_size()
-                .into_iter()
-                .take(3)
-                .fold(1, |acc, (_, size)| acc * size)
-        );
-    }
-    #[cfg(feature = "profile")]
-    {
-        let duration = Instant::now() - start_time;
-        eprintln!("Execution time: {:?}", duration);
+    #[cfg(feature = "cross-check")]
+    if args.cross_check {
+        match models::cross_check(&nodes_list) {
+            Ok(steps) => eprintln!("Cross-checked {} connections against Kruskal", steps),
+            Err(err) => {
+                eprintln!("Cross-check failed: {}", err);
+                std::process::exit(1);
+            }
+        }
     }
 
     #[cfg(feature = "profile")]
-    let start_time = Instant::now();
-    '_part2: {
-        let iter_closest_neighbours = nodes_list
-            .iter_closest_neighbours()
-            .expect("failed to build nodes heap");
-        let (_, full_op_history) = generate_circuit_map(iter_closest_neighbours, None)
-            .expect("failed to generate full circuit map");
-
-        let (node_a, node_b) = full_op_history
-            .iter()
-            .rev()
-            .find_map(|op| match op {
-                models::CircuitOperation::Join { node_a, node_b, .. } => {
-                    let node_a_coords = nodes_list
-                        .get_node_by_id(*node_a)
-                        .expect("node_a not found");
-                    let node_b_coords = nodes_list
-                        .get_node_by_id(*node_b)
-                        .expect("node_b not found");
-
-                    Some((*node_a_coords, *node_b_coords))
-                }
-                _ => None,
+    let start_time = std::time::Instant::now();
+
+    let iter_closest_neighbours = nodes_list
+        .iter_closest_neighbours()
+        .expect("failed to build nodes heap");
+    let answers = solve(
+        iter_closest_neighbours,
+        args.connections,
+        args.until_single_circuit,
+    );
+
+    if let Some(product) = answers.largest_circuits_product {
+        println!("Part 1: {}", product);
+    }
+    if args.until_single_circuit {
+        let (node_a, node_b) = answers
+            .last_join
+            .map(|(node_a, node_b)| {
+                (
+                    *nodes_list.get_node_by_id(node_a).expect("node_a not found"),
+                    *nodes_list.get_node_by_id(node_b).expect("node_b not found"),
+                )
             })
-            .expect("no join operations found");
+            .expect("nodes were never joined into a single circuit");
 
         println!("Part 2 nodes: {:?} and {:?}", node_a, node_b);
         println!("Part 2 answer: {:?}", node_a[0] as u64 * node_b[0] as u64);
     }
+
+    #[cfg(feature = "export")]
+    if let Some(path) = &args.export {
+        let events = nodes_list
+            .iter_closest_neighbours()
+            .expect("failed to build nodes heap")
+            .circuit_events();
+        let scene = match args.connections {
+            Some(connections) => Scene::from_events(&nodes_list, events.take(connections)),
+            None => {
+                // Up to and including the connection that leaves a single circuit.
+                let mut done = false;
+                Scene::from_events(
+                    &nodes_list,
+                    events.take_while(|event| {
+                        !std::mem::replace(&mut done, event.total_circuits <= 1)
+                    }),
+                )
+            }
+        };
+        if let Err(err) = scene.save(path) {
+            eprintln!("Failed to export {}: {}", path, err);
+            std::process::exit(1);
+        }
+    }
+
     #[cfg(feature = "profile")]
     {
-        let duration = Instant::now() - start_time;
+        let duration = start_time.elapsed();
         eprintln!("Execution time: {:?}", duration);
     }
 }
@@ -237,27 +354,73 @@ mod tests {
                               984,92,344
                               425,690,689";
 
-    #[test]
-    fn test_example() {
-        let nodes_list = models::NodesList::build_from_text(TEST_INPUT).unwrap();
+    fn answers(connections: Option<usize>, until_single_circuit: bool) -> Answers {
+        let nodes_list: models::NodesList = models::NodesList::build_from_text(TEST_INPUT).unwrap();
         let iter_closest_neighbours = nodes_list
             .iter_closest_neighbours()
             .expect("failed to build nodes heap");
-        let (circuit_tracker, _) = generate_circuit_map(iter_closest_neighbours, Some(10))
-            .expect("failed to generate circuit map");
+        solve(iter_closest_neighbours, connections, until_single_circuit)
+    }
+
+    #[test]
+    fn test_example() {
+        let nodes_list: models::NodesList = models::NodesList::build_from_text(TEST_INPUT).unwrap();
+        let answers = answers(Some(10), true);
 
-        let expected_counts = vec![5, 4, 2, 2, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(answers.largest_circuits_product, Some(40));
 
-        for (circuit_id, size) in circuit_tracker.circuits_by_size() {
-            eprintln!("circuit {} has size {}", circuit_id, size);
-        }
+        let (node_a, node_b) = answers.last_join.expect("never joined into one circuit");
+        assert_eq!(
+            nodes_list.get_node_by_id(node_a).unwrap()[0]
+                * nodes_list.get_node_by_id(node_b).unwrap()[0],
+            25272.0
+        );
+    }
+
+    #[test]
+    fn test_each_part_alone() {
+        let both = answers(Some(10), true);
+
+        assert_eq!(
+            answers(Some(10), false),
+            Answers {
+                largest_circuits_product: both.largest_circuits_product,
+                last_join: None,
+            }
+        );
+        assert_eq!(
+            answers(None, true),
+            Answers {
+                largest_circuits_product: None,
+                last_join: both.last_join,
+            }
+        );
+        // More connections than there are relations.
         assert_eq!(
-            circuit_tracker
-                .circuits_by_size()
-                .into_iter()
-                .map(|(_, size)| size)
-                .collect::<Vec<usize>>(),
-            expected_counts
-        )
+            answers(Some(1000), false).largest_circuits_product,
+            Some(20)
+        );
+    }
+
+    #[test]
+    fn test_args() {
+        let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.connections, Some(DEFAULT_CONNECTIONS));
+        assert!(args.until_single_circuit);
+
+        let args = parse(&["--connections=10", "--input", "input.txt"]).unwrap();
+        assert_eq!(args.connections, Some(10));
+        assert_eq!(args.input.as_deref(), Some("input.txt"));
+        assert!(!args.until_single_circuit);
+
+        let args = parse(&["--until-single-circuit"]).unwrap();
+        assert_eq!(args.connections, None);
+        assert!(args.until_single_circuit);
+
+        assert!(parse(&["--connections", "ten"]).is_err());
+        assert!(parse(&["--connections"]).is_err());
+        assert!(parse(&["--steps=10"]).is_err());
     }
 }
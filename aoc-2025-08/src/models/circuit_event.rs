@@ -0,0 +1,229 @@
+use super::{CircuitTracker, ClosestNeighboursIterator, NodeDistanceType, Relation};
+use std::collections::BTreeMap;
+
+/// One connection made between two nodes, and what it did to the circuits.
+///
+/// Connecting two nodes already in the same circuit is still an event, with the same
+/// circuit on both sides; see [`Self::is_join`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitEvent {
+    /// The number of connections made before this one.
+    pub step: usize,
+    pub node_a: usize,
+    pub node_b: usize,
+    pub distance: NodeDistanceType,
+    /// The sizes of the circuits of ``node_a`` and ``node_b`` before they were connected.
+    pub sizes_before: (usize, usize),
+    /// The size of the circuit containing both nodes after they were connected.
+    pub size_after: usize,
+    /// The number of circuits after the connection.
+    pub total_circuits: usize,
+    /// The product of the sizes of the three largest circuits after the connection, as
+    /// asked for by part 1 of the puzzle.
+    pub top_three_product: usize,
+}
+
+impl CircuitEvent {
+    /// Whether the connection merged two circuits into one.
+    pub fn is_join(&self) -> bool {
+        self.size_after > self.sizes_before.0
+    }
+}
+
+/// An iterator of [`CircuitEvent`]s, joining the two nodes of each [`Relation`] in turn.
+///
+/// Alongside the [`CircuitTracker`], the number of circuits of each size is kept, so that
+/// the product of the three largest is found in ``O(log N)`` at every step rather than
+/// by sorting every circuit with [`CircuitTracker::circuits_by_size`].
+pub struct CircuitEvents<I> {
+    relations: I,
+    tracker: CircuitTracker,
+    /// The number of circuits of each size.
+    size_counts: BTreeMap<usize, usize>,
+    step: usize,
+}
+
+impl<I: Iterator<Item = Relation>> CircuitEvents<I> {
+    /// Join the nodes of ``relations`` in turn, from ``length`` nodes each in its own
+    /// circuit.
+    pub fn new(relations: I, length: usize) -> Self {
+        Self {
+            relations,
+            tracker: CircuitTracker::with_capacity(length),
+            size_counts: BTreeMap::from_iter((length > 0).then_some((1, length))),
+            step: 0,
+        }
+    }
+
+    /// The circuits as of the last event.
+    pub fn tracker(&self) -> &CircuitTracker {
+        &self.tracker
+    }
+
+    /// Take the circuits as of the last event.
+    pub fn into_tracker(self) -> CircuitTracker {
+        self.tracker
+    }
+
+    /// The product of the sizes of the three largest circuits as of the last event; or
+    /// of all of them, if there are fewer than three.
+    pub fn top_three_product(&self) -> usize {
+        self.size_counts
+            .iter()
+            .rev()
+            .flat_map(|(&size, &count)| std::iter::repeat_n(size, count))
+            .take(3)
+            .product()
+    }
+
+    /// Count one fewer circuit of ``size``.
+    fn remove_size(&mut self, size: usize) {
+        if let Some(count) = self.size_counts.get_mut(&size) {
+            *count -= 1;
+            if *count == 0 {
+                self.size_counts.remove(&size);
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = Relation>> Iterator for CircuitEvents<I> {
+    type Item = CircuitEvent;
+
+    fn next(&mut self) -> Option<CircuitEvent> {
+        let Relation {
+            node_a,
+            node_b,
+            distance,
+        } = self.relations.next()?;
+
+        let size_of =
+            |tracker: &CircuitTracker, node| tracker.get_circuit_size(tracker.get_circuit_of(node));
+        let sizes_before = (
+            size_of(&self.tracker, node_a),
+            size_of(&self.tracker, node_b),
+        );
+
+        self.tracker.join(node_a, node_b);
+        let size_after = size_of(&self.tracker, node_a);
+
+        if size_after > sizes_before.0 {
+            self.remove_size(sizes_before.0);
+            self.remove_size(sizes_before.1);
+            *self.size_counts.entry(size_after).or_default() += 1;
+        }
+
+        let event = CircuitEvent {
+            step: self.step,
+            node_a,
+            node_b,
+            distance,
+            sizes_before,
+            size_after,
+            total_circuits: self.tracker.total_circuits(),
+            top_three_product: self.top_three_product(),
+        };
+        self.step += 1;
+
+        Some(event)
+    }
+}
+
+impl<'a, const N: usize> ClosestNeighboursIterator<'a, N> {
+    /// Join the closest pairs of nodes in turn, yielding a [`CircuitEvent`] for each.
+    pub fn circuit_events(self) -> CircuitEvents<Self> {
+        let length = self.nodes_list_len();
+        CircuitEvents::new(self, length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NodesList;
+
+    const TEST_INPUT: &str = "162,817,812
+                              57,618,57
+                              906,360,560
+                              592,479,940
+                              352,342,300
+                              466,668,158
+                              542,29,236
+                              431,825,988
+                              739,650,466
+                              52,470,668
+                              216,146,977
+                              819,987,18
+                              117,168,530
+                              805,96,715
+                              346,949,466
+                              970,615,88
+                              941,993,340
+                              862,61,35
+                              984,92,344
+                              425,690,689";
+
+    #[test]
+    fn test_example_events() {
+        let nodes_list: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
+        let events = nodes_list
+            .iter_closest_neighbours()
+            .expect("failed to build nodes heap")
+            .circuit_events()
+            .collect::<Vec<_>>();
+
+        let summary = |event: &CircuitEvent| {
+            (
+                event.sizes_before,
+                event.size_after,
+                event.total_circuits,
+                event.is_join(),
+            )
+        };
+        // The first four connections described in the puzzle.
+        assert_eq!(
+            events[..4].iter().map(summary).collect::<Vec<_>>(),
+            vec![
+                ((1, 1), 2, 19, true),
+                ((1, 2), 3, 18, true),
+                ((1, 1), 2, 17, true),
+                ((3, 3), 3, 17, false),
+            ]
+        );
+        assert_eq!(events[9].top_three_product, 40);
+
+        let last = events
+            .iter()
+            .find(|event| event.total_circuits == 1)
+            .expect("never joined into one circuit");
+        assert_eq!(last.size_after, 20);
+        assert_eq!(last.top_three_product, 20);
+        assert_eq!(
+            nodes_list.nodes[last.node_a][0] * nodes_list.nodes[last.node_b][0],
+            25272.0
+        );
+
+        assert!(events.iter().enumerate().all(|(i, event)| event.step == i));
+    }
+
+    #[test]
+    fn test_matches_tracker() {
+        let nodes_list: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
+        let mut tracker = CircuitTracker::with_capacity(nodes_list.len());
+
+        for event in nodes_list
+            .iter_closest_neighbours()
+            .expect("failed to build nodes heap")
+            .circuit_events()
+        {
+            tracker.join(event.node_a, event.node_b);
+
+            let by_size = tracker.circuits_by_size();
+            assert_eq!(event.total_circuits, by_size.len());
+            assert_eq!(
+                event.top_three_product,
+                by_size.iter().take(3).map(|&(_, size)| size).product::<usize>()
+            );
+        }
+    }
+}
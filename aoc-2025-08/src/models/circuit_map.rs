@@ -0,0 +1,135 @@
+use super::{CircuitTracker, ClosestNeighboursIterator, Node, NodesList};
+
+/// Connect the closest pairs of ``iter_closest_neighbours`` in turn until only
+/// ``circuits_remaining`` circuits are left, returning the circuits at that point.
+///
+/// If there are no more than ``circuits_remaining`` nodes to start with, nothing is
+/// connected; if the relations run out first, the circuits are as they were at the end.
+pub fn generate_circuit_map_until<const N: usize>(
+    iter_closest_neighbours: ClosestNeighboursIterator<'_, N>,
+    circuits_remaining: usize,
+) -> CircuitTracker {
+    let length = iter_closest_neighbours.nodes_list_len();
+    if length <= circuits_remaining {
+        return CircuitTracker::with_capacity(length);
+    }
+
+    let mut events = iter_closest_neighbours.circuit_events();
+    for event in &mut events {
+        if event.total_circuits <= circuits_remaining {
+            break;
+        }
+    }
+
+    events.into_tracker()
+}
+
+impl<const N: usize> NodesList<N> {
+    /// The coordinates of the members of every circuit of ``circuit_tracker``, with
+    /// the ID of each circuit.
+    ///
+    /// Circuits are sorted by size descending then by circuit ID, as in
+    /// [`CircuitTracker::circuits_by_size`]; and the members of each by node ID.
+    ///
+    /// Panics if ``circuit_tracker`` tracks more nodes than are in this list.
+    pub fn circuit_members(&self, circuit_tracker: &CircuitTracker) -> Vec<(usize, Vec<Node<N>>)> {
+        circuit_tracker
+            .circuits_by_size()
+            .into_iter()
+            .map(|(circuit_id, _)| {
+                let mut members = circuit_tracker
+                    .get_nodes_in_circuit(circuit_id)
+                    .expect("Unreachable; circuit IDs come from the tracker itself")
+                    .collect::<Vec<_>>();
+                members.sort_unstable();
+
+                (
+                    circuit_id,
+                    members.into_iter().map(|node| self.nodes[node]).collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "162,817,812
+                              57,618,57
+                              906,360,560
+                              592,479,940
+                              352,342,300
+                              466,668,158
+                              542,29,236
+                              431,825,988
+                              739,650,466
+                              52,470,668
+                              216,146,977
+                              819,987,18
+                              117,168,530
+                              805,96,715
+                              346,949,466
+                              970,615,88
+                              941,993,340
+                              862,61,35
+                              984,92,344
+                              425,690,689";
+
+    fn sizes_until(nodes_list: &NodesList, circuits_remaining: usize) -> Vec<usize> {
+        let iter_closest_neighbours = nodes_list
+            .iter_closest_neighbours()
+            .expect("failed to build nodes heap");
+        generate_circuit_map_until(iter_closest_neighbours, circuits_remaining)
+            .circuits_by_size()
+            .into_iter()
+            .map(|(_, size)| size)
+            .collect()
+    }
+
+    #[test]
+    fn test_generate_circuit_map_until() {
+        let nodes_list: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
+
+        // The state after the first ten connections of the puzzle.
+        assert_eq!(
+            sizes_until(&nodes_list, 11),
+            vec![5, 4, 2, 2, 1, 1, 1, 1, 1, 1, 1]
+        );
+        assert_eq!(sizes_until(&nodes_list, 1), vec![20]);
+        assert_eq!(sizes_until(&nodes_list, 20), vec![1; 20]);
+        assert_eq!(sizes_until(&nodes_list, 100), vec![1; 20]);
+        // Nothing ever leaves fewer than one circuit.
+        assert_eq!(sizes_until(&nodes_list, 0), vec![20]);
+    }
+
+    #[test]
+    fn test_circuit_members() {
+        let nodes_list: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
+        let iter_closest_neighbours = nodes_list
+            .iter_closest_neighbours()
+            .expect("failed to build nodes heap");
+        let circuit_tracker = generate_circuit_map_until(iter_closest_neighbours, 17);
+
+        let members = nodes_list.circuit_members(&circuit_tracker);
+        assert_eq!(members.len(), 17);
+        assert_eq!(
+            members[0].1,
+            vec![
+                [162.0, 817.0, 812.0],
+                [431.0, 825.0, 988.0],
+                [425.0, 690.0, 689.0]
+            ]
+        );
+        assert_eq!(
+            members[1].1,
+            vec![[906.0, 360.0, 560.0], [805.0, 96.0, 715.0]]
+        );
+        assert!(members[2..].iter().all(|(_, nodes)| nodes.len() == 1));
+        assert_eq!(
+            members.iter().map(|(_, nodes)| nodes.len()).sum::<usize>(),
+            20
+        );
+    }
+}
@@ -1,113 +1,152 @@
 use super::CircuitOperation;
 use fxhash::FxHashMap;
+use std::cmp::Reverse;
 
 /// A tracker for which nodes are connected in the same circuit.
 ///
 /// This supports merging circuits together and querying which circuit a node belongs to.
 ///
-/// This keeps track of two maps:
-///
-/// - [`Self::node_to_circuit_map`] maps each node ID to its current circuit ID, and
-/// - [`Self::circuit_to_nodes_map`] maps each circuit ID to the list of node IDs in that circuit.
-///
-/// They must be kept in sync when circuits are merged, hence this struct encapsulates both maps.
+/// Circuits are kept as a union-find forest: each node points to a parent in the same
+/// circuit, and the root of each tree stands for the whole circuit. Finding the root
+/// halves the path to it as it goes, and [`Self::join`] hangs the smaller tree under the
+/// larger, so that a join is amortised ``O(α(N))`` rather than relabelling every node of
+/// one of the circuits.
 ///
 /// Each node starts in its own circuit, identified by its own node ID. i.e. the circuit ID
 /// starts with ``(0, 0), (1, 1), (2, 2), ...``. When two nodes are [`Self::join`]ed,
-/// all nodes in the circuit of ``node_b`` are moved to the circuit of `node_a`, which is
-/// an ``O(1)`` lookup operation followed by an ``O(M)`` update operation, where ``M`` is the
-/// number of nodes in the circuit of ``node_b``.
+/// the merged circuit keeps the circuit ID of ``node_a``, whichever of the two trees
+/// ends up as the root; this is kept apart from the roots, in [`Self::circuit_ids`].
 pub struct CircuitTracker {
-    /// A map from node ID to circuit ID.
-    node_to_circuit_map: FxHashMap<usize, usize>,
-    circuit_to_nodes_map: FxHashMap<usize, Vec<usize>>,
+    /// The parent of each node; roots are their own parents.
+    parents: Vec<usize>,
+    /// The number of nodes under each root; only kept up to date for roots.
+    sizes: Vec<usize>,
+    /// The circuit ID of the tree under each root; only kept up to date for roots.
+    circuit_ids: Vec<usize>,
+    /// The next node in the same circuit, as a circular list, so that the members of a
+    /// circuit can be listed without scanning every node.
+    next_members: Vec<usize>,
+    /// A map from circuit ID to the root of its tree.
+    circuit_to_root_map: FxHashMap<usize, usize>,
 }
 
 impl CircuitTracker {
     /// To start with, each node is in its own circuit.
     pub fn with_capacity(capacity: usize) -> Self {
         CircuitTracker {
-            node_to_circuit_map: FxHashMap::from_iter((0..capacity).map(|i| (i, i))),
-            circuit_to_nodes_map: FxHashMap::from_iter((0..capacity).map(|i| (i, vec![i]))),
+            parents: (0..capacity).collect(),
+            sizes: vec![1; capacity],
+            circuit_ids: (0..capacity).collect(),
+            next_members: (0..capacity).collect(),
+            circuit_to_root_map: FxHashMap::from_iter((0..capacity).map(|i| (i, i))),
+        }
+    }
+
+    /// Find the root of the tree containing ``node``, without compressing the path.
+    fn root_of(&self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            node = self.parents[node];
+        }
+        node
+    }
+
+    /// Find the root of the tree containing ``node``, pointing every other node on the
+    /// way at its grandparent.
+    fn find_root(&mut self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            self.parents[node] = self.parents[self.parents[node]];
+            node = self.parents[node];
         }
+        node
     }
 
     /// Get the circuit ID for the given node.
     pub fn get_circuit_of(&self, node: usize) -> usize {
-        self.node_to_circuit_map[&node]
+        self.circuit_ids[self.root_of(node)]
     }
 
-    /// Get a list of all nodes in the given circuit.
-    pub fn get_nodes_in_circuit(&self, circuit_id: usize) -> Option<&Vec<usize>> {
-        self.circuit_to_nodes_map.get(&circuit_id)
+    /// Get all nodes in the given circuit, in no particular order.
+    pub fn get_nodes_in_circuit(&self, circuit_id: usize) -> Option<impl Iterator<Item = usize>> {
+        let root = *self.circuit_to_root_map.get(&circuit_id)?;
+
+        Some(std::iter::successors(Some(root), move |&node| {
+            Some(self.next_members[node]).filter(|&next| next != root)
+        }))
     }
 
     /// Merge the circuits containing the given nodes.
     ///
-    /// All of the nodes in the circuit of `node_b` will be moved to the circuit of `node_a`.
-    /// If the nodes are already in the same circuit, this is a no-op.
+    /// The merged circuit keeps the circuit ID of `node_a`; ``updated`` in the returned
+    /// [`CircuitOperation::Join`] is the number of nodes that were in the circuit of
+    /// `node_b`. If the nodes are already in the same circuit, this is a no-op.
     pub fn join(&mut self, node_a: usize, node_b: usize) -> CircuitOperation {
-        let circuit_a = self.node_to_circuit_map[&node_a];
-        let circuit_b = self.node_to_circuit_map[&node_b];
-
-        if circuit_a != circuit_b {
-            let circuit_b_members = self
-                .circuit_to_nodes_map
-                .remove(&circuit_b)
-                .expect("Circuit B should exist");
-            let updated = circuit_b_members.len();
-
-            circuit_b_members.iter().for_each(|&node_id| {
-                self.node_to_circuit_map.insert(node_id, circuit_a);
-            });
-            self.circuit_to_nodes_map
-                .get_mut(&circuit_a)
-                .expect("Circuit A should exist")
-                .extend(circuit_b_members);
+        let root_a = self.find_root(node_a);
+        let root_b = self.find_root(node_b);
 
+        if root_a == root_b {
             #[cfg(feature = "trace")]
             eprintln!(
-                "Joined circuits {} (node {}) and {} (node {}) (updated {} nodes)",
-                circuit_a, node_a, circuit_b, node_b, updated
+                "Nodes {node_a} and {node_b} are already joined in circuit {} (0 nodes updated)",
+                self.circuit_ids[root_a]
             );
+            return CircuitOperation::NoOp { node_a, node_b };
+        }
 
-            CircuitOperation::Join {
-                node_a,
-                node_b,
-                updated,
-            }
+        let circuit_a = self.circuit_ids[root_a];
+        let circuit_b = self.circuit_ids[root_b];
+        let updated = self.sizes[root_b];
+
+        // Union by size: the root of the larger tree stays the root.
+        let (root, child) = if self.sizes[root_a] >= self.sizes[root_b] {
+            (root_a, root_b)
         } else {
-            #[cfg(feature = "trace")]
-            eprintln!(
-                "Nodes {node_a} and {node_b} are already joined in circuit {circuit_a} (0 nodes updated)",
-            );
-            CircuitOperation::NoOp { node_a, node_b }
+            (root_b, root_a)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.circuit_ids[root] = circuit_a;
+
+        // Swapping the successors of one node from each circular list splices them into one.
+        self.next_members.swap(root_a, root_b);
+
+        self.circuit_to_root_map.remove(&circuit_b);
+        self.circuit_to_root_map.insert(circuit_a, root);
+
+        #[cfg(feature = "trace")]
+        eprintln!(
+            "Joined circuits {} (node {}) and {} (node {}) (updated {} nodes)",
+            circuit_a, node_a, circuit_b, node_b, updated
+        );
+
+        CircuitOperation::Join {
+            node_a,
+            node_b,
+            updated,
         }
     }
 
-    /// This is an O(N) operation that counts how many nodes are in the given circuit;
-    /// For diagnostic purposes only, do not iterate in performance-sensitive code.
-    /// See [`Self::circuits_by_size`] for a more efficient way to get sizes of all circuits.
+    /// Get the number of nodes in the given circuit, or ``0`` if there is no such circuit.
     pub fn get_circuit_size(&self, circuit_id: usize) -> usize {
-        self.circuit_to_nodes_map
+        self.circuit_to_root_map
             .get(&circuit_id)
-            .map(|nodes| nodes.len())
+            .map(|&root| self.sizes[root])
             .unwrap_or(0)
     }
 
     /// Get the total number of unique circuits.
     pub fn total_circuits(&self) -> usize {
-        self.circuit_to_nodes_map.len()
+        self.circuit_to_root_map.len()
     }
 
-    /// Get a list of circuits and their sizes, sorted by size descending.
+    /// Get a list of circuits and their sizes, sorted by size descending, then by
+    /// circuit ID.
     pub fn circuits_by_size(&self) -> Vec<(usize, usize)> {
         let mut counts_vec: Vec<(usize, usize)> = self
-            .circuit_to_nodes_map
+            .circuit_to_root_map
             .iter()
-            .map(|(&circuit_id, nodes)| (circuit_id, nodes.len()))
+            .map(|(&circuit_id, &root)| (circuit_id, self.sizes[root]))
             .collect();
-        counts_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        counts_vec.sort_by_key(|&(circuit_id, size)| (Reverse(size), circuit_id));
 
         counts_vec
     }
@@ -196,14 +235,77 @@ mod tests {
         ); // Joining circuits of 1 and 4 (which includes 2, 3, and 9, modifying only 1, 2 and 9)
 
         let expected_circuits = [0, 3, 3, 3, 3, 5, 5, 7, 8, 3];
-        for i in 0..10 {
+        for (i, &expected) in expected_circuits.iter().enumerate() {
             assert_eq!(
                 tracker.get_circuit_of(i),
-                expected_circuits[i],
+                expected,
                 "expected node {} to be in circuit {}",
                 i,
-                expected_circuits[i]
+                expected
             );
         }
     }
+
+    #[test]
+    fn test_nodes_in_circuit() {
+        let mut tracker = CircuitTracker::with_capacity(10);
+        for (node_a, node_b) in [(1, 9), (2, 9), (3, 4), (5, 6), (4, 1)] {
+            tracker.join(node_a, node_b);
+        }
+
+        let mut nodes = tracker
+            .get_nodes_in_circuit(3)
+            .expect("circuit 3 should exist")
+            .collect::<Vec<_>>();
+        nodes.sort();
+        assert_eq!(nodes, vec![1, 2, 3, 4, 9]);
+        assert_eq!(
+            tracker
+                .get_nodes_in_circuit(7)
+                .map(Iterator::collect::<Vec<_>>),
+            Some(vec![7])
+        );
+        assert!(tracker.get_nodes_in_circuit(1).is_none());
+
+        assert_eq!(tracker.get_circuit_size(3), 5);
+        assert_eq!(tracker.get_circuit_size(1), 0);
+        assert_eq!(tracker.total_circuits(), 5);
+        assert_eq!(
+            tracker.circuits_by_size(),
+            vec![(3, 5), (5, 2), (0, 1), (7, 1), (8, 1)]
+        );
+        assert_eq!(
+            tracker.join(9, 3),
+            CircuitOperation::NoOp {
+                node_a: 9,
+                node_b: 3
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "legacy-tracker")]
+    fn test_matches_legacy() {
+        use crate::models::LegacyCircuitTracker;
+
+        // Pseudo-random joins, from a linear congruential generator.
+        let mut state = 0x2545_f491_u64;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1);
+            (state >> 33) as usize % 200
+        };
+
+        let mut tracker = CircuitTracker::with_capacity(200);
+        let mut legacy = LegacyCircuitTracker::with_capacity(200);
+        for _ in 0..500 {
+            let (node_a, node_b) = (next(), next());
+            assert_eq!(tracker.join(node_a, node_b), legacy.join(node_a, node_b));
+            assert_eq!(tracker.total_circuits(), legacy.total_circuits());
+        }
+        for node in 0..200 {
+            assert_eq!(tracker.get_circuit_of(node), legacy.get_circuit_of(node));
+        }
+    }
 }
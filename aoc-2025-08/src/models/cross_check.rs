@@ -0,0 +1,144 @@
+//! Checking the lazy nearest-neighbour heap against an exhaustive sort of every pair.
+
+use super::{NodeCoordType, NodeDistanceType, NodesList, metrics};
+use simple_graph::Kruskal;
+
+/// Check that ``list`` joins its nodes into circuits the same way by squared Euclidean
+/// distance, whichever way its relations are sorted; see [`cross_check_by`].
+pub fn cross_check<const N: usize>(list: &NodesList<N>) -> anyhow::Result<usize> {
+    cross_check_by(list, &metrics::squared_euclidean)
+}
+
+/// Check that ``list`` joins its nodes into circuits the same way by the distance
+/// ``metric`` gives, whether its relations come lazily from
+/// [`NodesList::iter_closest_neighbours_by`], or from sorting every pair up front and
+/// running [`Kruskal`]'s algorithm over them.
+///
+/// The distance of every connection must match. Pairs at equal distances may be
+/// connected in either order, so the circuits are only compared at the end of each run
+/// of equal distances: the number of them, and the product of the sizes of the three
+/// largest. The check stops once every node is in one circuit, as nothing after that
+/// can change the circuits.
+///
+/// This sorts all ``N(N-1)/2`` pairs, so is only meant as a test of the heap.
+///
+/// Returns the number of connections checked, or an error describing the first that
+/// did not match.
+pub fn cross_check_by<const N: usize, F>(list: &NodesList<N>, metric: &F) -> anyhow::Result<usize>
+where
+    F: Fn(&[NodeCoordType], &[NodeCoordType]) -> NodeDistanceType + Sync,
+{
+    let length = list.len();
+    let mut kruskal = Kruskal::new(
+        length,
+        (0..length).flat_map(|node_a| {
+            (0..node_a).map(move |node_b| {
+                let distance = metric(&list.nodes[node_a], &list.nodes[node_b]);
+                (node_a, node_b, distance)
+            })
+        }),
+    );
+
+    let mut step = 0;
+    for event in list.iter_closest_neighbours_by(metric)?.circuit_events() {
+        let edge = kruskal.next().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Ran out of sorted pairs after {} connections, but the heap had more",
+                step
+            )
+        })?;
+        step += 1;
+
+        if edge.weight != event.distance {
+            anyhow::bail!(
+                "Connection {} was at distance {} from the heap, but {} from the sorted pairs",
+                event.step,
+                event.distance,
+                edge.weight
+            );
+        }
+
+        if kruskal
+            .peek_weight()
+            .is_some_and(|&weight| weight == event.distance)
+        {
+            continue;
+        }
+
+        let forest = kruskal.disjoint_set();
+        let mut sizes = forest.set_sizes().collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        let top_three_product = sizes.iter().take(3).product::<usize>();
+
+        if (event.total_circuits, event.top_three_product) != (forest.sets(), top_three_product) {
+            anyhow::bail!(
+                "After {} connections, the heap had {} circuits with a top three product of {}, \
+                 but the sorted pairs had {} with {}",
+                step,
+                event.total_circuits,
+                event.top_three_product,
+                forest.sets(),
+                top_three_product
+            );
+        }
+
+        if event.total_circuits <= 1 {
+            break;
+        }
+    }
+
+    if step == 0 && kruskal.next().is_some() {
+        anyhow::bail!("The heap had no relations, but there were pairs to sort");
+    }
+
+    Ok(step)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "162,817,812
+                              57,618,57
+                              906,360,560
+                              592,479,940
+                              352,342,300
+                              466,668,158
+                              542,29,236
+                              431,825,988
+                              739,650,466
+                              52,470,668
+                              216,146,977
+                              819,987,18
+                              117,168,530
+                              805,96,715
+                              346,949,466
+                              970,615,88
+                              941,993,340
+                              862,61,35
+                              984,92,344
+                              425,690,689";
+
+    #[test]
+    fn test_cross_check() {
+        let nodes_list: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
+        assert!(cross_check(&nodes_list).is_ok_and(|steps| steps >= 19));
+
+        cross_check_by(&nodes_list, &metrics::manhattan).expect("heap and sorted pairs differ");
+        cross_check_by(&nodes_list, &metrics::chebyshev).expect("heap and sorted pairs differ");
+    }
+
+    #[test]
+    fn test_cross_check_with_ties() {
+        // A grid, where most pairs are at the same distance as several others.
+        let nodes_list = NodesList::<2>::build_from(
+            (0..6)
+                .flat_map(|x| (0..6).map(move |y| [x as NodeCoordType, y as NodeCoordType]))
+                .collect(),
+        )
+        .unwrap();
+
+        assert!(cross_check_by(&nodes_list, &metrics::manhattan).is_ok());
+        assert!(cross_check_by(&nodes_list, &metrics::squared_euclidean).is_ok());
+    }
+}
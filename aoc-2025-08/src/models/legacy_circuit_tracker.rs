@@ -0,0 +1,64 @@
+use super::CircuitOperation;
+use fxhash::FxHashMap;
+
+/// The tracker that [`CircuitTracker`](super::CircuitTracker) replaced, kept only to
+/// benchmark against.
+///
+/// This keeps a map from each node ID to its circuit ID, and from each circuit ID to its
+/// nodes; when two circuits are [`Self::join`]ed, every node in the circuit of ``node_b``
+/// is relabelled, which is ``O(M)`` in the ``M`` nodes of that circuit.
+pub struct LegacyCircuitTracker {
+    node_to_circuit_map: FxHashMap<usize, usize>,
+    circuit_to_nodes_map: FxHashMap<usize, Vec<usize>>,
+}
+
+impl LegacyCircuitTracker {
+    /// To start with, each node is in its own circuit.
+    pub fn with_capacity(capacity: usize) -> Self {
+        LegacyCircuitTracker {
+            node_to_circuit_map: FxHashMap::from_iter((0..capacity).map(|i| (i, i))),
+            circuit_to_nodes_map: FxHashMap::from_iter((0..capacity).map(|i| (i, vec![i]))),
+        }
+    }
+
+    /// Get the circuit ID for the given node.
+    pub fn get_circuit_of(&self, node: usize) -> usize {
+        self.node_to_circuit_map[&node]
+    }
+
+    /// Merge the circuits containing the given nodes, moving all of the nodes in the
+    /// circuit of `node_b` to the circuit of `node_a`.
+    pub fn join(&mut self, node_a: usize, node_b: usize) -> CircuitOperation {
+        let circuit_a = self.node_to_circuit_map[&node_a];
+        let circuit_b = self.node_to_circuit_map[&node_b];
+
+        if circuit_a == circuit_b {
+            return CircuitOperation::NoOp { node_a, node_b };
+        }
+
+        let circuit_b_members = self
+            .circuit_to_nodes_map
+            .remove(&circuit_b)
+            .expect("Circuit B should exist");
+        let updated = circuit_b_members.len();
+
+        circuit_b_members.iter().for_each(|&node_id| {
+            self.node_to_circuit_map.insert(node_id, circuit_a);
+        });
+        self.circuit_to_nodes_map
+            .get_mut(&circuit_a)
+            .expect("Circuit A should exist")
+            .extend(circuit_b_members);
+
+        CircuitOperation::Join {
+            node_a,
+            node_b,
+            updated,
+        }
+    }
+
+    /// Get the total number of unique circuits.
+    pub fn total_circuits(&self) -> usize {
+        self.circuit_to_nodes_map.len()
+    }
+}
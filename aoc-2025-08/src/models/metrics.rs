@@ -0,0 +1,39 @@
+//! Distance metrics between nodes of any number of dimensions, for
+//! [`NodesList::iter_closest_neighbours_by`](super::NodesList::iter_closest_neighbours_by).
+
+use super::{NodeCoordType, NodeDistanceType};
+
+/// The square of the straight line distance; sorts the same as the distance itself,
+/// without taking any square roots. This is the metric of the puzzle.
+pub fn squared_euclidean(a: &[NodeCoordType], b: &[NodeCoordType]) -> NodeDistanceType {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
+
+/// The sum of the distances along each axis, as on a grid moving only orthogonally.
+pub fn manhattan(a: &[NodeCoordType], b: &[NodeCoordType]) -> NodeDistanceType {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum()
+}
+
+/// The largest of the distances along each axis, as on a grid moving diagonally too.
+pub fn chebyshev(a: &[NodeCoordType], b: &[NodeCoordType]) -> NodeDistanceType {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, NodeDistanceType::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let (a, b) = ([1.0, 2.0, 3.0], [4.0, -2.0, 3.0]);
+
+        assert_eq!(squared_euclidean(&a, &b), 25.0);
+        assert_eq!(manhattan(&a, &b), 7.0);
+        assert_eq!(chebyshev(&a, &b), 4.0);
+        assert_eq!(manhattan(&a[..2], &b[..2]), 7.0);
+        assert_eq!(chebyshev(&a, &a), 0.0);
+    }
+}
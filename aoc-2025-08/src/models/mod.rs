@@ -1,12 +1,30 @@
 mod circuit_tracker;
 pub use circuit_tracker::CircuitTracker;
 
+#[cfg(feature = "legacy-tracker")]
+mod legacy_circuit_tracker;
+#[cfg(feature = "legacy-tracker")]
+pub use legacy_circuit_tracker::LegacyCircuitTracker;
+
+mod circuit_event;
+pub use circuit_event::{CircuitEvent, CircuitEvents};
+
+#[cfg(feature = "cross-check")]
+mod cross_check;
+#[cfg(feature = "cross-check")]
+pub use cross_check::{cross_check, cross_check_by};
+
+mod circuit_map;
+pub use circuit_map::generate_circuit_map_until;
+
 mod circuit_operation;
 pub use circuit_operation::CircuitOperation;
 
 mod types;
 pub use types::*;
 
+pub mod metrics;
+
 mod nodes;
 pub use nodes::{ClosestNeighboursIterator, NodesList};
 
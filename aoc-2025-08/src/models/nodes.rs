@@ -1,5 +1,7 @@
-use super::{Node, NodeCoordType, NodeDistanceType, Relation};
+use super::{Node, NodeCoordType, NodeDistanceType, Relation, metrics};
 use kdtree::KdTree;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashSet},
@@ -92,19 +94,26 @@ use std::{
 ///
 /// - builds a KD-Tree from the input nodes for efficient nearest-neighbour computation,
 /// - for each node, finds its nearest-neighbour that has not already been paired with it
-///  (i.e. only the bottom half of the distance matrix), and
+///   (i.e. only the bottom half of the distance matrix), and
 /// - stores these relations in a min-heap sorted by distance,
 /// - when popping a relation from the heap, fans out from the ``node_a`` of that relation
 ///   to find its next nearest-neighbour that has not already been paired with it, and
 ///   pushes that new relation onto the heap, replacing the popped relation.
 /// - this continues until all unique relations have been popped from the heap, or
 ///   some stopping condition is met, e.g. all nodes have been joined into a single graph.
-pub struct NodesList {
-    pub nodes: Vec<Node>,
-    pub tree: KdTree<NodeDistanceType, usize, Node>,
+///
+/// ## Dimensions and metrics
+///
+/// Nodes have ``N`` coordinates, 3 by default as in the puzzle; and relations are sorted
+/// by squared Euclidean distance unless another metric is given to
+/// [`Self::iter_closest_neighbours_by`], such as [`metrics::manhattan`] or
+/// [`metrics::chebyshev`].
+pub struct NodesList<const N: usize = 3> {
+    pub nodes: Vec<Node<N>>,
+    pub tree: KdTree<NodeDistanceType, usize, Node<N>>,
 }
 
-impl NodesList {
+impl<const N: usize> NodesList<N> {
     /// Build a NodesList from a list of nodes.
     ///
     /// This will use a KD-Tree to efficiently compute nearest neighbors. Then for each
@@ -114,8 +123,11 @@ impl NodesList {
     /// This allows us to iterate over all unique nodes in order of increasing distance to
     /// nearest neighbour - which is useful in joining cluster of nodes into trees based
     /// on proximity.
-    pub fn build_from(nodes: Vec<Node>) -> anyhow::Result<Self> {
-        let mut tree = KdTree::new(3);
+    ///
+    /// The nodes are inserted one at a time even with the ``rayon`` feature, as
+    /// [`KdTree::add`] needs the tree mutably and there is no way to merge trees.
+    pub fn build_from(nodes: Vec<Node<N>>) -> anyhow::Result<Self> {
+        let mut tree = KdTree::new(N);
 
         nodes
             .iter()
@@ -126,52 +138,109 @@ impl NodesList {
         Ok(Self { nodes, tree })
     }
 
-    /// Build a NodesList from a textual representation of nodes.
+    /// Build a NodesList from a textual representation of nodes, with ``N`` comma
+    /// separated coordinates per line.
     pub fn build_from_text(input: &str) -> anyhow::Result<Self> {
-        let nodes: Vec<Node> = input
-            .lines()
-            .map(|line| {
-                let coords: Vec<NodeCoordType> = line
-                    .split(',')
-                    .map(|part| part.trim().parse::<NodeCoordType>())
-                    .collect::<Result<_, _>>()
-                    .map_err(|e| {
-                        anyhow::anyhow!(
-                            "Failed to parse node coordinates from line '{}': {}",
-                            line,
-                            e
-                        )
-                    })?;
-
-                if coords.len() != 3 {
-                    return Err(anyhow::anyhow!(
-                        "Expected 3 coordinates per node, got {} in line '{}'",
-                        coords.len(),
-                        line
-                    ));
-                }
-
-                Ok([coords[0], coords[1], coords[2]])
+        let parse_line = |line: &str| -> anyhow::Result<Node<N>> {
+            let coords: Vec<NodeCoordType> = line
+                .split(',')
+                .map(|part| part.trim().parse::<NodeCoordType>())
+                .collect::<Result<_, _>>()
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to parse node coordinates from line '{}': {}",
+                        line,
+                        e
+                    )
+                })?;
+
+            <Node<N>>::try_from(coords.as_slice()).map_err(|_| {
+                anyhow::anyhow!(
+                    "Expected {} coordinates per node, got {} in line '{}'",
+                    N,
+                    coords.len(),
+                    line
+                )
             })
-            .collect::<Result<_, _>>()?;
+        };
+
+        #[cfg(feature = "rayon")]
+        let nodes = input
+            .par_lines()
+            .map(parse_line)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        #[cfg(not(feature = "rayon"))]
+        let nodes = input
+            .lines()
+            .map(parse_line)
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         Self::build_from(nodes)
     }
 
     /// Get a reference to a node by its ID.
-    pub fn get_node_by_id(&self, node_id: usize) -> Option<&Node> {
+    pub fn get_node_by_id(&self, node_id: usize) -> Option<&Node<N>> {
         self.nodes.get(node_id)
     }
 
-    /// Get an iterator over unique relations sorted by distance.
-    pub fn iter_closest_neighbours<'a>(&'a self) -> anyhow::Result<ClosestNeighboursIterator<'a>> {
+    /// Get an iterator over unique relations sorted by squared Euclidean distance.
+    pub fn iter_closest_neighbours<'a>(
+        &'a self,
+    ) -> anyhow::Result<ClosestNeighboursIterator<'a, N>> {
         ClosestNeighboursIterator::new(self)
     }
 
+    /// Get an iterator over unique relations sorted by the distance ``metric`` gives
+    /// between two nodes.
+    ///
+    /// The KD-Tree prunes its search with the distance from a node to the bounds of each
+    /// of its branches, which it also finds with ``metric``; so ``metric`` must never
+    /// decrease as any coordinate moves further apart, as with every metric in
+    /// [`metrics`].
+    pub fn iter_closest_neighbours_by<'a, F>(
+        &'a self,
+        metric: &'a F,
+    ) -> anyhow::Result<ClosestNeighboursIterator<'a, N>>
+    where
+        F: Fn(&[NodeCoordType], &[NodeCoordType]) -> NodeDistanceType + Sync,
+    {
+        ClosestNeighboursIterator::new_by(self, metric)
+    }
+
     /// Get the number of nodes in this list.
     pub fn len(&self) -> usize {
         self.nodes.len()
     }
+
+    /// Whether this list has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// The nearest neighbours of one node, nearest first, with their distances.
+type Generator<'a> = Box<dyn Iterator<Item = (NodeDistanceType, &'a usize)> + Send + 'a>;
+
+/// Advance ``generator`` of ``node_id`` to the next relation that is valid, i.e. where
+///
+/// - the closest node is not itself,
+/// - the closest node has a lower ID than itself (to avoid duplicates), and
+/// - the closest node has not already been ``seen`` with it.
+///
+/// Returns [`None`] if the generator is exhausted.
+fn next_relation(
+    node_id: usize,
+    generator: &mut Generator<'_>,
+    seen: impl Fn(usize) -> bool,
+) -> Option<Relation> {
+    generator
+        .by_ref()
+        .find(|&(_, &closest_node_id)| closest_node_id < node_id && !seen(closest_node_id))
+        .map(|(distance, &closest_node_id)| Relation {
+            node_a: node_id,
+            node_b: closest_node_id,
+            distance,
+        })
 }
 
 /// An iterator over unique relations sorted by distance.
@@ -186,9 +255,9 @@ impl NodesList {
 ///
 /// Since this struct holds references to the underlying [`NodesList`], its lifetime
 /// is tied to that of the [`NodesList`].
-pub struct ClosestNeighboursIterator<'a> {
-    list: &'a NodesList,
-    generators: Vec<Box<dyn Iterator<Item = (NodeDistanceType, &'a usize)> + 'a>>,
+pub struct ClosestNeighboursIterator<'a, const N: usize = 3> {
+    list: &'a NodesList<N>,
+    generators: Vec<Generator<'a>>,
     seen: HashSet<(usize, usize)>,
 
     /// A min-heap of relations sorted by distance.
@@ -205,31 +274,68 @@ pub struct ClosestNeighboursIterator<'a> {
     sorted_distances: BinaryHeap<Reverse<Relation>>,
 }
 
-impl<'a> ClosestNeighboursIterator<'a> {
-    pub fn new(list: &'a NodesList) -> anyhow::Result<Self> {
+impl<'a, const N: usize> ClosestNeighboursIterator<'a, N> {
+    /// Iterate over the relations of ``list`` by squared Euclidean distance.
+    pub fn new(list: &'a NodesList<N>) -> anyhow::Result<Self> {
+        Self::new_by(list, &metrics::squared_euclidean)
+    }
+
+    /// Iterate over the relations of ``list`` by the distance ``metric`` gives; see
+    /// [`NodesList::iter_closest_neighbours_by`].
+    ///
+    /// With the ``rayon`` feature, the generators of every node are built and advanced to
+    /// their first relations in parallel; the relations are then pushed onto the heap in
+    /// order of node ID, so the iterator yields exactly the same sequence either way.
+    pub fn new_by<F>(list: &'a NodesList<N>, metric: &'a F) -> anyhow::Result<Self>
+    where
+        F: Fn(&[NodeCoordType], &[NodeCoordType]) -> NodeDistanceType + Sync,
+    {
         let length = list.len();
 
-        let generators =
-            list.nodes
-                .iter()
-                .map(
-                    |node| -> anyhow::Result<
-                        Box<dyn Iterator<Item = (NodeDistanceType, &usize)> + 'a>,
-                    > {
-                        let iter = list
-                            .tree
-                            .iter_nearest(node, &kdtree::distance::squared_euclidean)
-                            .map_err(|e| {
-                                anyhow::anyhow!(
-                                    "Failed to compute nearest neighbors for node {:?}: {}",
-                                    node,
-                                    e
-                                )
-                            })?;
-                        Ok(Box::new(iter) as Box<dyn Iterator<Item = (NodeDistanceType, &usize)>>)
-                    },
+        let generator_of = |node: &'a Node<N>| -> anyhow::Result<Generator<'a>> {
+            let iter = list.tree.iter_nearest(node, metric).map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to compute nearest neighbors for node {:?}: {}",
+                    node,
+                    e
                 )
-                .collect::<anyhow::Result<Vec<Box<_>>>>()?;
+            })?;
+            Ok(Box::new(iter))
+        };
+
+        #[cfg(feature = "rayon")]
+        let mut generators = list
+            .nodes
+            .par_iter()
+            .map(generator_of)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        #[cfg(not(feature = "rayon"))]
+        let mut generators = list
+            .nodes
+            .iter()
+            .map(generator_of)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // Each node only pairs with nodes of lower IDs, so no two nodes can find the same
+        // pair; nothing has been seen yet for any of them, and they can all be advanced
+        // independently.
+        let first_relation_of =
+            |(node_id, generator): (usize, &mut Generator<'a>)| -> Option<Relation> {
+                next_relation(node_id, generator, |_| false)
+            };
+
+        #[cfg(feature = "rayon")]
+        let first_relations = generators
+            .par_iter_mut()
+            .enumerate()
+            .map(first_relation_of)
+            .collect::<Vec<_>>();
+        #[cfg(not(feature = "rayon"))]
+        let first_relations = generators
+            .iter_mut()
+            .enumerate()
+            .map(first_relation_of)
+            .collect::<Vec<_>>();
 
         let mut instance = Self {
             list,
@@ -237,11 +343,10 @@ impl<'a> ClosestNeighboursIterator<'a> {
             seen: HashSet::new(),
             sorted_distances: BinaryHeap::new(),
         };
-
-        // Since we can't move `seen` into a closure, we do this with a for loop.
-        (0..length)
+        first_relations
             .into_iter()
-            .try_for_each(|node_id| instance.advance_generator_of(node_id).and(Ok(())))?;
+            .flatten()
+            .for_each(|relation| instance.push_relation(relation));
 
         eprintln!(
             "Built NodesList with {} nodes and {} unique relations",
@@ -257,51 +362,37 @@ impl<'a> ClosestNeighboursIterator<'a> {
         self.list.len()
     }
 
+    /// Internal function to push a relation onto the heap, marking its pair as seen.
+    fn push_relation(&mut self, relation: Relation) {
+        #[cfg(feature = "trace")]
+        {
+            println!(
+                "Node {:?} closest to {:?} with distance {}",
+                self.list.nodes[relation.node_a],
+                self.list.nodes[relation.node_b],
+                relation.distance
+            );
+        }
+        self.seen.insert((relation.node_a, relation.node_b));
+        self.sorted_distances.push(Reverse(relation));
+    }
+
     /// Internal function to advance the generator for a given node ID, pushing
     /// the next valid relation onto the heap.
     ///
-    /// It will check that
-    /// - the closest node is not itself,
-    /// - the closest node has a lower ID than itself (to avoid duplicates), and
-    /// - the pair has not already been seen.
+    /// See [`next_relation`] for which relations are valid.
     ///
     /// It returns [`Ok`] wrapping ``true`` if a new relation was pushed onto the heap,
     /// or ``false`` if the generator is exhausted.
     fn advance_generator_of(&mut self, node_id: usize) -> anyhow::Result<bool> {
-        for (closest_distance, closest_node_id) in self.generators[node_id].by_ref() {
-            if *closest_node_id == node_id {
-                // Skip self
-                continue;
-            } else if *closest_node_id > node_id {
-                // We only need the bottom half of the matrix, so we can stop here.
-                continue;
-            } else if self.seen.contains(&(node_id, *closest_node_id))
-                || self.seen.contains(&(*closest_node_id, node_id))
-            {
-                // Already seen this pair
-                continue;
-            }
-
-            #[cfg(feature = "trace")]
-            {
-                println!(
-                    "Node {:?} closest to {:?} with distance {}",
-                    self.list.nodes[node_id],
-                    self.list.nodes[*closest_node_id],
-                    closest_distance.sqrt()
-                );
-            }
-            self.sorted_distances.push(Reverse(Relation {
-                node_a: node_id,
-                node_b: *closest_node_id,
-                distance: closest_distance,
-            }));
-            self.seen.insert((node_id, *closest_node_id));
-
-            return Ok(true);
-        }
-
-        Ok(false)
+        let seen = &self.seen;
+        let relation = next_relation(node_id, &mut self.generators[node_id], |closest_node_id| {
+            seen.contains(&(node_id, closest_node_id)) || seen.contains(&(closest_node_id, node_id))
+        });
+
+        Ok(relation
+            .map(|relation| self.push_relation(relation))
+            .is_some())
     }
 
     /// Pop the next closest relation from the heap.
@@ -317,7 +408,7 @@ impl<'a> ClosestNeighboursIterator<'a> {
     }
 }
 
-impl Iterator for ClosestNeighboursIterator<'_> {
+impl<const N: usize> Iterator for ClosestNeighboursIterator<'_, N> {
     type Item = Relation;
 
     fn next(&mut self) -> Option<Relation> {
@@ -325,10 +416,10 @@ impl Iterator for ClosestNeighboursIterator<'_> {
     }
 }
 
-impl<'a> TryFrom<&'a NodesList> for ClosestNeighboursIterator<'a> {
+impl<'a, const N: usize> TryFrom<&'a NodesList<N>> for ClosestNeighboursIterator<'a, N> {
     type Error = anyhow::Error;
 
-    fn try_from(value: &'a NodesList) -> Result<Self, Self::Error> {
+    fn try_from(value: &'a NodesList<N>) -> Result<Self, Self::Error> {
         ClosestNeighboursIterator::new(value)
     }
 }
@@ -360,13 +451,13 @@ mod tests {
 
     #[test]
     fn test_build_nodes_heap_from_text() {
-        let nodes_heap = NodesList::build_from_text(TEST_INPUT).unwrap();
+        let nodes_heap: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
         assert_eq!(nodes_heap.nodes.len(), 20);
     }
 
     #[test]
     fn test_get_node_by_id() {
-        let nodes_heap = NodesList::build_from_text(TEST_INPUT).unwrap();
+        let nodes_heap: NodesList = NodesList::build_from_text(TEST_INPUT).unwrap();
         assert_eq!(
             nodes_heap.get_node_by_id(0).unwrap(),
             &[162.0, 817.0, 812.0]
@@ -412,4 +503,32 @@ mod tests {
             eprintln!("Passed iteration {}", i);
         }
     }
+
+    #[test]
+    fn test_metrics_in_2d() {
+        let nodes_list = NodesList::<2>::build_from_text("0,0\n3,4\n-2,7\n5,-1\n1,1\n6,6")
+            .expect("Failed to build NodesList");
+
+        for metric in [
+            metrics::squared_euclidean,
+            metrics::manhattan,
+            metrics::chebyshev,
+        ] {
+            // Every pair, sorted by distance the long way.
+            let nodes = &nodes_list.nodes;
+            let mut expected = (0..nodes.len())
+                .flat_map(|a| (0..a).map(move |b| metric(&nodes[a], &nodes[b])))
+                .collect::<Vec<_>>();
+            expected.sort_by(|a, b| a.total_cmp(b));
+
+            let distances = nodes_list
+                .iter_closest_neighbours_by(&metric)
+                .expect("Failed to create ClosestNeighboursIterator")
+                .map(|relation| relation.distance)
+                .collect::<Vec<_>>();
+            assert_eq!(distances, expected);
+        }
+
+        assert!(NodesList::<2>::build_from_text("1,2,3").is_err());
+    }
 }
@@ -1,3 +1,4 @@
 pub type NodeCoordType = f32;
 pub type NodeDistanceType = f32;
-pub type Node = [NodeCoordType; 3];
+/// A node with ``N`` coordinates; 3 in the puzzle.
+pub type Node<const N: usize = 3> = [NodeCoordType; N];
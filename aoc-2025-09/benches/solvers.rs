@@ -0,0 +1,42 @@
+//! Benchmarks of the histogram sweep against checking every pair of vertices, both on the
+//! same compressed grid of the real input.
+//!
+//! Run with ``cargo bench -p aoc-2025-09 --bench solvers``.
+
+use aoc_2025_09::{
+    CompressedGrid, INPUT,
+    compare::{compare_area_within, find_best_match},
+    histogram, indexed_coords_from_text,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+fn bench_solvers(c: &mut Criterion) {
+    let vertices = indexed_coords_from_text(INPUT).expect("Failed to parse indexed coords");
+    let grid = CompressedGrid::new(&vertices).expect("Failed to compress polygon");
+
+    c.bench_function("compress", |b| {
+        b.iter(|| CompressedGrid::new(black_box(&vertices)))
+    });
+
+    let mut group = c.benchmark_group("solvers");
+    group.bench_function("pairwise", |b| {
+        b.iter(|| find_best_match(black_box(&vertices), compare_area_within(&grid)))
+    });
+    #[cfg(feature = "rayon")]
+    group.bench_function("pairwise_parallel", |b| {
+        b.iter(|| {
+            aoc_2025_09::compare::par_find_best_match(
+                black_box(&vertices),
+                compare_area_within(&grid),
+            )
+        })
+    });
+    group.bench_function("histogram", |b| {
+        b.iter(|| histogram::largest_rectangle(&grid, black_box(&vertices)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_solvers);
+criterion_main!(benches);
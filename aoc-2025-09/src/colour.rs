@@ -1,8 +1,11 @@
-use crate::models::{Coords, Rectangle};
+use crate::models::{Coord, Coords, Rectangle, polygon_from_coords};
 use itertools::Itertools;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::io::Write;
+use tessellation_fill::{
+    Quad, SimplePolygon, contains_even_odd, fill_into, tessellation_fill, traits::RasterTarget,
+};
 
 #[cfg(feature = "profile")]
 use std::time::{Duration, Instant};
@@ -19,6 +22,23 @@ pub enum Colour {
     Colourless = 0,
 }
 
+impl TryFrom<u8> for Colour {
+    type Error = anyhow::Error;
+
+    /// The colour of a discriminant, as saved by [`Grid::save_to`] and
+    /// [`Grid::write_binary`].
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Colour::Colourless),
+            1 => Ok(Colour::Red),
+            2 => Ok(Colour::Green),
+            3 => Ok(Colour::White),
+            4 => Ok(Colour::Yellow),
+            _ => anyhow::bail!("Unknown colour {}", value),
+        }
+    }
+}
+
 impl std::fmt::Display for Colour {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -36,6 +56,9 @@ pub struct Grid {
     pub width: u32,
     pub height: u32,
     pub cells: Vec<Colour>,
+    /// The last boundary drawn, if it forms a valid orthogonal polygon; used by
+    /// [`Grid::fill_from`] to fill without visiting every cell.
+    pub polygon: Option<SimplePolygon<Coord>>,
 }
 
 impl Grid {
@@ -44,6 +67,7 @@ impl Grid {
             width,
             height,
             cells: vec![colour; width as usize * height as usize],
+            polygon: None,
         }
     }
 
@@ -92,8 +116,59 @@ impl Grid {
         })
     }
 
-    /// Recursive helper for flood fill.
+    /// Fill the side of the last [`Grid::boundary`] drawn that contains ``(x, y)``, only
+    /// colouring cells that are still uncoloured.
+    ///
+    /// The filled cells are found by [`fill_into`] or, from outside of the boundary, as the
+    /// complement of the quads of [`tessellation_fill()`], so the vast empty areas of the
+    /// real input are painted a rectangle at a time rather than searched one cell at a
+    /// time. If no valid boundary has been drawn, this falls back to
+    /// [`Grid::flood_fill_from`].
+    ///
+    /// Unlike [`Grid::flood_fill_from`], every cell on that side is filled, whether or not
+    /// it can be reached from ``(x, y)``. An inside pinched by a corridor too narrow to have
+    /// any cells of its own is filled on both sides of it; so is an outside cut in two by a
+    /// boundary against the top or left of the grid, where [`Grid::new_to_fit`] leaves no
+    /// margin. A tile cut off like this is still on the same side of the polygon, which is
+    /// what the fills of the puzzle are after.
     pub fn fill_from(&mut self, x: u32, y: u32, colour: Colour) {
+        let Some(polygon) = self.polygon.take() else {
+            return self.flood_fill_from(x, y, colour);
+        };
+
+        if self.get(x, y) == Some(Colour::Colourless) {
+            #[cfg(feature = "trace")]
+            {
+                eprintln!("Tessellation fill from ({}, {})", x, y);
+            }
+
+            if contains_even_odd(&polygon, [x, y]) {
+                // Straight into the grid, without building the quadtree.
+                fill_into(&polygon, &mut GridPainter { grid: self, colour });
+            } else {
+                // The complement of the filled quads, merged into a few rectangles, so
+                // that no cell needs to be looked up in the quadtree.
+                let bounds = Quad::new(0, 0, self.width as i64, self.height as i64);
+                let outside = tessellation_fill(&polygon)
+                    .to_rect_list()
+                    .complement(bounds);
+                for [x, y] in outside.rects().iter().flat_map(Quad::cells) {
+                    self.set_if_colourless(x as u32, y as u32, colour);
+                }
+            }
+        }
+
+        self.polygon = Some(polygon);
+    }
+
+    fn set_if_colourless(&mut self, x: u32, y: u32, colour: Colour) {
+        if self.get(x, y) == Some(Colour::Colourless) {
+            self.set(x, y, colour);
+        }
+    }
+
+    /// Breadth-first flood fill from ``(x, y)``, visiting every cell it fills.
+    pub fn flood_fill_from(&mut self, x: u32, y: u32, colour: Colour) {
         #[cfg(feature = "profile")]
         let mut last_log = Instant::now();
         let mut seen = HashSet::new();
@@ -136,10 +211,10 @@ impl Grid {
                         if let (Some(sx), Some(sy)) = (shifted_x, shifted_y) {
                             // If the cell is uncoloured, fill it and continue flood fill
                             // from there
-                            if self.get(sx as u32, sy as u32) == Some(Colour::Colourless) {
-                                if seen.insert((sx as u32, sy as u32)) {
-                                    queue.push_back((sx as u32, sy as u32));
-                                }
+                            if self.get(sx as u32, sy as u32) == Some(Colour::Colourless)
+                                && seen.insert((sx as u32, sy as u32))
+                            {
+                                queue.push_back((sx as u32, sy as u32));
                             }
                         }
                     }
@@ -160,7 +235,11 @@ impl Grid {
     /// must only differ by 1 in either the x or y axis from the previous coordinate.
     ///
     /// Otherwise a block will be drawn between non-adjacent coordinates.
+    ///
+    /// The boundary is also kept as a polygon for [`Grid::fill_from`].
     pub fn boundary(&mut self, coords: &[Coords]) {
+        self.polygon = polygon_from_coords(coords).ok();
+
         for (node_a, node_b) in coords.iter().circular_tuple_windows() {
             let range_x = if node_a[0] <= node_b[0] {
                 node_a[0]..=node_b[0]
@@ -249,11 +328,10 @@ impl Grid {
             .iter()
             .flat_map(|line| {
                 line.chars()
-                    .map(|ch| match ch {
-                        '1' => Colour::Red,
-                        '2' => Colour::Green,
-                        '3' => Colour::White,
-                        _ => Colour::Colourless,
+                    .map(|ch| {
+                        ch.to_digit(10)
+                            .and_then(|digit| Colour::try_from(digit as u8).ok())
+                            .unwrap_or(Colour::Colourless)
                     })
                     .collect::<Vec<Colour>>()
             })
@@ -263,10 +341,156 @@ impl Grid {
             width,
             height,
             cells,
+            polygon: None,
         })
     }
 }
 
+/// The first bytes of a grid saved by [`Grid::write_binary`].
+pub const BINARY_MAGIC: &[u8; 7] = b"AOCGRID";
+
+/// The version of the format written by [`Grid::write_binary`]; bumped whenever it
+/// changes, so that older files are refused rather than misread.
+pub const BINARY_VERSION: u8 = 1;
+
+/// Write ``value`` as an unsigned LEB128 varint: seven bits at a time, lowest first,
+/// with the top bit set on every byte but the last.
+fn write_varint(writer: &mut impl std::io::Write, mut value: u64) -> std::io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Read an unsigned LEB128 varint written by [`write_varint`].
+fn read_varint(reader: &mut impl std::io::Read) -> anyhow::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    anyhow::bail!("Varint is longer than 64 bits")
+}
+
+impl Grid {
+    /// Write the grid to ``writer`` in a compact binary format, keeping every colour.
+    ///
+    /// The format is [`BINARY_MAGIC`], a [`BINARY_VERSION`] byte, the width and height as
+    /// little-endian ``u32``s, then the cells row by row as runs of the same colour: each
+    /// a colour byte followed by the length of the run as a LEB128 varint. Runs carry on
+    /// from one row to the next, so a grid that is mostly one colour takes a few bytes
+    /// per change of colour, however large it is.
+    ///
+    /// The polygon of the last boundary drawn is not saved.
+    pub fn write_binary(&self, mut writer: impl std::io::Write) -> anyhow::Result<()> {
+        writer.write_all(BINARY_MAGIC)?;
+        writer.write_all(&[BINARY_VERSION])?;
+        writer.write_all(&self.width.to_le_bytes())?;
+        writer.write_all(&self.height.to_le_bytes())?;
+
+        for (colour, run) in &self.cells.iter().chunk_by(|&&colour| colour) {
+            writer.write_all(&[colour as u8])?;
+            write_varint(&mut writer, run.count() as u64)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a grid written by [`write_binary`](Self::write_binary) from ``reader``.
+    ///
+    /// Returns an error if ``reader`` does not start with [`BINARY_MAGIC`], is of
+    /// another [`BINARY_VERSION`], has an unknown colour, or has runs that do not add up
+    /// to exactly the width times the height.
+    pub fn read_binary(mut reader: impl std::io::Read) -> anyhow::Result<Self> {
+        let mut magic = [0; BINARY_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != BINARY_MAGIC {
+            anyhow::bail!(
+                "Not a binary grid; expected it to start with {:?}",
+                BINARY_MAGIC
+            );
+        }
+
+        let mut header = [0; 9];
+        reader.read_exact(&mut header)?;
+        if header[0] != BINARY_VERSION {
+            anyhow::bail!(
+                "Binary grid is of version {}, but only version {} can be read",
+                header[0],
+                BINARY_VERSION
+            );
+        }
+        let width = u32::from_le_bytes(header[1..5].try_into()?);
+        let height = u32::from_le_bytes(header[5..9].try_into()?);
+
+        let size = width as usize * height as usize;
+        let mut cells = Vec::with_capacity(size);
+        while cells.len() < size {
+            let mut colour = [0];
+            reader.read_exact(&mut colour)?;
+            let colour = Colour::try_from(colour[0])?;
+            let run = read_varint(&mut reader)?;
+            if run == 0 || run > (size - cells.len()) as u64 {
+                anyhow::bail!(
+                    "Run of {} cells of {:?} does not fit the {} cells left of the grid",
+                    run,
+                    colour,
+                    size - cells.len()
+                );
+            }
+            cells.resize(cells.len() + run as usize, colour);
+        }
+
+        if reader.read(&mut [0])? != 0 {
+            anyhow::bail!("Binary grid has data past its last cell");
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+            polygon: None,
+        })
+    }
+
+    /// Save the grid to ``path`` in the binary format of
+    /// [`write_binary`](Self::write_binary).
+    pub fn save_binary_to(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_binary(&mut writer)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Load a grid saved by [`save_binary_to`](Self::save_binary_to) from ``path``.
+    pub fn load_binary_from(path: &std::path::Path) -> anyhow::Result<Self> {
+        Self::read_binary(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+}
+
+/// Paints the cells filled by [`fill_into`] onto a [`Grid`], leaving coloured cells alone.
+struct GridPainter<'g> {
+    grid: &'g mut Grid,
+    colour: Colour,
+}
+
+impl RasterTarget for GridPainter<'_> {
+    fn bounds(&self) -> Quad {
+        Quad::new(0, 0, self.grid.width as i64, self.grid.height as i64)
+    }
+
+    fn set(&mut self, x: i64, y: i64) {
+        self.grid.set_if_colourless(x as u32, y as u32, self.colour);
+    }
+}
+
 impl std::fmt::Display for Grid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "  ")?;
@@ -321,6 +545,25 @@ mod test {
             assert_eq!(grid.get(3, 6), Some(Colour::Green));
         }
 
+        #[test]
+        fn test_fill_matches_flood_fill() {
+            let indexed_coords =
+                indexed_coords_from_text(INPUT).expect("Failed to parse indexed coords");
+            let coords: Vec<Coords> = indexed_coords.iter().map(|ic| ic.coords).collect();
+
+            for (x, y, colour) in [(2, 2, Colour::Green), (0, 0, Colour::White)] {
+                let mut grid = Grid::new_to_fit(coords.iter(), Colour::Colourless);
+                grid.boundary(&coords);
+                assert!(grid.polygon.is_some());
+
+                let mut flooded = grid.clone();
+                grid.fill_from(x, y, colour);
+                flooded.flood_fill_from(x, y, colour);
+
+                assert_eq!(grid.cells, flooded.cells);
+            }
+        }
+
         #[test]
         fn test_fill_outside() {
             let indexed_coords =
@@ -352,6 +595,28 @@ mod test {
 
             // std::fs::remove_file(path).expect("Failed to remove test file");
         }
+
+        #[test]
+        fn test_binary_io() {
+            let indexed_coords =
+                indexed_coords_from_text(INPUT).expect("Failed to parse indexed coords");
+            let coords: Vec<Coords> = indexed_coords.iter().map(|ic| ic.coords).collect();
+            let mut grid = Grid::new_to_fit(coords.iter(), Colour::Colourless);
+            grid.boundary(&coords);
+            grid.fill_from(2, 2, Colour::Green);
+            grid.set(2, 6, Colour::Yellow);
+            grid.set(6, 8, Colour::White);
+
+            let path = std::env::temp_dir().join("aoc-2025-09-test-binary-io.grid");
+            grid.save_binary_to(&path).expect("Failed to save grid");
+            let loaded_grid = Grid::load_binary_from(&path).expect("Failed to load grid");
+            std::fs::remove_file(&path).expect("Failed to remove test file");
+
+            assert_eq!(grid.width, loaded_grid.width);
+            assert_eq!(grid.height, loaded_grid.height);
+            assert_eq!(grid.cells, loaded_grid.cells);
+            assert_eq!(loaded_grid.get(2, 6), Some(Colour::Yellow));
+        }
     }
 
     mod example {
@@ -386,4 +651,130 @@ mod test {
             assert_eq!(grid.get(9, 6), Some(Colour::Green));
         }
     }
+
+    mod disconnected {
+        use super::*;
+
+        /// Two rooms joined by a corridor too narrow to have any cells inside of it.
+        const ROOMS: &str = "1,1
+                             5,1
+                             5,2
+                             7,2
+                             7,1
+                             11,1
+                             11,5
+                             7,5
+                             7,3
+                             5,3
+                             5,5
+                             1,5";
+
+        /// An L against the top and left of the grid, cutting the top left corner off from
+        /// the rest of the outside.
+        const CORNER: &str = "2,0
+                              6,0
+                              6,6
+                              0,6
+                              0,2
+                              2,2";
+
+        fn grid_of(input: &str) -> Grid {
+            let indexed_coords =
+                indexed_coords_from_text(input).expect("Failed to parse indexed coords");
+            let coords: Vec<Coords> = indexed_coords.iter().map(|ic| ic.coords).collect();
+            let mut grid = Grid::new_to_fit(coords.iter(), Colour::Colourless);
+            grid.boundary(&coords);
+            assert!(grid.polygon.is_some());
+
+            grid
+        }
+
+        #[test]
+        fn test_fill_every_room() {
+            let mut grid = grid_of(ROOMS);
+            let mut flooded = grid.clone();
+            grid.fill_from(2, 2, Colour::Green);
+            flooded.flood_fill_from(2, 2, Colour::Green);
+
+            eprintln!("{}", grid);
+
+            assert_eq!(grid.get(9, 3), Some(Colour::Green));
+            assert_eq!(flooded.get(9, 3), Some(Colour::Colourless));
+
+            // Flooding the other room as well fills the same cells.
+            flooded.flood_fill_from(9, 3, Colour::Green);
+            assert_eq!(grid.cells, flooded.cells);
+        }
+
+        #[test]
+        fn test_fill_every_part_of_the_outside() {
+            let mut grid = grid_of(CORNER);
+            let mut flooded = grid.clone();
+            grid.fill_from(0, 0, Colour::White);
+            flooded.flood_fill_from(0, 0, Colour::White);
+
+            eprintln!("{}", grid);
+
+            assert_eq!(grid.get(7, 7), Some(Colour::White));
+            assert_eq!(flooded.get(7, 7), Some(Colour::Colourless));
+
+            flooded.flood_fill_from(7, 7, Colour::White);
+            assert_eq!(grid.cells, flooded.cells);
+        }
+    }
+
+    mod binary {
+        use super::*;
+
+        fn written(grid: &Grid) -> Vec<u8> {
+            let mut bytes = Vec::new();
+            grid.write_binary(&mut bytes).expect("Failed to write grid");
+            bytes
+        }
+
+        #[test]
+        fn test_compact() {
+            let mut grid = Grid::new(1000, 1000, Colour::White);
+            grid.set(500, 500, Colour::Yellow);
+            let bytes = written(&grid);
+
+            // The header, then three runs of at most three bytes of length each.
+            assert!(bytes.len() <= 16 + 3 * 4, "{} bytes", bytes.len());
+            let loaded = Grid::read_binary(bytes.as_slice()).expect("Failed to read grid");
+            assert_eq!(loaded.cells, grid.cells);
+        }
+
+        #[test]
+        fn test_empty() {
+            let grid = Grid::new(0, 3, Colour::Red);
+            let loaded = Grid::read_binary(written(&grid).as_slice()).expect("Failed to read");
+            assert_eq!((loaded.width, loaded.height), (0, 3));
+            assert!(loaded.cells.is_empty());
+        }
+
+        #[test]
+        fn test_invalid() {
+            let grid = Grid::new(4, 2, Colour::Green);
+            let bytes = written(&grid);
+            let read = |bytes: &[u8]| Grid::read_binary(bytes);
+
+            assert!(read(&bytes).is_ok());
+            assert!(read(b"0000000").is_err());
+
+            let mut wrong_version = bytes.clone();
+            wrong_version[BINARY_MAGIC.len()] = BINARY_VERSION + 1;
+            assert!(read(&wrong_version).is_err());
+
+            let mut unknown_colour = bytes.clone();
+            unknown_colour[16] = 9;
+            assert!(read(&unknown_colour).is_err());
+
+            let mut too_long = bytes.clone();
+            too_long[17] = 9;
+            assert!(read(&too_long).is_err());
+
+            assert!(read(&bytes[..bytes.len() - 1]).is_err());
+            assert!(read(&[bytes.as_slice(), &[1, 1]].concat()).is_err());
+        }
+    }
 }
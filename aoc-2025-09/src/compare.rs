@@ -1,174 +1,204 @@
-use crate::models::*;
+use crate::{CompressedGrid, models::*};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// Finds the largest area from a list of indexed coordinates.
 ///
+/// ``predicate`` compares each rectangle to the best so far, and may carry state of its
+/// own from one call to the next; for more than the one best rectangle, see
+/// [`find_top_matches`].
+///
 /// ``indexed_coords`` must be sorted in their index in ascending order.
 pub fn find_best_match(
     indexed_coords: &[IndexedCoords],
-    predicate: impl Fn(&Rectangle, &Rectangle) -> anyhow::Result<std::cmp::Ordering>,
+    mut predicate: impl FnMut(&Rectangle, &Rectangle) -> anyhow::Result<std::cmp::Ordering>,
 ) -> anyhow::Result<Option<Rectangle>> {
-    indexed_coords.iter().fold(
-        Ok(None),
-        |candidate: anyhow::Result<Option<Rectangle>>, &current| {
+    indexed_coords
+        .iter()
+        .try_fold(None, |candidate: Option<Rectangle>, &current| {
             indexed_coords[current.index + 1..]
                 .iter()
-                .fold(candidate, |inner_candidate, &next| {
-                    if let Ok(opt_rec) = inner_candidate {
-                        let rect = Rectangle::new(current, next);
-                        #[cfg(feature = "trace")]
-                        {
-                            eprintln!(
-                                "Considering rectangle between {:?} and {:?} with area {}",
-                                current.coords,
-                                next.coords,
-                                rect.area()
-                            );
-                        }
-                        match opt_rec {
-                            Some(current_candidate) => {
-                                if rect.compare(&current_candidate, &predicate)?
-                                    == std::cmp::Ordering::Greater
-                                {
-                                    Ok(Some(rect))
-                                } else {
-                                    Ok(Some(current_candidate))
-                                }
+                .try_fold(candidate, |opt_rec, &next| {
+                    let rect = Rectangle::new(current, next);
+                    #[cfg(feature = "trace")]
+                    {
+                        eprintln!(
+                            "Considering rectangle between {:?} and {:?} with area {}",
+                            current.coords,
+                            next.coords,
+                            rect.area()
+                        );
+                    }
+                    match opt_rec {
+                        Some(current_candidate) => {
+                            if rect.compare(&current_candidate, &mut predicate)?
+                                == std::cmp::Ordering::Greater
+                            {
+                                Ok(Some(rect))
+                            } else {
+                                Ok(Some(current_candidate))
                             }
-                            None => Ok(Some(rect)),
                         }
-                    } else {
-                        inner_candidate
+                        None => Ok(Some(rect)),
                     }
                 })
-        },
-    )
+        })
 }
 
-pub fn compare_area_with_visibility(
-    candidate: &Rectangle,
-    current: &Rectangle,
-) -> anyhow::Result<std::cmp::Ordering> {
-    let chosen_points = (
-        candidate.original_coords[0].index,
-        candidate.original_coords[1].index,
-    );
-    let target = (1, 30);
-    match candidate.area().cmp(&current.area()) {
-        std::cmp::Ordering::Greater => {
-            let results = candidate
-                .original_points_by_corners()
-                .map(|(corner, indexed_coords)| -> anyhow::Result<bool> {
-                    let visbounds = indexed_coords.visibility_bounds.ok_or(anyhow::anyhow!(
-                        "Indexed coordinate {:?} has no visibility bounds",
-                        indexed_coords.coords
-                    ))?;
-
-                    if chosen_points == target{
-                        dbg!(&corner, &indexed_coords);
-                    }
+/// Finds the largest area from a list of indexed coordinates, as [`find_best_match`] but
+/// considering the pairs of coordinates in parallel.
+///
+/// Each thread folds over its share of the pairs starting from the same rectangle
+/// [`find_best_match`] starts from, so that ``predicate`` never has to accept a rectangle
+/// it has not checked; the best of each are then reduced to one. Where ``predicate``
+/// finds two rectangles equal, the one between the lower indices wins, as it would have
+/// done sequentially; so the result does not depend on how the pairs were split up.
+///
+/// ``indexed_coords`` must be sorted in their index in ascending order.
+#[cfg(feature = "rayon")]
+pub fn par_find_best_match(
+    indexed_coords: &[IndexedCoords],
+    predicate: impl Fn(&Rectangle, &Rectangle) -> anyhow::Result<std::cmp::Ordering> + Sync,
+) -> anyhow::Result<Option<Rectangle>> {
+    let [first, second, ..] = indexed_coords else {
+        return Ok(None);
+    };
+    let start = Rectangle::new(*first, *second);
 
-                    let is_within = match corner {
-                        Corner::TopLeft => {
-                            // Check if the top left corner can see beyond the candidate rectangle
-                            visbounds
-                                .right
-                                .map(|right_bound| right_bound >= candidate.top_right()[0])
-                                .unwrap_or(false)
-                                && visbounds
-                                    .bottom
-                                    .map(|bottom_bound| {
-                                        bottom_bound >= candidate.bottom_left()[1]
-                                    })
-                                    .unwrap_or(false)
-                        }
-                        Corner::TopRight => {
-                            visbounds
-                                .left
-                                .map(|left_bound| left_bound <= candidate.top_left()[0])
-                                .unwrap_or(false)
-                                && visbounds
-                                    .bottom
-                                    .map(|bottom_bound| {
-                                        bottom_bound >= candidate.bottom_right()[1]
-                                    })
-                                    .unwrap_or(false)
-                        }
-                        Corner::BottomLeft => {
-                            visbounds
-                                .right
-                                .map(|right_bound| right_bound >= candidate.bottom_right()[0])
-                                .unwrap_or(false)
-                                && visbounds
-                                    .top
-                                    .map(|top_bound| {
-                                        top_bound <= candidate.top_left()[1]
-                                    })
-                                    .unwrap_or(false)
-                        }
-                        Corner::BottomRight => {
-                            visbounds
-                                .left
-                                .map(|left_bound| left_bound <= candidate.bottom_left()[0])
-                                .unwrap_or(false)
-                                && visbounds
-                                    .top
-                                    .map(|top_bound| top_bound <= candidate.top_right()[1])
-                                    .unwrap_or(false)
-                        }
-                    };
+    let better = |current: Rectangle, rect: Rectangle| -> anyhow::Result<Rectangle> {
+        let indices = |rect: &Rectangle| rect.original_coords.map(|coords| coords.index);
+        Ok(match rect.compare(&current, &predicate)? {
+            std::cmp::Ordering::Greater => rect,
+            std::cmp::Ordering::Equal if indices(&rect) < indices(&current) => rect,
+            _ => current,
+        })
+    };
 
-                    if chosen_points == target {
-                        dbg!(&is_within);
-                    }
+    indexed_coords
+        .par_iter()
+        .flat_map_iter(|&current| {
+            indexed_coords[current.index + 1..]
+                .iter()
+                .map(move |&next| Rectangle::new(current, next))
+        })
+        .try_fold(|| start, better)
+        .try_reduce(|| start, better)
+        .map(Some)
+}
 
-                    #[cfg(feature = "trace")]
-                    {
-                        if is_within {
-                            eprintln!(
-                                "{:?} at {:?} can see the neighbouring corners of candidate rectangle",
-                                corner, indexed_coords.coords
-                            );
-                        } else {
-                            eprintln!(
-                                "{:?} at {:?} cannot see the neighbouring corners of candidate rectangle with bounds {:?}",
-                                corner, indexed_coords.coords, visbounds
-                            );
-                        }
-                    }
+/// A rectangle with its score, ordered by score, then by the indices of its corners,
+/// lowest first.
+struct Ranked<S> {
+    score: S,
+    rect: Rectangle,
+}
 
-                    Ok(is_within)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()?;
-
-            if results.iter().all(|&v| v) {
-                #[cfg(feature = "trace")]
-                {
-                    eprintln!(
-                        "Candidate rectangle with area {} is bigger than current with area {} and is within the polygon",
-                        candidate.area(),
-                        current.area()
-                    );
-                }
-                Ok(std::cmp::Ordering::Greater)
-            } else {
-                #[cfg(feature = "trace")]
-                {
-                    eprintln!(
-                        "Candidate rectangle with area {} is bigger than current with area {} but is NOT within the polygon",
-                        candidate.area(),
-                        current.area()
-                    );
+impl<S: Ord> Ranked<S> {
+    fn indices(&self) -> [usize; 2] {
+        self.rect.original_coords.map(|coords| coords.index)
+    }
+}
+
+impl<S: Ord> PartialEq for Ranked<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl<S: Ord> Eq for Ranked<S> {}
+
+impl<S: Ord> PartialOrd for Ranked<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Ord> Ord for Ranked<S> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score
+            .cmp(&other.score)
+            .then_with(|| other.indices().cmp(&self.indices()))
+    }
+}
+
+/// Finds the ``k`` best rectangles from a list of indexed coordinates, best first, with
+/// their scores.
+///
+/// Rather than comparing two rectangles, ``score`` rates each on its own, returning
+/// [`None`] to leave it out altogether; it may carry state of its own from one call to
+/// the next, such as a count of the rectangles it has left out. Rectangles of equal score
+/// are ranked by the indices of their corners, lowest first.
+///
+/// Only the best ``k`` are kept as the pairs are scored, so this takes ``O(N² log k)``.
+///
+/// ``indexed_coords`` must be sorted in their index in ascending order.
+pub fn find_top_matches<S: Ord>(
+    indexed_coords: &[IndexedCoords],
+    k: usize,
+    mut score: impl FnMut(&Rectangle) -> anyhow::Result<Option<S>>,
+) -> anyhow::Result<Vec<(S, Rectangle)>> {
+    // The worst of the best so far is at the top, to be dropped first.
+    let mut best = BinaryHeap::with_capacity(k + 1);
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+
+    for &current in indexed_coords {
+        for &next in &indexed_coords[current.index + 1..] {
+            let rect = Rectangle::new(current, next);
+            if let Some(score) = score(&rect)? {
+                best.push(Reverse(Ranked { score, rect }));
+                if best.len() > k {
+                    best.pop();
                 }
-                Ok(std::cmp::Ordering::Less)
             }
         }
+    }
+
+    Ok(best
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(Ranked { score, rect })| (score, rect))
+        .collect())
+}
+
+/// Score rectangles by area for [`find_top_matches`], leaving out those with any tile
+/// outside the polygon of ``grid``.
+pub fn score_area_within(
+    grid: &CompressedGrid,
+) -> impl FnMut(&Rectangle) -> anyhow::Result<Option<u64>> + '_ {
+    move |rect| Ok(grid.contains_rectangle(rect).then(|| rect.area()))
+}
+
+/// Compare rectangles by area, only preferring a larger ``candidate`` if every one of its
+/// tiles is inside the polygon of ``grid``, or on its boundary.
+///
+/// The rectangle ``find_best_match`` starts from is not checked, but it is always
+/// between the first two vertices, i.e. along the first edge, which is on the boundary.
+pub fn compare_area_within(
+    grid: &CompressedGrid,
+) -> impl Fn(&Rectangle, &Rectangle) -> anyhow::Result<std::cmp::Ordering> + '_ {
+    move |candidate, current| match candidate.area().cmp(&current.area()) {
+        std::cmp::Ordering::Greater if !grid.contains_rectangle(candidate) => {
+            #[cfg(feature = "trace")]
+            {
+                eprintln!(
+                    "Candidate rectangle with area {} is bigger than current with area {} but is NOT within the polygon",
+                    candidate.area(),
+                    current.area()
+                );
+            }
+            Ok(std::cmp::Ordering::Less)
+        }
         ord => Ok(ord),
     }
 }
 
 #[cfg(test)]
-mod tests_compare_area_with_visibility {
-    use crate::{colour, indexed_coords_from_text, models, visibility};
+pub(crate) mod tests_compare_area_within {
+    use crate::{CompressedGrid, colour, indexed_coords_from_text, models, models::polygon};
 
     use super::*;
 
@@ -176,12 +206,32 @@ mod tests_compare_area_with_visibility {
         ($name:ident($input:expr) = $expected:literal) => {
             #[test]
             fn $name() {
-                let indexed_coords = visibility::build_visibility_bounds_for_indexed_coords(
-                    indexed_coords_from_text($input).expect("Failed to parse indexed coords"),
-                );
+                let indexed_coords =
+                    indexed_coords_from_text($input).expect("Failed to parse indexed coords");
+                let compressed =
+                    CompressedGrid::new(&indexed_coords).expect("Failed to compress polygon");
+
+                let best_rectangle =
+                    find_best_match(&indexed_coords, compare_area_within(&compressed))
+                        .expect("Error finding best match within polygon")
+                        .expect("No rectangle found within polygon");
+
+                let (min_x, max_x, min_y, max_y) = best_rectangle.bounding;
+                for corner in [
+                    [min_x, min_y],
+                    [max_x, min_y],
+                    [min_x, max_y],
+                    [max_x, max_y],
+                ] {
+                    assert!(
+                        polygon::contains_point(&indexed_coords, corner),
+                        "Corner {:?} of the best rectangle is outside the polygon",
+                        corner
+                    );
+                }
+
                 let coords: Vec<models::Coords> =
                     indexed_coords.iter().map(|ic| ic.coords).collect();
-
                 let mut grid = {
                     let mut grid =
                         colour::Grid::new_to_fit(coords.iter(), colour::Colour::Colourless);
@@ -189,22 +239,6 @@ mod tests_compare_area_with_visibility {
                     grid.fill_from(0, 0, colour::Colour::White);
                     grid
                 };
-                eprintln!("Before:\n{}", grid);
-
-                let best_rectangle =
-                    find_best_match(&indexed_coords, |a, b| compare_area_with_visibility(a, b))
-                        .expect("Error finding best match with visibility")
-                        .expect("No rectangle found within polygon");
-
-                let visibility_bounds = best_rectangle
-                    .original_points_by_corners()
-                    .map(|(c, ic)| (c, ic.visibility_bounds))
-                    .collect::<Vec<_>>();
-
-                dbg!(&best_rectangle);
-                dbg!(visibility_bounds[0]);
-                dbg!(visibility_bounds[1]);
-
                 grid.draw_rectangle_if(
                     &best_rectangle,
                     |colour| colour != Some(colour::Colour::White),
@@ -213,15 +247,23 @@ mod tests_compare_area_with_visibility {
                 .expect("Failed to draw rectangle");
 
                 eprintln!("After:\n{}", grid);
-
-                dbg!("Best rectangle: {:?}", &best_rectangle.bounding);
-                dbg!(indexed_coords[0].visibility_bounds.as_ref());
                 assert_eq!(best_rectangle.area(), $expected);
+
+                let top = find_top_matches(&indexed_coords, 1, score_area_within(&compressed))
+                    .expect("Error finding top matches within polygon");
+                assert_eq!(top.first().map(|&(area, _)| area), Some($expected));
+
+                #[cfg(feature = "rayon")]
+                assert_eq!(
+                    par_find_best_match(&indexed_coords, compare_area_within(&compressed))
+                        .expect("Error finding best match in parallel"),
+                    Some(best_rectangle)
+                );
             }
         };
     }
 
-    const EXAMPLE: &str = "7,1
+    pub(crate) const EXAMPLE: &str = "7,1
                            11,1
                            11,7
                            9,7
@@ -232,7 +274,7 @@ mod tests_compare_area_with_visibility {
 
     create_test!(example_polygon(EXAMPLE) = 24);
 
-    const VERTICAL_STALAGMITE: &str = "1,3
+    pub(crate) const VERTICAL_STALAGMITE: &str = "1,3
                                        2,3
                                        2,1
                                        4,1
@@ -250,7 +292,7 @@ mod tests_compare_area_with_visibility {
                                        1,11";
     create_test!(v_stalagmite_polygon(VERTICAL_STALAGMITE) = 36);
 
-    const HORIZONTAL_STALAGMITE: &str = "3,1
+    pub(crate) const HORIZONTAL_STALAGMITE: &str = "3,1
                                          3,2
                                          1,2
                                          1,4
@@ -268,7 +310,7 @@ mod tests_compare_area_with_visibility {
                                          11,1";
     create_test!(h_stalagmite_polygon(HORIZONTAL_STALAGMITE) = 36);
 
-    const PACMAN: &str = "1,6
+    pub(crate) const PACMAN: &str = "1,6
                           1,5
                           2,5
                           2,4
@@ -318,5 +360,114 @@ mod tests_compare_area_with_visibility {
     // 4 ....#XXXXXXX#...
     // 5 ................
 
-    // create_test!(pacman(PACMAN) = 36);
+    create_test!(pacman(PACMAN) = 45);
+}
+
+#[cfg(test)]
+mod tests_find_top_matches {
+    use super::tests_compare_area_within::EXAMPLE;
+    use super::*;
+    use crate::indexed_coords_from_text;
+
+    #[test]
+    fn test_top_matches_within() {
+        let indexed_coords =
+            indexed_coords_from_text(EXAMPLE).expect("Failed to parse indexed coords");
+        let compressed = CompressedGrid::new(&indexed_coords).expect("Failed to compress polygon");
+
+        let top = find_top_matches(&indexed_coords, 4, score_area_within(&compressed))
+            .expect("Error finding top matches");
+        assert_eq!(
+            top.iter().map(|&(area, _)| area).collect::<Vec<_>>(),
+            vec![24, 21, 18, 15]
+        );
+        assert!(
+            top.iter()
+                .all(|(_, rect)| compressed.contains_rectangle(rect))
+        );
+
+        // More than there are rectangles within the polygon.
+        let all = find_top_matches(&indexed_coords, 100, score_area_within(&compressed))
+            .expect("Error finding top matches");
+        assert!(all.len() < 28);
+        assert!(all.windows(2).all(|pair| pair[0].0 >= pair[1].0));
+
+        assert!(
+            find_top_matches(&indexed_coords, 0, score_area_within(&compressed))
+                .expect("Error finding top matches")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_stateful_scoring() {
+        let indexed_coords =
+            indexed_coords_from_text(EXAMPLE).expect("Failed to parse indexed coords");
+        let compressed = CompressedGrid::new(&indexed_coords).expect("Failed to compress polygon");
+
+        let (mut scored, mut left_out) = (0, 0);
+        let mut within = score_area_within(&compressed);
+        let top = find_top_matches(&indexed_coords, 1, |rect| {
+            scored += 1;
+            let area = within(rect)?;
+            left_out += usize::from(area.is_none());
+            Ok(area)
+        })
+        .expect("Error finding top matches");
+        assert_eq!(top[0].0, 24);
+        assert_eq!(scored, 28);
+        assert!(left_out > 0);
+
+        let mut compared = 0;
+        let best = find_best_match(&indexed_coords, |a, b| {
+            compared += 1;
+            Ok(a.area().cmp(&b.area()))
+        })
+        .expect("Error finding best match");
+        assert_eq!(best.map(|rect| rect.area()), Some(50));
+        assert_eq!(compared, 27);
+    }
+
+    #[test]
+    fn test_ties() {
+        // A square, where each pair of opposite corners makes the same rectangle.
+        let indexed_coords =
+            indexed_coords_from_text("1,1\n4,1\n4,4\n1,4").expect("Failed to parse");
+
+        let top = find_top_matches(&indexed_coords, 2, |rect| Ok(Some(rect.area())))
+            .expect("Error finding top matches");
+        assert_eq!(
+            top.iter()
+                .map(|(area, rect)| (*area, rect.original_coords.map(|coords| coords.index)))
+                .collect::<Vec<_>>(),
+            vec![(16, [0, 2]), (16, [1, 3])]
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_largest_inscribed_rect {
+    use super::tests_compare_area_within::*;
+    use crate::{indexed_coords_from_text, models};
+    use tessellation_fill::largest_inscribed_rect;
+
+    macro_rules! create_test {
+        ($name:ident($input:expr) = $expected:literal) => {
+            #[test]
+            fn $name() {
+                let indexed_coords =
+                    indexed_coords_from_text($input).expect("Failed to parse indexed coords");
+                let polygon = models::polygon_from_indexed_coords(&indexed_coords)
+                    .expect("Failed to build polygon");
+
+                let rect = largest_inscribed_rect(&polygon).expect("No rectangle found");
+                assert_eq!(rect.area(), $expected);
+            }
+        };
+    }
+
+    create_test!(example_polygon(EXAMPLE) = 24);
+    create_test!(v_stalagmite_polygon(VERTICAL_STALAGMITE) = 36);
+    create_test!(h_stalagmite_polygon(HORIZONTAL_STALAGMITE) = 36);
+    create_test!(pacman(PACMAN) = 45);
 }
@@ -0,0 +1,278 @@
+//! Which tiles are inside the polygon, on a grid compressed down to the coordinates of its
+//! vertices.
+//!
+//! The polygon of the real input spans ``100000 x 100000`` tiles, but only has a few
+//! hundred vertices; between two neighbouring vertex coordinates, every column of tiles
+//! is the same as the next. So each axis is cut at every vertex coordinate and the one
+//! after it, and each compressed cell stands for a whole block of tiles, all inside or
+//! all outside.
+
+use crate::models::{Coord, IndexedCoords, Rectangle, polygon};
+use itertools::Itertools;
+
+/// The tiles inside or on the boundary of an orthogonal polygon, compressed to blocks
+/// between its vertex coordinates.
+///
+/// Whether each block is inside is found by casting a ray from it towards positive ``x``
+/// and counting the vertical edges it crosses, so unlike visibility bounds, this knows
+/// which side of each edge is the inside. Whether a rectangle is wholly inside is then
+/// a lookup in a summed area table of the blocks outside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedGrid {
+    /// The first tile of each column of blocks, with one past the last tile at the end;
+    /// column ``i`` covers the tiles from ``xs[i]`` to ``xs[i + 1] - 1``.
+    xs: Vec<Coord>,
+    /// The first tile of each row of blocks, as ``xs``.
+    ys: Vec<Coord>,
+    /// Whether each block is inside or on the boundary, row by row.
+    filled: Vec<bool>,
+    /// The number of blocks outside the polygon above and to the left of each block, with
+    /// an extra row and column of zeros at the start.
+    outside_before: Vec<u32>,
+}
+
+/// The axis-aligned edges of ``vertices``, as ``(at, low, high)`` for each of the
+/// vertical and horizontal edges.
+type AxisEdges = Vec<(Coord, Coord, Coord)>;
+
+impl CompressedGrid {
+    /// Compress the polygon with ``vertices`` in order.
+    ///
+    /// Returns an error for the first edge that is neither horizontal nor vertical.
+    pub fn new(vertices: &[IndexedCoords]) -> anyhow::Result<Self> {
+        let (vertical, horizontal) = Self::edges_of(vertices)?;
+
+        let cuts = |axis: usize| {
+            vertices
+                .iter()
+                .flat_map(|vertex| [vertex.coords[axis], vertex.coords[axis] + 1])
+                .sorted_unstable()
+                .dedup()
+                .collect::<Vec<_>>()
+        };
+        let (xs, ys) = (cuts(0), cuts(1));
+        let (columns, rows) = (xs.len().saturating_sub(1), ys.len().saturating_sub(1));
+
+        let mut filled = Vec::with_capacity(columns * rows);
+        for &y in ys.iter().take(rows) {
+            // The ray from a tile crosses a vertical edge if the edge covers the row of the
+            // tile, counting its top end but not its bottom, so that a ray through a vertex
+            // counts the two edges meeting there once between them, or not at all.
+            let crossings = vertical
+                .iter()
+                .filter(|&&(_, low, high)| low <= y && y < high)
+                .map(|&(x, _, _)| x)
+                .sorted_unstable()
+                .collect::<Vec<_>>();
+
+            let mut on_boundary = vec![false; columns];
+            let column_of = |x: Coord| xs.partition_point(|&cut| cut <= x) - 1;
+            vertical
+                .iter()
+                .filter(|&&(_, low, high)| low <= y && y <= high)
+                .for_each(|&(x, _, _)| on_boundary[column_of(x)] = true);
+            horizontal
+                .iter()
+                .filter(|&&(at, _, _)| at == y)
+                .for_each(|&(_, low, high)| {
+                    on_boundary[column_of(low)..=column_of(high)].fill(true)
+                });
+
+            let mut crossed = 0;
+            for (column, &x) in xs.iter().take(columns).enumerate() {
+                while crossed < crossings.len() && crossings[crossed] <= x {
+                    crossed += 1;
+                }
+                let inside = (crossings.len() - crossed) % 2 == 1;
+                filled.push(on_boundary[column] || inside);
+            }
+        }
+
+        let mut outside_before = vec![0; (columns + 1) * (rows + 1)];
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = |row: usize, column: usize| row * (columns + 1) + column;
+                outside_before[index(row + 1, column + 1)] = outside_before[index(row, column + 1)]
+                    + outside_before[index(row + 1, column)]
+                    - outside_before[index(row, column)]
+                    + u32::from(!filled[row * columns + column]);
+            }
+        }
+
+        Ok(Self {
+            xs,
+            ys,
+            filled,
+            outside_before,
+        })
+    }
+
+    /// Split the edges between consecutive ``vertices`` into vertical and horizontal.
+    fn edges_of(vertices: &[IndexedCoords]) -> anyhow::Result<(AxisEdges, AxisEdges)> {
+        let (mut vertical, mut horizontal) = (Vec::new(), Vec::new());
+
+        for ([x0, y0], [x1, y1]) in polygon::edges(vertices) {
+            if x0 == x1 {
+                vertical.push((x0, y0.min(y1), y0.max(y1)));
+            } else if y0 == y1 {
+                horizontal.push((y0, x0.min(x1), x0.max(x1)));
+            } else {
+                anyhow::bail!(
+                    "Edge from {:?} to {:?} is neither horizontal nor vertical",
+                    [x0, y0],
+                    [x1, y1]
+                );
+            }
+        }
+
+        Ok((vertical, horizontal))
+    }
+
+    /// The number of columns and rows of blocks.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (
+            self.xs.len().saturating_sub(1),
+            self.ys.len().saturating_sub(1),
+        )
+    }
+
+    /// The index of the column or row of blocks in ``cuts`` containing ``tile``, if any.
+    fn block_of(cuts: &[Coord], tile: Coord) -> Option<usize> {
+        let index = cuts.partition_point(|&cut| cut <= tile);
+        (index > 0 && index < cuts.len()).then(|| index - 1)
+    }
+
+    /// The column and row of the block containing the tile at ``coords``, if any.
+    pub fn block_containing(&self, coords: [Coord; 2]) -> Option<(usize, usize)> {
+        Some((
+            Self::block_of(&self.xs, coords[0])?,
+            Self::block_of(&self.ys, coords[1])?,
+        ))
+    }
+
+    /// Whether the block at ``column`` and ``row`` is inside the polygon or on its
+    /// boundary.
+    ///
+    /// Panics if either is out of bounds.
+    pub fn is_filled(&self, column: usize, row: usize) -> bool {
+        let (columns, rows) = self.dimensions();
+        assert!(
+            column < columns && row < rows,
+            "Block ({}, {}) is outside the grid of {} x {} blocks",
+            column,
+            row,
+            columns,
+            rows
+        );
+        self.filled[row * columns + column]
+    }
+
+    /// Whether the tile at ``coords`` is inside the polygon or on its boundary.
+    pub fn contains(&self, coords: [Coord; 2]) -> bool {
+        self.block_containing(coords)
+            .is_some_and(|(column, row)| self.is_filled(column, row))
+    }
+
+    /// Whether every tile of ``rectangle`` is inside the polygon or on its boundary.
+    pub fn contains_rectangle(&self, rectangle: &Rectangle) -> bool {
+        let (min_x, max_x, min_y, max_y) = rectangle.bounding;
+        let (Some(left), Some(right), Some(top), Some(bottom)) = (
+            Self::block_of(&self.xs, min_x),
+            Self::block_of(&self.xs, max_x),
+            Self::block_of(&self.ys, min_y),
+            Self::block_of(&self.ys, max_y),
+        ) else {
+            return false;
+        };
+
+        let (columns, _) = self.dimensions();
+        let at = |row: usize, column: usize| self.outside_before[row * (columns + 1) + column];
+        at(bottom + 1, right + 1) + at(top, left) == at(top, right + 1) + at(bottom + 1, left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{colour, indexed_coords_from_text};
+
+    const EXAMPLE: &str = "7,1
+                           11,1
+                           11,7
+                           9,7
+                           9,5
+                           2,5
+                           2,3
+                           7,3";
+
+    // Two arms a tile apart, joined at the top; every tile between them is on the
+    // boundary, but the space between the edges is outside.
+    const TUNING_FORK: &str = "1,1
+                               8,1
+                               8,8
+                               6,8
+                               6,3
+                               5,3
+                               5,8
+                               1,8";
+
+    #[test]
+    fn test_contains() {
+        let vertices = indexed_coords_from_text(EXAMPLE).expect("Failed to parse");
+        let grid = CompressedGrid::new(&vertices).expect("Failed to compress");
+
+        assert!(grid.contains([7, 1]));
+        assert!(grid.contains([9, 3]));
+        assert!(grid.contains([2, 4]));
+        assert!(grid.contains([10, 6]));
+        assert!(!grid.contains([2, 2]));
+        assert!(!grid.contains([8, 6]));
+        assert!(!grid.contains([0, 0]));
+        assert!(!grid.contains([12, 4]));
+    }
+
+    /// Check every tile against [`polygon::contains_point`], and every rectangle between
+    /// two vertices against a filled [`colour::Grid`].
+    fn assert_matches_grid(input: &str) {
+        let vertices = indexed_coords_from_text(input).expect("Failed to parse");
+        let compressed = CompressedGrid::new(&vertices).expect("Failed to compress");
+
+        for (x, y) in (0..=13).cartesian_product(0..=13) {
+            assert_eq!(
+                compressed.contains([x, y]),
+                polygon::contains_point(&vertices, [x, y]),
+                "{:?}",
+                [x, y]
+            );
+        }
+
+        let coords = vertices.iter().map(|ic| ic.coords).collect::<Vec<_>>();
+        let mut grid = colour::Grid::new_to_fit(coords.iter(), colour::Colour::Colourless);
+        grid.boundary(&coords);
+        grid.fill_from(0, 0, colour::Colour::White);
+
+        for (a, b) in vertices.iter().tuple_combinations() {
+            let rectangle = Rectangle::new(*a, *b);
+            let (x0, x1, y0, y1) = rectangle.bounding;
+            assert_eq!(
+                compressed.contains_rectangle(&rectangle),
+                grid.check_area(&[x0, y0], &[x1, y1], |colour| colour
+                    != Some(colour::Colour::White)),
+                "{:?}",
+                rectangle.bounding
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_grid() {
+        assert_matches_grid(EXAMPLE);
+        assert_matches_grid(TUNING_FORK);
+    }
+
+    #[test]
+    fn test_not_orthogonal() {
+        let vertices = indexed_coords_from_text("1,1\n5,1\n3,4").expect("Failed to parse");
+        assert!(CompressedGrid::new(&vertices).is_err());
+    }
+}
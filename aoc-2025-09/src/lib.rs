@@ -0,0 +1,14 @@
+//! The largest rectangle between two red tiles of Day 9 of 2025, using only red and green
+//! tiles, as a library so that its solvers can be benchmarked outside of the puzzle.
+
+pub mod colour;
+pub mod compare;
+pub mod compressed;
+pub use compressed::CompressedGrid;
+pub mod histogram;
+pub mod models;
+mod parse;
+pub use parse::*;
+mod input;
+pub use input::INPUT;
+pub mod render;
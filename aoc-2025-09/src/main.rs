@@ -1,33 +1,141 @@
-//! This code as it stands will produce the correct answer for the given input,
-//! but is fundamentally flawed and does not correctly solve the problem as stated.
+//! The largest rectangle between two red tiles, using only red and green tiles.
 //!
-//! The issue lies in the way visibility bounds are calculated for each point.
-//! See [`visibility`] module for more details.
-
-pub mod colour;
-mod compare;
-pub mod models;
-use compare::*;
-mod parse;
-use parse::*;
-mod input;
-pub use input::INPUT;
-pub mod visibility;
+//! Each candidate rectangle is checked against a [`CompressedGrid`] of which tiles are
+//! inside the polygon; see the [`compressed`] module for details.
+//!
+//! ``--solver histogram`` instead sweeps a histogram down the compressed grid; see the
+//! [`histogram`] module.
+//!
+//! ``--render <path>`` draws the polygon and the best rectangle to an SVG, or with the
+//! ``image`` feature, a PNG; by the extension of ``path``.
+
+#[cfg(doc)]
+use aoc_2025_09::compressed;
+use aoc_2025_09::{
+    CompressedGrid, INPUT,
+    compare::*,
+    histogram, indexed_coords_from_text,
+    render::{Render, RenderColours},
+};
 
 #[cfg(feature = "profile")]
 use std::time::Instant;
 
+/// The largest side of a rendered PNG, in pixels.
+#[cfg(feature = "image")]
+const PNG_MAX_SIZE: u32 = 2048;
+
+/// How to find the best rectangle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Solver {
+    /// Check every pair of vertices against the compressed grid, with
+    /// [`find_best_match`], or [`par_find_best_match`] with the ``rayon`` feature.
+    #[default]
+    Pairwise,
+    /// Sweep a histogram down the compressed grid, with
+    /// [`histogram::largest_rectangle`].
+    Histogram,
+}
+
+impl std::str::FromStr for Solver {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pairwise" => Ok(Self::Pairwise),
+            "histogram" => Ok(Self::Histogram),
+            _ => anyhow::bail!("Unknown solver {:?}; expected pairwise or histogram", s),
+        }
+    }
+}
+
+/// The command line arguments.
+#[derive(Debug, Default)]
+struct Args {
+    /// From ``--solver <pairwise|histogram>``, how to find the best rectangle.
+    solver: Solver,
+    /// From ``--top <k>``, to also list the ``k`` largest rectangles within the polygon.
+    top: Option<usize>,
+    /// From ``--render <path>``, to draw the polygon and the best rectangle to an SVG or
+    /// PNG, by the extension of ``path``.
+    render: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                    (arg, value)
+                }
+            };
+
+            match name.as_str() {
+                "--render" => parsed.render = Some(value),
+                "--solver" => parsed.solver = value.parse()?,
+                "--top" => {
+                    parsed.top = Some(value.parse().map_err(|e| {
+                        anyhow::anyhow!("Invalid number of rectangles {:?}: {}", value, e)
+                    })?)
+                }
+                _ => anyhow::bail!("Unknown argument {:?}", name),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Draw ``render`` to ``path``, as an SVG or PNG by its extension.
+fn save_render(render: &Render, grid: &CompressedGrid, path: &str) -> anyhow::Result<()> {
+    let colours = RenderColours::default();
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("svg") => Ok(render.save_svg(path, &colours)?),
+        #[cfg(feature = "image")]
+        Some("png") => Ok(render.save_png(path, grid, PNG_MAX_SIZE, &colours)?),
+        #[cfg(not(feature = "image"))]
+        Some("png") => {
+            let _ = grid;
+            anyhow::bail!("Rendering to PNG needs the image feature")
+        }
+        _ => anyhow::bail!("Cannot tell the format to render {:?} in", path),
+    }
+}
+
 fn main() {
-    let indexed_coords = visibility::build_visibility_bounds_for_indexed_coords(
-        indexed_coords_from_text(INPUT).expect("Failed to parse indexed coords"),
-    );
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let indexed_coords = indexed_coords_from_text(INPUT).expect("Failed to parse indexed coords");
 
     #[cfg(feature = "profile")]
     let start = Instant::now();
-    let best_rectangle_within_polygon =
-        find_best_match(&indexed_coords, |a, b| compare_area_with_visibility(a, b))
-            .expect("Error finding best match with visibility")
-            .expect("No rectangle found within polygon");
+    let compressed = CompressedGrid::new(&indexed_coords).expect("Failed to compress polygon");
+    let best_rectangle_within_polygon = match args.solver {
+        Solver::Pairwise => {
+            #[cfg(feature = "rayon")]
+            let find = par_find_best_match;
+            #[cfg(not(feature = "rayon"))]
+            let find = find_best_match;
+            find(&indexed_coords, compare_area_within(&compressed))
+                .expect("Error finding best match within polygon")
+        }
+        Solver::Histogram => histogram::largest_rectangle(&compressed, &indexed_coords),
+    }
+    .expect("No rectangle found within polygon");
 
     #[cfg(feature = "profile")]
     {
@@ -39,6 +147,23 @@ fn main() {
         "Best rectangle within polygon area: {}",
         best_rectangle_within_polygon.area()
     );
+
+    if let Some(k) = args.top {
+        let top = find_top_matches(&indexed_coords, k, score_area_within(&compressed))
+            .expect("Error finding top matches within polygon");
+        for (rank, (area, rectangle)) in top.iter().enumerate() {
+            let [a, b] = rectangle.original_coords.map(|coords| coords.coords);
+            println!("{}. {} between {:?} and {:?}", rank + 1, area, a, b);
+        }
+    }
+
+    if let Some(path) = &args.render {
+        let render = Render::new(&indexed_coords).with_rectangle(best_rectangle_within_polygon);
+        if let Err(err) = save_render(&render, &compressed, path) {
+            eprintln!("Failed to render to {}: {}", path, err);
+            std::process::exit(1);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -63,4 +188,40 @@ mod test_part_1 {
             .expect("No rectangle found");
         assert_eq!(best_rectangle.area(), 50);
     }
+
+    #[test]
+    fn test_args() {
+        let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(parse(&[]).unwrap().render, None);
+        assert_eq!(
+            parse(&["--render", "out.svg"]).unwrap().render.as_deref(),
+            Some("out.svg")
+        );
+        assert_eq!(
+            parse(&["--render=out.png"]).unwrap().render.as_deref(),
+            Some("out.png")
+        );
+        assert_eq!(parse(&[]).unwrap().solver, Solver::Pairwise);
+        assert_eq!(
+            parse(&["--solver", "histogram"]).unwrap().solver,
+            Solver::Histogram
+        );
+        assert!(parse(&["--solver=stack"]).is_err());
+        assert_eq!(parse(&["--top=3"]).unwrap().top, Some(3));
+        assert!(parse(&["--top", "three"]).is_err());
+        assert!(parse(&["--render"]).is_err());
+        assert!(parse(&["--output=out.svg"]).is_err());
+    }
+
+    #[test]
+    fn test_save_render_unknown_format() {
+        let indexed_coords =
+            indexed_coords_from_text(INPUT).expect("Failed to parse indexed coords");
+        let grid = CompressedGrid::new(&indexed_coords).expect("Failed to compress polygon");
+        let render = Render::new(&indexed_coords);
+
+        assert!(save_render(&render, &grid, "render.bmp").is_err());
+        assert!(save_render(&render, &grid, "render").is_err());
+    }
 }
@@ -1,44 +1,23 @@
-pub type Coord = u32;
-pub type Coords = [Coord; 2];
+pub mod polygon;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
-pub struct VisibilityBounds {
-    pub left: Option<Coord>,
-    pub right: Option<Coord>,
-    pub top: Option<Coord>,
-    pub bottom: Option<Coord>,
-}
+use tessellation_fill::{SimplePolygon, TessellationFillError};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum Corner {
-    TopLeft,
-    TopRight,
-    BottomLeft,
-    BottomRight,
-}
+pub type Coord = u32;
+pub type Coords = [Coord; 2];
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct IndexedCoords {
     pub index: usize,
     pub coords: Coords,
-    pub visibility_bounds: Option<VisibilityBounds>,
 }
 
 impl IndexedCoords {
     pub const fn new(index: usize, coords: Coords) -> Self {
-        Self {
-            index,
-            coords,
-            visibility_bounds: None,
-        }
+        Self { index, coords }
     }
 
     pub const fn from_coords(coords: Coords) -> Self {
-        Self {
-            index: 0,
-            coords,
-            visibility_bounds: None,
-        }
+        Self { index: 0, coords }
     }
 }
 
@@ -86,23 +65,6 @@ impl Rectangle {
         [self.bounding.1, self.bounding.3]
     }
 
-    /// Returns an iterator over the original points associated with each corner of the rectangle.
-    ///
-    /// This current implementation is horribly inefficient (O(n)) but n is always 2 and there are only
-    /// 4 corners, so... ¯\_(ツ)_/¯
-    pub fn original_points_by_corners(&self) -> impl Iterator<Item = (Corner, IndexedCoords)> + '_ {
-        self.original_coords.iter().map(|ic| match ic.coords {
-            coords if coords == self.top_left() => (Corner::TopLeft, *ic),
-            coords if coords == self.top_right() => (Corner::TopRight, *ic),
-            coords if coords == self.bottom_left() => (Corner::BottomLeft, *ic),
-            coords if coords == self.bottom_right() => (Corner::BottomRight, *ic),
-            _ => panic!(
-                "Unreacahable: Original coordinate {:?} does not match any rectangle corner",
-                ic.coords
-            ),
-        })
-    }
-
     /// The way area is calculated is a bit unusual - it says that `2,3` to `7,3` is width 1,
     /// not 6.
     pub fn width(&self) -> u32 {
@@ -122,8 +84,23 @@ impl Rectangle {
     pub fn compare(
         &self,
         other: &Self,
-        predicate: impl Fn(&Self, &Self) -> anyhow::Result<std::cmp::Ordering>,
+        predicate: impl FnOnce(&Self, &Self) -> anyhow::Result<std::cmp::Ordering>,
     ) -> anyhow::Result<std::cmp::Ordering> {
         predicate(self, other)
     }
 }
+
+/// Convert the vertices of the day-9 polygon into a [`SimplePolygon`] that can be filled by
+/// [`tessellation_fill`](tessellation_fill::tessellation_fill).
+pub fn polygon_from_indexed_coords(
+    indexed_coords: &[IndexedCoords],
+) -> Result<SimplePolygon<Coord>, TessellationFillError<Coord>> {
+    polygon_from_coords(indexed_coords.iter().map(|ic| &ic.coords))
+}
+
+/// Convert bare coordinates into a [`SimplePolygon`]; see [`polygon_from_indexed_coords`].
+pub fn polygon_from_coords<'a>(
+    coords: impl IntoIterator<Item = &'a Coords>,
+) -> Result<SimplePolygon<Coord>, TessellationFillError<Coord>> {
+    SimplePolygon::new_orthogonal(coords.into_iter().copied().collect())
+}
@@ -0,0 +1,210 @@
+//! Which side of a polygon is the inside.
+//!
+//! The vertices of the puzzle are given in order around the polygon, but not which way
+//! round; these find that out, and with it which side of each edge the inside lies on,
+//! or whether any single tile is inside at all.
+//!
+//! Coordinates are as drawn in the puzzle, with ``y`` increasing downwards; so
+//! [`Orientation::Clockwise`] is clockwise as the polygon appears on screen.
+
+use super::{Coords, IndexedCoords};
+use itertools::Itertools;
+
+/// Which way round a polygon goes, as drawn with ``y`` increasing downwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// A side of an axis-aligned edge, as drawn with ``y`` increasing downwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The edges between consecutive ``vertices``, including the one closing the polygon.
+pub fn edges(vertices: &[IndexedCoords]) -> impl Iterator<Item = (Coords, Coords)> + '_ {
+    vertices
+        .iter()
+        .circular_tuple_windows()
+        .map(|(from, to)| (from.coords, to.coords))
+}
+
+/// Twice the signed area of the polygon with ``vertices`` in order, by the shoelace
+/// formula; positive if it goes clockwise.
+fn doubled_signed_area(vertices: &[IndexedCoords]) -> i128 {
+    edges(vertices)
+        .map(|([x0, y0], [x1, y1])| x0 as i128 * y1 as i128 - x1 as i128 * y0 as i128)
+        .sum()
+}
+
+/// Which way round the polygon with ``vertices`` in order goes, or [`None`] if it
+/// encloses no area at all.
+pub fn orientation(vertices: &[IndexedCoords]) -> Option<Orientation> {
+    match doubled_signed_area(vertices).signum() {
+        1 => Some(Orientation::Clockwise),
+        -1 => Some(Orientation::CounterClockwise),
+        _ => None,
+    }
+}
+
+/// Whether ``point`` lies on the edge from ``from`` to ``to``, inclusive of both ends.
+fn on_edge(point: Coords, from: Coords, to: Coords) -> bool {
+    let [px, py, x0, y0, x1, y1] =
+        [point[0], point[1], from[0], from[1], to[0], to[1]].map(|coord| coord as i64);
+    (x1 - x0) * (py - y0) == (y1 - y0) * (px - x0)
+        && x0.min(x1) <= px
+        && px <= x0.max(x1)
+        && y0.min(y1) <= py
+        && py <= y0.max(y1)
+}
+
+/// Whether the tile at ``point`` is inside the polygon with ``vertices`` in order, or on
+/// its boundary.
+///
+/// Tiles not on the boundary are found by casting a ray from them towards positive ``x``,
+/// and counting the edges it crosses: an odd number means inside. Each edge counts its
+/// top end but not its bottom, so that a ray through a vertex counts the two edges
+/// meeting there once between them if it passes through the polygon there, and not at
+/// all if it only touches it.
+pub fn contains_point(vertices: &[IndexedCoords], point: Coords) -> bool {
+    if edges(vertices).any(|(from, to)| on_edge(point, from, to)) {
+        return true;
+    }
+
+    let [px, py] = point.map(|coord| coord as i64);
+    edges(vertices)
+        .filter(|&(from, to)| {
+            let [x0, y0, x1, y1] = [from[0], from[1], to[0], to[1]].map(|coord| coord as i64);
+            if (y0 <= py) == (y1 <= py) {
+                return false;
+            }
+            // Where the edge crosses the row of the point, compared without dividing:
+            // ``x0 + (py - y0) * (x1 - x0) / (y1 - y0) > px``.
+            let (numerator, denominator) = ((py - y0) * (x1 - x0), y1 - y0);
+            if denominator > 0 {
+                numerator > (px - x0) * denominator
+            } else {
+                numerator < (px - x0) * denominator
+            }
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// Which side of the edge from vertex ``index`` to the next one the inside of the
+/// polygon with ``vertices`` in order lies on.
+///
+/// Walking along the edge, the inside is on the right of a clockwise polygon, and on the
+/// left of a counter-clockwise one. Returns [`None`] if the edge is neither horizontal
+/// nor vertical, has no length, or the polygon encloses no area.
+///
+/// Panics if ``index`` is out of bounds.
+pub fn interior_side_of_edge(vertices: &[IndexedCoords], index: usize) -> Option<Side> {
+    let ([x0, y0], [x1, y1]) = (
+        vertices[index].coords,
+        vertices[(index + 1) % vertices.len()].coords,
+    );
+    let clockwise = orientation(vertices)? == Orientation::Clockwise;
+
+    // The side on the right of the edge, as drawn.
+    let right = match (x0.cmp(&x1), y0.cmp(&y1)) {
+        (std::cmp::Ordering::Less, std::cmp::Ordering::Equal) => Side::Down,
+        (std::cmp::Ordering::Greater, std::cmp::Ordering::Equal) => Side::Up,
+        (std::cmp::Ordering::Equal, std::cmp::Ordering::Less) => Side::Left,
+        (std::cmp::Ordering::Equal, std::cmp::Ordering::Greater) => Side::Right,
+        _ => return None,
+    };
+
+    Some(match (clockwise, right) {
+        (true, side) => side,
+        (false, Side::Down) => Side::Up,
+        (false, Side::Up) => Side::Down,
+        (false, Side::Left) => Side::Right,
+        (false, Side::Right) => Side::Left,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexed_coords_from_text;
+
+    const EXAMPLE: &str = "7,1
+                           11,1
+                           11,7
+                           9,7
+                           9,5
+                           2,5
+                           2,3
+                           7,3";
+
+    fn example() -> Vec<IndexedCoords> {
+        indexed_coords_from_text(EXAMPLE).expect("Failed to parse indexed coords")
+    }
+
+    #[test]
+    fn test_orientation() {
+        let mut vertices = example();
+        assert_eq!(orientation(&vertices), Some(Orientation::Clockwise));
+
+        vertices.reverse();
+        assert_eq!(orientation(&vertices), Some(Orientation::CounterClockwise));
+
+        let line = indexed_coords_from_text("1,1\n5,1").expect("Failed to parse");
+        assert_eq!(orientation(&line), None);
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let vertices = example();
+
+        assert!(contains_point(&vertices, [7, 1]));
+        assert!(contains_point(&vertices, [9, 1]));
+        assert!(contains_point(&vertices, [8, 4]));
+        assert!(contains_point(&vertices, [2, 4]));
+        assert!(contains_point(&vertices, [10, 6]));
+        assert!(!contains_point(&vertices, [2, 2]));
+        assert!(!contains_point(&vertices, [8, 6]));
+        // Level with a vertex, either side of the polygon.
+        assert!(!contains_point(&vertices, [1, 3]));
+        assert!(!contains_point(&vertices, [12, 7]));
+
+        let mut reversed = vertices.clone();
+        reversed.reverse();
+        assert!(contains_point(&reversed, [8, 4]));
+        assert!(!contains_point(&reversed, [8, 6]));
+    }
+
+    #[test]
+    fn test_interior_side_of_edge() {
+        let vertices = example();
+        let sides = (0..vertices.len())
+            .map(|index| interior_side_of_edge(&vertices, index))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sides,
+            [
+                Side::Down,
+                Side::Left,
+                Side::Up,
+                Side::Right,
+                Side::Up,
+                Side::Right,
+                Side::Down,
+                Side::Right,
+            ]
+            .map(Some)
+        );
+
+        let mut reversed = vertices.clone();
+        reversed.reverse();
+        // The first edge reversed is the one from 7,3 to 2,3.
+        assert_eq!(interior_side_of_edge(&reversed, 0), Some(Side::Down));
+    }
+}
@@ -0,0 +1,304 @@
+//! Drawing the polygon, its inside and the best rectangle found within it, to see the
+//! shape of inputs far too large for the ANSI dump of [`colour::Grid`](crate::colour::Grid).
+//!
+//! SVG is always available; PNG needs the ``image`` feature.
+
+use crate::models::{Coord, IndexedCoords, Rectangle};
+use std::{io, path::Path};
+
+#[cfg(feature = "image")]
+use crate::{CompressedGrid, models::polygon};
+#[cfg(feature = "image")]
+use image::{ImageFormat, ImageResult, Rgb, RgbImage};
+
+/// The colours of a render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderColours {
+    /// Tiles inside the polygon.
+    pub inside: [u8; 3],
+    /// Tiles outside the polygon.
+    pub outside: [u8; 3],
+    /// Tiles the boundary passes through.
+    pub boundary: [u8; 3],
+    /// Tiles of the rectangle, if any.
+    pub rectangle: [u8; 3],
+}
+
+impl Default for RenderColours {
+    fn default() -> Self {
+        Self {
+            inside: [0x2e, 0xa0, 0x43],
+            outside: [0x16, 0x1b, 0x22],
+            boundary: [0xf8, 0x51, 0x49],
+            rectangle: [0xd2, 0x99, 0x22],
+        }
+    }
+}
+
+/// A colour as an SVG hex string.
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// The polygon with ``vertices`` in order, and optionally a rectangle within it.
+///
+/// Each tile is drawn as a unit square, with the tile at ``[0, 0]`` at the top left; the
+/// render covers every tile up to one past the largest vertex coordinates, as
+/// [`colour::Grid::new_to_fit`](crate::colour::Grid::new_to_fit) does.
+#[derive(Debug, Clone)]
+pub struct Render<'a> {
+    vertices: &'a [IndexedCoords],
+    rectangle: Option<Rectangle>,
+}
+
+impl<'a> Render<'a> {
+    /// The largest side of an SVG, in pixels, as displayed.
+    pub const SVG_MAX_SIZE: u32 = 1024;
+
+    /// Render the polygon with ``vertices`` in order.
+    pub fn new(vertices: &'a [IndexedCoords]) -> Self {
+        Self {
+            vertices,
+            rectangle: None,
+        }
+    }
+
+    /// Also draw ``rectangle``, over the inside of the polygon.
+    pub fn with_rectangle(mut self, rectangle: Rectangle) -> Self {
+        self.rectangle = Some(rectangle);
+        self
+    }
+
+    /// The number of tiles across and down.
+    pub fn extent(&self) -> (Coord, Coord) {
+        let (max_x, max_y) = self.vertices.iter().fold((0, 0), |(max_x, max_y), vertex| {
+            (max_x.max(vertex.coords[0]), max_y.max(vertex.coords[1]))
+        });
+
+        (max_x + 2, max_y + 2)
+    }
+
+    /// Write the render to ``writer`` as an SVG, scaled to fit within
+    /// [`Self::SVG_MAX_SIZE`] pixels.
+    ///
+    /// The boundary runs through the middle of its tiles, and is a tile wide; or for
+    /// polygons too large for that to be visible at all, as wide as a pixel.
+    pub fn write_svg(&self, mut writer: impl io::Write, colours: &RenderColours) -> io::Result<()> {
+        let (width, height) = self.extent();
+        let tiles_per_pixel = width.max(height).div_ceil(Self::SVG_MAX_SIZE).max(1);
+
+        writeln!(
+            writer,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="{}" height="{}" shape-rendering="crispEdges">"#,
+            width.div_ceil(tiles_per_pixel),
+            height.div_ceil(tiles_per_pixel),
+        )?;
+        writeln!(
+            writer,
+            r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+            hex(colours.outside)
+        )?;
+
+        let points = self
+            .vertices
+            .iter()
+            .map(|vertex| format!("{}.5,{}.5", vertex.coords[0], vertex.coords[1]))
+            .collect::<Vec<_>>()
+            .join(" ");
+        // The inside, with half of each boundary tile, then the rest of the boundary
+        // tiles under the stroke.
+        writeln!(
+            writer,
+            r#"<polygon points="{points}" fill="{}"/>"#,
+            hex(colours.inside)
+        )?;
+
+        if let Some(Rectangle {
+            bounding: (min_x, max_x, min_y, max_y),
+            ..
+        }) = self.rectangle
+        {
+            writeln!(
+                writer,
+                r#"<rect x="{min_x}" y="{min_y}" width="{}" height="{}" fill="{}"/>"#,
+                max_x - min_x + 1,
+                max_y - min_y + 1,
+                hex(colours.rectangle)
+            )?;
+        }
+
+        writeln!(
+            writer,
+            r#"<polygon points="{points}" fill="none" stroke="{}" stroke-width="{tiles_per_pixel}" stroke-linejoin="miter" stroke-linecap="square"/>"#,
+            hex(colours.boundary)
+        )?;
+        writeln!(writer, "</svg>")
+    }
+
+    /// Save the render to ``path`` as an SVG; see [`write_svg`](Self::write_svg).
+    pub fn save_svg(&self, path: impl AsRef<Path>, colours: &RenderColours) -> io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let mut writer = io::BufWriter::new(file);
+        self.write_svg(&mut writer, colours)?;
+        io::Write::flush(&mut writer)
+    }
+
+    /// Render into an image no larger than ``max_size`` pixels on either side, taking
+    /// whether each tile is inside from ``grid``, which must be of the same polygon.
+    ///
+    /// If the polygon is larger than ``max_size`` tiles, each pixel stands for a square
+    /// of tiles, coloured by the tile at its top left; the rectangle and the boundary are
+    /// drawn over every pixel they touch, so that neither is lost to the scaling.
+    #[cfg(feature = "image")]
+    pub fn to_image(
+        &self,
+        grid: &CompressedGrid,
+        max_size: u32,
+        colours: &RenderColours,
+    ) -> RgbImage {
+        let (width, height) = self.extent();
+        let tiles_per_pixel = width.max(height).div_ceil(max_size.max(1)).max(1);
+
+        let mut image = RgbImage::from_fn(
+            width.div_ceil(tiles_per_pixel),
+            height.div_ceil(tiles_per_pixel),
+            |x, y| {
+                let tile = [x * tiles_per_pixel, y * tiles_per_pixel];
+                Rgb(if grid.contains(tile) {
+                    colours.inside
+                } else {
+                    colours.outside
+                })
+            },
+        );
+
+        // Every pixel covering the tiles between two corners, inclusive of both.
+        let mut fill = |[x0, y0]: [Coord; 2], [x1, y1]: [Coord; 2], colour: [u8; 3]| {
+            let ([x0, x1], [y0, y1]) = ([x0.min(x1), x0.max(x1)], [y0.min(y1), y0.max(y1)]);
+            for y in y0 / tiles_per_pixel..=y1 / tiles_per_pixel {
+                for x in x0 / tiles_per_pixel..=x1 / tiles_per_pixel {
+                    image.put_pixel(x, y, Rgb(colour));
+                }
+            }
+        };
+
+        if let Some(Rectangle {
+            bounding: (min_x, max_x, min_y, max_y),
+            ..
+        }) = self.rectangle
+        {
+            fill([min_x, min_y], [max_x, max_y], colours.rectangle);
+        }
+
+        // Edges are all horizontal or vertical, so are their own bounding boxes.
+        polygon::edges(self.vertices).for_each(|(from, to)| fill(from, to, colours.boundary));
+
+        image
+    }
+
+    /// Save the render to ``path`` as a PNG; see [`to_image`](Self::to_image).
+    #[cfg(feature = "image")]
+    pub fn save_png(
+        &self,
+        path: impl AsRef<Path>,
+        grid: &CompressedGrid,
+        max_size: u32,
+        colours: &RenderColours,
+    ) -> ImageResult<()> {
+        self.to_image(grid, max_size, colours)
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexed_coords_from_text;
+
+    const EXAMPLE: &str = "7,1
+                           11,1
+                           11,7
+                           9,7
+                           9,5
+                           2,5
+                           2,3
+                           7,3";
+
+    fn example() -> Vec<IndexedCoords> {
+        indexed_coords_from_text(EXAMPLE).expect("Failed to parse indexed coords")
+    }
+
+    #[test]
+    fn test_write_svg() {
+        let vertices = example();
+        let rectangle = Rectangle::new(vertices[4], vertices[6]);
+        let mut svg = Vec::new();
+        Render::new(&vertices)
+            .with_rectangle(rectangle)
+            .write_svg(&mut svg, &RenderColours::default())
+            .expect("Failed to write SVG");
+        let svg = String::from_utf8(svg).expect("SVG is not UTF-8");
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r#"viewBox="0 0 13 9" width="13" height="9""#));
+        assert!(svg.contains(r#"points="7.5,1.5 11.5,1.5 11.5,7.5 9.5,7.5"#));
+        assert!(svg.contains(r##"<rect x="2" y="3" width="8" height="3" fill="#d29922"/>"##));
+        assert_eq!(svg.matches("<polygon ").count(), 2);
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_write_svg_scaled() {
+        let vertices =
+            indexed_coords_from_text("0,0\n4999,0\n4999,2000\n0,2000").expect("Failed to parse");
+        let mut svg = Vec::new();
+        Render::new(&vertices)
+            .write_svg(&mut svg, &RenderColours::default())
+            .expect("Failed to write SVG");
+        let svg = String::from_utf8(svg).expect("SVG is not UTF-8");
+
+        assert!(svg.contains(r#"viewBox="0 0 5001 2002" width="1001" height="401""#));
+        assert!(svg.contains(r#"stroke-width="5""#));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_to_image() {
+        let vertices = example();
+        let grid = CompressedGrid::new(&vertices).expect("Failed to compress");
+        let rectangle = Rectangle::new(vertices[4], vertices[6]);
+        let colours = RenderColours::default();
+        let image = Render::new(&vertices)
+            .with_rectangle(rectangle)
+            .to_image(&grid, 100, &colours);
+
+        assert_eq!(image.dimensions(), (13, 9));
+        assert_eq!(image.get_pixel(0, 0).0, colours.outside);
+        assert_eq!(image.get_pixel(7, 1).0, colours.boundary);
+        assert_eq!(image.get_pixel(10, 6).0, colours.inside);
+        assert_eq!(image.get_pixel(8, 4).0, colours.rectangle);
+        assert_eq!(image.get_pixel(8, 6).0, colours.outside);
+
+        let image = Render::new(&vertices).to_image(&grid, 5, &colours);
+        assert_eq!(image.dimensions(), (5, 3));
+        assert_eq!(image.get_pixel(3, 0).0, colours.boundary);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_save_png() {
+        let path = std::env::temp_dir().join("aoc-2025-09-test-save-png.png");
+        let vertices = example();
+        let grid = CompressedGrid::new(&vertices).expect("Failed to compress");
+        let render = Render::new(&vertices);
+        let colours = RenderColours::default();
+
+        render
+            .save_png(&path, &grid, 100, &colours)
+            .expect("Failed to save PNG");
+        let loaded = image::open(&path).expect("Failed to open PNG").to_rgb8();
+        std::fs::remove_file(&path).expect("Failed to remove PNG");
+
+        assert_eq!(loaded, render.to_image(&grid, 100, &colours));
+    }
+}
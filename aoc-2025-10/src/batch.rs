@@ -0,0 +1,419 @@
+//! Solving every machine of the input, each independently of the others; so with the
+//! ``rayon`` feature, in parallel.
+//!
+//! With the ``progress`` feature, one bar counts the machines completed across all
+//! threads, rather than each solver drawing its own.
+
+use crate::{
+    cache::SolutionCache,
+    models::{Goal, Machine},
+};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "progress")]
+use kdam::{BarExt, tqdm};
+#[cfg(feature = "progress")]
+use std::io::{IsTerminal, stderr};
+#[cfg(feature = "progress")]
+use std::sync::Mutex;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// The outcome of solving one machine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachineResult {
+    /// The line of the input the machine was on, from ``1``.
+    pub line: usize,
+    /// The number of buttons on the machine.
+    pub buttons: usize,
+    /// The number of indicator lights, and of joltage counters.
+    pub counters: usize,
+    /// The buttons pressed, one entry per press.
+    pub solution: Vec<usize>,
+    /// How long the solver took.
+    pub elapsed: Duration,
+    /// Whether the solution came from the cache, rather than the solver.
+    pub cached: bool,
+}
+
+impl MachineResult {
+    /// The number of presses in the solution.
+    pub fn presses(&self) -> usize {
+        self.solution.len()
+    }
+}
+
+/// A machine of the input, with the line it was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedMachine {
+    /// The line, from ``1``.
+    pub line: usize,
+    /// The text of the line.
+    pub text: String,
+    /// The machine on the line.
+    pub machine: Machine,
+}
+
+/// A line of the input that is not a valid machine.
+#[derive(Debug)]
+pub struct ParseFailure {
+    /// The line, from ``1``.
+    pub line: usize,
+    /// The text of the line.
+    pub text: String,
+    /// Why it could not be parsed.
+    pub error: anyhow::Error,
+}
+
+/// Parse every machine in ``input``, one per line, skipping blank lines.
+///
+/// Rather than stopping at the first line that fails to parse, every failure is returned
+/// alongside the machines that did parse, each with its line number.
+pub fn parse_machines(input: &str) -> (Vec<ParsedMachine>, Vec<ParseFailure>) {
+    let mut machines = Vec::new();
+    let mut failures = Vec::new();
+
+    for (index, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        match Machine::new_from_input(text) {
+            Ok(machine) => machines.push(ParsedMachine {
+                line: index + 1,
+                text: text.to_owned(),
+                machine,
+            }),
+            Err(error) => failures.push(ParseFailure {
+                line: index + 1,
+                text: text.to_owned(),
+                error,
+            }),
+        }
+    }
+
+    (machines, failures)
+}
+
+/// A report of every one of ``failures``, a line of the input and its error per failure.
+pub fn failure_report(failures: &[ParseFailure]) -> String {
+    let mut report = format!(
+        "{} line{} could not be parsed:\n",
+        failures.len(),
+        if failures.len() == 1 { "" } else { "s" }
+    );
+    for failure in failures {
+        report.push_str(&format!(
+            "  line {}: {:#}\n    {}\n",
+            failure.line, failure.error, failure.text
+        ));
+    }
+
+    report
+}
+
+/// Solve each machine in ``machines`` with ``solve``, returning the results in the same
+/// order; ``desc`` labels the progress bar, if any.
+///
+/// Each solution is checked with [`Machine::verify`] against ``goal`` before it is
+/// accepted, so that a solver bug is an error rather than a wrong answer. Every machine
+/// is solved even if some fail; the error of the first to fail in order is returned.
+///
+/// With a ``cache``, a machine solved on a previous run is not solved again, unless its
+/// solution from the cache fails verification; new solutions are added to it as they are
+/// found.
+pub fn solve_machines<F>(
+    machines: &[ParsedMachine],
+    desc: &str,
+    goal: Goal,
+    cache: Option<&SolutionCache>,
+    solve: F,
+) -> anyhow::Result<Vec<MachineResult>>
+where
+    F: Fn(&Machine) -> anyhow::Result<Vec<usize>> + Sync,
+{
+    #[cfg(feature = "progress")]
+    let pbar = {
+        kdam::term::init(stderr().is_terminal());
+        Mutex::new(tqdm!(total = machines.len(), desc = desc.to_owned()))
+    };
+    #[cfg(not(feature = "progress"))]
+    let _ = desc;
+
+    let solve_one = |parsed: &ParsedMachine| -> anyhow::Result<MachineResult> {
+        let ParsedMachine {
+            line,
+            text,
+            machine,
+        } = parsed;
+        let start = Instant::now();
+
+        let cached = cache
+            .and_then(|cache| cache.get(text, goal))
+            .filter(|solution| machine.verify(solution, goal).is_ok())
+            .map(<[usize]>::to_vec);
+        let from_cache = cached.is_some();
+        let solution = match cached {
+            Some(solution) => Ok(solution),
+            None => solve(machine)
+                .and_then(|solution| machine.verify(&solution, goal).map(|_| solution))
+                .and_then(|solution| {
+                    if let Some(cache) = cache {
+                        cache.insert(text, goal, &solution).map_err(|e| {
+                            anyhow::anyhow!(
+                                "Failed to add to solution cache {}: {}",
+                                cache.path().display(),
+                                e
+                            )
+                        })?;
+                    }
+                    Ok(solution)
+                }),
+        }
+        .map_err(|err| anyhow::anyhow!("Failed to solve machine on line {}: {}", line, err));
+        let elapsed = start.elapsed();
+
+        #[cfg(feature = "progress")]
+        pbar.lock()
+            .map_err(|_| anyhow::anyhow!("Progress bar lock was poisoned"))?
+            .update(1)
+            .map_err(|e| anyhow::anyhow!("Failed to update progress bar: {}", e))?;
+
+        Ok(MachineResult {
+            line: *line,
+            buttons: machine.buttons.len(),
+            counters: machine.joltage.len(),
+            solution: solution?,
+            elapsed,
+            cached: from_cache,
+        })
+    };
+
+    #[cfg(feature = "rayon")]
+    let results = machines.par_iter().map(solve_one).collect::<Vec<_>>();
+    #[cfg(not(feature = "rayon"))]
+    let results = machines.iter().map(solve_one).collect::<Vec<_>>();
+
+    results.into_iter().collect()
+}
+
+/// A table of ``results``, a row per machine, with the total presses at the bottom.
+pub fn summary_table(results: &[MachineResult]) -> String {
+    let mut table = format!(
+        "{:>6} {:>8} {:>9} {:>8} {:>12}\n",
+        "Line", "Buttons", "Counters", "Presses", "Time"
+    );
+    for result in results {
+        table.push_str(&format!(
+            "{:>6} {:>8} {:>9} {:>8} {:>12}\n",
+            result.line,
+            result.buttons,
+            result.counters,
+            result.presses(),
+            if result.cached {
+                "cached".to_owned()
+            } else {
+                format!("{:.3?}", result.elapsed)
+            },
+        ));
+    }
+
+    let total_presses = results.iter().map(MachineResult::presses).sum::<usize>();
+    let total_elapsed = results
+        .iter()
+        .map(|result| result.elapsed)
+        .sum::<Duration>();
+    table.push_str(&format!(
+        "{:>6} {:>8} {:>9} {:>8} {:>12}\n",
+        "Total",
+        "",
+        "",
+        total_presses,
+        format!("{:.3?}", total_elapsed),
+    ));
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
+
+    fn example() -> Vec<ParsedMachine> {
+        let (machines, failures) = parse_machines(EXAMPLE);
+        assert!(failures.is_empty());
+        machines
+    }
+
+    #[test]
+    fn test_parse_machines() {
+        let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+
+[.#] (0) (2) {1,1}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[..] (0,1) {1,x}";
+        let (machines, failures) = parse_machines(input);
+
+        assert_eq!(
+            machines
+                .iter()
+                .map(|parsed| parsed.line)
+                .collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+        assert_eq!(
+            failures
+                .iter()
+                .map(|failure| failure.line)
+                .collect::<Vec<_>>(),
+            vec![3, 5]
+        );
+
+        let report = failure_report(&failures);
+        assert!(
+            report.starts_with("2 lines could not be parsed:"),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains("line 3: Invalid Button 1 \"(2)\": Button index 2 is out of range"),
+            "{}",
+            report
+        );
+        assert!(report.contains("line 5: Invalid Joltage"), "{}", report);
+        assert!(report.contains("    [..] (0,1) {1,x}"), "{}", report);
+    }
+
+    #[test]
+    fn test_solve_machines() {
+        let results = solve_machines(&example(), "Part 2", Goal::Joltage, None, |machine| {
+            machine.solve_milp(&machine.joltage.values)
+        })
+        .expect("Failed to solve machines");
+
+        assert_eq!(
+            results.iter().map(|result| result.line).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            results
+                .iter()
+                .map(MachineResult::presses)
+                .collect::<Vec<_>>(),
+            vec![10, 12, 11]
+        );
+        assert_eq!(results[1].buttons, 5);
+        assert_eq!(results[2].counters, 6);
+    }
+
+    #[test]
+    fn test_solve_machines_error() {
+        let err = solve_machines(&example(), "Failing", Goal::Indicators, None, |machine| {
+            if machine.buttons.len() == 5 {
+                anyhow::bail!("Too many buttons")
+            }
+            machine.brute_force()
+        })
+        .expect_err("Second machine should fail");
+
+        assert!(err.to_string().contains("line 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_solve_machines_wrong_answer() {
+        // The fewest presses for the indicators do not reach the joltage.
+        let err = solve_machines(&example(), "Wrong", Goal::Joltage, None, |machine| {
+            machine.brute_force()
+        })
+        .expect_err("Indicator solutions should not pass as joltage solutions");
+
+        assert!(err.to_string().contains("line 1"), "{}", err);
+        assert!(err.to_string().contains("joltage"), "{}", err);
+    }
+
+    #[test]
+    fn test_solve_machines_cached() {
+        let path = std::env::temp_dir().join("aoc-2025-10-test-batch-cached.cache");
+        let _ = std::fs::remove_file(&path);
+        let machines = example();
+        let solve = |machine: &Machine| machine.solve_milp(&machine.joltage.values);
+
+        let cache = SolutionCache::open(&path).expect("Failed to open cache");
+        let first = solve_machines(&machines, "First", Goal::Joltage, Some(&cache), solve)
+            .expect("Failed to solve machines");
+        assert!(first.iter().all(|result| !result.cached));
+        drop(cache);
+
+        // A wrong solution for the second machine is solved again.
+        let wrong = &machines[1].text;
+        SolutionCache::open(&path)
+            .expect("Failed to reopen cache")
+            .insert(wrong, Goal::Joltage, &[0])
+            .expect("Failed to insert");
+
+        let cache = SolutionCache::open(&path).expect("Failed to reopen cache");
+        let second = solve_machines(&machines, "Second", Goal::Joltage, Some(&cache), |_| {
+            anyhow::bail!("Solved again")
+        });
+        assert!(second.is_err());
+
+        let second = solve_machines(&machines, "Second", Goal::Joltage, Some(&cache), solve)
+            .expect("Failed to solve machines");
+        std::fs::remove_file(&path).expect("Failed to remove cache");
+
+        assert_eq!(
+            second
+                .iter()
+                .map(|result| result.cached)
+                .collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+        assert_eq!(
+            second
+                .iter()
+                .map(MachineResult::presses)
+                .collect::<Vec<_>>(),
+            first.iter().map(MachineResult::presses).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let results = vec![
+            MachineResult {
+                line: 1,
+                buttons: 6,
+                counters: 4,
+                solution: vec![4, 5],
+                elapsed: Duration::from_millis(2),
+                cached: false,
+            },
+            MachineResult {
+                line: 2,
+                buttons: 5,
+                counters: 5,
+                solution: vec![2, 3, 4],
+                elapsed: Duration::from_millis(3),
+                cached: true,
+            },
+        ];
+        let table = summary_table(&results);
+        let lines = table.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].split_whitespace().next(), Some("Line"));
+        assert_eq!(
+            lines[2].split_whitespace().collect::<Vec<_>>(),
+            vec!["2", "5", "5", "3", "cached"]
+        );
+        assert_eq!(
+            lines[3].split_whitespace().collect::<Vec<_>>(),
+            vec!["Total", "5", "5.000ms"]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Solutions kept on disk between runs, so that an interrupted run does not have to
+//! solve again the machines it already had.
+//!
+//! The cache is a text file, a line per solution: the hash of the machine's line of
+//! input and the goal, in hex, then a colon and the buttons pressed, separated by
+//! commas. Each solution is appended as soon as it is found. Lines that cannot be read
+//! are ignored, and their machines solved again; a line cut short by an interruption
+//! may still read as a solution, but solutions from the cache are verified like any
+//! other, so it will not be used.
+
+use crate::models::Goal;
+use fxhash::FxHashMap;
+use std::{
+    fs::{File, OpenOptions},
+    hash::{Hash, Hasher},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Solutions found on previous runs, and a file to add new ones to.
+#[derive(Debug)]
+pub struct SolutionCache {
+    path: PathBuf,
+    entries: FxHashMap<u64, Vec<usize>>,
+    file: Mutex<File>,
+}
+
+impl SolutionCache {
+    /// The default location of the cache, in the system's temporary directory.
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("aoc-2025-10-solutions.cache")
+    }
+
+    /// Open the cache at ``path``, reading every solution already in it, or creating it
+    /// if it does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+
+        let mut contents = String::new();
+        (&file).read_to_string(&mut contents)?;
+        let entries = contents.lines().filter_map(Self::parse_entry).collect();
+
+        // Start new solutions on a line of their own, after any cut short.
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            (&file).write_all(b"\n")?;
+        }
+
+        Ok(Self {
+            path,
+            entries,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Where the cache is kept.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of solutions read when the cache was opened.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no solutions were read when the cache was opened.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// A line of the file as a key and a solution, if it is one.
+    fn parse_entry(line: &str) -> Option<(u64, Vec<usize>)> {
+        let (key, solution) = line.split_once(':')?;
+        let key = u64::from_str_radix(key, 16).ok()?;
+        let solution = if solution.is_empty() {
+            Vec::new()
+        } else {
+            solution
+                .split(',')
+                .map(|button| button.parse().ok())
+                .collect::<Option<Vec<_>>>()?
+        };
+
+        Some((key, solution))
+    }
+
+    /// The key of the machine on ``line`` of the input, solved for ``goal``.
+    ///
+    /// [`fxhash`] is used over the standard library's hasher, whose output may change
+    /// between releases of Rust, invalidating the cache.
+    pub fn key(line: &str, goal: Goal) -> u64 {
+        let mut hasher = fxhash::FxHasher64::default();
+        line.trim().hash(&mut hasher);
+        goal.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The solution of the machine on ``line`` for ``goal`` from a previous run, if any.
+    pub fn get(&self, line: &str, goal: Goal) -> Option<&[usize]> {
+        self.entries.get(&Self::key(line, goal)).map(Vec::as_slice)
+    }
+
+    /// Append ``solution`` of the machine on ``line`` for ``goal`` to the file.
+    ///
+    /// It will be found by [`get`](Self::get) once the cache is opened again.
+    pub fn insert(&self, line: &str, goal: Goal, solution: &[usize]) -> io::Result<()> {
+        let buttons = solution
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let entry = format!("{:016x}:{}\n", Self::key(line, goal), buttons);
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| io::Error::other("Solution cache lock was poisoned"))?;
+        file.write_all(entry.as_bytes())?;
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join("aoc-2025-10-test-cache-round-trip.cache");
+        let _ = std::fs::remove_file(&path);
+
+        let cache = SolutionCache::open(&path).expect("Failed to open cache");
+        assert!(cache.is_empty());
+        cache
+            .insert(LINE, Goal::Indicators, &[4, 5])
+            .expect("Failed to insert");
+        cache
+            .insert(LINE, Goal::Joltage, &[0, 1, 1, 1, 3, 3, 3, 4, 5, 5])
+            .expect("Failed to insert");
+        // Only solutions from previous runs are read back.
+        assert_eq!(cache.get(LINE, Goal::Indicators), None);
+        drop(cache);
+
+        let cache = SolutionCache::open(&path).expect("Failed to reopen cache");
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(LINE, Goal::Indicators), Some(&[4, 5][..]));
+        assert_eq!(
+            cache.get(&format!("  {}  ", LINE), Goal::Joltage),
+            Some(&[0, 1, 1, 1, 3, 3, 3, 4, 5, 5][..])
+        );
+        assert_eq!(cache.get("[.] (0) {1}", Goal::Joltage), None);
+
+        std::fs::remove_file(&path).expect("Failed to remove cache");
+    }
+
+    #[test]
+    fn test_ignores_unreadable_lines() {
+        let path = std::env::temp_dir().join("aoc-2025-10-test-cache-unreadable.cache");
+        let key = SolutionCache::key(LINE, Goal::Indicators);
+        std::fs::write(
+            &path,
+            format!(
+                "not a solution\n{:016x}:4,x\n{:016x}:4,5\n{:016x}:4,",
+                key, key, key
+            ),
+        )
+        .expect("Failed to write cache");
+
+        let cache = SolutionCache::open(&path).expect("Failed to open cache");
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(LINE, Goal::Indicators), Some(&[4, 5][..]));
+
+        cache
+            .insert(LINE, Goal::Joltage, &[0, 1, 1, 1, 3, 3, 3, 4, 5, 5])
+            .expect("Failed to insert");
+        drop(cache);
+        let cache = SolutionCache::open(&path).expect("Failed to reopen cache");
+        std::fs::remove_file(&path).expect("Failed to remove cache");
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(LINE, Goal::Joltage).is_some());
+    }
+}
@@ -157,42 +157,212 @@
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+pub mod batch;
+pub mod cache;
 mod input;
 pub mod models;
+use cache::SolutionCache;
 use input::INPUT;
+use models::Goal;
+use std::path::PathBuf;
+
+/// How to solve the joltage requirements in part 2.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Solver {
+    /// Branch-and-bound over the simplex, with [`Machine::solve_milp`](models::Machine::solve_milp).
+    #[default]
+    Milp,
+    /// Integer Gaussian elimination, with [`Machine::solve_exact`](models::Machine::solve_exact).
+    Exact,
+}
+
+impl std::str::FromStr for Solver {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "milp" => Ok(Self::Milp),
+            "exact" => Ok(Self::Exact),
+            _ => anyhow::bail!("Unknown solver {:?}; expected milp or exact", s),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    /// From ``--solver <milp|exact>``, how to solve part 2.
+    solver: Solver,
+    /// From ``--report``, to print the press plan of every machine.
+    report: bool,
+    /// From ``--skip-invalid``, to solve the machines that parse even if some lines do
+    /// not, rather than stopping.
+    skip_invalid: bool,
+    /// From ``--cache <path>``, where to keep solutions between runs, instead of
+    /// [`SolutionCache::default_path`].
+    cache: Option<String>,
+    /// From ``--no-cache``, to neither use nor add to the solution cache.
+    no_cache: bool,
+}
+
+impl Args {
+    /// The field set by ``arg``, if it is a flag, which takes no value.
+    fn flag<'a>(parsed: &'a mut Self, arg: &str) -> Option<&'a mut bool> {
+        match arg {
+            "--report" => Some(&mut parsed.report),
+            "--skip-invalid" => Some(&mut parsed.skip_invalid),
+            "--no-cache" => Some(&mut parsed.no_cache),
+            _ => None,
+        }
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            // Flags take no value.
+            if let Some(flag) = Self::flag(&mut parsed, &arg) {
+                *flag = true;
+                continue;
+            }
+
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                    (arg, value)
+                }
+            };
+
+            match name.as_str() {
+                "--cache" => parsed.cache = Some(value),
+                "--solver" => parsed.solver = value.parse()?,
+                _ => anyhow::bail!("Unknown argument {:?}", name),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Print the press plan of each machine in ``machines`` for its solution in ``results``.
+fn print_reports(machines: &[batch::ParsedMachine], results: &[batch::MachineResult], goal: Goal) {
+    for (parsed, result) in machines.iter().zip(results) {
+        let plan = parsed
+            .machine
+            .verify(&result.solution, goal)
+            .expect("Solutions are verified as they are found");
+        println!("Line {}: {}", parsed.line, plan);
+    }
+}
 
 fn main() {
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let (machines, failures) = batch::parse_machines(INPUT);
+    if !failures.is_empty() {
+        eprint!("{}", batch::failure_report(&failures));
+        if !args.skip_invalid {
+            eprintln!("Fix the input, or pass --skip-invalid to solve the rest.");
+            std::process::exit(1);
+        }
+        eprintln!("Skipping these lines, and solving the rest.");
+    }
+
+    let cache = (!args.no_cache).then(|| {
+        let path = args
+            .cache
+            .clone()
+            .map_or_else(SolutionCache::default_path, PathBuf::from);
+        SolutionCache::open(&path).unwrap_or_else(|err| {
+            eprintln!(
+                "Failed to open solution cache {}: {}; pass --no-cache to run without it.",
+                path.display(),
+                err
+            );
+            std::process::exit(1);
+        })
+    });
+
     '_part1: {
-        let part1 = INPUT
-            .lines()
-            .map(|line| {
-                println!("Part 1 Processing line: {}", line);
-                let machine = models::Machine::new_from_input(line).unwrap();
-                let solution = machine.brute_force().expect("No solution found");
-                solution.len()
-            })
-            .sum::<usize>();
+        let results = batch::solve_machines(
+            &machines,
+            "Part 1",
+            Goal::Indicators,
+            cache.as_ref(),
+            |machine| machine.brute_force(),
+        )
+        .expect("No solution found");
 
+        if args.report {
+            print_reports(&machines, &results, Goal::Indicators);
+        }
+        print!("{}", batch::summary_table(&results));
+        let part1 = results
+            .iter()
+            .map(batch::MachineResult::presses)
+            .sum::<usize>();
         println!("Total buttons pressed across all machines: {}", part1);
     }
 
-    #[cfg(feature = "milp")]
     '_part2: {
-        let part2 = INPUT
-            .lines()
-            .map(|line| {
-                println!("Part 2 Processing line: {}", line);
-                let machine = models::Machine::new_from_input(line).unwrap();
-                let solution = machine
-                    .solve_milp(&machine.joltage.values)
-                    .expect("No solution found");
-                solution.len()
-            })
-            .sum::<usize>();
+        let results = batch::solve_machines(
+            &machines,
+            "Part 2",
+            Goal::Joltage,
+            cache.as_ref(),
+            |machine| match args.solver {
+                Solver::Milp => machine.solve_milp(&machine.joltage.values),
+                Solver::Exact => machine.solve_exact(&machine.joltage.values),
+            },
+        )
+        .expect("No solution found");
 
+        if args.report {
+            print_reports(&machines, &results, Goal::Joltage);
+        }
+        print!("{}", batch::summary_table(&results));
+        let part2 = results
+            .iter()
+            .map(batch::MachineResult::presses)
+            .sum::<usize>();
         println!(
             "Total buttons pressed across all machines (Part 2): {}",
             part2
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(parse(&[]).unwrap().solver, Solver::Milp);
+        assert_eq!(parse(&["--solver", "exact"]).unwrap().solver, Solver::Exact);
+        assert_eq!(parse(&["--solver=milp"]).unwrap().solver, Solver::Milp);
+        assert!(parse(&["--solver=z3"]).is_err());
+        assert!(parse(&["--solver"]).is_err());
+        assert!(parse(&["--no-cache=false"]).is_err());
+        assert!(!parse(&[]).unwrap().report);
+        assert!(parse(&["--report", "--solver", "exact"]).unwrap().report);
+        assert!(!parse(&["--report"]).unwrap().skip_invalid);
+        assert!(parse(&["--skip-invalid"]).unwrap().skip_invalid);
+        assert!(!parse(&[]).unwrap().no_cache);
+        assert!(parse(&["--no-cache"]).unwrap().no_cache);
+        assert_eq!(
+            parse(&["--cache", "solutions.cache"])
+                .unwrap()
+                .cache
+                .as_deref(),
+            Some("solutions.cache")
+        );
+    }
+}
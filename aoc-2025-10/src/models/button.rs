@@ -33,6 +33,13 @@ impl Button {
         if indices.is_empty() {
             anyhow::bail!("Button effect cannot be empty");
         }
+        if let Some(&last) = indices.last().filter(|&&last| last as usize >= length) {
+            anyhow::bail!(
+                "Button index {} is out of range for {} indicators",
+                last,
+                length
+            );
+        }
 
         let effect = CountArray::<bool>::from(indices.into_iter().fold(
             vec![false; length],
@@ -48,6 +55,10 @@ impl Button {
         self.effect.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.effect.is_empty()
+    }
+
     pub fn combine<'b>(
         mut buttons: impl Iterator<Item = &'b Button>,
         length: usize,
@@ -2,12 +2,15 @@ use std::{cmp::Reverse, collections::BinaryHeap};
 
 use super::{Button, CountArray, Difference, PartialOrdered};
 
+/// The next buttons to try, with the state each would lead to, closest to the target first.
+type NextButtons = BinaryHeap<Reverse<PartialOrdered<(usize, CountArray<u16>)>>>;
+
 pub struct ButtonCombination<'b> {
     buttons: &'b [Button],
     target: &'b CountArray<u16>,
     pub(crate) difference: Difference<i16>,
     pub(crate) combination: Vec<usize>,
-    heap: BinaryHeap<Reverse<PartialOrdered<(usize, CountArray<u16>)>>>,
+    heap: NextButtons,
 }
 
 impl<'b> ButtonCombination<'b> {
@@ -75,6 +78,10 @@ impl<'b> ButtonCombination<'b> {
         self.target.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.target.is_empty()
+    }
+
     fn init(&mut self, init_state: CountArray<u16>) -> anyhow::Result<()> {
         self.buttons
             .iter()
@@ -33,6 +33,10 @@ where
         self.values.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.values.iter()
     }
@@ -79,7 +79,7 @@ impl<U> std::cmp::Eq for PartialOrdered<U> {}
 
 impl<U> std::cmp::PartialOrd for PartialOrdered<U> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.difference.partial_cmp(&other.difference)
+        Some(self.cmp(other))
     }
 }
 
@@ -0,0 +1,256 @@
+//! An exact solver for the joltage requirements, by integer Gaussian elimination.
+//!
+//! Pressing button ``j`` ``x[j]`` times adds ``A[i][j] x[j]`` to counter ``i``, where
+//! ``A[i][j]`` is ``1`` if the button is wired to the counter; so the requirements are
+//! the linear system ``A x = b``. Eliminating it leaves some buttons - the pivots - each
+//! determined by the others, which are free. Every solution is then a choice of presses
+//! for the free buttons; so the fewest presses are found by trying every choice, which
+//! is cheap since there are rarely more than a few free buttons, and none can be pressed
+//! more times than the smallest requirement of the counters it is wired to.
+//!
+//! Elimination is kept in integers throughout, scaling rows rather than dividing them, so
+//! there is no rounding to worry about; a pivot that does not come out as a whole,
+//! non-negative number of presses simply rules out that choice.
+
+use super::{Button, CountArray};
+
+/// Greatest common divisor, always non-negative.
+fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// A row of the eliminated system, determining one pivot button from the free ones:
+/// ``coefficient * x[pivot] + Σ free_coefficients[k] * x[free[k]] = rhs``.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PivotRow {
+    pivot: usize,
+    coefficient: i64,
+    free_coefficients: Vec<i64>,
+    rhs: i64,
+}
+
+impl PivotRow {
+    /// The presses of the pivot button, given the presses of the free ones, if they come
+    /// out as a whole, non-negative number.
+    fn solve(&self, free_presses: &[i64]) -> Option<i64> {
+        let numerator = self.rhs
+            - self
+                .free_coefficients
+                .iter()
+                .zip(free_presses)
+                .map(|(coefficient, presses)| coefficient * presses)
+                .sum::<i64>();
+        (numerator % self.coefficient == 0)
+            .then(|| numerator / self.coefficient)
+            .filter(|&presses| presses >= 0)
+    }
+}
+
+/// The joltage requirements of a machine, eliminated down to the buttons that can be
+/// pressed freely.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JoltageSystem {
+    pivot_rows: Vec<PivotRow>,
+    free: Vec<usize>,
+    /// The most times each button can be pressed without overshooting a counter.
+    bounds: Vec<i64>,
+}
+
+impl JoltageSystem {
+    /// Eliminate the system of reaching ``target`` with ``buttons``.
+    ///
+    /// Returns an error if the system has no solution at all, even allowing fractions or
+    /// negative presses.
+    pub fn new(buttons: &[Button], target: &CountArray<u16>) -> anyhow::Result<Self> {
+        let width = buttons.len();
+        let mut rows = (0..target.len())
+            .map(|counter| {
+                let mut row = buttons
+                    .iter()
+                    .map(|button| i64::from(button.effect.values[counter]))
+                    .collect::<Vec<_>>();
+                row.push(i64::from(target.values[counter]));
+                row
+            })
+            .collect::<Vec<_>>();
+
+        // Gauss-Jordan elimination without division: to clear a column from another row,
+        // scale that row by the pivot and subtract the pivot row scaled by its entry, then
+        // divide the result by the gcd of its entries to keep them small.
+        let mut pivots = Vec::new();
+        for column in 0..width {
+            let Some(pivot_row) = (pivots.len()..rows.len())
+                .filter(|&row| rows[row][column] != 0)
+                .min_by_key(|&row| rows[row][column].abs())
+            else {
+                continue;
+            };
+            rows.swap(pivots.len(), pivot_row);
+            let pivot_row = pivots.len();
+
+            for row in 0..rows.len() {
+                let factor = rows[row][column];
+                if row == pivot_row || factor == 0 {
+                    continue;
+                }
+                let scale = rows[pivot_row][column];
+                let pivot_values = rows[pivot_row].clone();
+                rows[row]
+                    .iter_mut()
+                    .zip(&pivot_values)
+                    .for_each(|(value, pivot_value)| {
+                        *value = *value * scale - pivot_value * factor
+                    });
+
+                let divisor = rows[row].iter().fold(0, |acc, &value| gcd(acc, value));
+                if divisor > 1 {
+                    rows[row].iter_mut().for_each(|value| *value /= divisor);
+                }
+            }
+
+            pivots.push(column);
+        }
+
+        if rows[pivots.len()..].iter().any(|row| row[width] != 0) {
+            anyhow::bail!("Joltage requirements {:?} are inconsistent", target.values);
+        }
+
+        let free = (0..width)
+            .filter(|column| !pivots.contains(column))
+            .collect::<Vec<_>>();
+        let pivot_rows = pivots
+            .iter()
+            .zip(&rows)
+            .map(|(&pivot, row)| {
+                // Keep the pivot positive, so that its presses are the rhs over it.
+                let sign = row[pivot].signum();
+                PivotRow {
+                    pivot,
+                    coefficient: row[pivot] * sign,
+                    free_coefficients: free.iter().map(|&column| row[column] * sign).collect(),
+                    rhs: row[width] * sign,
+                }
+            })
+            .collect();
+
+        let bounds = buttons
+            .iter()
+            .map(|button| {
+                button
+                    .effect
+                    .iter()
+                    .zip(target.iter())
+                    .filter(|&(&wired, _)| wired)
+                    .map(|(_, &requirement)| i64::from(requirement))
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        Ok(Self {
+            pivot_rows,
+            free,
+            bounds,
+        })
+    }
+
+    /// The buttons whose presses are not determined by the others.
+    pub fn free_buttons(&self) -> &[usize] {
+        &self.free
+    }
+
+    /// The presses of each button that reach the target in the fewest presses in total,
+    /// if any; ties go to the first found, trying fewer presses of earlier free buttons
+    /// first.
+    pub fn minimise(&self) -> Option<Vec<u64>> {
+        let mut best: Option<(i64, Vec<i64>)> = None;
+        let mut free_presses = Vec::with_capacity(self.free.len());
+        self.search(&mut free_presses, 0, &mut best);
+
+        best.map(|(_, presses)| presses.into_iter().map(|presses| presses as u64).collect())
+    }
+
+    /// Try every number of presses of the next free button after ``free_presses``,
+    /// keeping the fewest presses in total in ``best``.
+    fn search(
+        &self,
+        free_presses: &mut Vec<i64>,
+        free_total: i64,
+        best: &mut Option<(i64, Vec<i64>)>,
+    ) {
+        // The pivots cannot be pressed a negative number of times, so the free presses
+        // alone are a lower bound on the total.
+        if best.as_ref().is_some_and(|(total, _)| free_total >= *total) {
+            return;
+        }
+
+        let Some(&button) = self.free.get(free_presses.len()) else {
+            let mut presses = vec![0; self.bounds.len()];
+            for (&button, &count) in self.free.iter().zip(free_presses.iter()) {
+                presses[button] = count;
+            }
+            for row in &self.pivot_rows {
+                match row.solve(free_presses) {
+                    Some(count) if count <= self.bounds[row.pivot] => presses[row.pivot] = count,
+                    _ => return,
+                }
+            }
+
+            let total = presses.iter().sum::<i64>();
+            if best
+                .as_ref()
+                .is_none_or(|(best_total, _)| total < *best_total)
+            {
+                *best = Some((total, presses));
+            }
+            return;
+        };
+
+        for count in 0..=self.bounds[button] {
+            free_presses.push(count);
+            self.search(free_presses, free_total + count, best);
+            free_presses.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Machine;
+
+    fn eliminate(input: &str) -> JoltageSystem {
+        let machine = Machine::new_from_input(input).expect("Failed to parse Machine");
+        JoltageSystem::new(&machine.buttons, &machine.joltage.values)
+            .expect("Failed to eliminate system")
+    }
+
+    #[test]
+    fn test_free_buttons() {
+        // Six buttons over four independent counters leaves two free.
+        let system = eliminate("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}");
+        assert_eq!(system.free_buttons().len(), 2);
+
+        // Three independent buttons over four counters leaves none.
+        let system = eliminate("[..#.] (1,2,3) (1,3) (0,3) {6,14,4,20}");
+        assert!(system.free_buttons().is_empty());
+        assert_eq!(system.minimise(), Some(vec![4, 10, 6]));
+    }
+
+    #[test]
+    fn test_inconsistent() {
+        let machine = Machine::new_from_input("[..] (0,1) {1,2}").expect("Failed to parse Machine");
+        assert!(JoltageSystem::new(&machine.buttons, &machine.joltage.values).is_err());
+    }
+
+    #[test]
+    fn test_no_whole_solution() {
+        // Each button would have to be pressed 1.5 times.
+        let system = eliminate("[...] (0,1) (1,2) (0,2) {3,3,3}");
+        assert_eq!(system.minimise(), None);
+    }
+}
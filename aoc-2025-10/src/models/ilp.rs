@@ -0,0 +1,412 @@
+//! A small integer linear programming solver, to replace the one we used to borrow.
+//!
+//! The problems of Part 2 are of the form: minimise ``c·x`` subject to ``A x = b``, with
+//! every ``x`` a non-negative integer. Without the integer part, this is a linear program,
+//! which the simplex method solves exactly; the answer just may not be whole numbers.
+//!
+//! Branch-and-bound makes up the difference. If the linear program - the "relaxation" -
+//! says a button should be pressed ``3.5`` times, then the best integer answer presses it
+//! either at most ``3`` or at least ``4`` times; so the problem is split in two with each
+//! of those bounds added, and each half solved the same way. Each relaxation is also a
+//! lower bound on any integer answer within it, so a branch can be dropped as soon as its
+//! relaxation is no better than the best integer answer found so far.
+
+/// Values within this of each other are treated as equal, to absorb rounding errors.
+const EPSILON: f64 = 1e-9;
+
+/// The outcome of solving a linear program.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LpOutcome {
+    /// The values of the variables at an optimum.
+    Optimal(Vec<f64>),
+    /// No values satisfy the constraints.
+    Infeasible,
+    /// The objective can be made as small as we like.
+    Unbounded,
+}
+
+/// Minimise ``costs·x`` subject to ``rows x = rhs`` and ``x >= 0``, with the two-phase
+/// simplex method.
+///
+/// Phase one adds an artificial variable to each row and minimises their sum, to find
+/// any values at all that satisfy the constraints; phase two then minimises the real
+/// objective from there. Pivots follow Bland's rule, which is slow but never cycles.
+///
+/// Every row must have as many coefficients as there are ``costs``.
+pub fn simplex(rows: &[Vec<f64>], rhs: &[f64], costs: &[f64]) -> LpOutcome {
+    let (height, width) = (rows.len(), costs.len());
+
+    // Each row of the tableau is the coefficients of the real variables, then of the
+    // artificial ones, then the right hand side, which is kept non-negative.
+    let mut tableau = rows
+        .iter()
+        .zip(rhs)
+        .enumerate()
+        .map(|(index, (row, &value))| {
+            let sign = if value < 0.0 { -1.0 } else { 1.0 };
+            let mut tableau_row = row.iter().map(|&a| a * sign).collect::<Vec<_>>();
+            tableau_row.extend((0..height).map(|other| if other == index { 1.0 } else { 0.0 }));
+            tableau_row.push(value * sign);
+            tableau_row
+        })
+        .collect::<Vec<_>>();
+    let mut basis = (width..width + height).collect::<Vec<_>>();
+
+    let phase_one_costs = (0..width + height)
+        .map(|column| if column < width { 0.0 } else { 1.0 })
+        .collect::<Vec<_>>();
+    if !pivot_to_optimum(&mut tableau, &mut basis, &phase_one_costs, width + height) {
+        unreachable!("Phase one is bounded below by zero");
+    }
+    let infeasibility = basis
+        .iter()
+        .zip(&tableau)
+        .filter(|&(&column, _)| column >= width)
+        .map(|(_, row)| row[width + height])
+        .sum::<f64>();
+    if infeasibility > EPSILON {
+        return LpOutcome::Infeasible;
+    }
+
+    // Drive any artificial variables left in the basis out of it; those that cannot be
+    // are on redundant rows, which stay at zero whatever happens to the rest.
+    for row in 0..height {
+        if basis[row] >= width
+            && let Some(column) = (0..width).find(|&column| tableau[row][column].abs() > EPSILON)
+        {
+            pivot(&mut tableau, &mut basis, row, column);
+        }
+    }
+
+    let phase_two_costs = costs
+        .iter()
+        .copied()
+        .chain(std::iter::repeat_n(0.0, height))
+        .collect::<Vec<_>>();
+    if !pivot_to_optimum(&mut tableau, &mut basis, &phase_two_costs, width) {
+        return LpOutcome::Unbounded;
+    }
+
+    let mut values = vec![0.0; width];
+    for (row, &column) in basis.iter().enumerate() {
+        if column < width {
+            values[column] = tableau[row][width + height];
+        }
+    }
+    LpOutcome::Optimal(values)
+}
+
+/// Pivot until no column below ``entering_limit`` can reduce the objective of ``costs``
+/// any further; returns ``false`` if one could reduce it without bound.
+fn pivot_to_optimum(
+    tableau: &mut [Vec<f64>],
+    basis: &mut [usize],
+    costs: &[f64],
+    entering_limit: usize,
+) -> bool {
+    let rhs = tableau.first().map_or(0, |row| row.len() - 1);
+
+    loop {
+        // Bland's rule: the lowest column whose reduced cost is negative enters.
+        let Some(entering) = (0..entering_limit).find(|&column| {
+            let reduced_cost = costs[column]
+                - basis
+                    .iter()
+                    .zip(tableau.iter())
+                    .map(|(&basic, row)| costs[basic] * row[column])
+                    .sum::<f64>();
+            reduced_cost < -EPSILON
+        }) else {
+            return true;
+        };
+
+        // The row that limits the entering variable the most leaves; ties go to the
+        // lowest basic column, again by Bland's rule.
+        let Some(leaving) = (0..tableau.len())
+            .filter(|&row| tableau[row][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio = |row: usize| tableau[row][rhs] / tableau[row][entering];
+                ratio(a).total_cmp(&ratio(b)).then(basis[a].cmp(&basis[b]))
+            })
+        else {
+            return false;
+        };
+
+        pivot(tableau, basis, leaving, entering);
+    }
+}
+
+/// Make ``column`` basic in ``row``, eliminating it from every other row.
+fn pivot(tableau: &mut [Vec<f64>], basis: &mut [usize], row: usize, column: usize) {
+    let divisor = tableau[row][column];
+    tableau[row].iter_mut().for_each(|value| *value /= divisor);
+
+    let pivot_row = tableau[row].clone();
+    for (other, other_row) in tableau.iter_mut().enumerate() {
+        let factor = other_row[column];
+        if other != row && factor.abs() > EPSILON {
+            other_row
+                .iter_mut()
+                .zip(&pivot_row)
+                .for_each(|(value, pivot_value)| *value -= factor * pivot_value);
+        }
+    }
+
+    basis[row] = column;
+}
+
+/// The bounds a branch puts on one variable, on top of it being non-negative.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Bounds {
+    lower: Option<u64>,
+    upper: Option<u64>,
+}
+
+/// The best integer solution found by [`IntegerProgram::minimise`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct IlpSolution {
+    /// The value of each variable.
+    pub values: Vec<u64>,
+    /// The value of the objective.
+    pub objective: f64,
+    /// The number of relaxations solved along the way.
+    pub nodes_explored: usize,
+}
+
+/// Minimise ``objective·x`` subject to a set of equalities, with every ``x`` a
+/// non-negative integer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntegerProgram {
+    objective: Vec<f64>,
+    rows: Vec<Vec<f64>>,
+    rhs: Vec<f64>,
+}
+
+impl IntegerProgram {
+    /// A program over as many variables as ``objective`` has coefficients, with no
+    /// constraints yet.
+    pub fn new(objective: Vec<f64>) -> Self {
+        Self {
+            objective,
+            rows: Vec::new(),
+            rhs: Vec::new(),
+        }
+    }
+
+    /// The number of variables.
+    pub fn len(&self) -> usize {
+        self.objective.len()
+    }
+
+    /// Whether there are no variables at all.
+    pub fn is_empty(&self) -> bool {
+        self.objective.is_empty()
+    }
+
+    /// Require ``coefficients·x`` to equal ``value``.
+    pub fn add_equality(&mut self, coefficients: Vec<f64>, value: f64) -> anyhow::Result<()> {
+        if coefficients.len() != self.len() {
+            anyhow::bail!(
+                "Constraint has {} coefficients, but there are {} variables",
+                coefficients.len(),
+                self.len()
+            );
+        }
+
+        self.rows.push(coefficients);
+        self.rhs.push(value);
+        Ok(())
+    }
+
+    /// Solve the relaxation within ``bounds``, turning each bound into an equality with
+    /// a slack variable of its own.
+    fn relaxation(&self, bounds: &[Bounds]) -> LpOutcome {
+        let width = self.len();
+        let bound_rows = bounds
+            .iter()
+            .enumerate()
+            .flat_map(|(variable, bounds)| {
+                [(bounds.lower, -1.0), (bounds.upper, 1.0)]
+                    .into_iter()
+                    .filter_map(move |(bound, slack)| Some((variable, bound? as f64, slack)))
+            })
+            .collect::<Vec<_>>();
+        let slacks = bound_rows.len();
+
+        let mut rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                row.resize(width + slacks, 0.0);
+                row
+            })
+            .collect::<Vec<_>>();
+        let mut rhs = self.rhs.clone();
+        for (index, &(variable, bound, slack)) in bound_rows.iter().enumerate() {
+            let mut row = vec![0.0; width + slacks];
+            row[variable] = 1.0;
+            row[width + index] = slack;
+            rows.push(row);
+            rhs.push(bound);
+        }
+
+        let mut costs = self.objective.clone();
+        costs.resize(width + slacks, 0.0);
+
+        match simplex(&rows, &rhs, &costs) {
+            LpOutcome::Optimal(mut values) => {
+                values.truncate(width);
+                LpOutcome::Optimal(values)
+            }
+            outcome => outcome,
+        }
+    }
+
+    /// Find the integer solution with the smallest objective, by branch-and-bound.
+    ///
+    /// Branches are explored depth first, rounding down before up, so that an integer
+    /// solution is found early and can prune the rest. If every coefficient of the
+    /// objective is an integer, so is the objective of every integer solution; a branch
+    /// is then dropped unless its relaxation, rounded up, beats the best so far.
+    ///
+    /// Returns an error if there is no integer solution, or the relaxation is unbounded.
+    pub fn minimise(&self) -> anyhow::Result<IlpSolution> {
+        let integral_objective = self.objective.iter().all(|c| c.fract() == 0.0);
+        let mut best: Option<IlpSolution> = None;
+        let mut nodes_explored = 0;
+
+        let mut stack = vec![vec![Bounds::default(); self.len()]];
+        while let Some(bounds) = stack.pop() {
+            nodes_explored += 1;
+
+            let values = match self.relaxation(&bounds) {
+                LpOutcome::Optimal(values) => values,
+                LpOutcome::Infeasible => continue,
+                LpOutcome::Unbounded => anyhow::bail!("Integer program is unbounded"),
+            };
+            let objective = values
+                .iter()
+                .zip(&self.objective)
+                .map(|(x, c)| x * c)
+                .sum::<f64>();
+
+            if let Some(best) = &best {
+                let bound = if integral_objective {
+                    (objective - EPSILON).ceil()
+                } else {
+                    objective
+                };
+                if bound >= best.objective - EPSILON {
+                    continue;
+                }
+            }
+
+            // Branch on the variable furthest from a whole number.
+            let fractional = values
+                .iter()
+                .enumerate()
+                .map(|(variable, &value)| (variable, value, (value - value.round()).abs()))
+                .filter(|&(_, _, distance)| distance > 1e-6)
+                .max_by(|a, b| a.2.total_cmp(&b.2));
+
+            #[cfg(feature = "trace")]
+            eprintln!(
+                "Node {}: relaxation {:.3} at {:?}, branching on {:?}",
+                nodes_explored, objective, values, fractional
+            );
+
+            match fractional {
+                None => {
+                    best = Some(IlpSolution {
+                        values: values.iter().map(|value| value.round() as u64).collect(),
+                        objective: if integral_objective {
+                            objective.round()
+                        } else {
+                            objective
+                        },
+                        nodes_explored,
+                    });
+                }
+                Some((variable, value, _)) => {
+                    let mut up = bounds.clone();
+                    up[variable].lower = Some(value.ceil() as u64);
+                    let mut down = bounds;
+                    down[variable].upper = Some(value.floor() as u64);
+
+                    stack.push(up);
+                    stack.push(down);
+                }
+            }
+        }
+
+        best.map(|best| IlpSolution {
+            nodes_explored,
+            ..best
+        })
+        .ok_or_else(|| anyhow::anyhow!("Integer program has no solution"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplex() {
+        // Minimise -x - y subject to x + 2y + s = 4, 3x + y + t = 6: x = 1.6, y = 1.2.
+        let outcome = simplex(
+            &[vec![1.0, 2.0, 1.0, 0.0], vec![3.0, 1.0, 0.0, 1.0]],
+            &[4.0, 6.0],
+            &[-1.0, -1.0, 0.0, 0.0],
+        );
+        let LpOutcome::Optimal(values) = outcome else {
+            panic!("Expected an optimum, got {:?}", outcome);
+        };
+        assert!((values[0] - 1.6).abs() < 1e-6);
+        assert!((values[1] - 1.2).abs() < 1e-6);
+
+        assert_eq!(
+            simplex(&[vec![1.0, 1.0]], &[-1.0], &[1.0, 1.0]),
+            LpOutcome::Infeasible
+        );
+        assert_eq!(
+            simplex(&[vec![1.0, -1.0]], &[1.0], &[0.0, -1.0]),
+            LpOutcome::Unbounded
+        );
+    }
+
+    #[test]
+    fn test_simplex_redundant_rows() {
+        let outcome = simplex(&[vec![1.0, 1.0], vec![2.0, 2.0]], &[3.0, 6.0], &[1.0, 2.0]);
+        assert_eq!(outcome, LpOutcome::Optimal(vec![3.0, 0.0]));
+    }
+
+    #[test]
+    fn test_minimise() {
+        // The relaxation presses the second button 7/3 times; no integer solution does
+        // better than pressing the first twice and the second once.
+        let mut program = IntegerProgram::new(vec![1.0, 1.0]);
+        program
+            .add_equality(vec![2.0, 3.0], 7.0)
+            .expect("Failed to add constraint");
+        let solution = program.minimise().expect("Failed to solve");
+        assert_eq!(solution.values, vec![2, 1]);
+        assert_eq!(solution.objective, 3.0);
+        assert!(solution.nodes_explored > 1);
+
+        assert!(program.add_equality(vec![1.0], 1.0).is_err());
+    }
+
+    #[test]
+    fn test_minimise_no_integer_solution() {
+        // The only solution presses each button 1.5 times.
+        let mut program = IntegerProgram::new(vec![1.0; 3]);
+        for coefficients in [[1.0, 1.0, 0.0], [0.0, 1.0, 1.0], [1.0, 0.0, 1.0]] {
+            program
+                .add_equality(coefficients.to_vec(), 3.0)
+                .expect("Failed to add constraint");
+        }
+        assert!(program.minimise().is_err());
+    }
+}
@@ -34,6 +34,10 @@ impl Indicators {
         self.values.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, bool> {
         self.values.iter()
     }
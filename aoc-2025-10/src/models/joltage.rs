@@ -35,6 +35,10 @@ impl Joltage {
         self.values.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
     pub fn iter(&self) -> std::slice::Iter<'_, u16> {
         self.values.iter()
     }
@@ -1,15 +1,14 @@
 use itertools::Itertools;
 
-use anyhow::Ok;
+use anyhow::{Context, Ok};
 
 use crate::models::{CountArray, combination};
 use fxhash::FxHashSet;
 
 use super::{Button, Indicators, Joltage};
 
-#[cfg(feature="milp")]
-use good_lp::{variables, variable, default_solver, SolverModel, Solution, Variable, Expression};
-
+use super::exact::JoltageSystem;
+use super::ilp::IntegerProgram;
 
 #[cfg(feature = "progress")]
 use kdam::{BarExt, tqdm};
@@ -41,7 +40,10 @@ impl Machine {
             match segment.chars().next() {
                 Some('[') => {
                     if indicators.is_none() {
-                        indicators = Some(Indicators::new_from_input(segment)?);
+                        indicators = Some(
+                            Indicators::new_from_input(segment)
+                                .with_context(|| format!("Invalid Indicators {:?}", segment))?,
+                        );
                     } else {
                         anyhow::bail!("Multiple Indicators found in Machine input");
                     }
@@ -49,7 +51,10 @@ impl Machine {
                 Some('(') => {
                     if let Some(indicators) = &indicators {
                         let length = indicators.len();
-                        let button = Button::new_from_input(buttons.len(), segment, length)?;
+                        let button = Button::new_from_input(buttons.len(), segment, length)
+                            .with_context(|| {
+                                format!("Invalid Button {} {:?}", buttons.len(), segment)
+                            })?;
                         buttons.push(button);
                     } else {
                         anyhow::bail!("Button found before Indicators in Machine input");
@@ -57,7 +62,10 @@ impl Machine {
                 }
                 Some('{') => {
                     if joltage.is_none() {
-                        joltage = Some(Joltage::new_from_input(segment)?);
+                        joltage = Some(
+                            Joltage::new_from_input(segment)
+                                .with_context(|| format!("Invalid Joltage {:?}", segment))?,
+                        );
                     } else {
                         anyhow::bail!("Multiple Joltage found in Machine input");
                     }
@@ -107,55 +115,55 @@ impl Machine {
     }
 
     /// Solve the Machine using Mixed Integer Linear Programming (MILP).
-    /// 
-    /// Or more specifically, call someone else's MILP solver to do the heavy lifting.
-    /// I honestly have no idea how this works, we define the problem and call `solve()`
-    /// and things just work. This is very depressing and defeating. Not cool.
-    #[cfg(feature="milp")]
+    ///
+    /// This used to call someone else's MILP solver to do the heavy lifting; now it is
+    /// our own, in [`ilp`](super::ilp): one variable per button for how many times it
+    /// is pressed, one equality per counter, and the total presses to minimise.
     pub fn solve_milp(&self, target: &CountArray<u16>) -> anyhow::Result<Vec<usize>> {
-        let mut vars = variables!();
-    
-        // 1. Create a variable for each mask type (how many times to use it)
-        // We assume masks are unique.
-        let mask_counts: Vec<Variable> = (0..self.buttons.len())
-            .map(|_| vars.add(variable().min(0).integer())) 
-            .collect();
-
-        // 2. Define the objective: Minimize total count
-        let objective = mask_counts.iter().sum::<Expression>();
-    
-        let mut problem = vars.maximise(objective * -1) // Minimizing is maximizing negative
-            .using(default_solver); // or specific solver
-
-        // 3. Add constraints for each column (0 to N-1)
+        let mut problem = IntegerProgram::new(vec![1.0; self.buttons.len()]);
+
         for col_idx in 0..target.len() {
-            let mut constraint_expr = Expression::from(0);
-            for button in self.buttons.iter() {
-                if button.effect.values[col_idx] {
-                    constraint_expr += mask_counts[button.index];
-                }
-            }
-            // The sum of masks at this column must equal target[col]
-            problem.add_constraint(constraint_expr.eq(target.values[col_idx]));
+            let coefficients = self
+                .buttons
+                .iter()
+                .map(|button| f64::from(u8::from(button.effect.values[col_idx])))
+                .collect();
+            problem.add_equality(coefficients, f64::from(target.values[col_idx]))?;
         }
 
-        // 4. Solve
-        let solution = problem.solve().map_err(|e| anyhow::anyhow!("MILP solve error: {}", e))?;
+        let solution = problem
+            .minimise()
+            .map_err(|e| anyhow::anyhow!("MILP solve error: {}", e))?;
 
-        // 5. Extract solution
-        let mut result: Vec<usize> = Vec::new();
-        for (button_idx, var) in mask_counts.iter().enumerate() {
-            let count = solution.value(*var) as usize;
-            for _ in 0..count {
-                result.push(button_idx);
-            }
-        }
+        Ok(solution
+            .values
+            .iter()
+            .enumerate()
+            .flat_map(|(button_idx, &count)| std::iter::repeat_n(button_idx, count as usize))
+            .collect())
+    }
+
+    /// Solve the Machine exactly, by integer Gaussian elimination of the joltage
+    /// requirements; see [`exact`](super::exact).
+    ///
+    /// Returns the same presses as [`solve_milp`](Self::solve_milp) in number, though not
+    /// necessarily the same buttons.
+    pub fn solve_exact(&self, target: &CountArray<u16>) -> anyhow::Result<Vec<usize>> {
+        let presses = JoltageSystem::new(&self.buttons, target)?
+            .minimise()
+            .ok_or_else(|| {
+                anyhow::anyhow!("No whole number of presses reaches {:?}", target.values)
+            })?;
 
-        Ok(result)
+        Ok(presses
+            .iter()
+            .enumerate()
+            .flat_map(|(button_idx, &count)| std::iter::repeat_n(button_idx, count as usize))
+            .collect())
     }
 
     /// Solve the Machine using a depth-first search approach.
-    /// 
+    ///
     /// This is optimized to avoid revisiting already explored combinations.
     pub fn solve_dfs(&self, target: &CountArray<u16>) -> anyhow::Result<Vec<usize>> {
         #[cfg(feature = "progress")]
@@ -317,7 +325,6 @@ mod test_solve {
     macro_rules! create_test {
         ($name:ident($input:expr) = $expected:expr) => {
             #[test]
-            #[cfg(feature="milp")]
             fn $name() {
                 let machine = Machine::new_from_input($input).expect("Failed to parse Machine");
                 let solution = machine
@@ -346,18 +353,45 @@ mod test_solve {
                 let expected_state = Button::combine(
                     expected.iter().map(|id| &machine.buttons[*id]),
                     machine.indicators.len(),
-                ).expect("Failed to combine expected buttons");
-                assert_eq!(expected_state, machine.joltage.values, "Final state from model answer does not match expected state");
+                )
+                .expect("Failed to combine expected buttons");
+                assert_eq!(
+                    expected_state, machine.joltage.values,
+                    "Final state from model answer does not match expected state"
+                );
 
-                assert_eq!(solution, expected, "Solution does not match expected");
+                // Presses can often be traded for others without changing the total, so
+                // only the number of presses has to match.
+                assert_eq!(
+                    solution.len(),
+                    expected.len(),
+                    "Solution is not as short as expected"
+                );
+
+                let exact = machine
+                    .solve_exact(&machine.joltage.values)
+                    .expect("Failed to solve Machine exactly");
+                let exact_state = Button::combine(
+                    exact.iter().map(|id| &machine.buttons[*id]),
+                    machine.indicators.len(),
+                )
+                .expect("Failed to combine exact buttons");
+                assert_eq!(
+                    exact_state, machine.joltage.values,
+                    "Final state from exact solution does not match Machine joltage"
+                );
+                assert_eq!(
+                    exact.len(),
+                    expected.len(),
+                    "Exact solution is not as short as expected"
+                );
             }
         };
     }
 
     create_test!(
         test_example_1("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}") =
-            vec![0, 1, 1, 1, 1, 1, 3, 4, 4, 4]
-            // vec![0, 1, 1, 1, 1, 3, 3, 4, 4, 5] // The listed answer is actually [0, 1, 1, 1, 3, 3, 3, 4, 5, 5] - but both yield the same final state, and our solver happens to find this one.
+            vec![0, 1, 1, 1, 1, 1, 3, 4, 4, 4] // vec![0, 1, 1, 1, 1, 3, 3, 4, 4, 5] // The listed answer is actually [0, 1, 1, 1, 3, 3, 3, 4, 5, 5] - but both yield the same final state, and our solver happens to find this one.
     );
     create_test!(
         test_example_2("[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}") =
@@ -372,7 +406,8 @@ mod test_solve {
             vec![0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2]
     );
     create_test!(
-        test_input_2("[...##.] (0,1,2,4,5) (0,2,5) (0,1,5) (0,2,3,4) (0,4) {29,14,21,4,18,21}") = 
-            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4]
+        test_input_2("[...##.] (0,1,2,4,5) (0,2,5) (0,1,5) (0,2,3,4) (0,4) {29,14,21,4,18,21}") = vec![
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4
+        ]
     );
 }
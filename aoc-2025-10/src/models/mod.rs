@@ -10,6 +10,10 @@ mod joltage;
 pub use joltage::*;
 mod combination;
 pub use combination::*;
+pub mod exact;
+pub mod ilp;
 
 mod machine;
 pub use machine::*;
+mod plan;
+pub use plan::*;
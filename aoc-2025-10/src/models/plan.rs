@@ -0,0 +1,197 @@
+//! Checking a solution against its machine, and describing what it does.
+
+use std::fmt;
+
+use super::{Button, CountArray, Machine};
+
+/// What a solution is meant to achieve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Goal {
+    /// Part 1: light the indicators in the diagram, where each press toggles its lights.
+    Indicators,
+    /// Part 2: raise the joltage counters to their requirements, where each press adds
+    /// one to its counters.
+    Joltage,
+}
+
+/// A verified solution: how many times each button is pressed, and the counters that
+/// results in.
+///
+/// Its [`Display`](fmt::Display) is a report of the buttons pressed, and of what each
+/// button contributes to each counter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PressPlan<'m> {
+    machine: &'m Machine,
+    goal: Goal,
+    /// The presses of each button, by index.
+    pub presses: Vec<usize>,
+    /// The counters after every press, before any toggling is taken into account.
+    pub state: CountArray<u16>,
+}
+
+impl PressPlan<'_> {
+    /// The total number of presses.
+    pub fn total(&self) -> usize {
+        self.presses.iter().sum()
+    }
+}
+
+impl Machine {
+    /// Check that pressing the buttons in ``solution``, each entry a button index, meets
+    /// ``goal``, by recomputing the state of the machine from scratch.
+    ///
+    /// Returns an error for an index without a button, or if the state reached is not
+    /// the one required.
+    pub fn verify(&self, solution: &[usize], goal: Goal) -> anyhow::Result<PressPlan<'_>> {
+        let mut presses = vec![0; self.buttons.len()];
+        for &button_id in solution {
+            *presses.get_mut(button_id).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Solution presses button {} of a Machine with {} buttons",
+                    button_id,
+                    self.buttons.len()
+                )
+            })? += 1;
+        }
+
+        let state = Button::combine(
+            solution.iter().map(|id| &self.buttons[*id]),
+            self.indicators.len(),
+        )?;
+
+        match goal {
+            Goal::Indicators if state.mask() != self.indicators.values => anyhow::bail!(
+                "Solution lights {} but the diagram is {}",
+                state.mask().display_as_tuple(),
+                self.indicators.values.display_as_tuple()
+            ),
+            Goal::Joltage if state != self.joltage.values => anyhow::bail!(
+                "Solution reaches joltage {:?} but {:?} is required",
+                state.values,
+                self.joltage.values.values
+            ),
+            _ => {}
+        }
+
+        Ok(PressPlan {
+            machine: self,
+            goal,
+            presses,
+            state,
+        })
+    }
+}
+
+impl fmt::Display for PressPlan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels = self
+            .machine
+            .buttons
+            .iter()
+            .map(|button| button.effect.display_as_tuple())
+            .collect::<Vec<_>>();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+
+        writeln!(
+            f,
+            "{} presses of {} buttons:",
+            self.total(),
+            self.presses.iter().filter(|&&count| count > 0).count()
+        )?;
+        for (label, &count) in labels.iter().zip(&self.presses) {
+            if count > 0 {
+                writeln!(f, "  {:<width$} x{}", label, count)?;
+            }
+        }
+
+        writeln!(f, "Counters:")?;
+        for (counter, &value) in self.state.iter().enumerate() {
+            let contributions = self
+                .machine
+                .buttons
+                .iter()
+                .zip(&self.presses)
+                .filter(|&(button, &count)| count > 0 && button.effect.values[counter])
+                .map(|(button, count)| format!("{} x{}", labels[button.index], count))
+                .collect::<Vec<_>>();
+            let reached = match self.goal {
+                Goal::Indicators => format!(
+                    "{:>3} {:<5}",
+                    value,
+                    if value % 2 == 1 { "(on)" } else { "(off)" }
+                ),
+                Goal::Joltage => format!("{:>3}", value),
+            };
+
+            writeln!(
+                f,
+                "  {:>3}: {} = {}",
+                counter,
+                reached,
+                if contributions.is_empty() {
+                    "nothing".to_owned()
+                } else {
+                    contributions.join(" + ")
+                }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}";
+
+    fn example() -> Machine {
+        Machine::new_from_input(EXAMPLE).expect("Failed to parse Machine")
+    }
+
+    #[test]
+    fn test_verify_indicators() {
+        let machine = example();
+        let plan = machine
+            .verify(&[4, 5], Goal::Indicators)
+            .expect("Solution should light the indicators");
+        assert_eq!(plan.presses, vec![0, 0, 0, 0, 1, 1]);
+        assert_eq!(plan.total(), 2);
+
+        assert!(machine.verify(&[4], Goal::Indicators).is_err());
+        assert!(machine.verify(&[4, 6], Goal::Indicators).is_err());
+    }
+
+    #[test]
+    fn test_verify_joltage() {
+        let machine = example();
+        let solution = [0, 1, 1, 1, 3, 3, 3, 4, 5, 5];
+        let plan = machine
+            .verify(&solution, Goal::Joltage)
+            .expect("Solution should reach the joltage");
+        assert_eq!(plan.state, vec![3, 5, 4, 7].into());
+
+        // Lighting the indicators is not enough to reach the joltage.
+        assert!(machine.verify(&[4, 5], Goal::Joltage).is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let machine = example();
+        let report = machine
+            .verify(&[0, 1, 1, 1, 3, 3, 3, 4, 5, 5], Goal::Joltage)
+            .expect("Solution should reach the joltage")
+            .to_string();
+        let lines = report.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "10 presses of 5 buttons:");
+        assert_eq!(
+            lines[1].split_whitespace().collect::<Vec<_>>(),
+            ["(3)", "x1"]
+        );
+        assert_eq!(lines[6], "Counters:");
+        assert_eq!(lines[7], "    0:   3 = (0, 2) x1 + (0, 1) x2");
+        assert_eq!(lines.len(), 11);
+    }
+}
@@ -0,0 +1,50 @@
+//! Benchmarks of counting the paths of part 1 sequentially, with [`simple_graph::dfs_count`]
+//! and with our own checked DFS, against counting each subtree of the start concurrently.
+//!
+//! Run with ``cargo bench -p aoc-2025-11 --bench subtrees --features rayon``.
+
+use aoc_2025_11::{
+    INPUT,
+    models::{DeviceId, Distance},
+    parse, paths,
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use simple_graph::DfsOptions;
+use std::hint::black_box;
+
+fn bench_subtrees(c: &mut Criterion) {
+    let devices = parse::text_to_devices(INPUT)
+        .expect("Failed to parse devices")
+        .devices;
+    let start_id = parse::str_to_device_id("you");
+    let destination_id = parse::str_to_device_id("out");
+    let start = &devices[&start_id];
+
+    let mut group = c.benchmark_group("subtrees");
+    group.bench_function("dfs_count", |b| {
+        b.iter(|| {
+            simple_graph::dfs_count::<DeviceId, Distance, _>(
+                black_box(start),
+                &destination_id,
+                devices.len(),
+                DfsOptions::default(),
+                |key| devices.get(key),
+            )
+        })
+    });
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            paths::count_number_of_solutions(black_box(&devices), start_id, destination_id, &[])
+        })
+    });
+    #[cfg(feature = "rayon")]
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            paths::par_count_number_of_solutions(black_box(&devices), start_id, destination_id, &[])
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_subtrees);
+criterion_main!(benches);
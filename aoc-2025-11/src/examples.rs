@@ -0,0 +1,302 @@
+//! Concrete paths between devices, as examples to check the counts against.
+
+use crate::{
+    models::{Device, DeviceId, DeviceMap, Distance},
+    parse::device_id_to_str,
+};
+use fxhash::FxHashMap;
+use simple_graph::{Dfs, DfsOptions, Reachability};
+
+/// The devices on the paths from a start device to a destination that pass through
+/// every one of a set of waypoints, from which to find the paths themselves.
+///
+/// A path visits its waypoints in topological order, so it is a chain of segments between
+/// consecutive stops: the start, each waypoint in that order, and the destination. Each
+/// segment keeps a map of its own, of only the devices on some path between its stops,
+/// so that a [`Dfs`] over it never follows a connection that leads nowhere.
+///
+/// The maps are pruned in advance rather than through the lookup given to the [`Dfs`],
+/// since a [`Device`] stops listing its neighbours at the first one that is not found.
+#[derive(Debug, Clone)]
+pub struct PathFinder {
+    /// The start, every waypoint in topological order, and the destination.
+    stops: Vec<DeviceId>,
+    /// The devices between each pair of consecutive stops.
+    segments: Vec<DeviceMap>,
+    /// Whether some stop cannot be reached from the one before it.
+    unreachable: bool,
+}
+
+impl PathFinder {
+    /// Prepare to find the paths from ``start_id`` to ``destination_id`` through every
+    /// one of ``waypoints``.
+    ///
+    /// Returns an error if any of the devices does not exist, or if the devices
+    /// reachable from the start are connected in a cycle.
+    pub fn new(
+        devices: &DeviceMap,
+        start_id: DeviceId,
+        destination_id: DeviceId,
+        waypoints: &[DeviceId],
+    ) -> anyhow::Result<Self> {
+        let get_device = |key: &DeviceId| devices.get(key);
+        let start = get_device(&start_id).ok_or_else(|| {
+            anyhow::anyhow!("Start device {} not found", device_id_to_str(start_id))
+        })?;
+        for &id in waypoints.iter().chain([&destination_id]) {
+            anyhow::ensure!(
+                devices.contains_key(&id),
+                "Device {} not found",
+                device_id_to_str(id)
+            );
+        }
+        anyhow::ensure!(
+            start_id != destination_id,
+            "Start and destination are both {}",
+            device_id_to_str(start_id)
+        );
+
+        let order = simple_graph::topological_sort::<_, Distance, _>([start], get_device)
+            .map_err(|err| anyhow::anyhow!("Cannot order the devices: {}", err))?
+            .into_iter()
+            .enumerate()
+            .map(|(position, device)| (device.id(), position))
+            .collect::<FxHashMap<_, _>>();
+
+        let mut waypoints = waypoints
+            .iter()
+            .copied()
+            .filter(|&id| id != start_id && id != destination_id)
+            .collect::<Vec<_>>();
+        waypoints.sort_unstable();
+        waypoints.dedup();
+        waypoints.sort_by_key(|id| order.get(id));
+        let stops = std::iter::once(start_id)
+            .chain(waypoints)
+            .chain([destination_id])
+            .collect::<Vec<_>>();
+
+        let reachability = Reachability::new::<Distance, _>([start], get_device);
+        let on_segment =
+            |id, from, to| reachability.can_reach(&from, &id) && reachability.can_reach(&id, &to);
+        let segments = stops
+            .windows(2)
+            .map(|pair| {
+                let (from, to) = (pair[0], pair[1]);
+                devices
+                    .values()
+                    .filter(|device| on_segment(device.id(), from, to))
+                    .map(|device| {
+                        let connected = device
+                            .connected_devices()
+                            .iter()
+                            .copied()
+                            .filter(|&id| on_segment(id, from, to));
+                        (device.id(), Device::new(device.id(), connected))
+                    })
+                    .collect::<DeviceMap>()
+            })
+            .collect::<Vec<_>>();
+        let unreachable = stops
+            .windows(2)
+            .any(|pair| !reachability.can_reach(&pair[0], &pair[1]));
+
+        Ok(Self {
+            stops,
+            segments,
+            unreachable,
+        })
+    }
+
+    /// The paths, as device names, lazily; up to ``max_paths`` of them if given.
+    ///
+    /// The paths are every combination of the paths of the segments, moving on the last
+    /// segment first.
+    pub fn paths(&self, max_paths: Option<usize>) -> PathIter<'_> {
+        PathIter {
+            finder: self,
+            searches: Vec::new(),
+            segments: Vec::new(),
+            remaining: max_paths,
+            done: self.unreachable,
+        }
+    }
+}
+
+/// The paths found by [`PathFinder::paths`].
+pub struct PathIter<'a> {
+    finder: &'a PathFinder,
+    /// The search of each segment begun so far.
+    searches: Vec<Dfs<'a, DeviceId, Distance, Device>>,
+    /// The current path of each segment in ``searches``.
+    segments: Vec<Vec<DeviceId>>,
+    /// How many more paths to yield, if capped.
+    remaining: Option<usize>,
+    done: bool,
+}
+
+impl<'a> PathIter<'a> {
+    /// Begin the search of the next segment, returning its first path if it has any.
+    fn begin_segment(&mut self) -> Option<Vec<DeviceId>> {
+        let index = self.searches.len();
+        let (from, to) = (self.finder.stops[index], self.finder.stops[index + 1]);
+        let devices = &self.finder.segments[index];
+        let mut search = Dfs::new(
+            &devices[&from],
+            &devices[&to],
+            devices.len(),
+            DfsOptions::default(),
+        )
+        .expect("Unreachable; consecutive stops are distinct");
+
+        let path = Self::next_path(&mut search, devices);
+        self.searches.push(search);
+        path
+    }
+
+    /// The next path of ``search`` over the devices of its segment.
+    fn next_path(
+        search: &mut Dfs<'a, DeviceId, Distance, Device>,
+        devices: &'a DeviceMap,
+    ) -> Option<Vec<DeviceId>> {
+        search
+            .next_solution(|key| devices.get(key))
+            .map(|(path, _)| path.into_iter().copied().collect())
+    }
+}
+
+impl Iterator for PathIter<'_> {
+    type Item = Vec<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.remaining == Some(0) {
+            return None;
+        }
+
+        let segment_count = self.finder.segments.len();
+        // Once a path has been yielded, the last segment has to move on first.
+        let mut move_on = self.segments.len() == segment_count;
+        loop {
+            if move_on {
+                let Some(mut search) = self.searches.pop() else {
+                    self.done = true;
+                    return None;
+                };
+                self.segments.pop();
+                let devices = &self.finder.segments[self.searches.len()];
+                if let Some(path) = Self::next_path(&mut search, devices) {
+                    self.searches.push(search);
+                    self.segments.push(path);
+                    move_on = false;
+                }
+            } else if self.segments.len() == segment_count {
+                break;
+            } else {
+                match self.begin_segment() {
+                    Some(path) => self.segments.push(path),
+                    // The earlier segments have to move on to find one that continues.
+                    None => {
+                        self.searches.pop();
+                        move_on = true;
+                    }
+                }
+            }
+        }
+
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        let path = std::iter::once(self.finder.stops[0])
+            .chain(
+                self.segments
+                    .iter()
+                    .flat_map(|segment| segment[1..].iter().copied()),
+            )
+            .map(device_id_to_str)
+            .collect();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::str_to_device_id;
+    use std::collections::HashSet;
+
+    // The example of part 2.
+    const INPUT: &str = "svr: aaa bbb
+                         aaa: fft
+                         fft: ccc
+                         bbb: tty
+                         tty: ccc
+                         ccc: ddd eee
+                         ddd: hub
+                         hub: fff
+                         eee: dac
+                         dac: fff
+                         fff: ggg hhh
+                         ggg: out
+                         hhh: out";
+
+    fn paths(waypoints: &[&str], max_paths: Option<usize>) -> Vec<String> {
+        let devices = crate::parse::text_to_devices(INPUT)
+            .expect("Failed to parse devices")
+            .devices;
+        let waypoints = waypoints
+            .iter()
+            .map(|name| str_to_device_id(name))
+            .collect::<Vec<_>>();
+        PathFinder::new(
+            &devices,
+            str_to_device_id("svr"),
+            str_to_device_id("out"),
+            &waypoints,
+        )
+        .expect("Failed to prepare paths")
+        .paths(max_paths)
+        .map(|path| path.join(" "))
+        .collect()
+    }
+
+    #[test]
+    fn test_paths() {
+        let all = paths(&[], None);
+        assert_eq!(all.len(), 8);
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 8);
+        assert!(
+            all.iter()
+                .all(|path| path.starts_with("svr ") && path.ends_with(" out"))
+        );
+
+        // In either order, the waypoints are visited fft first.
+        let expected = [
+            "svr aaa fft ccc eee dac fff ggg out",
+            "svr aaa fft ccc eee dac fff hhh out",
+        ];
+        assert_eq!(paths(&["dac", "fft"], None), expected);
+        assert_eq!(paths(&["fft", "dac", "svr"], None), expected);
+
+        assert_eq!(paths(&["fft", "hub", "dac"], None), Vec::<String>::new());
+        assert_eq!(paths(&["hub"], None).len(), 4);
+    }
+
+    #[test]
+    fn test_max_paths() {
+        assert_eq!(paths(&[], Some(3)).len(), 3);
+        assert_eq!(paths(&["dac", "fft"], Some(5)).len(), 2);
+        assert!(paths(&[], Some(0)).is_empty());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let devices = crate::parse::text_to_devices("svr: aaa\naaa: svr out")
+            .expect("Failed to parse devices")
+            .devices;
+        let svr = str_to_device_id("svr");
+        let out = str_to_device_id("out");
+        assert!(PathFinder::new(&devices, svr, out, &[]).is_err());
+        assert!(PathFinder::new(&devices, out, out, &[]).is_err());
+        assert!(PathFinder::new(&devices, svr, str_to_device_id("zzz"), &[]).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-pub const INPUT: &'static str = "smx: otc
+pub const INPUT: &str = "smx: otc
 mmh: gvp
 gbo: hyf alz
 laa: vky qjq
@@ -0,0 +1,10 @@
+//! Counting the paths between the devices of the reactor, from Day 11 of 2025, as a library
+//! so that its counts can be benchmarked outside of the puzzle.
+
+pub mod examples;
+pub mod models;
+pub mod parse;
+pub mod paths;
+
+mod input;
+pub use input::INPUT;
@@ -96,14 +96,9 @@
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
-pub mod models;
-pub mod parse;
-
-mod input;
-use input::INPUT;
-
-use crate::models::DeviceMap;
+use aoc_2025_11::{INPUT, examples, models, parse, paths};
 
+use std::path::PathBuf;
 #[cfg(feature = "profile")]
 use std::time::Instant;
 
@@ -114,124 +109,191 @@ const SERVER_RACK: &str = "svr";
 const DAC: &str = "dac";
 const FFT: &str = "fft";
 
-fn build_devices(input: &str) -> anyhow::Result<models::DeviceMap> {
-    let mut map = parse::text_to_devices(input)?;
-
-    let destination_id = parse::str_to_device_id(DESTINATION);
-    map.insert(
-        destination_id,
-        models::Device::new(destination_id, std::iter::empty()),
+/// Read the device map from ``input``, in any of the [`parse::InputFormat`]s.
+///
+/// Neither the puzzle's destination nor ``destination_id`` need be defined, as they
+/// have no outputs; any other device that is connected to but never defined is an error.
+fn build_devices(
+    input: &str,
+    destination_id: models::DeviceId,
+) -> anyhow::Result<models::DeviceMap> {
+    let parse::ParsedDevices {
+        mut devices,
+        undefined,
+    } = parse::text_to_devices(input)?;
+
+    let undefined = undefined
+        .into_iter()
+        .filter(|&id| id != destination_id && id != parse::str_to_device_id(DESTINATION))
+        .map(parse::device_id_to_str)
+        .collect::<Vec<_>>();
+    anyhow::ensure!(
+        undefined.is_empty(),
+        "Devices are connected to but never defined: {}",
+        undefined.join(", ")
     );
-    Ok(map)
+
+    devices
+        .entry(destination_id)
+        .or_insert_with(|| models::Device::new_empty(destination_id));
+    Ok(devices)
 }
 
-fn count_number_of_solutions(
-    devices: &DeviceMap,
-    start_id: models::DeviceId,
-    destination_id: models::DeviceId,
-    avoid: &[&models::DeviceId],
-) -> anyhow::Result<usize> {
-    let mut private_devices = devices.clone();
-    for avoid_id in avoid {
-        private_devices.remove(*avoid_id);
-    }
+/// The number of paths from the server rack to the destination through both the DAC and
+/// the FFT.
+fn part_2_solutions_count(devices: &models::DeviceMap) -> anyhow::Result<paths::PathCount> {
+    paths::count_paths_via(
+        devices,
+        parse::str_to_device_id(SERVER_RACK),
+        parse::str_to_device_id(DESTINATION),
+        &[parse::str_to_device_id(DAC), parse::str_to_device_id(FFT)],
+    )
+}
 
-    let solution_count = simple_graph::dfs_count::<models::DeviceId, models::Distance, models::Device>(
-        private_devices
-            .get(&start_id)
-            .ok_or_else(|| anyhow::anyhow!("Start node not found"))?,
-        &destination_id,
-        private_devices.len(),
-        |key| private_devices.get(key)
-    );
+#[derive(Debug, Default)]
+struct Args {
+    /// From ``--input <path>``, a file to read the device map from, instead of the
+    /// puzzle input.
+    input: Option<PathBuf>,
+    /// From ``--start <device>``, where the paths of a query start.
+    start: Option<models::DeviceId>,
+    /// From ``--destination <device>``, where the paths of a query end.
+    destination: Option<models::DeviceId>,
+    /// From ``--via <device>``, repeatable, the waypoints the paths of a query pass
+    /// through.
+    via: Vec<models::DeviceId>,
+    /// From ``--examples <n>``, how many of the paths counted to print for each part.
+    examples: usize,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                    (arg, value)
+                }
+            };
+
+            match name.as_str() {
+                "--input" => parsed.input = Some(PathBuf::from(value)),
+                "--start" => parsed.start = Some(parse::parse_device_id(&value)?),
+                "--destination" => parsed.destination = Some(parse::parse_device_id(&value)?),
+                "--via" => parsed.via.push(parse::parse_device_id(&value)?),
+                "--examples" => parsed.examples = value.parse()?,
+                _ => anyhow::bail!("Unknown argument {:?}", name),
+            }
+        }
+
+        Ok(parsed)
+    }
 
-    Ok(solution_count)
+    /// Whether to count a query of the start, destination and waypoints given, instead
+    /// of the two parts of the puzzle.
+    fn is_query(&self) -> bool {
+        self.start.is_some() || self.destination.is_some() || !self.via.is_empty()
+    }
 }
 
-fn part_2_solutions_count(
+/// Print up to ``limit`` of the paths from ``start_id`` to ``destination_id`` through
+/// every one of ``waypoints``.
+fn print_examples(
     devices: &models::DeviceMap,
-) -> anyhow::Result<usize> {
-    let inverted_devices = models::invert_device_map(devices);
-
-    let server_rack_id = parse::str_to_device_id(SERVER_RACK);
-    let destination_id = parse::str_to_device_id(DESTINATION);
-    let dac_id = parse::str_to_device_id(DAC);
-    let fft_id = parse::str_to_device_id(FFT);
-
-    let svr_to_dac_count =
-        count_number_of_solutions(&devices, server_rack_id, dac_id, &[])
-            .expect("Failed to count number of solutions from SVR to DAC");
-    println!("Number of paths from SVR to DAC: {}", svr_to_dac_count);
-
-    let svr_to_fft_count: usize =
-        count_number_of_solutions(&inverted_devices, fft_id, server_rack_id, &[])
-            .expect("Failed to count number of solutions from SVR to FFT");
-    println!("Number of paths from SVR to FFT: {}", svr_to_fft_count);
-
-    let dac_to_fft_count =
-        count_number_of_solutions(&inverted_devices, fft_id, dac_id, &[])
-            .expect("Failed to count number of solutions from DAC to FFT");
-    println!("Number of paths from DAC to FFT: {}", dac_to_fft_count);
-
-    let fft_to_dac_count =
-        count_number_of_solutions(&inverted_devices, dac_id, fft_id, &[])
-            .expect("Failed to count number of solutions from FFT to DAC");
-    println!("Number of paths from FFT to DAC: {}", fft_to_dac_count);
-
-    let dac_to_out_count =
-        count_number_of_solutions(&devices, dac_id, destination_id, &[])
-            .expect("Failed to count number of solutions from DAC to OUT");
-    println!("Number of paths from DAC to OUT: {}", dac_to_out_count);
-
-    let fft_to_out_count =
-        count_number_of_solutions(&devices, fft_id, destination_id, &[])
-            .expect("Failed to count number of solutions from FFT to OUT");
-    println!("Number of paths from FFT to OUT: {}", fft_to_out_count);
-
-    let svr_to_out_through_dac_count = 
-        svr_to_fft_count
-            .checked_mul(fft_to_dac_count)
-            .expect("Overflow when calculating FFT to DAC through SVR")
-        .checked_mul(dac_to_out_count)
-        .expect("Overflow when calculating SVR to OUT through DAC");
-
-    let svr_to_out_through_fft_count = 
-        svr_to_dac_count
-            .checked_mul(dac_to_fft_count)
-            .expect("Overflow when calculating DAC to FFT through SVR")
-        .checked_mul(fft_to_out_count)
-        .expect("Overflow when calculating SVR to OUT through FFT");
-
-    let solution_count = svr_to_out_through_dac_count
-        .checked_add(svr_to_out_through_fft_count)
-        .expect("Overflow when calculating total paths from SVR to OUT");
-
-    #[cfg(feature = "assert-truth")]
-    {
-        assert_eq!(svr_to_dac_count, 1040248093572);
-        assert_eq!(svr_to_fft_count, 5418);
-        assert_eq!(dac_to_fft_count, 0);
-        assert_eq!(fft_to_dac_count, 13733136);
-        assert_eq!(dac_to_out_count, 3952);
-        assert_eq!(fft_to_out_count, 3822779890610);
+    start_id: models::DeviceId,
+    destination_id: models::DeviceId,
+    waypoints: &[models::DeviceId],
+    limit: usize,
+) -> anyhow::Result<()> {
+    let finder = examples::PathFinder::new(devices, start_id, destination_id, waypoints)?;
+    for path in finder.paths(Some(limit)) {
+        println!("  {}", path.join(" -> "));
     }
-
-    Ok(solution_count)
+    Ok(())
 }
 
 fn main() {
-    let devices = build_devices(INPUT).expect("Failed to build devices from input");
-
-    let start_id = parse::str_to_device_id(START);
-    let destination_id = parse::str_to_device_id(DESTINATION);
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let input = match &args.input {
+        Some(path) => std::fs::read_to_string(path).unwrap_or_else(|err| {
+            eprintln!("Failed to read {}: {}", path.display(), err);
+            std::process::exit(1);
+        }),
+        None => INPUT.to_owned(),
+    };
+
+    let start_id = args.start.unwrap_or_else(|| parse::str_to_device_id(START));
+    let destination_id = args
+        .destination
+        .unwrap_or_else(|| parse::str_to_device_id(DESTINATION));
+
+    let devices = build_devices(&input, destination_id)
+        .and_then(|devices| paths::check_acyclic(&devices).map(|_| devices))
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+
+    if args.is_query() {
+        let names = |ids: &[models::DeviceId]| {
+            ids.iter()
+                .map(|&id| parse::device_id_to_str(id))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let solution_count = paths::count_paths_via(&devices, start_id, destination_id, &args.via)
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            });
+
+        println!(
+            "Paths from {} to {}{}: {}",
+            parse::device_id_to_str(start_id),
+            parse::device_id_to_str(destination_id),
+            if args.via.is_empty() {
+                String::new()
+            } else {
+                format!(" through {}", names(&args.via))
+            },
+            solution_count
+        );
+        if args.examples > 0 {
+            print_examples(&devices, start_id, destination_id, &args.via, args.examples)
+                .expect("Failed to find example paths");
+        }
+        return;
+    }
 
     #[cfg(feature = "profile")]
     let start = Instant::now();
     '_part1: {
-        let solution_count = count_number_of_solutions(&devices, start_id, destination_id, &[])
+        if !devices.contains_key(&start_id) {
+            println!("Part 1: Skipped, as there is no {} device", START);
+            break '_part1;
+        }
+
+        #[cfg(feature = "rayon")]
+        let count = paths::par_count_number_of_solutions;
+        #[cfg(not(feature = "rayon"))]
+        let count = paths::count_number_of_solutions;
+        let solution_count = count(&devices, start_id, destination_id, &[])
             .expect("Failed to count number of solutions for Part 1");
 
         println!("Part 1: Total number of distinct paths: {}", solution_count);
+        if args.examples > 0 {
+            print_examples(&devices, start_id, destination_id, &[], args.examples)
+                .expect("Failed to find example paths for Part 1");
+        }
     }
     #[cfg(feature = "profile")]
     {
@@ -242,10 +304,30 @@ fn main() {
     #[cfg(feature = "profile")]
     let start = Instant::now();
     '_part2: {
+        if !devices.contains_key(&parse::str_to_device_id(SERVER_RACK)) {
+            println!("Part 2: Skipped, as there is no {} device", SERVER_RACK);
+            break '_part2;
+        }
+
         let solution_count =
             part_2_solutions_count(&devices)
                 .expect("Failed to count number of solutions for Part 2");
         println!("Part 2: Total number of valid paths: {}", solution_count);
+        if args.examples > 0 {
+            print_examples(
+                &devices,
+                parse::str_to_device_id(SERVER_RACK),
+                destination_id,
+                &[parse::str_to_device_id(DAC), parse::str_to_device_id(FFT)],
+                args.examples,
+            )
+            .expect("Failed to find example paths for Part 2");
+        }
+
+        #[cfg(feature = "assert-truth")]
+        if args.input.is_none() {
+            assert_eq!(solution_count, 294053029111296);
+        }
     }
     #[cfg(feature = "profile")]
     {
@@ -259,7 +341,7 @@ mod test {
     use super::*;
     use std::collections::HashSet;
 
-    const PART1_INPUT: &'static str = "aaa: you hhh
+    const PART1_INPUT: &str = "aaa: you hhh
                                       you: bbb ccc
                                       bbb: ddd eee
                                       ccc: ddd eee fff
@@ -270,7 +352,7 @@ mod test {
                                       hhh: ccc fff iii
                                       iii: out";
 
-    const PART2_INPUT: &'static str = "svr: aaa bbb
+    const PART2_INPUT: &str = "svr: aaa bbb
                                        aaa: fft
                                        fft: ccc
                                        bbb: tty
@@ -284,9 +366,40 @@ mod test {
                                        ggg: out
                                        hhh: out";
 
+    #[test]
+    fn test_args() {
+        let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(parse(&[]).unwrap().examples, 0);
+        assert_eq!(parse(&["--examples", "3"]).unwrap().examples, 3);
+        assert_eq!(parse(&["--examples=5"]).unwrap().examples, 5);
+        assert!(parse(&["--examples"]).is_err());
+        assert!(parse(&["--examples", "x"]).is_err());
+        assert!(parse(&["--unknown", "1"]).is_err());
+
+        let args = parse(&["--input", "devices.dot", "--via", "dac", "--via=fft"]).unwrap();
+        assert_eq!(args.input, Some(PathBuf::from("devices.dot")));
+        assert_eq!(
+            args.via,
+            [
+                parse::str_to_device_id("dac"),
+                parse::str_to_device_id("fft")
+            ]
+        );
+        assert!(args.is_query());
+        assert!(!parse(&["--input", "devices.csv"]).unwrap().is_query());
+        assert_eq!(
+            parse(&["--start", "svr"]).unwrap().start,
+            Some(parse::str_to_device_id("svr"))
+        );
+        assert!(parse(&["--destination", "output"]).is_err());
+        assert!(parse(&["--via"]).is_err());
+    }
+
     #[test]
     fn test_parsing() {
-        let devices = build_devices(PART1_INPUT).expect("Failed to build devices from test input");
+        let devices = build_devices(PART1_INPUT, parse::str_to_device_id(DESTINATION))
+            .expect("Failed to build devices from test input");
         assert_eq!(devices.len(), 11);
         assert!(devices.contains_key(&parse::str_to_device_id("aaa")));
         assert!(devices.contains_key(&parse::str_to_device_id("you")));
@@ -301,14 +414,31 @@ mod test {
         assert!(devices.contains_key(&parse::str_to_device_id("out")));
     }
 
+    #[test]
+    fn test_undefined_devices() {
+        // Destinations that are never defined are expected.
+        assert!(build_devices("you: aaa\naaa: zzz", parse::str_to_device_id("zzz")).is_ok());
+        assert!(build_devices("you: aaa out\naaa: zzz", parse::str_to_device_id("zzz")).is_ok());
+
+        let err = build_devices(
+            "you: aaa bbb\naaa: out\nbbb: zzz yyy",
+            parse::str_to_device_id(DESTINATION),
+        )
+        .expect_err("Undefined devices should be reported");
+        assert_eq!(
+            err.to_string(),
+            "Devices are connected to but never defined: yyy, zzz"
+        );
+    }
+
     #[test]
     fn test_part1() {
-        let devices = build_devices(PART1_INPUT).expect("Failed to build devices from test input");
+        let devices = build_devices(PART1_INPUT, parse::str_to_device_id(DESTINATION))
+            .expect("Failed to build devices from test input");
 
         let start_id = parse::str_to_device_id(START);
         let destination_id = parse::str_to_device_id(DESTINATION);
         let start_device = devices.get(&start_id).expect("Start device not found");
-        let destination_id = destination_id;
 
         let get_node_by_key = |key: &models::DeviceId| devices.get(key);
         let mut dfs = simple_graph::Dfs::new(
@@ -316,7 +446,8 @@ mod test {
             devices
                 .get(&destination_id)
                 .expect("Destination device not found"),
-                devices.len()
+                devices.len(),
+                simple_graph::DfsOptions::default(),
         )
         .expect("Failed to create DFS instance");
 
@@ -327,7 +458,7 @@ mod test {
                     solution
                         .0
                         .into_iter()
-                        .map(|k| *k)
+                        .copied()
                         .collect::<Vec<models::DeviceId>>(),
                     solution.1,
                 ));
@@ -340,7 +471,8 @@ mod test {
 
     #[test]
     fn test_part2() {
-        let devices = build_devices(PART2_INPUT).expect("Failed to build devices from test input");
+        let devices = build_devices(PART2_INPUT, parse::str_to_device_id(DESTINATION))
+            .expect("Failed to build devices from test input");
         let solution_count =
             part_2_solutions_count(&devices)
                 .expect("Failed to count number of solutions for Part 2");
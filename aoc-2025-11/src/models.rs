@@ -14,10 +14,7 @@ pub trait DeviceIdToStr {
 #[cfg(feature = "trace")]
 impl DeviceIdToStr for DeviceId {
     fn to_str(&self) -> String {
-        let c1 = ((*self >> 16) & 0xFF) as u8 as char;
-        let c2 = ((*self >> 8) & 0xFF) as u8 as char;
-        let c3 = (*self & 0xFF) as u8 as char;
-        format!("{}{}{}", c1, c2, c3)
+        crate::parse::device_id_to_str(*self)
     }
 }
 
@@ -45,6 +42,14 @@ impl Device {
     pub fn id(&self) -> DeviceId {
         self.id
     }
+
+    pub fn connected_devices(&self) -> &[DeviceId] {
+        &self.connected_devices
+    }
+
+    pub fn connect(&mut self, neighbour_id: DeviceId) {
+        self.connected_devices.push(neighbour_id);
+    }
 }
 
 impl<'s> traits::IsNode<'s, DeviceId, u32> for Device {
@@ -79,10 +84,16 @@ impl<'s> traits::IsNodeWithIndexedNeighbours<'s, DeviceId, u32> for Device {
     }
 }
 
+/// The same devices with every connection reversed; each device's connections are in
+/// order of the IDs of the devices they came from.
 pub fn invert_device_map(map: &DeviceMap) -> DeviceMap {
     let mut inverted: DeviceMap = DeviceMap::default();
 
-    for (device_id, device) in map.iter() {
+    // The order of the map depends on everything in it; go by ID to be deterministic.
+    let mut sorted = map.iter().collect::<Vec<_>>();
+    sorted.sort_unstable_by_key(|(device_id, _)| **device_id);
+
+    for (device_id, device) in sorted {
         inverted
                 .entry(*device_id)
                 .or_insert_with(|| Device::new_empty(*device_id));
@@ -119,7 +130,9 @@ mod test_invert_device_map {
 
     #[test]
     fn test_invert_device_map() {
-        let devices = parse::text_to_devices(INPUT).expect("Failed to parse devices from input");
+        let devices = parse::text_to_devices(INPUT)
+            .expect("Failed to parse devices from input")
+            .devices;
         let inverted = invert_device_map(&devices);
 
         assert_eq!(inverted[&parse::str_to_device_id("svr")].connected_devices.len(), 0);
@@ -132,7 +145,7 @@ mod test_invert_device_map {
         assert_eq!(inverted[&parse::str_to_device_id("eee")].connected_devices, vec![parse::str_to_device_id("ccc")]);
         assert_eq!(inverted[&parse::str_to_device_id("hub")].connected_devices, vec![parse::str_to_device_id("ddd")]);
         assert_eq!(inverted[&parse::str_to_device_id("dac")].connected_devices, vec![parse::str_to_device_id("eee")]);
-        assert_eq!(inverted[&parse::str_to_device_id("fff")].connected_devices, vec![parse::str_to_device_id("hub"), parse::str_to_device_id("dac")]);
+        assert_eq!(inverted[&parse::str_to_device_id("fff")].connected_devices, vec![parse::str_to_device_id("dac"), parse::str_to_device_id("hub")]);
         assert_eq!(inverted[&parse::str_to_device_id("ggg")].connected_devices, vec![parse::str_to_device_id("fff")]);
         assert_eq!(inverted[&parse::str_to_device_id("hhh")].connected_devices, vec![parse::str_to_device_id("fff")]);
         assert_eq!(inverted[&parse::str_to_device_id("out")].connected_devices, vec![parse::str_to_device_id("ggg"), parse::str_to_device_id("hhh")]);
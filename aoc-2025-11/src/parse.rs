@@ -1,3 +1,5 @@
+//! Reading the device map, from the puzzle's own format or from a DOT or CSV edge list.
+
 use crate::models::*;
 
 pub fn str_to_device_id(s: &str) -> DeviceId {
@@ -5,30 +7,310 @@ pub fn str_to_device_id(s: &str) -> DeviceId {
     ((chars[0] as u32) << 16) | ((chars[1] as u32) << 8) | (chars[2] as u32)
 }
 
+/// The name of the device ``id``, the reverse of [`str_to_device_id`].
+pub fn device_id_to_str(id: DeviceId) -> String {
+    [id >> 16, id >> 8, id]
+        .into_iter()
+        .map(|byte| (byte & 0xFF) as u8 as char)
+        .collect()
+}
+
+/// Like [`str_to_device_id`], but returns an error rather than panicking or truncating
+/// if ``name`` is not three ASCII characters.
+pub fn parse_device_id(name: &str) -> anyhow::Result<DeviceId> {
+    let name = name.trim();
+    anyhow::ensure!(
+        name.len() == 3 && name.is_ascii(),
+        "Invalid device name: {:?}",
+        name
+    );
+    Ok(str_to_device_id(name))
+}
+
 /// Breaks down lines of ``ccc: ddd eee fff`` into [`Device`] objects
 pub fn line_to_device(line: &str) -> anyhow::Result<Device> {
-    line.split_once(": ")
-        .ok_or_else(|| anyhow::anyhow!("Invalid line format: {}", line))
-        .and_then(|(id_str, neighbours_str)| {
-            let id = str_to_device_id(id_str);
-            let neighbours = neighbours_str
-                .trim()
-                .split_whitespace()
-                .map(str_to_device_id)
-                .collect::<Vec<_>>();
-            Ok(Device::new(id, neighbours.into_iter()))
-        })
+    let (id_str, neighbours_str) = line
+        .split_once(": ")
+        .ok_or_else(|| anyhow::anyhow!("Invalid line format: {}", line))?;
+    let id = parse_device_id(id_str)?;
+    let neighbours = neighbours_str
+        .split_whitespace()
+        .map(parse_device_id)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(Device::new(id, neighbours.into_iter()))
+}
+
+/// The formats a device map can be read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    /// The puzzle's own, a line per device: ``ccc: ddd eee fff``.
+    Puzzle,
+    /// A Graphviz ``digraph``, with an edge statement per connection: ``ccc -> ddd``, or
+    /// ``ccc -> { ddd eee fff }``.
+    Dot,
+    /// A line per connection: ``ccc,ddd``; with an optional ``from,to`` header.
+    Csv,
 }
 
-pub fn text_to_devices(input: &str) -> anyhow::Result<DeviceMap> {
-    let devices = fxhash::FxHashMap::from_iter(
-        input
+impl InputFormat {
+    /// Guess the format of ``input`` from its first line.
+    pub fn detect(input: &str) -> Self {
+        let first_line = input
             .lines()
-            .map(|line| {
-                let device = line_to_device(line)?;
-                Ok((device.id(), device))
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("//"))
+            .unwrap_or_default();
+
+        if first_line.contains(": ") {
+            Self::Puzzle
+        } else if ["digraph", "strict", "graph"]
+            .iter()
+            .any(|keyword| first_line.starts_with(keyword))
+        {
+            Self::Dot
+        } else {
+            Self::Csv
+        }
+    }
+}
+
+/// A device map read from the input, with the devices it connects to but never defines.
+#[derive(Debug, Clone)]
+pub struct ParsedDevices {
+    /// Every device, including those in ``undefined``, which have no connections.
+    pub devices: DeviceMap,
+    /// The devices that are connected to, but have no line or statement of their own;
+    /// sorted by ID.
+    pub undefined: Vec<DeviceId>,
+}
+
+/// Devices and their connections, collected as the input is read.
+#[derive(Default)]
+struct DeviceMapBuilder {
+    devices: DeviceMap,
+    referenced: Vec<DeviceId>,
+}
+
+impl DeviceMapBuilder {
+    fn define(&mut self, id: DeviceId) -> &mut Device {
+        self.devices
+            .entry(id)
+            .or_insert_with(|| Device::new_empty(id))
+    }
+
+    fn connect(&mut self, from: DeviceId, to: DeviceId) {
+        self.define(from).connect(to);
+        self.referenced.push(to);
+    }
+
+    fn build(mut self) -> ParsedDevices {
+        let mut undefined = self
+            .referenced
+            .into_iter()
+            .filter(|id| !self.devices.contains_key(id))
+            .collect::<Vec<_>>();
+        undefined.sort_unstable();
+        undefined.dedup();
+
+        // Stand-ins, so that looking up a neighbour never fails.
+        for &id in &undefined {
+            self.devices.insert(id, Device::new_empty(id));
+        }
+
+        ParsedDevices {
+            devices: self.devices,
+            undefined,
+        }
+    }
+}
+
+/// Read a device map from ``input``, in any of the [`InputFormat`]s.
+pub fn text_to_devices(input: &str) -> anyhow::Result<ParsedDevices> {
+    let mut builder = DeviceMapBuilder::default();
+    match InputFormat::detect(input) {
+        InputFormat::Puzzle => puzzle_to_devices(input, &mut builder)?,
+        InputFormat::Dot => dot_to_devices(input, &mut builder)?,
+        InputFormat::Csv => csv_to_devices(input, &mut builder)?,
+    }
+    Ok(builder.build())
+}
+
+fn puzzle_to_devices(input: &str, builder: &mut DeviceMapBuilder) -> anyhow::Result<()> {
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        let device = line_to_device(line)?;
+        builder.define(device.id());
+        for &neighbour_id in device.connected_devices() {
+            builder.connect(device.id(), neighbour_id);
+        }
+    }
+    Ok(())
+}
+
+/// Read the statements of a ``digraph``: edge statements, possibly chained and with
+/// ``{ }`` sets of devices on either side, and node statements, which define a device
+/// without connecting it. Attributes and ``//`` comments are ignored.
+fn dot_to_devices(input: &str, builder: &mut DeviceMapBuilder) -> anyhow::Result<()> {
+    let input = input
+        .lines()
+        .map(|line| line.split_once("//").map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let body = input
+        .split_once('{')
+        .and_then(|(_, rest)| rest.rsplit_once('}'))
+        .map(|(body, _)| body)
+        .ok_or_else(|| anyhow::anyhow!("DOT input has no {{ }} body"))?;
+
+    for statement in without_attributes(body).split([';', '\n']).map(str::trim) {
+        if statement.is_empty()
+            || statement.contains('=')
+            || ["graph", "node", "edge"].contains(&statement)
+        {
+            continue;
+        }
+        anyhow::ensure!(
+            !statement.contains("--"),
+            "Undirected edges are not supported: {:?}",
+            statement
+        );
+
+        let groups = statement
+            .split("->")
+            .map(|group| {
+                group
+                    .trim()
+                    .trim_start_matches('{')
+                    .trim_end_matches('}')
+                    .split([' ', '\t', ','])
+                    .filter(|name| !name.is_empty())
+                    .map(|name| parse_device_id(name.trim_matches('"')))
+                    .collect::<anyhow::Result<Vec<_>>>()
             })
-            .collect::<anyhow::Result<DeviceMap>>()?,
-    );
-    Ok(devices)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for group in &groups {
+            anyhow::ensure!(!group.is_empty(), "Invalid DOT statement: {:?}", statement);
+        }
+        for &id in &groups[0] {
+            builder.define(id);
+        }
+        for pair in groups.windows(2) {
+            for &from in &pair[0] {
+                for &to in &pair[1] {
+                    builder.connect(from, to);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// ``text`` with every ``[ ]`` attribute list removed.
+fn without_attributes(text: &str) -> String {
+    let mut depth = 0usize;
+    text.chars()
+        .filter(|&c| {
+            match c {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                _ => return depth == 0,
+            }
+            false
+        })
+        .collect()
+}
+
+/// Read a line per connection, ``from,to``; a line with nothing after the comma defines
+/// a device without connecting it.
+fn csv_to_devices(input: &str, builder: &mut DeviceMapBuilder) -> anyhow::Result<()> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    for (index, (line_number, line)) in lines.enumerate() {
+        let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+        let [from, to] = fields[..] else {
+            anyhow::bail!("Invalid CSV line {}: {:?}", line_number + 1, line);
+        };
+        let is_header =
+            |(a, b): (&str, &str)| from.eq_ignore_ascii_case(a) && to.eq_ignore_ascii_case(b);
+        if index == 0
+            && [("from", "to"), ("source", "target")]
+                .into_iter()
+                .any(is_header)
+        {
+            continue;
+        }
+
+        let from = parse_device_id(from)?;
+        if to.is_empty() {
+            builder.define(from);
+        } else {
+            builder.connect(from, parse_device_id(to)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(name: &str) -> DeviceId {
+        str_to_device_id(name)
+    }
+
+    fn neighbours(parsed: &ParsedDevices, name: &str) -> Vec<DeviceId> {
+        parsed.devices[&id(name)].connected_devices().to_vec()
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(InputFormat::detect("aaa: bbb ccc"), InputFormat::Puzzle);
+        assert_eq!(InputFormat::detect("\ndigraph {\n}"), InputFormat::Dot);
+        assert_eq!(InputFormat::detect("from,to\naaa,bbb"), InputFormat::Csv);
+    }
+
+    #[test]
+    fn test_formats_agree() {
+        let puzzle = "you: bbb ccc\nbbb: out\nccc: bbb out";
+        let dot = "digraph devices {
+            // The server room
+            you -> { bbb ccc };
+            bbb -> out [color=red]
+            ccc -> \"bbb\", out;
+            node [shape=box];
+        }";
+        let csv = "from,to\nyou,bbb\nyou,ccc\nbbb,out\nccc,bbb\nccc,out";
+
+        for input in [puzzle, dot, csv] {
+            let parsed = text_to_devices(input).expect("Failed to parse devices");
+            assert_eq!(parsed.devices.len(), 4, "{}", input);
+            assert_eq!(neighbours(&parsed, "you"), [id("bbb"), id("ccc")]);
+            assert_eq!(neighbours(&parsed, "ccc"), [id("bbb"), id("out")]);
+            assert_eq!(parsed.undefined, [id("out")]);
+        }
+    }
+
+    #[test]
+    fn test_undefined() {
+        let parsed =
+            text_to_devices("you: aaa bbb\naaa: out\nbbb: zzz").expect("Failed to parse devices");
+        assert_eq!(parsed.undefined, [id("out"), id("zzz")]);
+        assert!(neighbours(&parsed, "zzz").is_empty());
+
+        // Node statements and lines without a target define a device.
+        let parsed = text_to_devices("digraph { you -> out; out }").expect("Failed to parse");
+        assert!(parsed.undefined.is_empty());
+        let parsed = text_to_devices("you,out\nout,").expect("Failed to parse");
+        assert!(parsed.undefined.is_empty());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(text_to_devices("you: toolong").is_err());
+        assert!(text_to_devices("digraph { you -- out }").is_err());
+        assert!(text_to_devices("you,out,aaa").is_err());
+        assert!(text_to_devices("digraph you -> out").is_err());
+    }
 }
@@ -0,0 +1,417 @@
+//! Counting the paths between devices, optionally through a set of waypoints.
+
+use crate::{
+    models::{DeviceId, DeviceMap, Distance},
+    parse::device_id_to_str,
+};
+use fxhash::{FxHashMap, FxHashSet};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use simple_graph::{SimpleGraphError, traits::IsNode};
+
+/// The number of paths between two devices.
+///
+/// The real input already has over ``10^14`` paths through the waypoints of part 2, a
+/// few more orders of magnitude from overflowing [`u64`]; so counts are [`u128`]
+/// throughout, and every sum and product is checked, so that an overflow is an error
+/// rather than a wrong answer.
+pub type PathCount = u128;
+
+/// Check that no device can be reached again from itself.
+///
+/// Counting relies on the device map being acyclic: on a cycle, the DFS would never
+/// finish the devices on it, or memoize their counts before they are complete. This
+/// sorts every device topologically first, and returns an error naming the devices of
+/// the first cycle found, in the order they are connected.
+pub fn check_acyclic(devices: &DeviceMap) -> anyhow::Result<()> {
+    // Sorted, so that the same cycle is reported however the map is ordered.
+    let mut starts = devices.values().collect::<Vec<_>>();
+    starts.sort_unstable_by_key(|device| device.id());
+
+    match simple_graph::topological_sort::<_, Distance, _>(starts, |key| devices.get(key)) {
+        Ok(_) => Ok(()),
+        Err(SimpleGraphError::CycleDetected { cycle }) => {
+            let names = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&id| device_id_to_str(id))
+                .collect::<Vec<_>>();
+            anyhow::bail!("Devices are connected in a cycle: {}", names.join(" -> "))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Count the number of paths from ``start_id`` to ``destination_id``, without passing
+/// through any of ``avoid``.
+///
+/// This is the same memoized DFS as [`simple_graph::dfs_count`], which counts in
+/// [`usize`]; here the number of paths from each device to the destination is a
+/// [`PathCount`], added with overflow checks.
+pub fn count_number_of_solutions(
+    devices: &DeviceMap,
+    start_id: DeviceId,
+    destination_id: DeviceId,
+    avoid: &[&DeviceId],
+) -> anyhow::Result<PathCount> {
+    let avoid = avoid.iter().copied().collect::<FxHashSet<_>>();
+    let get_device = |key: &DeviceId| devices.get(key).filter(|_| !avoid.contains(key));
+    let start = get_device(&start_id).ok_or_else(|| anyhow::anyhow!("Start node not found"))?;
+
+    let mut memo: FxHashMap<DeviceId, PathCount> = FxHashMap::default();
+    memo.insert(destination_id, 1);
+
+    // Each device on the stack with the neighbours it has yet to add, and its count so
+    // far.
+    let mut stack = vec![(start, start.neighbours(get_device), 0 as PathCount)];
+    while let Some((device, neighbours, count)) = stack.last_mut() {
+        if device.id() == destination_id {
+            stack.pop();
+            continue;
+        }
+
+        match neighbours.next() {
+            Some((neighbour, _)) => match memo.get(&neighbour.id()) {
+                Some(&neighbour_count) => {
+                    *count = count.checked_add(neighbour_count).ok_or_else(|| {
+                        anyhow::anyhow!("Path count overflows {} bits", PathCount::BITS)
+                    })?;
+                }
+                None => stack.push((neighbour, neighbour.neighbours(get_device), 0)),
+            },
+            None => {
+                let (device, _, count) = stack.pop().expect("Unreachable; stack is not empty");
+                memo.insert(device.id(), count);
+                if let Some((_, _, parent_count)) = stack.last_mut() {
+                    *parent_count = parent_count.checked_add(count).ok_or_else(|| {
+                        anyhow::anyhow!("Path count overflows {} bits", PathCount::BITS)
+                    })?;
+                }
+            }
+        }
+    }
+
+    Ok(memo.get(&start_id).copied().unwrap_or_default())
+}
+
+/// Same as [`count_number_of_solutions`], but counts the paths from each neighbour of
+/// the start concurrently, each with a memo of its own, and sums them.
+///
+/// The neighbours' subtrees usually share most of their devices, which every thread
+/// then counts again; so this is only faster when they are large and mostly apart.
+#[cfg(feature = "rayon")]
+pub fn par_count_number_of_solutions(
+    devices: &DeviceMap,
+    start_id: DeviceId,
+    destination_id: DeviceId,
+    avoid: &[&DeviceId],
+) -> anyhow::Result<PathCount> {
+    let start = devices
+        .get(&start_id)
+        .filter(|_| !avoid.contains(&&start_id))
+        .ok_or_else(|| anyhow::anyhow!("Start node not found"))?;
+    if start_id == destination_id {
+        return Ok(1);
+    }
+
+    start
+        .connected_devices()
+        .par_iter()
+        .filter(|&neighbour_id| {
+            devices.contains_key(neighbour_id) && !avoid.contains(&neighbour_id)
+        })
+        .map(|&neighbour_id| {
+            count_number_of_solutions(devices, neighbour_id, destination_id, avoid)
+        })
+        .try_reduce(
+            || 0,
+            |total, count| {
+                total
+                    .checked_add(count)
+                    .ok_or_else(|| anyhow::anyhow!("Path count overflows {} bits", PathCount::BITS))
+            },
+        )
+}
+
+/// Count the number of paths from ``start_id`` to ``destination_id`` that pass through
+/// every one of ``waypoints``, in any order.
+///
+/// A path through every waypoint visits them in some order, so it is a chain of segments
+/// from the start to the first waypoint, between consecutive waypoints, and from the last
+/// waypoint to the destination; the number of such paths is the product of the counts of
+/// its segments. Every order is tried, by a DP over which waypoints are still to visit,
+/// and the products summed. The device map has no cycles (see [`check_acyclic`]), so a
+/// path can only visit its waypoints in one order, and is never counted twice; and no
+/// segment can revisit a device of another.
+///
+/// Each segment is only counted once, however many orders it appears in.
+pub fn count_paths_via(
+    devices: &DeviceMap,
+    start_id: DeviceId,
+    destination_id: DeviceId,
+    waypoints: &[DeviceId],
+) -> anyhow::Result<PathCount> {
+    let mut waypoints = waypoints.to_vec();
+    waypoints.sort_unstable();
+    waypoints.dedup();
+    anyhow::ensure!(
+        waypoints.len() < u32::BITS as usize,
+        "Too many waypoints: {}",
+        waypoints.len()
+    );
+
+    let mut counter = ViaCounter {
+        devices,
+        destination_id,
+        waypoints: &waypoints,
+        segments: FxHashMap::default(),
+        remaining: FxHashMap::default(),
+    };
+    counter.count_from(start_id, (1 << waypoints.len()) - 1)
+}
+
+/// The state of [`count_paths_via`].
+struct ViaCounter<'a> {
+    devices: &'a DeviceMap,
+    destination_id: DeviceId,
+    waypoints: &'a [DeviceId],
+    /// The number of paths between each pair of devices counted so far.
+    segments: FxHashMap<(DeviceId, DeviceId), PathCount>,
+    /// The number of paths from a device through each set of waypoints still to visit,
+    /// as a bitmask of indices into ``waypoints``, to the destination.
+    remaining: FxHashMap<(DeviceId, u32), PathCount>,
+}
+
+impl ViaCounter<'_> {
+    /// The number of paths from ``from`` to ``to``.
+    fn segment(&mut self, from: DeviceId, to: DeviceId) -> anyhow::Result<PathCount> {
+        if let Some(&count) = self.segments.get(&(from, to)) {
+            return Ok(count);
+        }
+
+        let count = count_number_of_solutions(self.devices, from, to, &[])?;
+        self.segments.insert((from, to), count);
+        Ok(count)
+    }
+
+    /// The number of paths from ``from`` through every waypoint in ``mask`` to the
+    /// destination.
+    fn count_from(&mut self, from: DeviceId, mask: u32) -> anyhow::Result<PathCount> {
+        if mask == 0 {
+            return self.segment(from, self.destination_id);
+        }
+        if let Some(&count) = self.remaining.get(&(from, mask)) {
+            return Ok(count);
+        }
+
+        let mut total: PathCount = 0;
+        for (index, &waypoint) in self.waypoints.iter().enumerate() {
+            let bit = 1 << index;
+            if mask & bit == 0 {
+                continue;
+            }
+
+            let segment = self.segment(from, waypoint)?;
+            if segment == 0 {
+                continue;
+            }
+            let rest = self.count_from(waypoint, mask & !bit)?;
+            total = segment
+                .checked_mul(rest)
+                .and_then(|through| total.checked_add(through))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Path count through {:?} overflows {} bits",
+                        self.waypoints,
+                        PathCount::BITS
+                    )
+                })?;
+        }
+
+        self.remaining.insert((from, mask), total);
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::str_to_device_id;
+
+    // The example of part 2.
+    const INPUT: &str = "svr: aaa bbb
+                         aaa: fft
+                         fft: ccc
+                         bbb: tty
+                         tty: ccc
+                         ccc: ddd eee
+                         ddd: hub
+                         hub: fff
+                         eee: dac
+                         dac: fff
+                         fff: ggg hhh
+                         ggg: out
+                         hhh: out";
+
+    fn build_devices(input: &str) -> DeviceMap {
+        crate::parse::text_to_devices(input)
+            .expect("Failed to parse devices")
+            .devices
+    }
+
+    fn count(waypoints: &[&str]) -> PathCount {
+        let devices = build_devices(INPUT);
+        let waypoints = waypoints
+            .iter()
+            .map(|name| str_to_device_id(name))
+            .collect::<Vec<_>>();
+        count_paths_via(
+            &devices,
+            str_to_device_id("svr"),
+            str_to_device_id("out"),
+            &waypoints,
+        )
+        .expect("Failed to count paths")
+    }
+
+    /// A chain of ``diamonds`` diamonds from ``n00`` to ``out``, each doubling the number
+    /// of paths.
+    fn diamonds(diamonds: usize) -> DeviceMap {
+        let name = |prefix: char, index: usize| {
+            let letter = |digit: usize| (b'a' + digit as u8) as char;
+            format!("{}{}{}", prefix, letter(index / 26), letter(index % 26))
+        };
+        let input = (0..diamonds)
+            .flat_map(|index| {
+                let next = if index + 1 == diamonds {
+                    "out".to_owned()
+                } else {
+                    name('n', index + 1)
+                };
+                [
+                    format!(
+                        "{}: {} {}",
+                        name('n', index),
+                        name('a', index),
+                        name('b', index)
+                    ),
+                    format!("{}: {}", name('a', index), next),
+                    format!("{}: {}", name('b', index), next),
+                ]
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        build_devices(&input)
+    }
+
+    #[test]
+    fn test_count_beyond_u64() {
+        let devices = diamonds(70);
+        let count = count_number_of_solutions(
+            &devices,
+            str_to_device_id("naa"),
+            str_to_device_id("out"),
+            &[],
+        )
+        .expect("Failed to count paths");
+        assert_eq!(count, 1 << 70);
+    }
+
+    #[test]
+    fn test_count_overflow() {
+        let devices = diamonds(130);
+        let start = str_to_device_id("naa");
+        let out = str_to_device_id("out");
+
+        let err = count_number_of_solutions(&devices, start, out, &[])
+            .expect_err("2^130 paths should overflow");
+        assert!(err.to_string().contains("overflows 128 bits"), "{}", err);
+
+        // Half way is fine on its own, but not once squared through a waypoint.
+        let half = str_to_device_id("ncn");
+        assert_eq!(
+            count_number_of_solutions(&devices, start, half, &[]).expect("Failed to count"),
+            1 << 65
+        );
+        assert!(count_paths_via(&devices, start, out, &[half]).is_err());
+    }
+
+    #[test]
+    fn test_avoid() {
+        let devices = build_devices(INPUT);
+        let count = |avoid: &[&str]| {
+            let avoid = avoid
+                .iter()
+                .map(|name| str_to_device_id(name))
+                .collect::<Vec<_>>();
+            count_number_of_solutions(
+                &devices,
+                str_to_device_id("svr"),
+                str_to_device_id("out"),
+                &avoid.iter().collect::<Vec<_>>(),
+            )
+            .expect("Failed to count paths")
+        };
+
+        assert_eq!(count(&[]), 8);
+        assert_eq!(count(&["fft"]), 4);
+        assert_eq!(count(&["ggg", "dac"]), 2);
+        assert_eq!(count(&["ccc"]), 0);
+    }
+
+    #[test]
+    fn test_check_acyclic() {
+        let devices = build_devices(INPUT);
+        check_acyclic(&devices).expect("Example should be acyclic");
+
+        let devices = build_devices("svr: aaa\naaa: bbb out\nbbb: ccc\nccc: aaa");
+        let err = check_acyclic(&devices).expect_err("Cycle should be detected");
+        assert_eq!(
+            err.to_string(),
+            "Devices are connected in a cycle: aaa -> bbb -> ccc -> aaa"
+        );
+
+        let devices = build_devices("svr: svr out");
+        let err = check_acyclic(&devices).expect_err("Loop should be detected");
+        assert!(err.to_string().ends_with("svr -> svr"), "{}", err);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_count() {
+        let devices = build_devices(INPUT);
+        let svr = str_to_device_id("svr");
+        let out = str_to_device_id("out");
+        let fft = str_to_device_id("fft");
+        for avoid in [vec![], vec![&fft]] {
+            assert_eq!(
+                par_count_number_of_solutions(&devices, svr, out, &avoid).expect("Failed to count"),
+                count_number_of_solutions(&devices, svr, out, &avoid).expect("Failed to count")
+            );
+        }
+        assert_eq!(
+            par_count_number_of_solutions(&devices, out, out, &[]).expect("Failed to count"),
+            1
+        );
+
+        let devices = diamonds(70);
+        assert_eq!(
+            par_count_number_of_solutions(&devices, str_to_device_id("naa"), out, &[])
+                .expect("Failed to count"),
+            1 << 70
+        );
+    }
+
+    #[test]
+    fn test_count_paths_via() {
+        assert_eq!(count(&[]), 8);
+        assert_eq!(count(&["fft"]), 4);
+        assert_eq!(count(&["dac"]), 4);
+        assert_eq!(count(&["dac", "fft"]), 2);
+        assert_eq!(count(&["fft", "dac"]), 2);
+        assert_eq!(count(&["fft", "dac", "fft"]), 2);
+        assert_eq!(count(&["fft", "dac", "hub"]), 0);
+        assert_eq!(count(&["ccc", "fff", "hhh"]), 4);
+    }
+}
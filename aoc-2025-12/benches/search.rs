@@ -0,0 +1,86 @@
+//! Benchmarks of searching the examples for a fulfillment, with and without breaking the
+//! symmetries of the container and pruning dead cells.
+//!
+//! The time of a search is mostly the number of nodes it visits, which does not vary
+//! between runs; so it is printed before the benchmarks, and put in their names.
+//!
+//! Run with ``cargo bench -p aoc-2025-12 --bench search``. Each search of Example 3 takes
+//! seconds; add ``-- "example [12]"`` to leave out its measurements, though its nodes are
+//! still counted.
+
+use aoc_2025_12::{
+    _test, models,
+    solve::{self, SearchOptions},
+};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::time::Duration;
+
+const OPTIONS: [(&str, SearchOptions); 4] = [
+    (
+        "plain",
+        SearchOptions {
+            break_symmetry: false,
+            prune_dead_cells: false,
+        },
+    ),
+    (
+        "symmetry",
+        SearchOptions {
+            break_symmetry: true,
+            prune_dead_cells: false,
+        },
+    ),
+    (
+        "dead-cells",
+        SearchOptions {
+            break_symmetry: false,
+            prune_dead_cells: true,
+        },
+    ),
+    (
+        "both",
+        SearchOptions {
+            break_symmetry: true,
+            prune_dead_cells: true,
+        },
+    ),
+];
+
+fn bench_search(c: &mut Criterion) {
+    for requirement_id in [0, 1, 2] {
+        let (shapes, requirement) = _test::build_all_components(requirement_id);
+        let placements = models::build_placements_for_requirement(&shapes, &requirement);
+
+        let mut group = c.benchmark_group(format!("search/example {}", requirement_id + 1));
+        if requirement_id == 2 {
+            // Example 3 has no fulfillment, and exhausting the search takes seconds.
+            group
+                .sample_size(10)
+                .measurement_time(Duration::from_secs(300));
+        }
+
+        for (name, options) in OPTIONS {
+            let search = |placements: &[models::Placement<_>]| {
+                solve::search_fulfillment(&requirement, placements, options, |_, _| {})
+                    .expect("Failed to search for a fulfillment")
+            };
+
+            let nodes_visited = search(&placements).nodes_visited;
+            println!(
+                "Example #{} with {}: {} nodes",
+                requirement_id + 1,
+                name,
+                nodes_visited
+            );
+
+            group.bench_function(format!("{} ({} nodes)", name, nodes_visited), |b| {
+                b.iter(|| search(black_box(&placements)))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_search);
+criterion_main!(benches);
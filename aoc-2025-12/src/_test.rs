@@ -35,7 +35,7 @@ pub const EXAMPLE_INPUT: &str = "0:
                                  12x5: 1 0 1 0 2 2
                                  12x5: 1 0 1 0 3 2";
 
-pub fn build_all_components<'r>(
+pub fn build_all_components(
     requirement_id: usize,
 ) -> (Vec<models::Shape>, models::Requirement<SHAPE_COUNT>) {
     let (shape_builders, mut requirements) =
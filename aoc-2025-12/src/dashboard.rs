@@ -0,0 +1,124 @@
+//! Progress bars for solving requirements concurrently: one for every requirement in
+//! flight, and one for all of them.
+
+use std::sync::{Mutex, MutexGuard};
+
+use kdam::{Bar, BarExt, tqdm};
+
+use aoc_2025_12::{models, progress, solve};
+
+/// How many placements to build between updates of a bar.
+const PLACEMENTS_PER_UPDATE: usize = 4096;
+
+/// The progress bars of a pool of workers.
+///
+/// The first bar counts the requirements done; below it, each worker of the pool has a
+/// bar of its own, following the requirement it is on. A worker finds its bar by its
+/// index in the pool, so the methods must be called from within the pool.
+pub struct Dashboard {
+    overall: Mutex<Bar>,
+    workers: Vec<Mutex<Bar>>,
+}
+
+impl Dashboard {
+    /// Draw the bars for ``total`` requirements, solved by ``jobs`` workers.
+    pub fn new(total: usize, jobs: usize) -> Self {
+        kdam::term::init(false);
+
+        let overall = tqdm!(
+            total = total,
+            desc = "Requirements",
+            position = 0,
+            force_refresh = true
+        );
+        let workers = (0..jobs)
+            .map(|worker| {
+                Mutex::new(tqdm!(
+                    desc = format!("Worker {}", worker),
+                    position = worker as u16 + 1,
+                    unit = " placements"
+                ))
+            })
+            .collect();
+
+        Self {
+            overall: Mutex::new(overall),
+            workers,
+        }
+    }
+
+    /// The bar of the worker this is called from.
+    fn worker(&self) -> MutexGuard<'_, Bar> {
+        let index = rayon::current_thread_index().unwrap_or_default();
+        self.workers[index]
+            .lock()
+            .expect("A worker panicked while drawing its bar")
+    }
+
+    /// Like [`models::build_placements_for_requirement`], but counting the placements on
+    /// the worker's bar.
+    pub fn build_placements<'r, const S: usize>(
+        &self,
+        requirement_index: usize,
+        shapes: &[models::Shape],
+        requirement: &'r models::Requirement<S>,
+    ) -> Vec<models::Placement<'r, S>> {
+        let total = progress::calculate_total_placements(shapes, requirement);
+        {
+            let mut bar = self.worker();
+            bar.reset(Some(total));
+            bar.set_description(format!(
+                "#{} {}x{}",
+                requirement_index, requirement.container.width, requirement.container.height
+            ));
+            bar.set_postfix("placing");
+            bar.refresh().ok();
+        }
+
+        let mut placements = Vec::with_capacity(total);
+        for placement in models::iter_placements_for_requirement(shapes, requirement) {
+            placements.push(placement);
+            if placements.len() % PLACEMENTS_PER_UPDATE == 0 {
+                self.worker().update_to(placements.len()).ok();
+            }
+        }
+        self.worker().update_to(placements.len()).ok();
+
+        placements
+    }
+
+    /// Like [`solve::find_one_fulfillment`], but showing the progress of the search on
+    /// the worker's bar.
+    pub fn find_one_fulfillment<const S: usize>(
+        &self,
+        requirement: &models::Requirement<S>,
+        placements: &[models::Placement<S>],
+    ) -> anyhow::Result<Option<Vec<usize>>> {
+        {
+            let mut bar = self.worker();
+            bar.set_postfix("searching");
+            bar.refresh().ok();
+        }
+
+        solve::find_one_fulfillment_with_progress(requirement, placements, |nodes, depth| {
+            let mut bar = self.worker();
+            bar.set_postfix(format!("searching, {} nodes at depth {}", nodes, depth));
+            bar.refresh().ok();
+        })
+    }
+
+    /// Count a requirement as done, whether it was searched or skipped.
+    pub fn done(&self) {
+        self.overall
+            .lock()
+            .expect("A worker panicked while drawing the overall bar")
+            .update(1)
+            .ok();
+    }
+
+    /// Move the cursor below the bars, so that what is printed next does not overwrite
+    /// them.
+    pub fn finish(self) {
+        eprint!("{}", "\n".repeat(self.workers.len() + 1));
+    }
+}
@@ -0,0 +1,14 @@
+//! Fitting the presents under the Christmas trees, from Day 12 of 2025, as a library so
+//! that its search can be benchmarked outside of the puzzle.
+
+pub mod models;
+
+mod input;
+pub use input::INPUT;
+
+pub mod progress;
+pub mod solve;
+
+/// The examples of the puzzle, for the tests and the benchmarks.
+#[doc(hidden)]
+pub mod _test;
@@ -2,20 +2,141 @@
 #[global_allocator]
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
-pub mod models;
+#[cfg(feature = "compute")]
+use aoc_2025_12::solve;
+use aoc_2025_12::{INPUT, models};
 
 pub const SHAPE_COUNT: usize = 6;
 
-mod input;
-pub use input::INPUT;
+#[cfg(feature = "rayon")]
+mod dashboard;
+mod summary;
 
-mod progress;
-mod solve;
+#[derive(Debug, Default)]
+struct Args {
+    /// From ``--jobs <n>``, how many requirements to solve at once; ``0``, the default,
+    /// for one per core. Only with the ``rayon`` feature.
+    jobs: usize,
+}
 
-#[cfg(test)]
-mod _test;
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), value.to_owned()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))?;
+                    (arg, value)
+                }
+            };
+
+            match name.as_str() {
+                "--jobs" => parsed.jobs = value.parse()?,
+                _ => anyhow::bail!("Unknown argument {:?}", name),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Search ``requirement``, unless it ``possibly_fits`` not at all, returning its row of the
+/// summary and what to print about it.
+#[cfg(feature = "compute")]
+fn solve_requirement<const S: usize>(
+    requirement_index: usize,
+    requirement: models::Requirement<S>,
+    shapes: &[models::Shape],
+    possibly_fits: bool,
+    #[cfg(feature = "rayon")] dashboard: &dashboard::Dashboard,
+) -> (summary::RequirementSummary<S>, String) {
+    use crate::models::helpers;
+
+    if !possibly_fits {
+        let row = summary::RequirementSummary {
+            index: requirement_index,
+            requirement,
+            placements: 0,
+            outcome: summary::Outcome::Skipped,
+            elapsed: std::time::Duration::ZERO,
+        };
+        let report = format!(
+            "Skipping requirement #{} as it cannot possibly fit.\n",
+            requirement_index
+        );
+        return (row, report);
+    }
+
+    let start_of_requirement = std::time::Instant::now();
+
+    #[cfg(not(feature = "rayon"))]
+    let placements = models::build_placements_for_requirement(shapes, &requirement);
+    #[cfg(feature = "rayon")]
+    let placements = dashboard.build_placements(requirement_index, shapes, &requirement);
+
+    let mut report = format!(
+        "For requirement on container {}x{} with shape counts {:?}, found \x1b[36m{}\x1b[0m possible placements.\n",
+        requirement.container.width,
+        requirement.container.height,
+        requirement.shape_counts,
+        placements.len()
+    );
+
+    #[cfg(not(feature = "rayon"))]
+    let can_fulfill = solve::find_one_fulfillment(&requirement, &placements);
+    #[cfg(feature = "rayon")]
+    let can_fulfill = dashboard.find_one_fulfillment(&requirement, &placements);
+    let can_fulfill = can_fulfill.expect("Failed to determine if requirement can be fulfilled");
+
+    let row = summary::RequirementSummary {
+        index: requirement_index,
+        requirement,
+        placements: placements.len(),
+        outcome: summary::Outcome::Searched(can_fulfill.is_some()),
+        elapsed: start_of_requirement.elapsed(),
+    };
+
+    report += &format!(
+        "\x1b[1mCalculated:\x1b[0m Requirement #{} fulfillment result: \x1b[{}m{:?}\x1b[0m\n",
+        requirement_index,
+        if can_fulfill.is_some() { "32" } else { "31" },
+        can_fulfill
+    );
+    if let Some(solution) = can_fulfill {
+        solve::verify_fulfillment(&requirement, &placements, &solution)
+            .expect("Fulfillment found is not valid");
+        report += &format!(
+            "{}\n",
+            helpers::SolutionDisplay::new(shapes, &placements, solution)
+        );
+    }
+
+    #[cfg(feature = "cheat")]
+    {
+        report += &format!(
+            "\x1b[1mAnswer:    \x1b[0m Requirement #{} fulfillment result: \x1b[32mtrue\x1b[0m\n",
+            requirement_index
+        );
+    }
+
+    (row, report)
+}
 
 fn main() {
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    #[cfg(not(feature = "rayon"))]
+    if args.jobs > 1 {
+        eprintln!("--jobs needs the rayon feature; solving one requirement at a time.");
+    }
+
     let (shape_builders, requirements) =
         models::parse_input::<SHAPE_COUNT>(INPUT).expect("Failed to parse input");
 
@@ -61,55 +182,93 @@ fn main() {
             );
         }
 
-        for (requirement_index, requirement) in requirements.into_iter().enumerate() {
-            use crate::models::helpers;
-
-            #[cfg(feature = "cheat")]
-            {
-                if !requirements_that_can_be_fulfilled
-                    .contains(&requirement_index)
-                {
-                    println!(
-                        "Skipping requirement #{} as it cannot possibly fit.",
-                        requirement_index
-                    );
-                    continue;
-                }
-            }
+        #[cfg(feature = "cheat")]
+        let possibly_fits =
+            |requirement_index: usize| requirements_that_can_be_fulfilled.contains(&requirement_index);
+        #[cfg(not(feature = "cheat"))]
+        let possibly_fits = |_requirement_index: usize| true;
 
-            let placements = models::build_placements_for_requirement(&shapes, &requirement);
+        let requirements = requirements.into_iter().enumerate().collect::<Vec<_>>();
 
-            println!(
-                "For requirement on container {}x{} with shape counts {:?}, found \x1b[36m{}\x1b[0m possible placements.",
-                requirement.container.width,
-                requirement.container.height,
-                requirement.shape_counts,
-                placements.len()
-            );
-            let can_fulfill = solve::find_one_fulfillment(&requirement, &placements)
-                .expect("Failed to determine if requirement can be fulfilled");
+        #[cfg(not(feature = "rayon"))]
+        let summary = requirements
+            .into_iter()
+            .map(|(requirement_index, requirement)| {
+                let (row, report) = solve_requirement(
+                    requirement_index,
+                    requirement,
+                    &shapes,
+                    possibly_fits(requirement_index),
+                );
+                print!("{}", report);
+                row
+            })
+            .collect::<summary::Summary<SHAPE_COUNT>>();
+
+        #[cfg(feature = "rayon")]
+        let summary = {
+            use rayon::prelude::*;
 
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(args.jobs)
+                .build()
+                .expect("Failed to build the thread pool");
+            let dashboard = dashboard::Dashboard::new(requirements.len(), pool.current_num_threads());
+
+            let solved = pool.install(|| {
+                requirements
+                    .into_par_iter()
+                    .map(|(requirement_index, requirement)| {
+                        let solved = solve_requirement(
+                            requirement_index,
+                            requirement,
+                            &shapes,
+                            possibly_fits(requirement_index),
+                            &dashboard,
+                        );
+                        dashboard.done();
+                        solved
+                    })
+                    .collect::<Vec<_>>()
+            });
+            dashboard.finish();
+
+            // In the order of the requirements, whichever order they were solved in.
+            solved
+                .into_iter()
+                .map(|(row, report)| {
+                    print!("{}", report);
+                    row
+                })
+                .collect::<summary::Summary<SHAPE_COUNT>>()
+        };
+
+        println!("{}", summary);
+
+        #[cfg(feature = "cheat")]
+        if summary.fulfillable_count() != requirements_that_can_be_fulfilled.len() {
             println!(
-                "\x1b[1mCalculated:\x1b[0m Requirement #{} fulfillment result: \x1b[{}m{}\x1b[0m",
-                requirement_index,
-                if can_fulfill.is_some() { "32" } else { "31" },
-                format!("{:?}", can_fulfill)
+                "\x1b[93mThe cheat counted {} requirements that can possibly fit, but only {} can.\x1b[0m",
+                requirements_that_can_be_fulfilled.len(),
+                summary.fulfillable_count()
             );
-            if let Some(solution) = can_fulfill {
-                println!("{}", helpers::SolutionDisplay::new(&shapes, &placements, solution));
-            }
-                
-            #[cfg(feature = "cheat")]
-            {
-                let should_fulfill =
-                    requirements_that_can_be_fulfilled.contains(&requirement_index);
-                println!(
-                    "\x1b[1mAnswer:    \x1b[0m Requirement #{} fulfillment result: \x1b[{}m{}\x1b[0m",
-                    requirement_index,
-                    if should_fulfill { "32" } else { "31" },
-                    should_fulfill
-                );
-            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        let parse = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(parse(&[]).unwrap().jobs, 0);
+        assert_eq!(parse(&["--jobs", "4"]).unwrap().jobs, 4);
+        assert_eq!(parse(&["--jobs=2"]).unwrap().jobs, 2);
+        assert!(parse(&["--jobs"]).is_err());
+        assert!(parse(&["--jobs", "-1"]).is_err());
+        assert!(parse(&["--unknown", "1"]).is_err());
+    }
+}
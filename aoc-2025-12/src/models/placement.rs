@@ -155,6 +155,28 @@ impl<'r, const S: usize> std::fmt::Display for Placement<'r, S> {
     }
 }
 
+/// Every possible placement of shapes within the requirement's container, lazily.
+pub fn iter_placements_for_requirement<'r, const S: usize>(
+    shapes: &[Shape],
+    requirement: &'r Requirement<S>,
+) -> impl Iterator<Item = Placement<'r, S>> {
+    (0..shapes.len())
+        .cartesian_product(
+            requirement
+                .container
+                .iter_all_positions(shapes[0].width(), shapes[0].height()),
+        )
+        .flat_map(|(shape_index, (x, y))| {
+            PlacementBuilder::new(&shapes[shape_index], requirement, x, y)
+        })
+        .inspect(|placement| {
+            assert!(
+                !placement.state().is_empty(),
+                "Generated placement has empty state!"
+            );
+        })
+}
+
 /// Pre-compute all possible placements of shapes within the requirement's container.
 pub fn build_placements_for_requirement<'r, const S: usize>(
     shapes: &[Shape],
@@ -162,23 +184,9 @@ pub fn build_placements_for_requirement<'r, const S: usize>(
 ) -> Vec<Placement<'r, S>> {
     let total_placements_count = progress::calculate_total_placements(shapes, requirement);
     tqdm!(
-        (0..shapes.len())
-            .cartesian_product(
-                requirement
-                    .container
-                    .iter_all_positions(shapes[0].width(), shapes[0].height())
-            )
-            .flat_map(|(shape_index, (x, y))| {
-                PlacementBuilder::new(&shapes[shape_index], requirement, x, y)
-            }),
+        iter_placements_for_requirement(shapes, requirement),
         // Set the total count for the progress bar, part of the `tqdm!()` macro
         total = total_placements_count
     )
-    .inspect(|placement| {
-        assert!(
-            !placement.state().is_empty(),
-            "Generated placement has empty state!"
-        );
-    })
     .collect::<Vec<_>>()
 }
@@ -1,5 +1,9 @@
+//! The original solver: a DFS over the placements, checking for conflicts between
+//! [`StateStorage`] bitsets. Superseded by [`super::dlx`], and kept to test against.
+
 use itertools::Itertools;
 
+use super::is_conflict;
 use crate::models::{self, PlacementMask, ShapeCounts, StateStorage, helpers};
 
 #[cfg(feature = "cached-conflicts")]
@@ -11,11 +15,6 @@ use std::time::Instant;
 #[cfg(doc)]
 use crate::models::{Container, Requirement};
 
-/// Check if two state storages have any conflicting bits set.
-fn is_conflict(state1: &StateStorage, state2: &StateStorage) -> bool {
-    !state1.and_cloned(state2).is_empty()
-}
-
 /// A private struct to hold the current state during the step-wise search for a fulfillment path.
 struct StepStateStore<'r, const S: usize> {
     /// The requirement being fulfilled.
@@ -396,8 +395,8 @@ impl<'r, const S: usize> std::fmt::Display for StepStateStore<'r, S> {
     }
 }
 
-/// Using the given pre-computed placements of shapes, perform a Dancing Links to
-/// determine if the requirement can be fulfilled.
+/// Using the given pre-computed placements of shapes, perform a depth-first search over
+/// their bitsets to determine if the requirement can be fulfilled.
 ///
 /// This is performed by
 /// - DFS through the placements without duplicates, keeping track of the
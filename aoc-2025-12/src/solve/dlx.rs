@@ -0,0 +1,624 @@
+//! Knuth's Algorithm X, on Dancing Links.
+//!
+//! The matrix is kept as a toroidal doubly linked list of its ``1``s, in flat arrays
+//! indexed by node: node ``0`` is the root, nodes ``1..=columns`` are the column headers,
+//! and every node after them is a ``1`` of some row. Covering a column unlinks it and
+//! every row that has a ``1`` in it; uncovering relinks them in reverse order, which is
+//! all the backtracking needs to restore.
+//!
+//! Columns come in two kinds. Primary columns are linked into the header list, so the
+//! search has to cover each of them exactly once. Secondary columns are not, so they are
+//! only ever covered as a side effect of choosing a row, and at most once.
+//!
+//! Primary columns can also be ordered into chains, see [`DancingLinks::order_columns`],
+//! for interchangeable columns whose rows would otherwise be tried in every order.
+
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// The root node, from which the header list of the primary columns hangs.
+const ROOT: usize = 0;
+
+/// How often a search reports its progress.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many rows to try between checks of the time since the last report.
+const ROWS_PER_CLOCK_CHECK: usize = 1024;
+
+/// An exact cover matrix, ready to be searched.
+#[derive(Debug, Clone)]
+pub struct DancingLinks {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+
+    /// The column header of each node.
+    column: Vec<usize>,
+
+    /// The row of each node; meaningless for the root and the column headers.
+    row: Vec<usize>,
+
+    /// The number of nodes still linked into each column, by column header.
+    size: Vec<usize>,
+
+    /// Whether each column is covered, by column header.
+    covered: Vec<bool>,
+
+    /// The number of primary columns, whose headers come before the secondary ones.
+    primary: usize,
+
+    /// How many secondary columns a solution may leave uncovered, if limited.
+    slack: Option<usize>,
+
+    /// The rank of each row, if any columns are ordered.
+    ranks: Vec<usize>,
+
+    /// The column headers ordered right before and right after each column header.
+    before: Vec<Option<usize>>,
+    after: Vec<Option<usize>>,
+
+    /// The first nodes of the rows hidden for breaking the order of a chain, in the order
+    /// they were hidden.
+    hidden: Vec<usize>,
+
+    /// The number of rows tried so far, across every search.
+    nodes_visited: usize,
+}
+
+impl DancingLinks {
+    /// Build the matrix from ``rows``, each listing the columns it has a ``1`` in.
+    ///
+    /// Columns ``0..primary`` are primary, and columns ``primary..primary + secondary``
+    /// are secondary.
+    pub fn new<R>(primary: usize, secondary: usize, rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: IntoIterator<Item = usize>,
+    {
+        let columns = primary + secondary;
+        let headers = columns + 1;
+
+        // The root and the primary headers form a ring; secondary headers are on their own.
+        let ring = primary + 1;
+        let (left, right) = (0..headers)
+            .map(|node| {
+                if node < ring {
+                    ((node + primary) % ring, (node + 1) % ring)
+                } else {
+                    (node, node)
+                }
+            })
+            .unzip();
+
+        let mut links = Self {
+            left,
+            right,
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+            covered: vec![false; headers],
+            primary,
+            slack: None,
+            ranks: Vec::new(),
+            before: vec![None; headers],
+            after: vec![None; headers],
+            hidden: Vec::new(),
+            nodes_visited: 0,
+        };
+
+        for (row_index, row) in rows.into_iter().enumerate() {
+            let first = links.left.len();
+            for column in row {
+                assert!(
+                    column < columns,
+                    "Column {} out of bounds for {} columns",
+                    column,
+                    columns
+                );
+                links.append(first, column + 1, row_index);
+            }
+        }
+
+        links
+    }
+
+    /// Append a node to the bottom of the column ``header``, and to the end of the row
+    /// whose first node is ``first``.
+    fn append(&mut self, first: usize, header: usize, row_index: usize) {
+        let node = self.left.len();
+
+        let bottom = self.up[header];
+        self.up.push(bottom);
+        self.down.push(header);
+        self.down[bottom] = node;
+        self.up[header] = node;
+
+        if node == first {
+            self.left.push(node);
+            self.right.push(node);
+        } else {
+            let last = self.left[first];
+            self.left.push(last);
+            self.right.push(first);
+            self.right[last] = node;
+            self.left[first] = node;
+        }
+
+        self.column.push(header);
+        self.row.push(row_index);
+        self.size[header] += 1;
+    }
+
+    /// Require the rows chosen for each chain of primary columns in ``chains`` to rank in
+    /// increasing order, as given by ``ranks`` for every row; the row covering the second
+    /// column of a chain has to rank after the row covering the first, and so on.
+    ///
+    /// Rows are ranked by the one ordered column they cover; a row should cover at most
+    /// one. Choosing a row hides the rows either side of it in its chain that would break
+    /// the order, so columns whose rows are otherwise the same are not tried in every order.
+    pub fn order_columns(
+        &mut self,
+        chains: impl IntoIterator<Item = Range<usize>>,
+        ranks: Vec<usize>,
+    ) {
+        for chain in chains {
+            assert!(
+                chain.end <= self.primary,
+                "Columns {:?} are not all primary, so cannot be ordered",
+                chain
+            );
+            for header in chain.start + 1..chain.end {
+                self.after[header] = Some(header + 1);
+                self.before[header + 1] = Some(header);
+            }
+        }
+        self.ranks = ranks;
+    }
+
+    /// Allow a solution to leave at most ``slack`` secondary columns uncovered.
+    ///
+    /// A secondary column that is not covered and has no rows left can never be covered,
+    /// so a partial solution is abandoned as soon as more columns than that are left so.
+    pub fn limit_uncovered(&mut self, slack: usize) {
+        self.slack = Some(slack);
+    }
+
+    /// Whether more secondary columns can no longer be covered than the slack allows.
+    fn exceeds_slack(&self) -> bool {
+        self.slack.is_some_and(|slack| {
+            (self.primary + 1..self.size.len())
+                .filter(|&header| self.size[header] == 0 && !self.covered[header])
+                .count()
+                > slack
+        })
+    }
+
+    /// The number of rows tried so far, across every search of this matrix.
+    pub fn nodes_visited(&self) -> usize {
+        self.nodes_visited
+    }
+
+    /// Remove the column ``header`` from the header list, and every row that has a ``1``
+    /// in it from the other columns.
+    fn cover(&mut self, header: usize) {
+        self.covered[header] = true;
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+
+        let mut row_node = self.down[header];
+        while row_node != header {
+            let mut node = self.right[row_node];
+            while node != row_node {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row_node = self.down[row_node];
+        }
+    }
+
+    /// Undo [`Self::cover`] of the column ``header``; the columns have to be uncovered in
+    /// the reverse order they were covered in.
+    fn uncover(&mut self, header: usize) {
+        let mut row_node = self.up[header];
+        while row_node != header {
+            let mut node = self.left[row_node];
+            while node != row_node {
+                self.size[self.column[node]] += 1;
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                node = self.left[node];
+            }
+            row_node = self.up[row_node];
+        }
+
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+        self.covered[header] = false;
+    }
+
+    /// Unlink every node of the row of ``row_node`` from its column, as if the row was
+    /// never there.
+    fn hide_row(&mut self, row_node: usize) {
+        let mut node = row_node;
+        loop {
+            let (up, down) = (self.up[node], self.down[node]);
+            self.down[up] = down;
+            self.up[down] = up;
+            self.size[self.column[node]] -= 1;
+
+            node = self.right[node];
+            if node == row_node {
+                break;
+            }
+        }
+    }
+
+    /// Undo [`Self::hide_row`]; the rows have to be unhidden in the reverse order they
+    /// were hidden in.
+    fn unhide_row(&mut self, row_node: usize) {
+        let mut node = row_node;
+        loop {
+            node = self.left[node];
+            self.size[self.column[node]] += 1;
+            let (up, down) = (self.up[node], self.down[node]);
+            self.down[up] = node;
+            self.up[down] = node;
+
+            if node == row_node {
+                break;
+            }
+        }
+    }
+
+    /// Hide the rows left in the column ``header`` whose rank ``breaks_order``, unless the
+    /// column is covered already, in which case its row has been chosen.
+    fn hide_rows_where(&mut self, header: usize, breaks_order: impl Fn(usize) -> bool) {
+        if self.covered[header] {
+            return;
+        }
+
+        let mut row_node = self.down[header];
+        while row_node != header {
+            let next = self.down[row_node];
+            if breaks_order(self.ranks[self.row[row_node]]) {
+                self.hide_row(row_node);
+                self.hidden.push(row_node);
+            }
+            row_node = next;
+        }
+    }
+
+    /// Hide the rows that cannot be chosen along with the row of ``row_node`` without
+    /// breaking the order of a chain, see [`Self::order_columns`]; returns how many rows
+    /// were hidden before, to unhide back to with [`Self::unhide_rows`].
+    fn hide_out_of_order(&mut self, row_node: usize) -> usize {
+        let mark = self.hidden.len();
+        if self.ranks.is_empty() {
+            return mark;
+        }
+
+        let rank = self.ranks[self.row[row_node]];
+        let mut node = row_node;
+        loop {
+            let header = self.column[node];
+            if let Some(after) = self.after[header] {
+                self.hide_rows_where(after, |other| other <= rank);
+            }
+            if let Some(before) = self.before[header] {
+                self.hide_rows_where(before, |other| other >= rank);
+            }
+
+            node = self.right[node];
+            if node == row_node {
+                break;
+            }
+        }
+        mark
+    }
+
+    /// Unhide the rows hidden since ``mark``, as returned by [`Self::hide_out_of_order`].
+    fn unhide_rows(&mut self, mark: usize) {
+        while self.hidden.len() > mark {
+            let row_node = self.hidden.pop().expect("Unreachable; checked the length");
+            self.unhide_row(row_node);
+        }
+    }
+
+    /// The uncovered primary column with the fewest rows left, to branch on; ``None`` if
+    /// every primary column is covered.
+    fn choose_column(&self) -> Option<usize> {
+        let mut chosen: Option<usize> = None;
+        let mut header = self.right[ROOT];
+        while header != ROOT {
+            if chosen.is_none_or(|chosen| self.size[header] < self.size[chosen]) {
+                chosen = Some(header);
+                if self.size[header] == 0 {
+                    break;
+                }
+            }
+            header = self.right[header];
+        }
+        chosen
+    }
+
+    /// Find a set of rows that covers every primary column exactly once, and every
+    /// secondary column at most once; ``None`` if there is no such set.
+    ///
+    /// Partial solutions that ``pruner`` calls a dead end, or that leave more secondary
+    /// columns uncoverable than [`Self::limit_uncovered`] allows, are not searched any
+    /// further.
+    /// ``report`` is called with the number of nodes visited so far and the current depth
+    /// of the search, about once a second.
+    ///
+    /// The rows are in the order they were chosen. The matrix is left as it was found, so
+    /// it can be searched again.
+    pub fn find_one_with(
+        &mut self,
+        pruner: &mut impl Pruner,
+        mut report: impl FnMut(usize, usize),
+    ) -> Option<Vec<usize>> {
+        if self.exceeds_slack() || pruner.is_dead_end() {
+            return None;
+        }
+
+        let mut solution = Vec::new();
+        let mut last_report = Instant::now();
+        self.search(&mut solution, pruner, &mut |nodes_visited, depth| {
+            if nodes_visited % ROWS_PER_CLOCK_CHECK == 0 && last_report.elapsed() >= REPORT_INTERVAL
+            {
+                report(nodes_visited, depth);
+                last_report = Instant::now();
+            }
+        })
+        .then_some(solution)
+    }
+
+    /// Branch on the most constrained column, trying each of its rows in turn; returns
+    /// whether a solution was found, with its rows in ``solution``.
+    ///
+    /// ``tick`` is called with the number of nodes visited and the depth after each row
+    /// is tried.
+    fn search(
+        &mut self,
+        solution: &mut Vec<usize>,
+        pruner: &mut impl Pruner,
+        tick: &mut impl FnMut(usize, usize),
+    ) -> bool {
+        let Some(header) = self.choose_column() else {
+            return true;
+        };
+
+        self.cover(header);
+
+        let mut found = false;
+        let mut row_node = self.down[header];
+        while row_node != header && !found {
+            let row = self.row[row_node];
+            self.nodes_visited += 1;
+            solution.push(row);
+            tick(self.nodes_visited, solution.len());
+
+            let mut node = self.right[row_node];
+            while node != row_node {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+            let mark = self.hide_out_of_order(row_node);
+
+            pruner.choose(row);
+            if !self.exceeds_slack() && !pruner.is_dead_end() {
+                found = self.search(solution, pruner, tick);
+            }
+            pruner.unchoose(row);
+
+            self.unhide_rows(mark);
+            let mut node = self.left[row_node];
+            while node != row_node {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+
+            if !found {
+                solution.pop();
+            }
+            row_node = self.down[row_node];
+        }
+
+        self.uncover(header);
+        found
+    }
+}
+
+/// Knowledge of the problem that the exact cover matrix does not capture, to abandon
+/// partial solutions that cannot be completed.
+pub trait Pruner {
+    /// ``row`` has been added to the partial solution.
+    fn choose(&mut self, row: usize);
+
+    /// ``row``, the last one chosen, has been removed from the partial solution.
+    fn unchoose(&mut self, row: usize);
+
+    /// Whether the partial solution cannot be completed.
+    fn is_dead_end(&mut self) -> bool;
+}
+
+/// A [`Pruner`] that prunes nothing, leaving the search to Algorithm X alone.
+pub struct NoPruning;
+
+impl Pruner for NoPruning {
+    fn choose(&mut self, _row: usize) {}
+
+    fn unchoose(&mut self, _row: usize) {}
+
+    fn is_dead_end(&mut self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_one(links: &mut DancingLinks) -> Option<Vec<usize>> {
+        links.find_one_with(&mut NoPruning, |_, _| {})
+    }
+
+    /// Knuth's example from the paper: rows 0, 3 and 4 are the only exact cover.
+    fn knuth_example() -> DancingLinks {
+        DancingLinks::new(
+            7,
+            0,
+            [
+                vec![2, 4, 5],
+                vec![0, 3, 6],
+                vec![1, 2, 5],
+                vec![0, 3],
+                vec![1, 6],
+                vec![3, 4, 6],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_exact_cover() {
+        let mut links = knuth_example();
+        let mut solution = find_one(&mut links).expect("Expected an exact cover");
+        solution.sort_unstable();
+        assert_eq!(solution, vec![0, 3, 4]);
+
+        // The matrix is restored, so searching again finds the same.
+        let visited = links.nodes_visited();
+        let mut again = find_one(&mut links).expect("Expected an exact cover again");
+        again.sort_unstable();
+        assert_eq!(again, solution);
+        assert_eq!(links.nodes_visited(), visited * 2);
+    }
+
+    #[test]
+    fn test_no_cover() {
+        let mut links = DancingLinks::new(3, 0, [vec![0, 1], vec![1, 2]]);
+        assert_eq!(find_one(&mut links), None);
+
+        // A column that no row covers fails without trying anything.
+        let mut links = DancingLinks::new(2, 0, [vec![0]]);
+        assert_eq!(find_one(&mut links), None);
+        assert_eq!(links.nodes_visited(), 0);
+    }
+
+    #[test]
+    fn test_secondary_columns() {
+        // Rows 0 and 1 cover the primaries, but clash on secondary column 2.
+        let rows = [vec![0, 2], vec![1, 2], vec![1, 3]];
+        let mut links = DancingLinks::new(2, 2, rows);
+        let mut solution = find_one(&mut links).expect("Expected a cover");
+        solution.sort_unstable();
+        assert_eq!(solution, vec![0, 2]);
+
+        // Secondary columns need not be covered at all.
+        let mut links = DancingLinks::new(1, 1, [vec![0]]);
+        assert_eq!(find_one(&mut links), Some(vec![0]));
+
+        // With nothing to cover, the empty set is a solution.
+        let mut links = DancingLinks::new(0, 1, [vec![0]]);
+        assert_eq!(find_one(&mut links), Some(vec![]));
+    }
+
+    #[test]
+    fn test_ordered_columns() {
+        // Two interchangeable columns, either of which can take cell 2 or cell 3.
+        let rows = [vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3]];
+        let mut links = DancingLinks::new(2, 2, rows);
+        // Cell 3 ranks first, so column 0 has to take it.
+        links.order_columns(std::iter::once(0..2), vec![1, 0, 1, 0]);
+        let mut solution = find_one(&mut links).expect("Expected an ordered cover");
+        solution.sort_unstable();
+        assert_eq!(solution, vec![1, 2]);
+
+        // Three interchangeable columns cannot fit in two cells, which takes fewer rows
+        // to find out in order.
+        let rows = (0..3)
+            .flat_map(|column| [vec![column, 3], vec![column, 4]])
+            .collect::<Vec<_>>();
+        let mut plain = DancingLinks::new(3, 2, rows.clone());
+        assert_eq!(find_one(&mut plain), None);
+        let mut ordered = DancingLinks::new(3, 2, rows);
+        ordered.order_columns(std::iter::once(0..3), vec![0, 1, 0, 1, 0, 1]);
+        assert_eq!(find_one(&mut ordered), None);
+        assert!(ordered.nodes_visited() < plain.nodes_visited());
+    }
+
+    #[test]
+    fn test_limit_uncovered() {
+        // The only cover leaves secondary column 2 uncovered.
+        let rows = [vec![0, 1]];
+        let mut links = DancingLinks::new(1, 2, rows.clone());
+        links.limit_uncovered(1);
+        assert_eq!(find_one(&mut links), Some(vec![0]));
+
+        let mut links = DancingLinks::new(1, 2, rows);
+        links.limit_uncovered(0);
+        assert_eq!(find_one(&mut links), None);
+        assert_eq!(links.nodes_visited(), 0);
+
+        // Choosing row 2 leaves column 3 without rows, so row 3 is chosen instead.
+        let rows = [vec![0, 2], vec![0, 2, 3], vec![1], vec![1, 3]];
+        let mut links = DancingLinks::new(2, 2, rows.clone());
+        assert_eq!(find_one(&mut links), Some(vec![0, 2]));
+
+        let mut links = DancingLinks::new(2, 2, rows);
+        links.limit_uncovered(0);
+        assert_eq!(find_one(&mut links), Some(vec![0, 3]));
+    }
+
+    /// Calls any partial solution with ``row`` in it a dead end.
+    struct Without {
+        row: usize,
+        chosen: Vec<usize>,
+    }
+
+    impl Pruner for Without {
+        fn choose(&mut self, row: usize) {
+            self.chosen.push(row);
+        }
+
+        fn unchoose(&mut self, row: usize) {
+            assert_eq!(self.chosen.pop(), Some(row));
+        }
+
+        fn is_dead_end(&mut self) -> bool {
+            self.chosen.contains(&self.row)
+        }
+    }
+
+    #[test]
+    fn test_pruner() {
+        // Either row 0 or rows 1 and 2 cover everything.
+        let rows = [vec![0, 1], vec![0], vec![1]];
+        let mut links = DancingLinks::new(2, 0, rows);
+
+        let mut pruner = Without {
+            row: 0,
+            chosen: Vec::new(),
+        };
+        let mut solution = links
+            .find_one_with(&mut pruner, |_, _| {})
+            .expect("Expected a cover without row 0");
+        solution.sort_unstable();
+        assert_eq!(solution, vec![1, 2]);
+        assert!(pruner.chosen.is_empty());
+
+        let mut pruner = Without {
+            row: 1,
+            chosen: Vec::new(),
+        };
+        assert_eq!(links.find_one_with(&mut pruner, |_, _| {}), Some(vec![0]));
+    }
+}
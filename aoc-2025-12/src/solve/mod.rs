@@ -0,0 +1,400 @@
+//! Searching for a combination of placements that fulfills a requirement.
+//!
+//! The search is Algorithm X on Dancing Links, see [`dlx`]; the bitset DFS it replaced is
+//! kept in ``bitset``, to test one against the other with the ``bitset-solver`` feature.
+
+#[cfg(all(test, feature = "bitset-solver"))]
+mod bitset;
+pub mod dlx;
+mod prune;
+mod symmetry;
+
+use crate::models::{self, StateStorage};
+
+#[cfg(doc)]
+use crate::models::{Container, Requirement};
+
+/// Check if two state storages have any conflicting bits set.
+fn is_conflict(state1: &StateStorage, state2: &StateStorage) -> bool {
+    !state1.and_cloned(state2).is_empty()
+}
+
+/// The cells of the container that each of ``placements`` fills.
+///
+/// A [`StateStorage`] has [`Container::size`] bits for the cells of the container,
+/// followed by [`Requirement::total_shape_count`] bits for the instances of shapes.
+fn placement_cells<const S: usize>(
+    requirement: &models::Requirement<S>,
+    placements: &[models::Placement<S>],
+) -> anyhow::Result<Vec<Vec<usize>>> {
+    let grid_size = requirement.container.size();
+    let instance_count = requirement.total_shape_count();
+
+    placements
+        .iter()
+        .enumerate()
+        .map(|(index, placement)| {
+            let state = placement.state();
+            anyhow::ensure!(
+                state.len() == grid_size + instance_count,
+                "Placement #{} has {} bits of state, expected {}",
+                index,
+                state.len(),
+                grid_size + instance_count
+            );
+            Ok((0..grid_size)
+                .filter(|&cell| state.get(cell).unwrap_or(false))
+                .collect())
+        })
+        .collect()
+}
+
+/// The rank of each placement among all of them, by its cells in lexicographic order,
+/// which is the order [`symmetry::is_canonical`] compares placements in; placements with
+/// the same cells rank the same.
+fn rank_by_cells(cells: &[Vec<usize>]) -> Vec<usize> {
+    let mut order = (0..cells.len()).collect::<Vec<_>>();
+    order.sort_unstable_by_key(|&row| &cells[row]);
+
+    let mut ranks = vec![0; cells.len()];
+    for pair in order.windows(2) {
+        ranks[pair[1]] = ranks[pair[0]] + (cells[pair[0]] != cells[pair[1]]) as usize;
+    }
+    ranks
+}
+
+/// Which reductions of the search space to make, on top of Algorithm X.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Keep the first instance of the last shape required to the placements that come
+    /// first among their rotations and mirror images in the container, see ``symmetry``.
+    pub break_symmetry: bool,
+
+    /// Abandon partial fulfillments that cut off empty regions too small for any shape
+    /// still to place, see ``prune``, or that leave more cells out of reach of every
+    /// placement left than the container has to spare.
+    pub prune_dead_cells: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            break_symmetry: true,
+            prune_dead_cells: true,
+        }
+    }
+}
+
+/// The outcome of [`search_fulfillment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search {
+    /// The indices of the placements used, in the order they were chosen; ``None`` if the
+    /// requirement cannot be fulfilled.
+    pub fulfillment: Option<Vec<usize>>,
+
+    /// The number of placements tried.
+    pub nodes_visited: usize,
+}
+
+/// Using the given pre-computed placements of shapes, perform Algorithm X on Dancing
+/// Links to determine if the requirement can be fulfilled.
+///
+/// Each placement is a row of the exact cover matrix. Each instance of a shape is a
+/// primary column, which exactly one placement has to cover; each cell of the container
+/// is a secondary column, which at most one placement may cover. The search always
+/// branches on the instance with the fewest placements left.
+///
+/// The instances of a shape are interchangeable, so they are ordered into a chain: each
+/// instance has to be placed after the one before it, by the cells it fills in
+/// lexicographic order, and only one order of the same placements is ever tried.
+///
+/// Placements that ``options`` rule out are left as empty rows, which can never be
+/// chosen, so that the rows still line up with ``placements``. ``report`` is called
+/// with the number of nodes visited so far and the current depth of the search, about
+/// once a second.
+pub fn search_fulfillment<const S: usize>(
+    requirement: &models::Requirement<S>,
+    placements: &[models::Placement<S>],
+    options: SearchOptions,
+    report: impl FnMut(usize, usize),
+) -> anyhow::Result<Search> {
+    let grid_size = requirement.container.size();
+    let instance_count = requirement.total_shape_count();
+    let cells = placement_cells(requirement, placements)?;
+
+    // Ranking first among its instances, the first instance of a shape can be kept to
+    // canonical placements; with the fewest placements left, it is also the first placed.
+    // The last shape did better than the first on the examples.
+    let restricted = requirement
+        .shape_counts
+        .iter()
+        .rev()
+        .find(|&&count| count > 0)
+        .map(|count| instance_count - count);
+
+    let rows = placements
+        .iter()
+        .zip(&cells)
+        .map(|(placement, cells)| {
+            let instance = requirement
+                .shape_counts
+                .get_shape_instance_offset(placement.shape_index, placement.shape_count)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Shape #{} has no instance #{}",
+                        placement.shape_index,
+                        placement.shape_count
+                    )
+                })?;
+
+            if options.break_symmetry
+                && Some(instance) == restricted
+                && !symmetry::is_canonical(cells, &requirement.container)
+            {
+                return Ok(Vec::new());
+            }
+
+            Ok(std::iter::once(instance)
+                .chain(cells.iter().map(|cell| instance_count + cell))
+                .collect::<Vec<_>>())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut links = dlx::DancingLinks::new(instance_count, grid_size, rows);
+    let chains = requirement.shape_counts.iter().scan(0, |start, &count| {
+        *start += count;
+        Some(*start - count..*start)
+    });
+    links.order_columns(chains, rank_by_cells(&cells));
+
+    let fulfillment = if options.prune_dead_cells {
+        let mut areas = vec![0; S];
+        for (placement, cells) in placements.iter().zip(&cells) {
+            areas[placement.shape_index] = cells.len();
+        }
+        let required = requirement
+            .shape_counts
+            .iter()
+            .zip(&areas)
+            .map(|(count, area)| count * area)
+            .sum::<usize>();
+        links.limit_uncovered(grid_size.saturating_sub(required));
+
+        let mut pruner = prune::DeadCellPruner::new(requirement, placements, &cells);
+        links.find_one_with(&mut pruner, report)
+    } else {
+        links.find_one_with(&mut dlx::NoPruning, report)
+    };
+
+    Ok(Search {
+        fulfillment,
+        nodes_visited: links.nodes_visited(),
+    })
+}
+
+/// [`search_fulfillment`] with the default [`SearchOptions`], printing its progress with
+/// the ``progress`` feature.
+///
+/// Returns the indices of the placements used, in the order they were chosen.
+// With the ``rayon`` feature, requirements are searched through the dashboard instead.
+#[cfg_attr(feature = "rayon", allow(dead_code))]
+pub fn find_one_fulfillment<const S: usize>(
+    requirement: &models::Requirement<S>,
+    placements: &[models::Placement<S>],
+) -> anyhow::Result<Option<Vec<usize>>> {
+    #[cfg(feature = "trace")]
+    eprintln!(
+        "Starting fulfillment search with \x1b[36m{}\x1b[0m placements",
+        placements.len()
+    );
+
+    #[cfg(feature = "progress")]
+    let start_of_search = std::time::Instant::now();
+
+    let search = search_fulfillment(
+        requirement,
+        placements,
+        SearchOptions::default(),
+        |_nodes_visited, _depth| {
+            #[cfg(feature = "progress")]
+            eprintln!(
+                "Visited \x1b[36m{}\x1b[0m nodes, at depth \x1b[36m{}\x1b[0m",
+                _nodes_visited, _depth
+            );
+        },
+    )?;
+
+    #[cfg(feature = "progress")]
+    eprintln!(
+        "Search completed in \x1b[36m{:?}\x1b[0m, visiting \x1b[36m{}\x1b[0m nodes",
+        start_of_search.elapsed(),
+        search.nodes_visited
+    );
+
+    Ok(search.fulfillment)
+}
+
+/// Like [`find_one_fulfillment`], but quietly, calling ``report`` with the number of
+/// nodes visited and the current depth of the search about once a second instead.
+#[cfg(feature = "rayon")]
+pub fn find_one_fulfillment_with_progress<const S: usize>(
+    requirement: &models::Requirement<S>,
+    placements: &[models::Placement<S>],
+    report: impl FnMut(usize, usize),
+) -> anyhow::Result<Option<Vec<usize>>> {
+    Ok(search_fulfillment(requirement, placements, SearchOptions::default(), report)?.fulfillment)
+}
+
+/// Check that ``solution`` fulfills the requirement: that none of its placements overlap,
+/// and that every instance of every shape is placed exactly once.
+pub fn verify_fulfillment<const S: usize>(
+    requirement: &models::Requirement<S>,
+    placements: &[models::Placement<S>],
+    solution: &[usize],
+) -> anyhow::Result<()> {
+    let mut state = requirement.build_new_state_storage();
+    for &placement_id in solution {
+        let placement = placements.get(placement_id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Placement #{} out of bounds for {} placements",
+                placement_id,
+                placements.len()
+            )
+        })?;
+        anyhow::ensure!(
+            !is_conflict(&state, placement.state()),
+            "Placement #{} overlaps the placements before it",
+            placement_id
+        );
+        state |= placement.state();
+    }
+
+    anyhow::ensure!(
+        state.and_cloned(&requirement.build_instance_state_mask())
+            == requirement.build_instance_state_mask(),
+        "Not every instance of every shape is placed"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_solve {
+    use super::*;
+    use crate::_test;
+    use crate::models::helpers;
+
+    macro_rules! create_test {
+        ($(#[$meta:meta])* $name:ident($requirement:literal) = $expected:literal) => {
+            #[test]
+            $(#[$meta])*
+            fn $name() {
+                let (shapes, requirement) = _test::build_all_components($requirement);
+                let placements = models::build_placements_for_requirement(&shapes, &requirement);
+
+                let fulfillment_result = find_one_fulfillment(&requirement, &placements)
+                    .expect("Failed to find fulfillment");
+
+                if let Some(fulfillment_path) = fulfillment_result.as_ref() {
+                    println!(
+                        "{}",
+                        helpers::SolutionDisplay::new(
+                            &shapes,
+                            &placements,
+                            fulfillment_path.clone()
+                        )
+                    );
+                    verify_fulfillment(&requirement, &placements, fulfillment_path)
+                        .expect("Fulfillment path is not valid");
+                }
+
+                assert_eq!(fulfillment_result.is_some(), $expected);
+            }
+        };
+    }
+
+    create_test!(test_example_1(0) = true);
+    create_test!(test_example_2(1) = true);
+    // Exhausting the search takes about 3s in release, but about 40s without
+    // optimisations.
+    create_test!(test_example_3(2) = false);
+
+    #[test]
+    fn test_rank_by_cells() {
+        let cells = [vec![1, 2], vec![0, 5], vec![1, 2], vec![0]];
+        assert_eq!(rank_by_cells(&cells), vec![2, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_verify_fulfillment() {
+        let (shapes, requirement) = _test::build_all_components(0);
+        let placements = models::build_placements_for_requirement(&shapes, &requirement);
+        let solution = find_one_fulfillment(&requirement, &placements)
+            .expect("Failed to find fulfillment")
+            .expect("Expected a fulfillment path");
+
+        assert!(verify_fulfillment(&requirement, &placements, &solution).is_ok());
+        // One instance short.
+        assert!(verify_fulfillment(&requirement, &placements, &solution[1..]).is_err());
+        // The same placement twice overlaps itself.
+        assert!(verify_fulfillment(&requirement, &placements, &[solution[0]; 2]).is_err());
+        assert!(verify_fulfillment(&requirement, &placements, &[placements.len()]).is_err());
+    }
+
+    /// Every combination of options should find a valid fulfillment of the examples that
+    /// have one; how many nodes each visits depends on the order rows are tried in.
+    #[test]
+    fn test_search_options() {
+        for requirement_id in [0, 1] {
+            let (shapes, requirement) = _test::build_all_components(requirement_id);
+            let placements = models::build_placements_for_requirement(&shapes, &requirement);
+
+            let search = |break_symmetry, prune_dead_cells| {
+                let options = SearchOptions {
+                    break_symmetry,
+                    prune_dead_cells,
+                };
+                let search = search_fulfillment(&requirement, &placements, options, |_, _| {})
+                    .expect("Failed to search for a fulfillment");
+                let path = search
+                    .fulfillment
+                    .as_ref()
+                    .unwrap_or_else(|| panic!("Example #{} with {:?}", requirement_id, options));
+                verify_fulfillment(&requirement, &placements, path)
+                    .expect("Fulfillment path is not valid");
+            };
+
+            for (break_symmetry, prune_dead_cells) in
+                [(false, false), (true, false), (false, true), (true, true)]
+            {
+                search(break_symmetry, prune_dead_cells);
+            }
+        }
+    }
+
+    /// Both solvers should agree on whether each example can be fulfilled, and both
+    /// their paths should be valid; the paths themselves need not be the same.
+    #[cfg(feature = "bitset-solver")]
+    #[test]
+    fn test_against_bitset_solver() {
+        for requirement_id in [0, 1] {
+            let (shapes, requirement) = _test::build_all_components(requirement_id);
+            let placements = models::build_placements_for_requirement(&shapes, &requirement);
+
+            let dlx = find_one_fulfillment(&requirement, &placements)
+                .expect("Failed to find fulfillment with Dancing Links");
+            let bitset = bitset::find_one_fulfillment(&requirement, &placements)
+                .expect("Failed to find fulfillment with the bitset solver");
+
+            assert_eq!(
+                dlx.is_some(),
+                bitset.is_some(),
+                "Example #{}",
+                requirement_id
+            );
+            for path in dlx.iter().chain(bitset.iter()) {
+                verify_fulfillment(&requirement, &placements, path)
+                    .expect("Fulfillment path is not valid");
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Pruning partial fulfillments that leave too little room for the shapes still to place.
+//!
+//! An empty region of the container, cut off from the rest by filled cells and its edges,
+//! that is smaller than every shape still to place can never be filled; its cells are
+//! dead. Once the cells left alive are fewer than the cells the remaining shapes need,
+//! nothing placed afterwards can fulfill the requirement.
+
+use super::dlx::Pruner;
+use crate::models;
+
+/// A [`Pruner`] that abandons a partial fulfillment once its dead cells leave too little
+/// room for the shapes still to place.
+pub struct DeadCellPruner<'c> {
+    width: usize,
+    height: usize,
+
+    /// The cells of each placement, by row of the exact cover matrix.
+    cells: &'c [Vec<usize>],
+
+    /// The shape of each placement, by row of the exact cover matrix.
+    shapes: Vec<usize>,
+
+    /// The number of cells of each shape.
+    areas: Vec<usize>,
+
+    /// The number of instances of each shape still to place.
+    remaining: Vec<usize>,
+
+    /// Whether the cells of every placement are connected, without which a small region
+    /// could still be filled by part of a shape, and only the total area can be checked.
+    connected: bool,
+
+    filled: Vec<bool>,
+    empty_count: usize,
+
+    /// Scratch space for the flood fills, kept to save allocating on every node.
+    visited: Vec<bool>,
+    stack: Vec<usize>,
+}
+
+impl<'c> DeadCellPruner<'c> {
+    /// Prune the search of ``placements`` for ``requirement``, where ``cells`` lists the
+    /// cells of each placement.
+    pub fn new<const S: usize>(
+        requirement: &models::Requirement<S>,
+        placements: &[models::Placement<S>],
+        cells: &'c [Vec<usize>],
+    ) -> Self {
+        let container = &requirement.container;
+
+        let mut areas = vec![0; S];
+        for (placement, cells) in placements.iter().zip(cells) {
+            areas[placement.shape_index] = cells.len();
+        }
+
+        let mut pruner = Self {
+            width: container.width,
+            height: container.height,
+            cells,
+            shapes: placements
+                .iter()
+                .map(|placement| placement.shape_index)
+                .collect(),
+            areas,
+            remaining: requirement.shape_counts.iter().copied().collect(),
+            connected: true,
+            filled: vec![false; container.size()],
+            empty_count: container.size(),
+            visited: vec![false; container.size()],
+            stack: Vec::new(),
+        };
+        pruner.connected = cells.iter().all(|cells| pruner.is_connected(cells));
+        pruner
+    }
+
+    /// The 4-connected neighbours of ``cell`` in the container.
+    fn neighbours(&self, cell: usize) -> impl Iterator<Item = usize> + use<> {
+        let (x, y) = (cell % self.width, cell / self.width);
+        let width = self.width;
+        [
+            (x > 0).then(|| cell - 1),
+            (x + 1 < width).then(|| cell + 1),
+            (y > 0).then(|| cell - width),
+            (y + 1 < self.height).then(|| cell + width),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Whether ``cells`` form a single 4-connected region.
+    fn is_connected(&self, cells: &[usize]) -> bool {
+        let Some(&first) = cells.first() else {
+            return true;
+        };
+
+        let mut reached = vec![first];
+        let mut stack = vec![first];
+        while let Some(cell) = stack.pop() {
+            for neighbour in self.neighbours(cell) {
+                if cells.contains(&neighbour) && !reached.contains(&neighbour) {
+                    reached.push(neighbour);
+                    stack.push(neighbour);
+                }
+            }
+        }
+        reached.len() == cells.len()
+    }
+
+    /// The number of cells the shapes still to place need.
+    fn required_area(&self) -> usize {
+        self.remaining
+            .iter()
+            .zip(&self.areas)
+            .map(|(remaining, area)| remaining * area)
+            .sum()
+    }
+
+    /// The number of empty cells in regions at least ``min_area`` large.
+    fn live_cells(&mut self, min_area: usize) -> usize {
+        self.visited.copy_from_slice(&self.filled);
+
+        let mut live = 0;
+        for start in 0..self.visited.len() {
+            if self.visited[start] {
+                continue;
+            }
+
+            self.visited[start] = true;
+            self.stack.push(start);
+            let mut region = 0;
+            while let Some(cell) = self.stack.pop() {
+                region += 1;
+                for neighbour in self.neighbours(cell) {
+                    if !self.visited[neighbour] {
+                        self.visited[neighbour] = true;
+                        self.stack.push(neighbour);
+                    }
+                }
+            }
+
+            if region >= min_area {
+                live += region;
+            }
+        }
+        live
+    }
+}
+
+impl Pruner for DeadCellPruner<'_> {
+    fn choose(&mut self, row: usize) {
+        for &cell in &self.cells[row] {
+            self.filled[cell] = true;
+        }
+        self.empty_count -= self.cells[row].len();
+        self.remaining[self.shapes[row]] -= 1;
+    }
+
+    fn unchoose(&mut self, row: usize) {
+        for &cell in &self.cells[row] {
+            self.filled[cell] = false;
+        }
+        self.empty_count += self.cells[row].len();
+        self.remaining[self.shapes[row]] += 1;
+    }
+
+    fn is_dead_end(&mut self) -> bool {
+        let required = self.required_area();
+        if self.empty_count < required {
+            return true;
+        }
+        if !self.connected {
+            return false;
+        }
+
+        let Some(min_area) = self
+            .remaining
+            .iter()
+            .zip(&self.areas)
+            .filter(|&(&remaining, _)| remaining > 0)
+            .map(|(_, &area)| area)
+            .min()
+        else {
+            return false;
+        };
+        self.live_cells(min_area) < required
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::_test;
+    use crate::solve::placement_cells;
+
+    #[test]
+    fn test_dead_cells() {
+        // Six shapes of 7 cells each, needing 42 of the 60 cells of a 12x5 container.
+        let (shapes, requirement) = _test::build_all_components(1);
+        let placements = models::build_placements_for_requirement(&shapes, &requirement);
+        let cells = placement_cells(&requirement, &placements).expect("Failed to list the cells");
+        let mut pruner = DeadCellPruner::new(&requirement, &placements, &cells);
+
+        assert!(pruner.connected);
+        assert_eq!(pruner.required_area(), 42);
+        assert!(!pruner.is_dead_end());
+
+        let fill_column = |pruner: &mut DeadCellPruner, x: usize| {
+            for y in 0..pruner.height {
+                pruner.filled[y * pruner.width + x] = true;
+                pruner.empty_count -= 1;
+            }
+        };
+
+        // Walling off the first column leaves 5 dead cells, but 50 alive are enough.
+        fill_column(&mut pruner, 1);
+        assert_eq!(pruner.live_cells(7), 50);
+        assert!(!pruner.is_dead_end());
+
+        // Walling off the last column too leaves 50 empty cells, but only 40 alive.
+        fill_column(&mut pruner, 10);
+        assert_eq!(pruner.empty_count, 50);
+        assert_eq!(pruner.live_cells(7), 40);
+        assert!(pruner.is_dead_end());
+    }
+
+    #[test]
+    fn test_choose_and_unchoose() {
+        let (shapes, requirement) = _test::build_all_components(1);
+        let placements = models::build_placements_for_requirement(&shapes, &requirement);
+        let cells = placement_cells(&requirement, &placements).expect("Failed to list the cells");
+        let mut pruner = DeadCellPruner::new(&requirement, &placements, &cells);
+
+        pruner.choose(0);
+        assert_eq!(pruner.empty_count, 60 - cells[0].len());
+        assert_eq!(pruner.required_area(), 42 - cells[0].len());
+        assert!(cells[0].iter().all(|&cell| pruner.filled[cell]));
+
+        pruner.unchoose(0);
+        assert_eq!(pruner.empty_count, 60);
+        assert_eq!(pruner.required_area(), 42);
+        assert!(pruner.filled.iter().all(|&filled| !filled));
+    }
+}
@@ -0,0 +1,122 @@
+//! The symmetries of a container, to tell apart the placements that are only rotations or
+//! mirror images of one another.
+//!
+//! Every shape is built in all of its orientations, and placed at every position that
+//! fits, so the image of a fulfillment under a symmetry of the container is a fulfillment
+//! too. The instance that comes first in the order its shape's instances are placed in can
+//! therefore be kept to the placements that come first among their images, without losing
+//! any requirement that can be fulfilled.
+
+use crate::models::Container;
+
+/// A symmetry of a container, other than the identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Symmetry {
+    FlipHorizontal,
+    FlipVertical,
+    Rotate180,
+    // Only of a square container, from here on.
+    Transpose,
+    AntiTranspose,
+    RotateLeft,
+    RotateRight,
+}
+
+impl Symmetry {
+    const ALL: [Self; 7] = [
+        Self::FlipHorizontal,
+        Self::FlipVertical,
+        Self::Rotate180,
+        Self::Transpose,
+        Self::AntiTranspose,
+        Self::RotateLeft,
+        Self::RotateRight,
+    ];
+
+    /// Whether this is a symmetry of ``container``.
+    fn applies_to(&self, container: &Container) -> bool {
+        matches!(
+            self,
+            Self::FlipHorizontal | Self::FlipVertical | Self::Rotate180
+        ) || container.width == container.height
+    }
+
+    /// The image of the cell at ``index`` in ``container``.
+    fn apply(&self, index: usize, container: &Container) -> usize {
+        let (x, y) = (index % container.width, index / container.width);
+        let (mirrored_x, mirrored_y) = (container.width - 1 - x, container.height - 1 - y);
+        let (x, y) = match self {
+            Self::FlipHorizontal => (mirrored_x, y),
+            Self::FlipVertical => (x, mirrored_y),
+            Self::Rotate180 => (mirrored_x, mirrored_y),
+            Self::Transpose => (y, x),
+            Self::AntiTranspose => (mirrored_y, mirrored_x),
+            Self::RotateLeft => (y, mirrored_x),
+            Self::RotateRight => (mirrored_y, x),
+        };
+        y * container.width + x
+    }
+}
+
+/// Whether ``cells``, sorted, come first among their images under every symmetry of
+/// ``container``, in lexicographic order.
+pub fn is_canonical(cells: &[usize], container: &Container) -> bool {
+    let mut cells = cells.to_vec();
+    cells.sort_unstable();
+
+    Symmetry::ALL
+        .iter()
+        .filter(|symmetry| symmetry.applies_to(container))
+        .all(|symmetry| {
+            let mut image = cells
+                .iter()
+                .map(|&cell| symmetry.apply(cell, container))
+                .collect::<Vec<_>>();
+            image.sort_unstable();
+            cells <= image
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetries_are_permutations() {
+        for container in [Container::new(4, 4), Container::new(5, 3)] {
+            for symmetry in Symmetry::ALL
+                .iter()
+                .filter(|symmetry| symmetry.applies_to(&container))
+            {
+                let mut images = (0..container.size())
+                    .map(|cell| symmetry.apply(cell, &container))
+                    .collect::<Vec<_>>();
+                images.sort_unstable();
+                assert_eq!(images, (0..container.size()).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        // Of the corners of a square, only the top left one is canonical.
+        let square = Container::new(4, 4);
+        assert!(is_canonical(&[0], &square));
+        assert!(!is_canonical(&[3], &square));
+        assert!(!is_canonical(&[12], &square));
+        assert!(!is_canonical(&[15], &square));
+
+        // Neither is the cell below the top left corner, its transpose being to the right.
+        assert!(is_canonical(&[1], &square));
+        assert!(!is_canonical(&[4], &square));
+
+        // A rectangle cannot be transposed.
+        let rectangle = Container::new(5, 3);
+        assert!(is_canonical(&[5], &rectangle));
+        assert!(!is_canonical(&[10], &rectangle));
+        assert!(is_canonical(&[1, 5], &rectangle));
+
+        // A cell in the middle is its own image.
+        assert!(is_canonical(&[7], &rectangle));
+    }
+}
@@ -0,0 +1,179 @@
+//! A summary of every requirement, as a table, with the answer to the puzzle.
+
+use std::time::Duration;
+
+use crate::models::Requirement;
+
+/// What became of a requirement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Not searched, since the shapes cannot possibly fit in the container.
+    Skipped,
+    /// Searched, with whether a fulfillment was found.
+    Searched(bool),
+}
+
+impl Outcome {
+    pub fn is_fulfillable(&self) -> bool {
+        matches!(self, Self::Searched(true))
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Skipped => "skipped",
+            Self::Searched(true) => "fulfillable",
+            Self::Searched(false) => "impossible",
+        }
+    }
+}
+
+/// A row of the [`Summary`].
+#[derive(Debug, Clone, Copy)]
+pub struct RequirementSummary<const S: usize> {
+    pub index: usize,
+    pub requirement: Requirement<S>,
+    /// The number of placements built for the search; ``0`` if skipped.
+    pub placements: usize,
+    pub outcome: Outcome,
+    /// The time taken to build the placements and search them.
+    pub elapsed: Duration,
+}
+
+/// The outcomes of the requirements, in the order they were collected.
+#[derive(Debug, Clone, Default)]
+pub struct Summary<const S: usize> {
+    rows: Vec<RequirementSummary<S>>,
+}
+
+impl<const S: usize> Summary<S> {
+    /// The number of requirements that can be fulfilled, which is the answer to the puzzle.
+    pub fn fulfillable_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.outcome.is_fulfillable())
+            .count()
+    }
+
+    pub fn skipped_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.outcome == Outcome::Skipped)
+            .count()
+    }
+
+    pub fn total_elapsed(&self) -> Duration {
+        self.rows.iter().map(|row| row.elapsed).sum()
+    }
+}
+
+impl<const S: usize> FromIterator<RequirementSummary<S>> for Summary<S> {
+    fn from_iter<I: IntoIterator<Item = RequirementSummary<S>>>(rows: I) -> Self {
+        Self {
+            rows: rows.into_iter().collect(),
+        }
+    }
+}
+
+impl<const S: usize> std::fmt::Display for Summary<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let shapes = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.requirement
+                    .shape_counts
+                    .iter()
+                    .map(|count| count.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>();
+        let shapes_width = shapes
+            .iter()
+            .map(String::len)
+            .chain(["Shapes".len()])
+            .max()
+            .unwrap_or_default();
+
+        writeln!(
+            f,
+            "\x1b[1m{:>5} | {:>9} | {:<shapes_width$} | {:>10} | {:<11} | {:>10}\x1b[0m",
+            "#", "Container", "Shapes", "Placements", "Result", "Time"
+        )?;
+        for (row, shapes) in self.rows.iter().zip(shapes) {
+            let container = format!(
+                "{}x{}",
+                row.requirement.container.width, row.requirement.container.height
+            );
+            writeln!(
+                f,
+                "{:>5} | {:>9} | {:<shapes_width$} | {:>10} | {:<11} | {:>10.3?}",
+                row.index,
+                container,
+                shapes,
+                row.placements,
+                row.outcome.label(),
+                row.elapsed
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Fulfillable: \x1b[36m{}\x1b[0m of \x1b[36m{}\x1b[0m requirements (\x1b[36m{}\x1b[0m skipped), in \x1b[36m{:.3?}\x1b[0m",
+            self.fulfillable_count(),
+            self.rows.len(),
+            self.skipped_count(),
+            self.total_elapsed()
+        )?;
+        write!(
+            f,
+            "\x1b[1mAnswer:\x1b[0m \x1b[32m{}\x1b[0m",
+            self.fulfillable_count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Container, ShapeCounts};
+
+    fn row(index: usize, outcome: Outcome, millis: u64) -> RequirementSummary<3> {
+        RequirementSummary {
+            index,
+            requirement: Requirement::new(Container::new(12, 5), ShapeCounts::new([1, 0, 2])),
+            placements: if outcome == Outcome::Skipped { 0 } else { 1080 },
+            outcome,
+            elapsed: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = [
+            row(0, Outcome::Searched(true), 10),
+            row(1, Outcome::Skipped, 0),
+            row(2, Outcome::Searched(false), 25),
+            row(3, Outcome::Searched(true), 5),
+        ]
+        .into_iter()
+        .collect::<Summary<3>>();
+
+        assert_eq!(summary.fulfillable_count(), 2);
+        assert_eq!(summary.skipped_count(), 1);
+        assert_eq!(summary.total_elapsed(), Duration::from_millis(40));
+
+        let table = summary.to_string();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines[1],
+            "    0 |      12x5 | 1 0 2  |       1080 | fulfillable |   10.000ms"
+        );
+        assert_eq!(
+            lines[2],
+            "    1 |      12x5 | 1 0 2  |          0 | skipped     |    0.000ns"
+        );
+        assert!(lines[6].ends_with("\x1b[32m2\x1b[0m"));
+    }
+}
@@ -0,0 +1,276 @@
+//! Benchmarks of the searches in this crate against their [`petgraph`] equivalents.
+//!
+//! The same generated graphs are loaded into both: as a [`Vec`] of nodes implementing
+//! the traits of this crate, and as a [`petgraph::graph::DiGraph`] with the same node
+//! indices. This quantifies the cost of looking nodes up through ``get_node_by_key``
+//! instead of owning the graph structure.
+//!
+//! Run with ``cargo bench -p simple-graph --features dfs-count``.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use petgraph::{
+    algo,
+    graph::{DiGraph, NodeIndex},
+};
+use simple_graph::{
+    DfsOptions, dfs_count, dijkstra, strongly_connected_components,
+    traits::{IsNode, IsNodeWithIndexedNeighbours},
+};
+use std::hint::black_box;
+
+#[derive(Debug)]
+struct BenchNode {
+    id: u32,
+    neighbours: Vec<(u32, u32)>,
+}
+
+impl<'s> IsNode<'s, u32, u32> for BenchNode {
+    fn id(&self) -> &u32 {
+        &self.id
+    }
+
+    fn neighbours(
+        &'s self,
+        get_node_by_key: impl Fn(&u32) -> Option<&'s Self>,
+    ) -> impl Iterator<Item = (&'s Self, u32)> {
+        self.neighbours
+            .iter()
+            .filter_map(move |(key, distance)| get_node_by_key(key).map(|node| (node, *distance)))
+    }
+}
+
+impl<'s> IsNodeWithIndexedNeighbours<'s, u32, u32> for BenchNode {
+    fn get_neighbour(
+        &'s self,
+        index: usize,
+        get_node_by_key: impl Fn(&u32) -> Option<&'s Self>,
+    ) -> Option<(&'s Self, u32)> {
+        self.neighbours
+            .get(index)
+            .and_then(|(key, distance)| get_node_by_key(key).map(|node| (node, *distance)))
+    }
+}
+
+/// A generated graph, where the key of every node is its index.
+struct BenchGraph {
+    nodes: Vec<BenchNode>,
+}
+
+impl BenchGraph {
+    fn from_edges(len: usize, edges: impl IntoIterator<Item = (u32, u32, u32)>) -> Self {
+        let mut nodes = (0..len as u32)
+            .map(|id| BenchNode {
+                id,
+                neighbours: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        edges.into_iter().for_each(|(start, end, distance)| {
+            nodes[start as usize].neighbours.push((end, distance))
+        });
+
+        Self { nodes }
+    }
+
+    fn get(&self, key: &u32) -> Option<&BenchNode> {
+        self.nodes.get(*key as usize)
+    }
+
+    fn first(&self) -> &BenchNode {
+        &self.nodes[0]
+    }
+
+    fn last_id(&self) -> u32 {
+        self.nodes.len() as u32 - 1
+    }
+
+    fn to_petgraph(&self) -> DiGraph<(), u32> {
+        DiGraph::from_edges(self.nodes.iter().flat_map(|node| {
+            node.neighbours
+                .iter()
+                .map(|(end, distance)| (node.id, *end, *distance))
+        }))
+    }
+}
+
+/// A fixed-seed xorshift generator, so that every run benchmarks the same graphs.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u32) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as u32
+    }
+}
+
+/// A ``width`` by ``width`` grid, where every cell has an edge to the cell to its right
+/// and the cell below it.
+fn lattice(width: u32) -> BenchGraph {
+    let mut rng = Rng(0x5eed);
+    let edges = (0..width)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .flat_map(|(x, y)| {
+            let id = y * width + x;
+            [
+                (x + 1 < width).then_some(id + 1),
+                (y + 1 < width).then_some(id + width),
+            ]
+            .into_iter()
+            .flatten()
+            .map(move |end| (id, end))
+        })
+        .map(|(start, end)| (start, end, rng.below(9) + 1))
+        .collect::<Vec<_>>();
+
+    BenchGraph::from_edges((width * width) as usize, edges)
+}
+
+/// ``layers`` layers of ``width`` nodes, where every node has ``fanout`` edges to random
+/// nodes of the next layer, between a single source and a single sink.
+fn layered(layers: u32, width: u32, fanout: u32) -> BenchGraph {
+    let mut rng = Rng(0xfeed);
+    let sink = layers * width + 1;
+    let layer_node = |layer: u32, index: u32| layer * width + index + 1;
+    let mut edges = Vec::new();
+
+    for index in 0..width {
+        edges.push((0, layer_node(0, index), 1));
+        edges.push((layer_node(layers - 1, index), sink, 1));
+    }
+    for layer in 0..layers - 1 {
+        for index in 0..width {
+            for _ in 0..fanout {
+                let end = layer_node(layer + 1, rng.below(width));
+                edges.push((layer_node(layer, index), end, rng.below(9) + 1));
+            }
+        }
+    }
+
+    BenchGraph::from_edges(sink as usize + 1, edges)
+}
+
+/// ``len`` nodes in a ring, each with ``fanout`` more edges to random nodes.
+fn random(len: u32, fanout: u32) -> BenchGraph {
+    let mut rng = Rng(0xbeef);
+    let mut edges = Vec::new();
+
+    for id in 0..len {
+        edges.push((id, (id + 1) % len, rng.below(99) + 1));
+        for _ in 0..fanout {
+            edges.push((id, rng.below(len), rng.below(99) + 1));
+        }
+    }
+
+    BenchGraph::from_edges(len as usize, edges)
+}
+
+/// ``count`` rings of ``size`` nodes, where every node has ``fanout`` more edges to random
+/// nodes in the same or a later ring; each ring is a strongly connected component.
+fn clusters(count: u32, size: u32, fanout: u32) -> BenchGraph {
+    let mut rng = Rng(0xcafe);
+    let len = count * size;
+    let mut edges = Vec::new();
+
+    for id in 0..len {
+        let (cluster, index) = (id / size, id % size);
+        edges.push((id, cluster * size + (index + 1) % size, 1));
+        for _ in 0..fanout {
+            let end = cluster * size + rng.below(len - cluster * size);
+            edges.push((id, end, 1));
+        }
+    }
+
+    BenchGraph::from_edges(len as usize, edges)
+}
+
+fn bench_dfs_count(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dfs_count");
+
+    for (name, graph) in [
+        ("lattice/8", lattice(8)),
+        ("lattice/10", lattice(10)),
+        ("layered/6x8x3", layered(6, 8, 3)),
+    ] {
+        let pg = graph.to_petgraph();
+        let (start, destination) = (graph.first(), graph.last_id());
+
+        group.bench_function(BenchmarkId::new("simple-graph", name), |b| {
+            b.iter(|| {
+                dfs_count(
+                    black_box(start),
+                    &destination,
+                    graph.nodes.len(),
+                    DfsOptions::default(),
+                    |key| graph.get(key),
+                )
+            })
+        });
+        group.bench_function(BenchmarkId::new("petgraph", name), |b| {
+            b.iter(|| {
+                algo::all_simple_paths::<Vec<_>, _, std::hash::RandomState>(
+                    &pg,
+                    black_box(NodeIndex::new(0)),
+                    NodeIndex::new(destination as usize),
+                    0,
+                    None,
+                )
+                .count()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dijkstra");
+
+    for (name, graph) in [
+        ("lattice/100", lattice(100)),
+        ("random/10000x4", random(10_000, 4)),
+    ] {
+        let pg = graph.to_petgraph();
+        let (start, destination) = (graph.first(), graph.last_id());
+
+        group.bench_function(BenchmarkId::new("simple-graph", name), |b| {
+            b.iter(|| dijkstra(black_box(start), &destination, |key| graph.get(key)))
+        });
+        group.bench_function(BenchmarkId::new("petgraph", name), |b| {
+            b.iter(|| {
+                algo::astar(
+                    &pg,
+                    black_box(NodeIndex::new(0)),
+                    |node| node.index() == destination as usize,
+                    |edge| *edge.weight(),
+                    |_| 0,
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_scc(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scc");
+
+    for (name, graph) in [
+        ("random/10000x2", random(10_000, 2)),
+        ("clusters/100x100x2", clusters(100, 100, 2)),
+    ] {
+        let pg = graph.to_petgraph();
+
+        group.bench_function(BenchmarkId::new("simple-graph", name), |b| {
+            b.iter(|| strongly_connected_components(black_box(&graph.nodes), |key| graph.get(key)))
+        });
+        group.bench_function(BenchmarkId::new("petgraph", name), |b| {
+            b.iter(|| algo::tarjan_scc(black_box(&pg)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_dfs_count, bench_dijkstra, bench_scc);
+criterion_main!(benches);
@@ -1,3 +1,4 @@
+use crate::monitor::{InterruptReason, Progress};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,6 +16,35 @@ pub enum SimpleGraphError<K: std::fmt::Debug, D: std::fmt::Debug> {
         distance: D,
     },
 
+    #[error("node {destination:?} not reachable from {start:?} within a budget of {budget:?}")]
+    BudgetExceeded {
+        start: K,
+        destination: K,
+        budget: D,
+    },
+
+    #[error("node {destination:?} not reachable from {start:?} within {max_depth} steps")]
+    DepthExceeded {
+        start: K,
+        destination: K,
+        max_depth: usize,
+    },
+
+    #[error("graph is not acyclic; cycle detected through {cycle:?}")]
+    CycleDetected { cycle: Vec<K> },
+
+    #[error("graph is not a tree; node {node:?} can be reached in more than one way")]
+    NotATree { node: K },
+
+    #[error("node {key:?} is the neighbour of another node, but could not be found")]
+    MissingNode { key: K },
+
+    #[error("search interrupted ({reason:?}) after {} expansions", progress.expanded)]
+    Interrupted {
+        reason: InterruptReason,
+        progress: Progress<D>,
+    },
+
     #[error("this should be unreachable: {0}")]
     Unreachable(String),
 
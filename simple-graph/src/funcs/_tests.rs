@@ -63,10 +63,10 @@ impl<'s> IsNodeWithIndexedNeighbours<'s, u8, u32> for TestNode {
     ) -> Option<(&'s Self, u32)> {
         // VERY POOR IMPLEMENTATION FOR TESTING PURPOSES ONLY - NEVER USE `self.neighbours().nth()` IN `get_neighbour`,
         // always do it the other way around for performance reasons.
-        self.neighbours.get(index).map(|(neighbour_id, distance)| {
-            let neighbour_node =
-                get_node_by_key(neighbour_id).expect("Neighbour node not found in get_node_by_key");
-            (neighbour_node, *distance)
-        })
+        self.neighbours
+            .get(index)
+            .and_then(|(neighbour_id, distance)| {
+                get_node_by_key(neighbour_id).map(|neighbour_node| (neighbour_node, *distance))
+            })
     }
 }
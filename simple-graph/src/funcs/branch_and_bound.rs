@@ -0,0 +1,158 @@
+//! A best-first branch-and-bound driver for optimisation searches over arbitrary states.
+//!
+//! Unlike the rest of this crate, the states here are not nodes of a pre-existing graph:
+//! they are generated on the fly by a ``branch`` closure, which suits puzzles where the
+//! "graph" is the space of partial solutions, such as choosing how many times to press
+//! each button.
+
+use crate::wrapper::UnorderedItem;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// The best solution found by [`branch_and_bound`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchAndBoundSolution<S, C> {
+    /// The complete state with the lowest cost.
+    pub state: S,
+    /// The cost of ``state``.
+    pub cost: C,
+    /// The number of states branched from before the search finished.
+    pub expanded: usize,
+}
+
+/// Find the complete state of the lowest cost reachable from ``initial``.
+///
+/// - ``lower_bound`` returns a cost that no complete state reachable from the given
+///   state can beat. The tighter it is, the more of the search space is pruned; it must
+///   never overestimate, or the solution found may not be optimal.
+/// - ``branch`` returns the states directly reachable from the given state.
+/// - ``solution_cost`` returns the cost of the given state if it is complete, or [`None`]
+///   if it needs to be branched further. Complete states are never branched from.
+///
+/// Incomplete states are explored in order of their lower bounds, and any state whose
+/// lower bound is no better than the best complete state found so far (the incumbent)
+/// is pruned; the search finishes as soon as no state in the frontier can beat the
+/// incumbent.
+///
+/// Returns [`None`] if no complete state is reachable.
+pub fn branch_and_bound<S, C, I>(
+    initial: S,
+    lower_bound: impl Fn(&S) -> C,
+    mut branch: impl FnMut(&S) -> I,
+    solution_cost: impl Fn(&S) -> Option<C>,
+) -> Option<BranchAndBoundSolution<S, C>>
+where
+    C: Ord + Clone,
+    I: IntoIterator<Item = S>,
+{
+    let mut incumbent: Option<(S, C)> = None;
+    let mut expanded = 0;
+    let mut frontier: BinaryHeap<(Reverse<C>, UnorderedItem<S>)> = BinaryHeap::new();
+
+    let beats_incumbent = |incumbent: &Option<(S, C)>, cost: &C| {
+        incumbent
+            .as_ref()
+            .is_none_or(|(_, incumbent_cost)| cost < incumbent_cost)
+    };
+
+    match solution_cost(&initial) {
+        Some(cost) => incumbent = Some((initial, cost)),
+        None => frontier.push((Reverse(lower_bound(&initial)), UnorderedItem::new(initial))),
+    }
+
+    while let Some((Reverse(bound), UnorderedItem(state))) = frontier.pop() {
+        if !beats_incumbent(&incumbent, &bound) {
+            // Every other state in the frontier has a bound at least as high.
+            break;
+        }
+
+        expanded += 1;
+
+        for next_state in branch(&state) {
+            match solution_cost(&next_state) {
+                Some(cost) if beats_incumbent(&incumbent, &cost) => {
+                    incumbent = Some((next_state, cost));
+                }
+                Some(_) => {}
+                None => {
+                    let next_bound = lower_bound(&next_state);
+                    if beats_incumbent(&incumbent, &next_bound) {
+                        frontier.push((Reverse(next_bound), UnorderedItem::new(next_state)));
+                    }
+                }
+            }
+        }
+    }
+
+    incumbent.map(|(state, cost)| BranchAndBoundSolution {
+        state,
+        cost,
+        expanded,
+    })
+}
+
+#[cfg(test)]
+mod tests_branch_and_bound {
+    use super::*;
+
+    /// The remaining amount, the number of coins used so far, and the index of the
+    /// smallest coin allowed next, so that every combination is only generated once.
+    type ChangeState = (u32, u32, usize);
+
+    /// Make ``amount`` with the fewest coins, where coins are sorted in descending order.
+    fn make_change(coins: &[u32], amount: u32) -> Option<BranchAndBoundSolution<ChangeState, u32>> {
+        branch_and_bound(
+            (amount, 0, 0),
+            |&(remaining, used, _)| used + remaining.div_ceil(coins[0]),
+            |&(remaining, used, next)| {
+                (next..coins.len())
+                    .filter(move |&index| coins[index] <= remaining)
+                    .map(move |index| (remaining - coins[index], used + 1, index))
+            },
+            |&(remaining, used, _)| (remaining == 0).then_some(used),
+        )
+    }
+
+    #[test]
+    fn test_make_change() {
+        // Greedy would pick 9 + 1 + 1.
+        let solution = make_change(&[9, 6, 5, 1], 11).expect("No solution found");
+        assert_eq!(solution.cost, 2);
+        assert_eq!(solution.state, (0, 2, 2));
+
+        let solution = make_change(&[25, 10, 1], 30).expect("No solution found");
+        assert_eq!(solution.cost, 3);
+    }
+
+    #[test]
+    fn test_pruning() {
+        let informed = make_change(&[9, 6, 5, 1], 50).expect("No solution found");
+        assert_eq!(informed.cost, 6);
+
+        // Ignoring the remaining amount degrades into a uniform cost search, which expands
+        // more states.
+        let coins = [9, 6, 5, 1];
+        let uninformed = branch_and_bound(
+            (50u32, 0u32, 0usize),
+            |&(_, used, _)| used,
+            |&(remaining, used, next)| {
+                (next..coins.len())
+                    .filter(move |&index| coins[index] <= remaining)
+                    .map(move |index| (remaining - coins[index], used + 1, index))
+            },
+            |&(remaining, used, _)| (remaining == 0).then_some(used),
+        )
+        .expect("No solution found");
+
+        assert_eq!(uninformed.cost, 6);
+        assert!(informed.expanded < uninformed.expanded);
+    }
+
+    #[test]
+    fn test_no_solution() {
+        assert!(make_change(&[4, 2], 7).is_none());
+        assert_eq!(
+            make_change(&[4, 2], 0).map(|solution| (solution.cost, solution.expanded)),
+            Some((0, 0))
+        );
+    }
+}
@@ -1,12 +1,18 @@
-use crate::{SimpleGraphError, traits};
+use crate::{SimpleGraphError, monitor::SearchMonitor, traits};
+#[cfg(feature = "dedup")]
+use accumulative_hash::AccumulativeHash;
 #[cfg(feature = "dfs-count")]
 use fxhash::FxHashMap;
+#[cfg(feature = "dedup")]
+use fxhash::FxHashSet;
 use num_traits::Zero;
+#[cfg(feature = "dfs-count")]
+use std::cell::Cell;
 use std::{cmp::Ord, fmt::Debug, hash::Hash};
 
 pub struct NodeInProgress<'s, K, D, N> {
-    node: &'s N,
-    distance: D,
+    pub(crate) node: &'s N,
+    pub(crate) distance: D,
     next_index_to_visit: usize,
     _phantom: std::marker::PhantomData<K>,
 }
@@ -53,6 +59,61 @@ where
     }
 }
 
+/// Limits to keep a depth-first search from running unbounded on pathological graphs.
+///
+/// All limits default to [`None`], i.e. unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DfsOptions {
+    /// The maximum number of edges in a path; longer paths are pruned.
+    ///
+    /// Since the number of paths from a node now depends on how deep it was reached,
+    /// [`dfs_count`] disables its memoization when this is set.
+    pub max_depth: Option<usize>,
+    /// Stop searching after this many solutions have been found.
+    pub max_solutions: Option<usize>,
+    /// Stop searching after this many nodes have been expanded, i.e. pushed onto the
+    /// path being explored.
+    pub max_expansions: Option<usize>,
+    /// How [`Dfs`] recognises paths that it has effectively explored already.
+    ///
+    /// [`dfs_count`] counts every path regardless of this setting.
+    pub dedup: DedupPolicy,
+}
+
+/// How a [`Dfs`] recognises states that it has effectively explored already.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DedupPolicy {
+    /// Every path is explored, even if it visits the same nodes as another one.
+    #[default]
+    None,
+    /// A path reaching a node having visited the same *set* of nodes as an earlier path,
+    /// in whatever order, is pruned; only the first such path is ever yielded.
+    ///
+    /// The set is identified by its commutative hash from [`accumulative_hash`], so only
+    /// a [`u64`] is stored per state instead of the whole path. Nodes already on the path
+    /// are never revisited under this policy, which also makes the search safe on graphs
+    /// with cycles.
+    #[cfg(feature = "dedup")]
+    VisitedSet,
+}
+
+impl DfsOptions {
+    /// Whether a path with ``depth`` edges is too deep for these options.
+    fn exceeds_max_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_some_and(|max_depth| depth > max_depth)
+    }
+}
+
+/// A path found by [`Dfs`], along with its total distance.
+pub type DfsSolution<'s, K, D> = (Vec<&'s K>, D);
+
+/// The limit in [`DfsOptions`] that stopped a search early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfsLimit {
+    MaxSolutions,
+    MaxExpansions,
+}
+
 pub struct Dfs<'s, K, D, N>
 where
     K: Debug + Clone + Eq + Hash + 's,
@@ -61,7 +122,19 @@ where
 {
     start: &'s N,
     destination: &'s N,
+    budget: Option<D>,
+    options: DfsOptions,
+    solutions_found: usize,
+    best_distance: Option<D>,
+    expansions: usize,
+    limit_reached: Option<DfsLimit>,
     tracker: Vec<NodeInProgress<'s, K, D, N>>,
+    /// Commutative hash of the nodes in ``tracker``, for [`DedupPolicy::VisitedSet`].
+    #[cfg(feature = "dedup")]
+    visited_hash: AccumulativeHash<u64>,
+    /// Every `(node, visited set)` state reached so far, for [`DedupPolicy::VisitedSet`].
+    #[cfg(feature = "dedup")]
+    seen_states: FxHashSet<(&'s K, u64)>,
 }
 
 impl<'s, K, D, N> std::fmt::Debug for Dfs<'s, K, D, N>
@@ -99,6 +172,33 @@ where
         start: &'s N,
         destination: &'s N,
         size_hint: usize,
+        options: DfsOptions,
+    ) -> Result<Self, SimpleGraphError<K, D>> {
+        Self::new_with_optional_budget(start, destination, size_hint, None, options)
+    }
+
+    /// Create a new [`Dfs`] that only yields paths whose total distance does not
+    /// exceed ``budget``.
+    ///
+    /// Any partial path that goes over the budget is pruned immediately, so this also
+    /// bounds the search on graphs with cycles, as long as every cycle has a positive
+    /// total distance.
+    pub fn new_with_budget(
+        start: &'s N,
+        destination: &'s N,
+        size_hint: usize,
+        budget: D,
+        options: DfsOptions,
+    ) -> Result<Self, SimpleGraphError<K, D>> {
+        Self::new_with_optional_budget(start, destination, size_hint, Some(budget), options)
+    }
+
+    fn new_with_optional_budget(
+        start: &'s N,
+        destination: &'s N,
+        size_hint: usize,
+        budget: Option<D>,
+        options: DfsOptions,
     ) -> Result<Self, SimpleGraphError<K, D>> {
         if start.id() == destination.id() {
             return Err(SimpleGraphError::CannotPathToSelf {
@@ -112,16 +212,108 @@ where
         Ok(Self {
             start,
             destination,
+            budget,
+            options,
+            solutions_found: 0,
+            best_distance: None,
+            expansions: 0,
+            limit_reached: None,
             tracker,
+            #[cfg(feature = "dedup")]
+            visited_hash: AccumulativeHash::from([fxhash::hash64(start.id())]),
+            #[cfg(feature = "dedup")]
+            seen_states: FxHashSet::default(),
         })
     }
 
-    #[allow(unused_assignments)]
+    /// The limit in [`DfsOptions`] that stopped this search, if any.
+    ///
+    /// If this is [`None`] after [`Dfs::next_solution`] returns [`None`], the search has
+    /// been exhausted and all solutions were found.
+    pub fn limit_reached(&self) -> Option<DfsLimit> {
+        self.limit_reached
+    }
+
+    /// Whether ``next_node`` should be skipped under the [`DedupPolicy`] of this search.
+    ///
+    /// If not, the state it represents is recorded as seen.
+    fn is_duplicate(&mut self, _next_node: &NodeInProgress<'s, K, D, N>) -> bool {
+        match self.options.dedup {
+            DedupPolicy::None => false,
+            #[cfg(feature = "dedup")]
+            DedupPolicy::VisitedSet => {
+                self.tracker
+                    .iter()
+                    .any(|visited| visited.node.id() == _next_node.node.id())
+                    || !self.seen_states.insert((
+                        _next_node.node.id(),
+                        self.visited_hash
+                            .and_hash(fxhash::hash64(_next_node.node.id())),
+                    ))
+            }
+        }
+    }
+
+    /// Push ``_next_node`` onto the path being explored.
+    fn push(&mut self, next_node: NodeInProgress<'s, K, D, N>) {
+        #[cfg(feature = "dedup")]
+        if self.options.dedup == DedupPolicy::VisitedSet {
+            self.visited_hash.add(fxhash::hash64(next_node.node.id()));
+        }
+
+        self.tracker.push(next_node);
+    }
+
+    /// Backtrack by popping the last node off the path being explored.
+    fn pop(&mut self) {
+        let _popped = self.tracker.pop();
+
+        #[cfg(feature = "dedup")]
+        if let Some(popped) = _popped
+            && self.options.dedup == DedupPolicy::VisitedSet
+        {
+            self.visited_hash.remove(fxhash::hash64(popped.node.id()));
+        }
+    }
+
     pub fn next_solution(
         &mut self,
         get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
     ) -> Option<(Vec<&'s K>, D)> {
-        while self.tracker.len() > 0 {
+        self.next_solution_with_optional_monitor(None, get_node_by_key)
+            .expect("Unreachable; cannot be interrupted without a monitor")
+    }
+
+    /// Same as [`Dfs::next_solution`], but reports its progress to ``monitor`` as it goes,
+    /// and stops with [`SimpleGraphError::Interrupted`] if ``monitor`` says so.
+    ///
+    /// The best distance reported is that of the shortest solution found so far, across
+    /// all calls on this [`Dfs`]. An interrupted search can be resumed by calling this
+    /// again, with a monitor that allows it to continue.
+    pub fn next_solution_monitored(
+        &mut self,
+        monitor: &mut SearchMonitor<'_, D>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Option<DfsSolution<'s, K, D>>, SimpleGraphError<K, D>> {
+        self.next_solution_with_optional_monitor(Some(monitor), get_node_by_key)
+    }
+
+    #[allow(unused_assignments)]
+    fn next_solution_with_optional_monitor(
+        &mut self,
+        mut monitor: Option<&mut SearchMonitor<'_, D>>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Option<DfsSolution<'s, K, D>>, SimpleGraphError<K, D>> {
+        if self
+            .options
+            .max_solutions
+            .is_some_and(|max_solutions| self.solutions_found >= max_solutions)
+        {
+            self.limit_reached = Some(DfsLimit::MaxSolutions);
+            return Ok(None);
+        }
+
+        while !self.tracker.is_empty() {
             let opt_next_node = {
                 self.tracker
                     .last_mut()
@@ -130,6 +322,35 @@ where
             };
 
             match opt_next_node {
+                Some(next_node)
+                    if self
+                        .budget
+                        .as_ref()
+                        .is_some_and(|budget| next_node.distance > *budget) =>
+                {
+                    #[cfg(feature = "trace")]
+                    eprintln!(
+                        "Pruning node {:?} with distance {:?} over budget {:?}",
+                        next_node.node.id(),
+                        next_node.distance,
+                        self.budget
+                    );
+                }
+                Some(_next_node) if self.options.exceeds_max_depth(self.tracker.len()) => {
+                    #[cfg(feature = "trace")]
+                    eprintln!(
+                        "Pruning node {:?} beyond max depth {:?}",
+                        _next_node.node.id(),
+                        self.options.max_depth
+                    );
+                }
+                Some(_next_node) if self.is_duplicate(&_next_node) => {
+                    #[cfg(feature = "trace")]
+                    eprintln!(
+                        "Pruning node {:?} reached with an already seen set of nodes",
+                        _next_node.node.id()
+                    );
+                }
                 Some(next_node) => {
                     if next_node.node.id() == self.destination.id() {
                         let path_to_node = self
@@ -147,7 +368,23 @@ where
                             path_to_node
                         );
 
-                        return Some((path_to_node, next_node.distance));
+                        self.solutions_found += 1;
+                        if self
+                            .best_distance
+                            .as_ref()
+                            .is_none_or(|best_distance| next_node.distance < *best_distance)
+                        {
+                            self.best_distance = Some(next_node.distance.clone());
+                        }
+                        return Ok(Some((path_to_node, next_node.distance)));
+                    } else if self
+                        .options
+                        .max_expansions
+                        .is_some_and(|max_expansions| self.expansions >= max_expansions)
+                    {
+                        self.limit_reached = Some(DfsLimit::MaxExpansions);
+                        self.tracker.clear();
+                        return Ok(None);
                     } else {
                         #[cfg(feature = "trace")]
                         {
@@ -164,25 +401,40 @@ where
                                 path_to_node,
                             );
                         }
-                        self.tracker.push(next_node);
+                        self.expansions += 1;
+                        self.push(next_node);
+                        if let Some(monitor) = monitor.as_deref_mut() {
+                            // Pushed first, so that an interrupted search can be resumed.
+                            monitor.record_expansion(
+                                self.tracker.len(),
+                                self.best_distance.as_ref(),
+                                self.solutions_found,
+                            )?;
+                        }
                     }
                 }
                 None => {
                     // Backtrack
-                    self.tracker.pop();
+                    self.pop();
                 }
             }
         }
 
-        None
+        Ok(None)
     }
 }
 
+/// Count the number of distinct paths from ``start`` to ``destination_id``, memoizing the
+/// number of paths from each visited node to the destination.
+///
+/// If any of the limits in ``options`` stops the search early, the returned count is a
+/// lower bound of the true count.
 #[cfg(feature = "dfs-count")]
 pub fn dfs_count<'s, K, D, N>(
     start: &'s N,
     destination_id: &'s K,
     size_hint: usize,
+    options: DfsOptions,
     get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
 ) -> usize
 where
@@ -190,13 +442,125 @@ where
     D: Zero + Ord + Clone + Debug,
     N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
 {
+    dfs_count_with_optional_monitor(
+        start,
+        destination_id,
+        size_hint,
+        options,
+        None,
+        None,
+        get_node_by_key,
+    )
+    .expect("Unreachable; cannot be interrupted without a monitor")
+}
+
+/// Same as [`dfs_count`], but returns [`SimpleGraphError::MissingNode`] as soon as
+/// ``get_node_by_key`` fails to find the neighbour of a node.
+///
+/// [`dfs_count`] treats such a dangling edge as if it did not exist, which can hide
+/// mistakes in building the graph.
+#[cfg(feature = "dfs-count")]
+pub fn try_dfs_count<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &'s K,
+    size_hint: usize,
+    options: DfsOptions,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<usize, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    let missing_node = Cell::new(None);
+    let get_node_by_key = |key: &K| {
+        let node = get_node_by_key(key);
+        if node.is_none() {
+            missing_node.set(Some(key.clone()));
+        }
+        node
+    };
+
+    dfs_count_with_optional_monitor(
+        start,
+        destination_id,
+        size_hint,
+        options,
+        None,
+        Some(&missing_node),
+        get_node_by_key,
+    )
+}
+
+/// Same as [`dfs_count`], but reports its progress to ``monitor`` as it goes, and stops
+/// with [`SimpleGraphError::Interrupted`] if ``monitor`` says so.
+///
+/// The number of solutions reported includes those counted through memoization; when
+/// interrupted, it is a lower bound of the true count.
+#[cfg(feature = "dfs-count")]
+pub fn dfs_count_monitored<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &'s K,
+    size_hint: usize,
+    options: DfsOptions,
+    monitor: &mut SearchMonitor<'_, D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<usize, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    dfs_count_with_optional_monitor(
+        start,
+        destination_id,
+        size_hint,
+        options,
+        Some(monitor),
+        None,
+        get_node_by_key,
+    )
+}
+
+/// The shared implementation of the ``dfs_count`` variants.
+///
+/// If ``missing_node`` is given, ``get_node_by_key`` is expected to record any key it
+/// failed to find in it, and the search stops with [`SimpleGraphError::MissingNode`].
+#[cfg(feature = "dfs-count")]
+fn dfs_count_with_optional_monitor<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &'s K,
+    size_hint: usize,
+    options: DfsOptions,
+    mut monitor: Option<&mut SearchMonitor<'_, D>>,
+    missing_node: Option<&Cell<Option<K>>>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<usize, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    // The memoized counts are only valid if the remaining depth does not matter.
+    let memoize = options.max_depth.is_none();
     let mut count = 0;
+    let mut expansions = 0;
     let mut memoized_counts_by_node: fxhash::FxHashMap<&'s K, usize> =
         FxHashMap::with_capacity_and_hasher(size_hint, Default::default());
     let mut tracker: Vec<NodeInProgress<'s, K, D, N>> = Vec::with_capacity(size_hint);
     tracker.push(NodeInProgress::new(start, D::zero()));
 
-    while tracker.len() > 0 {
+    while !tracker.is_empty() {
+        if options
+            .max_solutions
+            .is_some_and(|max_solutions| count >= max_solutions)
+        {
+            #[cfg(feature = "trace")]
+            eprintln!("Reached max solutions {:?}", options.max_solutions);
+
+            break;
+        }
+
         let opt_next_node = {
             tracker
                 .last_mut()
@@ -204,7 +568,19 @@ where
                 .next_unvisited_neighbour(get_node_by_key.clone())
         };
 
+        if let Some(key) = missing_node.and_then(Cell::take) {
+            return Err(SimpleGraphError::MissingNode { key });
+        }
+
         match opt_next_node {
+            Some(_next_node) if options.exceeds_max_depth(tracker.len()) => {
+                #[cfg(feature = "trace")]
+                eprintln!(
+                    "Pruning node {:?} beyond max depth {:?}",
+                    _next_node.node.id(),
+                    options.max_depth
+                );
+            }
             Some(next_node) if destination_id == next_node.node.id() => {
                 // We found a solution
                 count += 1;
@@ -225,17 +601,21 @@ where
                     );
                 }
 
-                let last_node = tracker
-                    .last()
-                    .expect("Unreachable; memo length checked above");
+                if memoize {
+                    let last_node = tracker
+                        .last()
+                        .expect("Unreachable; memo length checked above");
 
-                // If we have just reached a destination, the count increase is always 1 for the last node
-                memoized_counts_by_node
-                    .entry(last_node.node.id())
-                    .and_modify(|c| *c += 1)
-                    .or_insert(1);
+                    // If we have just reached a destination, the count increase is always 1 for the last node
+                    memoized_counts_by_node
+                        .entry(last_node.node.id())
+                        .and_modify(|c| *c += 1)
+                        .or_insert(1);
+                }
             }
-            Some(next_node) if memoized_counts_by_node.contains_key(next_node.node.id()) => {
+            Some(next_node)
+                if memoize && memoized_counts_by_node.contains_key(next_node.node.id()) =>
+            {
                 // We have already computed the number of paths from this node to the destination
                 let unique_paths_from_next_node = *memoized_counts_by_node
                     .get(next_node.node.id())
@@ -268,6 +648,16 @@ where
                     .and_modify(|c| *c += unique_paths_from_next_node)
                     .or_insert(unique_paths_from_next_node);
             }
+            Some(_next_node)
+                if options
+                    .max_expansions
+                    .is_some_and(|max_expansions| expansions >= max_expansions) =>
+            {
+                #[cfg(feature = "trace")]
+                eprintln!("Reached max expansions {:?}", options.max_expansions);
+
+                break;
+            }
             Some(next_node) => {
                 #[cfg(feature = "trace")]
                 {
@@ -284,7 +674,11 @@ where
                         path_to_node,
                     );
                 }
+                expansions += 1;
                 tracker.push(next_node);
+                if let Some(monitor) = monitor.as_deref_mut() {
+                    monitor.record_expansion(tracker.len(), None, count)?;
+                }
             }
             None => {
                 // Backtrack
@@ -299,7 +693,7 @@ where
                     eprintln!("Backtracking from node {:?} to path {:?}", _popped.node.id(), path_to_node);
                 }
             
-                if tracker.len() > 0 {
+                if memoize && !tracker.is_empty() {
                     // We should update the memoization for the last node in the tracker, even if the count is zero
                     let count_from_popped = memoized_counts_by_node.get(&_popped.node.id()).copied().unwrap_or_default();
                     let last_node = tracker
@@ -317,7 +711,144 @@ where
     #[cfg(feature = "trace")]
     eprintln!("Final memoized counts: {:?}", memoized_counts_by_node);
 
-    count
+    Ok(options
+        .max_solutions
+        .map_or(count, |max_solutions| count.min(max_solutions)))
+}
+
+/// Count the number of paths from ``start`` to ``destination_id`` whose total distance
+/// does not exceed ``budget``.
+///
+/// Unlike [`dfs_count`], the number of paths from a node now depends on the distance
+/// already travelled to reach it, so the counts cannot be memoized per node; instead
+/// every path is walked, and pruned as soon as it goes over the budget.
+#[cfg(feature = "dfs-count")]
+pub fn dfs_count_within_budget<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &'s K,
+    size_hint: usize,
+    budget: D,
+    options: DfsOptions,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> usize
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    dfs_count_within_budget_with_optional_monitor(
+        start,
+        destination_id,
+        size_hint,
+        budget,
+        options,
+        None,
+        get_node_by_key,
+    )
+    .expect("Unreachable; cannot be interrupted without a monitor")
+}
+
+/// Same as [`dfs_count_within_budget`], but reports its progress to ``monitor`` as it
+/// goes, and stops with [`SimpleGraphError::Interrupted`] if ``monitor`` says so.
+///
+/// The best distance reported is that of the shortest path counted so far.
+#[cfg(feature = "dfs-count")]
+pub fn dfs_count_within_budget_monitored<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &'s K,
+    size_hint: usize,
+    budget: D,
+    options: DfsOptions,
+    monitor: &mut SearchMonitor<'_, D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<usize, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    dfs_count_within_budget_with_optional_monitor(
+        start,
+        destination_id,
+        size_hint,
+        budget,
+        options,
+        Some(monitor),
+        get_node_by_key,
+    )
+}
+
+#[cfg(feature = "dfs-count")]
+fn dfs_count_within_budget_with_optional_monitor<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &'s K,
+    size_hint: usize,
+    budget: D,
+    options: DfsOptions,
+    mut monitor: Option<&mut SearchMonitor<'_, D>>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<usize, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    let mut count = 0;
+    let mut expansions = 0;
+    let mut best_distance: Option<D> = None;
+    let mut tracker: Vec<NodeInProgress<'s, K, D, N>> = Vec::with_capacity(size_hint);
+    tracker.push(NodeInProgress::new(start, D::zero()));
+
+    while let Some(last_node) = tracker.last_mut() {
+        if options
+            .max_solutions
+            .is_some_and(|max_solutions| count >= max_solutions)
+        {
+            break;
+        }
+
+        match last_node.next_unvisited_neighbour(get_node_by_key.clone()) {
+            Some(next_node) if next_node.distance > budget => {
+                #[cfg(feature = "trace")]
+                eprintln!(
+                    "Pruning node {:?} with distance {:?} over budget {:?}",
+                    next_node.node.id(),
+                    next_node.distance,
+                    budget
+                );
+            }
+            Some(_next_node) if options.exceeds_max_depth(tracker.len()) => {}
+            Some(next_node) if destination_id == next_node.node.id() => {
+                count += 1;
+                if best_distance
+                    .as_ref()
+                    .is_none_or(|best_distance| next_node.distance < *best_distance)
+                {
+                    best_distance = Some(next_node.distance);
+                }
+            }
+            Some(_next_node)
+                if options
+                    .max_expansions
+                    .is_some_and(|max_expansions| expansions >= max_expansions) =>
+            {
+                break;
+            }
+            Some(next_node) => {
+                expansions += 1;
+                tracker.push(next_node);
+                if let Some(monitor) = monitor.as_deref_mut() {
+                    monitor.record_expansion(tracker.len(), best_distance.as_ref(), count)?;
+                }
+            }
+            None => {
+                // Backtrack
+                tracker.pop();
+            }
+        }
+    }
+
+    Ok(count)
 }
 
 #[cfg(test)]
@@ -339,6 +870,7 @@ mod tests_dfs {
             start_node,
             get_node_by_key(&destination_id).expect("Unreachable, destination node not found"),
             nodes.len(),
+            DfsOptions::default(),
         )
         .expect("Failed to create DFS instance");
 
@@ -346,7 +878,7 @@ mod tests_dfs {
             let mut sols = HashSet::new();
             while let Some(solution) = dfs.next_solution(get_node_by_key) {
                 sols.insert((
-                    solution.0.into_iter().map(|k| *k).collect::<Vec<u8>>(),
+                    solution.0.into_iter().copied().collect::<Vec<u8>>(),
                     solution.1,
                 ));
             }
@@ -361,13 +893,90 @@ mod tests_dfs {
                 (vec![1, 2, 4, 5], 28),
                 (vec![1, 2, 3, 6, 5], 28),
                 (vec![1, 2, 3, 4, 5], 34),
-            ]
-            .into_iter(),
+            ],
         );
 
         assert_eq!(solutions, expected_solutions);
     }
 
+    #[test]
+    fn test_dfs_with_budget() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let mut dfs = Dfs::new_with_budget(
+            start_node,
+            get_node_by_key(&5).expect("Unreachable, destination node not found"),
+            nodes.len(),
+            26,
+            DfsOptions::default(),
+        )
+        .expect("Failed to create DFS instance");
+
+        let mut solutions = Vec::new();
+        while let Some((path, distance)) = dfs.next_solution(get_node_by_key) {
+            solutions.push((path.into_iter().copied().collect::<Vec<u8>>(), distance));
+        }
+        solutions.sort_by_key(|(_, distance)| *distance);
+
+        assert_eq!(
+            solutions,
+            vec![
+                (vec![1, 3, 6, 5], 20),
+                (vec![1, 6, 5], 23),
+                (vec![1, 3, 4, 5], 26),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dfs-count")]
+    fn test_dfs_count_within_budget() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let get_node_by_key = |key: &u8| nodes.get(key);
+
+        assert_eq!(
+            dfs_count_within_budget(
+                start_node,
+                &5,
+                nodes.len(),
+                19,
+                DfsOptions::default(),
+                get_node_by_key
+            ),
+            0
+        );
+        assert_eq!(
+            dfs_count_within_budget(
+                start_node,
+                &5,
+                nodes.len(),
+                26,
+                DfsOptions::default(),
+                get_node_by_key
+            ),
+            3
+        );
+        assert_eq!(
+            dfs_count_within_budget(
+                start_node,
+                &5,
+                nodes.len(),
+                34,
+                DfsOptions::default(),
+                get_node_by_key
+            ),
+            6
+        );
+    }
+
     #[test]
     #[cfg(feature = "dfs-count")]
     fn test_dfs_count() {
@@ -378,8 +987,326 @@ mod tests_dfs {
         let start_node = nodes.get(&1).expect("Start node not found");
         let destination_id = 5;
         let get_node_by_key = |key: &u8| nodes.get(key);
-        let count = dfs_count(start_node, &destination_id, nodes.len(), get_node_by_key);
+        let count = dfs_count(
+                start_node,
+                &destination_id,
+                nodes.len(),
+                DfsOptions::default(),
+                get_node_by_key,
+            );
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    #[cfg(feature = "dfs-count")]
+    fn test_try_dfs_count() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        assert_eq!(
+            try_dfs_count(
+                start_node,
+                &5,
+                nodes.len(),
+                DfsOptions::default(),
+                get_node_by_key
+            )
+            .expect("All nodes are present"),
+            6
+        );
+
+        // Node 6 is still referenced by nodes 1 and 3.
+        let dangling: HashMap<u8, TestNode> = (1..=5)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+        let start_node = dangling.get(&1).expect("Start node not found");
+        let get_node_by_key = |key: &u8| dangling.get(key);
+
+        assert_eq!(
+            dfs_count(
+                start_node,
+                &5,
+                dangling.len(),
+                DfsOptions::default(),
+                get_node_by_key
+            ),
+            3
+        );
+        assert!(matches!(
+            try_dfs_count(
+                start_node,
+                &5,
+                dangling.len(),
+                DfsOptions::default(),
+                get_node_by_key
+            ),
+            Err(SimpleGraphError::MissingNode { key: 6 })
+        ));
+    }
+
+    #[test]
+    fn test_dfs_with_options() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let destination_node = nodes.get(&5).expect("Destination node not found");
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let collect_solutions = |options: DfsOptions| {
+            let mut dfs = Dfs::new(start_node, destination_node, nodes.len(), options)
+                .expect("Failed to create DFS instance");
+            let mut sols = Vec::new();
+            while let Some((path, distance)) = dfs.next_solution(get_node_by_key) {
+                sols.push((path.into_iter().copied().collect::<Vec<u8>>(), distance));
+            }
+            (sols, dfs.limit_reached())
+        };
+
+        let (solutions, limit) = collect_solutions(DfsOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(solutions, vec![(vec![1, 6, 5], 23)]);
+        assert_eq!(limit, None);
+
+        let (solutions, limit) = collect_solutions(DfsOptions {
+            max_solutions: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(limit, Some(DfsLimit::MaxSolutions));
+
+        let (solutions, limit) = collect_solutions(DfsOptions {
+            max_expansions: Some(1),
+            ..Default::default()
+        });
+        assert!(solutions.len() < 6);
+        assert_eq!(limit, Some(DfsLimit::MaxExpansions));
+    }
+
+    #[test]
+    #[cfg(feature = "dfs-count")]
+    fn test_dfs_count_with_options() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let count = |options: DfsOptions| {
+            dfs_count(start_node, &5, nodes.len(), options, get_node_by_key)
+        };
+
+        assert_eq!(
+            count(DfsOptions {
+                max_depth: Some(2),
+                ..Default::default()
+            }),
+            1
+        );
+        assert_eq!(
+            count(DfsOptions {
+                max_depth: Some(3),
+                ..Default::default()
+            }),
+            4
+        );
+        assert_eq!(
+            count(DfsOptions {
+                max_solutions: Some(4),
+                ..Default::default()
+            }),
+            4
+        );
+        assert_eq!(
+            count(DfsOptions {
+                max_expansions: Some(0),
+                ..Default::default()
+            }),
+            0
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dedup")]
+    fn test_dfs_with_visited_set_dedup() {
+        // 2 and 3 are connected both ways, so 4 can be reached via 1-2-3-4 and 1-3-2-4,
+        // which visit the same set of nodes.
+        const CYCLIC_CONNECTIONS: &[(u8, u8, u32)] = &[
+            (1, 2, 1),
+            (1, 3, 1),
+            (2, 3, 1),
+            (3, 2, 1),
+            (2, 4, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+        ];
+        let nodes: HashMap<u8, TestNode> = (1..=5)
+            .map(|id| (id, TestNode::new_with_connections(id, CYCLIC_CONNECTIONS)))
+            .collect();
+
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let mut dfs = Dfs::new(
+            &nodes[&1],
+            &nodes[&5],
+            nodes.len(),
+            DfsOptions {
+                dedup: DedupPolicy::VisitedSet,
+                ..Default::default()
+            },
+        )
+        .expect("Failed to create DFS instance");
+
+        let mut visited_sets = Vec::new();
+        while let Some((path, _)) = dfs.next_solution(get_node_by_key) {
+            let mut visited_set = path.into_iter().copied().collect::<Vec<u8>>();
+            visited_set.sort();
+            visited_sets.push(visited_set);
+        }
+        visited_sets.sort();
+
+        assert_eq!(
+            visited_sets,
+            vec![
+                vec![1, 2, 3, 4, 5],
+                vec![1, 2, 4, 5],
+                vec![1, 3, 4, 5],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dfs_monitored() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let mut reports = Vec::new();
+        let mut monitor = SearchMonitor::new().on_progress(1, |progress| {
+            reports.push(progress.clone());
+        });
+
+        let mut dfs = Dfs::new(&nodes[&1], &nodes[&5], nodes.len(), DfsOptions::default())
+            .expect("Failed to create DFS instance");
+        let mut solutions = 0;
+        while dfs
+            .next_solution_monitored(&mut monitor, get_node_by_key)
+            .expect("Monitor should not interrupt")
+            .is_some()
+        {
+            solutions += 1;
+        }
+        assert_eq!(solutions, 6);
+        drop(monitor);
+
+        let last_report = reports.last().expect("No progress reported");
+        assert_eq!(last_report.expanded, reports.len());
+        assert!(last_report.solutions <= 6);
+        assert_eq!(last_report.best_distance, Some(20));
+        assert!(reports.iter().all(|progress| progress.frontier >= 2));
+    }
+
+    #[test]
+    #[cfg(feature = "dfs-count")]
+    fn test_dfs_count_monitored() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let mut monitor = SearchMonitor::new();
+
+        let count = dfs_count_monitored(
+            &nodes[&1],
+            &5,
+            nodes.len(),
+            DfsOptions::default(),
+            &mut monitor,
+            get_node_by_key,
+        )
+        .expect("Monitor should not interrupt");
         assert_eq!(count, 6);
+        let memoized_expansions = monitor.expanded();
+
+        let count = dfs_count_within_budget_monitored(
+            &nodes[&1],
+            &5,
+            nodes.len(),
+            34,
+            DfsOptions::default(),
+            &mut monitor,
+            get_node_by_key,
+        )
+        .expect("Monitor should not interrupt");
+        assert_eq!(count, 6);
+        // Without memoization, the same nodes are expanded more than once.
+        assert!(monitor.expanded() - memoized_expansions > memoized_expansions);
+    }
+
+    #[test]
+    fn test_dfs_cancelled_and_resumed() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let get_node_by_key = |key: &u8| nodes.get(key);
+        let cancelled = AtomicBool::new(true);
+        let mut dfs = Dfs::new(&nodes[&1], &nodes[&5], nodes.len(), DfsOptions::default())
+            .expect("Failed to create DFS instance");
+
+        let mut solutions = HashSet::new();
+        let mut interruptions = 0;
+        loop {
+            let mut monitor = SearchMonitor::new().cancel_on(&cancelled);
+            match dfs.next_solution_monitored(&mut monitor, get_node_by_key) {
+                Ok(Some((path, _))) => {
+                    solutions.insert(path.into_iter().copied().collect::<Vec<u8>>());
+                }
+                Ok(None) => break,
+                Err(SimpleGraphError::Interrupted { .. }) => {
+                    interruptions += 1;
+                    // Let the next attempt run to completion.
+                    cancelled.store(false, Ordering::Relaxed);
+                }
+                Err(err) => panic!("Unexpected error: {err}"),
+            }
+        }
+
+        assert_eq!(interruptions, 1);
+        assert_eq!(solutions.len(), 6);
+    }
+
+    #[test]
+    #[cfg(feature = "dfs-count")]
+    fn test_dfs_count_deadline() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let mut monitor = SearchMonitor::new().deadline(std::time::Instant::now());
+        let result = dfs_count_monitored(
+            &nodes[&1],
+            &5,
+            nodes.len(),
+            DfsOptions::default(),
+            &mut monitor,
+            |key: &u8| nodes.get(key),
+        );
+
+        assert!(matches!(
+            result,
+            Err(SimpleGraphError::Interrupted {
+                reason: crate::monitor::InterruptReason::DeadlineExceeded,
+                progress,
+            }) if progress.expanded == 1 && progress.solutions == 0
+        ));
     }
 
     /// This test case emphasizes the single memoization aspect of DFS count
@@ -412,11 +1339,18 @@ mod tests_dfs {
             let start_node = nodes.get(&1).expect("Start node not found");
             let destination_id = 10;
             let get_node_by_key = |key: &u8| nodes.get(key);
-            let count = dfs_count(start_node, &destination_id, nodes.len(), get_node_by_key);
+            let count = dfs_count(
+                start_node,
+                &destination_id,
+                nodes.len(),
+                DfsOptions::default(),
+                get_node_by_key,
+            );
             assert_eq!(count, 5);
         }
     }
 
+    #[cfg(feature = "dfs-count")]
     mod case_2 {
         use super::*;
 
@@ -452,7 +1386,13 @@ mod tests_dfs {
             let start_node = nodes.get(&1).expect("Start node not found");
             let destination_id = 11;
             let get_node_by_key = |key: &u8| nodes.get(key);
-            let count = dfs_count(start_node, &destination_id, nodes.len(), get_node_by_key);
+            let count = dfs_count(
+                start_node,
+                &destination_id,
+                nodes.len(),
+                DfsOptions::default(),
+                get_node_by_key,
+            );
             assert_eq!(count, 81);
         }
     }
@@ -1,4 +1,4 @@
-use crate::{SimpleGraphError, traits, wrapper};
+use crate::{SimpleGraphError, monitor::SearchMonitor, traits, wrapper};
 use num_traits::Zero;
 use std::{
     cmp::{Ord, Reverse},
@@ -18,6 +18,61 @@ where
     D: Zero + Ord + Clone + Debug,
     N: traits::IsNode<'s, K, D>,
 {
+    dijkstra_with_optional_budget(start, destination, None, None, get_node_by_key)
+}
+
+/// Same as [`dijkstra`], but reports its progress to ``monitor`` as it goes, and stops
+/// with [`SimpleGraphError::Interrupted`] if ``monitor`` says so.
+///
+/// The best distance reported is that of the latest node settled, which only ever
+/// increases; once it reaches the distance of the destination, the search is done.
+pub fn dijkstra_monitored<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    monitor: &mut SearchMonitor<'_, D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D>,
+{
+    dijkstra_with_optional_budget(start, destination, None, Some(monitor), get_node_by_key)
+}
+
+/// Implements Dijkstra's algorithm, but never expands any path whose total distance
+/// exceeds ``budget``.
+///
+/// This saves exploring the far reaches of a large graph when the caller only cares
+/// about destinations within a certain distance. If the destination cannot be reached
+/// within the budget, [`SimpleGraphError::BudgetExceeded`] is returned.
+pub fn dijkstra_within_budget<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    budget: D,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D>,
+{
+    dijkstra_with_optional_budget(start, destination, Some(budget), None, get_node_by_key)
+}
+
+fn dijkstra_with_optional_budget<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    budget: Option<D>,
+    mut monitor: Option<&mut SearchMonitor<'_, D>>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D>,
+{
+    let mut pruned_over_budget = false;
     let mut current_node = start;
     let mut visited_nodes: HashSet<&'s K> = HashSet::new();
     let mut unvisited_nodes: HashMap<&'s K, (&'s N, Vec<&'s K>, D)> = HashMap::new();
@@ -55,6 +110,10 @@ where
             return Ok((current_path, current_distance));
         }
 
+        if let Some(monitor) = monitor.as_deref_mut() {
+            monitor.record_expansion(unvisited_distances.len(), Some(&current_distance), 0)?;
+        }
+
         // Stage 2 - Update neighbours
         current_node
             .neighbours(get_node_by_key.clone())
@@ -76,6 +135,16 @@ where
                 }
 
                 let new_distance = current_distance.clone() + distance.clone();
+                if budget.as_ref().is_some_and(|budget| new_distance > *budget) {
+                    #[cfg(feature = "trace")]
+                    eprintln!(
+                        "Neighbour node {neighbour_id:?} at distance {new_distance:?} is over budget, skipping",
+                    );
+                    pruned_over_budget = true;
+
+                    return Ok(());
+                }
+
                 unvisited_nodes
                     .entry(neighbour_id)
                     .and_modify(|(_, path, existing_distance)| {
@@ -136,6 +205,18 @@ where
                         )));
                     }
                 }
+                None if pruned_over_budget => {
+                    return Err(match budget {
+                        Some(budget) => SimpleGraphError::BudgetExceeded {
+                            start: start.id().clone(),
+                            destination: destination.clone(),
+                            budget,
+                        },
+                        None => SimpleGraphError::Unreachable(
+                            "Pruned a node without a budget".to_owned(),
+                        ),
+                    });
+                }
                 None => {
                     return Err(SimpleGraphError::Unreachable(format!(
                         "Destination node {:?} is unreachable from start node {:?}",
@@ -174,4 +255,64 @@ mod tests_dijkstra {
         assert_eq!(path, vec![&1, &3, &6, &5]);
         assert_eq!(distance, 20);
     }
+
+    #[test]
+    fn wiki_example_within_budget() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let (path, distance) = dijkstra_within_budget(start_node, &5, 20, |key| nodes.get(key))
+            .expect("Dijkstra failed");
+
+        assert_eq!(path, vec![&1, &3, &6, &5]);
+        assert_eq!(distance, 20);
+
+        assert!(matches!(
+            dijkstra_within_budget(start_node, &5, 19, |key| nodes.get(key)),
+            Err(SimpleGraphError::BudgetExceeded { budget: 19, .. })
+        ));
+    }
+
+    #[test]
+    fn wiki_example_monitored() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let mut best_distances = Vec::new();
+        let mut monitor = SearchMonitor::new().on_progress(1, |progress| {
+            best_distances.push(progress.best_distance.expect("Dijkstra always has a distance"));
+        });
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        let (_, distance) = dijkstra_monitored(start_node, &5, &mut monitor, |key| nodes.get(key))
+            .expect("Dijkstra failed");
+        assert_eq!(distance, 20);
+        assert_eq!(monitor.expanded(), 4);
+        drop(monitor);
+
+        // Nodes are expanded in order of distance: 1, 2, 3, 6; then 5 is reached.
+        assert_eq!(best_distances, vec![0, 7, 9, 11]);
+    }
+
+    #[test]
+    fn wiki_example_cancelled() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let cancelled = std::sync::atomic::AtomicBool::new(true);
+        let mut monitor = SearchMonitor::new().cancel_on(&cancelled);
+
+        let start_node = nodes.get(&1).expect("Start node not found");
+        assert!(matches!(
+            dijkstra_monitored(start_node, &5, &mut monitor, |key| nodes.get(key)),
+            Err(SimpleGraphError::Interrupted {
+                reason: crate::monitor::InterruptReason::Cancelled,
+                progress,
+            }) if progress.expanded == 1 && progress.best_distance == Some(0)
+        ));
+    }
 }
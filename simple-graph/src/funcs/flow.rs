@@ -0,0 +1,462 @@
+//! Maximum flows through a directed graph, and the questions they answer: how many
+//! edge-disjoint paths connect two nodes, and which edges form the narrowest cut.
+
+use crate::{SimpleGraphError, traits};
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, collections::VecDeque, fmt::Debug, hash::Hash, ops::Sub};
+
+/// A single edge of a [`FlowNetwork`], with the capacity left on it.
+///
+/// Every edge of the graph is stored next to a reverse edge of no capacity, at the index
+/// with the lowest bit flipped; pushing flow along one edge frees the same amount of
+/// capacity on the other, so that flow can later be re-routed.
+#[derive(Debug, Clone)]
+struct ResidualEdge<C> {
+    to: usize,
+    residual: C,
+}
+
+/// A copy of a directed graph with a capacity on every edge, for computing flows.
+///
+/// The network is built from all nodes reachable from the given starts; the graph itself
+/// is not modified. Edges are never merged, so parallel edges add up their capacities.
+#[derive(Debug, Clone)]
+pub struct FlowNetwork<'s, K, C>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    ids: Vec<&'s K>,
+    indices: FxHashMap<&'s K, usize>,
+    /// Forward edges at even indices, each followed by its reverse edge.
+    edges: Vec<ResidualEdge<C>>,
+    /// The original capacity of every forward edge, by half its index.
+    capacities: Vec<C>,
+    /// The indices into ``edges`` leaving each node, both forward and reverse.
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl<'s, K, D> FlowNetwork<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug + Sub<Output = D>,
+{
+    /// Build a network from all nodes reachable from ``starts``, using the distance of
+    /// each edge as its capacity.
+    pub fn new<N>(
+        starts: impl IntoIterator<Item = &'s N>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Self
+    where
+        N: traits::IsNode<'s, K, D> + 's,
+    {
+        Self::with_capacities(starts, |_, _, distance| distance, get_node_by_key)
+    }
+}
+
+impl<'s, K, C> FlowNetwork<'s, K, C>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    C: Zero + Ord + Clone + Debug + Sub<Output = C>,
+{
+    /// Build a network from all nodes reachable from ``starts``, where ``capacity`` gives
+    /// the capacity of the edge between two nodes with the given distance.
+    pub fn with_capacities<D, N>(
+        starts: impl IntoIterator<Item = &'s N>,
+        capacity: impl Fn(&'s N, &'s N, D) -> C,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Self
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNode<'s, K, D> + 's,
+    {
+        let mut network = Self {
+            ids: Vec::new(),
+            indices: FxHashMap::default(),
+            edges: Vec::new(),
+            capacities: Vec::new(),
+            adjacency: Vec::new(),
+        };
+        let mut queue: VecDeque<&'s N> = VecDeque::new();
+
+        for start in starts {
+            if network.insert_node(start.id()).1 {
+                queue.push_back(start);
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            let from = network.indices[node.id()];
+
+            for (neighbour, distance) in node.neighbours(get_node_by_key.clone()) {
+                let (to, inserted) = network.insert_node(neighbour.id());
+                if inserted {
+                    queue.push_back(neighbour);
+                }
+
+                let edge_capacity = capacity(node, neighbour, distance);
+                network.adjacency[from].push(network.edges.len());
+                network.edges.push(ResidualEdge {
+                    to,
+                    residual: edge_capacity.clone(),
+                });
+                network.adjacency[to].push(network.edges.len());
+                network.edges.push(ResidualEdge {
+                    to: from,
+                    residual: C::zero(),
+                });
+                network.capacities.push(edge_capacity);
+            }
+        }
+
+        network
+    }
+
+    /// Add ``node_id`` to the network if it is not already there, returning its index and
+    /// whether it was inserted.
+    fn insert_node(&mut self, node_id: &'s K) -> (usize, bool) {
+        match self.indices.get(node_id) {
+            Some(&index) => (index, false),
+            None => {
+                let index = self.ids.len();
+                self.indices.insert(node_id, index);
+                self.ids.push(node_id);
+                self.adjacency.push(Vec::new());
+                (index, true)
+            }
+        }
+    }
+
+    /// The number of nodes in the network.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the network has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Find the shortest path (in edges) from ``source`` to ``sink`` with capacity left
+    /// on every edge, returning the edge used to reach each node on it.
+    fn augmenting_path(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut reached_by: Vec<Option<usize>> = vec![None; self.len()];
+        let mut queue: VecDeque<usize> = VecDeque::from([source]);
+
+        while let Some(current) = queue.pop_front() {
+            for &edge_index in &self.adjacency[current] {
+                let edge = &self.edges[edge_index];
+                if edge.to == source || reached_by[edge.to].is_some() || edge.residual <= C::zero()
+                {
+                    continue;
+                }
+
+                reached_by[edge.to] = Some(edge_index);
+                if edge.to == sink {
+                    let mut path = Vec::new();
+                    let mut node = sink;
+                    while node != source {
+                        let edge_index = reached_by[node]
+                            .expect("Unreachable; every node on the path is reached");
+                        path.push(edge_index);
+                        node = self.edges[edge_index ^ 1].to;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(edge.to);
+            }
+        }
+
+        None
+    }
+
+    /// Push as much flow as possible from ``source`` to ``sink``, using the Edmonds-Karp
+    /// algorithm, and return the total flow.
+    ///
+    /// Flow already pushed by a previous call is kept, so calling this again with the
+    /// same nodes returns zero. Returns [`SimpleGraphError::CannotPathToSelf`] if
+    /// ``source`` and ``sink`` are the same, or [`SimpleGraphError::NodeNotConnected`] if
+    /// ``source`` is not part of the network; a ``sink`` that is not part of the network
+    /// simply receives no flow.
+    pub fn max_flow(&mut self, source: &K, sink: &K) -> Result<C, SimpleGraphError<K, C>> {
+        if source == sink {
+            return Err(SimpleGraphError::CannotPathToSelf {
+                node: source.clone(),
+            });
+        }
+        let Some(&source_index) = self.indices.get(source) else {
+            return Err(SimpleGraphError::NodeNotConnected {
+                start: source.clone(),
+                destination: sink.clone(),
+            });
+        };
+        let Some(&sink_index) = self.indices.get(sink) else {
+            return Ok(C::zero());
+        };
+
+        let mut total = C::zero();
+        while let Some(path) = self.augmenting_path(source_index, sink_index) {
+            let bottleneck = path
+                .iter()
+                .map(|&edge_index| self.edges[edge_index].residual.clone())
+                .min()
+                .expect("Unreachable; an augmenting path has at least one edge");
+
+            for edge_index in path {
+                let edge = &mut self.edges[edge_index];
+                edge.residual = edge.residual.clone() - bottleneck.clone();
+                let reverse = &mut self.edges[edge_index ^ 1];
+                reverse.residual = reverse.residual.clone() + bottleneck.clone();
+            }
+
+            total = total + bottleneck;
+        }
+
+        Ok(total)
+    }
+
+    /// The flow along the forward edge at ``edge_index``.
+    fn flow_at(&self, edge_index: usize) -> C {
+        self.capacities[edge_index / 2].clone() - self.edges[edge_index].residual.clone()
+    }
+
+    /// Iterate over every edge carrying flow, as ``(from, to, flow)``.
+    pub fn flows(&self) -> impl Iterator<Item = (&'s K, &'s K, C)> + '_ {
+        (0..self.edges.len()).step_by(2).filter_map(|edge_index| {
+            let flow = self.flow_at(edge_index);
+            (flow > C::zero()).then(|| {
+                (
+                    self.ids[self.edges[edge_index ^ 1].to],
+                    self.ids[self.edges[edge_index].to],
+                    flow,
+                )
+            })
+        })
+    }
+
+    /// The IDs of the nodes still reachable from ``source`` through edges with capacity
+    /// left; after [`FlowNetwork::max_flow`], these form the source side of a minimum cut.
+    pub fn min_cut(&self, source: &K) -> Vec<&'s K> {
+        let Some(&source_index) = self.indices.get(source) else {
+            return Vec::new();
+        };
+
+        let mut reached = vec![false; self.len()];
+        reached[source_index] = true;
+        let mut queue: VecDeque<usize> = VecDeque::from([source_index]);
+
+        while let Some(current) = queue.pop_front() {
+            for &edge_index in &self.adjacency[current] {
+                let edge = &self.edges[edge_index];
+                if !reached[edge.to] && edge.residual > C::zero() {
+                    reached[edge.to] = true;
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        reached
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, reached)| reached.then_some(self.ids[index]))
+            .collect()
+    }
+}
+
+/// The result of [`edge_disjoint_paths`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeDisjointPaths<'s, K> {
+    /// The maximum number of paths that share no edge.
+    pub count: usize,
+    /// One such set of paths, each listing the IDs of its nodes from start to
+    /// destination inclusive.
+    pub paths: Vec<Vec<&'s K>>,
+}
+
+/// Find the maximum number of paths from ``start`` to ``destination_id`` that do not
+/// share any edge, along with one set of such paths.
+///
+/// By Menger's theorem, this is also the minimum number of edges that need to be removed
+/// to disconnect ``destination_id`` from ``start``. Parallel edges count separately; for
+/// undirected graphs, where each edge is stored in both directions, each direction may be
+/// used by a different path.
+pub fn edge_disjoint_paths<'s, K, D, N>(
+    start: &'s N,
+    destination_id: &K,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<EdgeDisjointPaths<'s, K>, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D> + 's,
+{
+    if start.id() == destination_id {
+        return Err(SimpleGraphError::CannotPathToSelf {
+            node: destination_id.clone(),
+        });
+    }
+
+    let mut network = FlowNetwork::with_capacities([start], |_, _, _| 1usize, get_node_by_key);
+    let count = network
+        .max_flow(start.id(), destination_id)
+        .map_err(|err| SimpleGraphError::Unreachable(err.to_string()))?;
+
+    let Some(&sink) = network.indices.get(destination_id) else {
+        return Ok(EdgeDisjointPaths {
+            count,
+            paths: Vec::new(),
+        });
+    };
+    let source = network.indices[start.id()];
+
+    // Every forward edge carries a flow of either 0 or 1; follow and consume them.
+    let mut remaining: Vec<bool> = (0..network.edges.len())
+        .map(|edge_index| edge_index % 2 == 0 && network.flow_at(edge_index) > 0)
+        .collect();
+    let paths = (0..count)
+        .map(|_| {
+            let mut path: Vec<usize> = vec![source];
+            while let Some(&current) = path.last().filter(|&&current| current != sink) {
+                let edge_index = network.adjacency[current]
+                    .iter()
+                    .copied()
+                    .find(|&edge_index| remaining[edge_index])
+                    .expect("Unreachable; flow is conserved at every node but the ends");
+                remaining[edge_index] = false;
+
+                let next = network.edges[edge_index].to;
+                match path.iter().position(|&node| node == next) {
+                    // The flow went round a cycle, which is of no use to the path.
+                    Some(position) => path.truncate(position + 1),
+                    None => path.push(next),
+                }
+            }
+
+            path.into_iter().map(|index| network.ids[index]).collect()
+        })
+        .collect();
+
+    Ok(EdgeDisjointPaths { count, paths })
+}
+
+#[cfg(test)]
+mod tests_flow {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use std::collections::HashMap;
+
+    fn build_nodes(connections: &[(u8, u8, u32)]) -> HashMap<u8, TestNode> {
+        connections
+            .iter()
+            .flat_map(|(start, end, _)| [*start, *end])
+            .map(|id| (id, TestNode::new_with_connections(id, connections)))
+            .collect()
+    }
+
+    #[test]
+    fn test_max_flow() {
+        // The classic example from CLRS, with nodes renumbered from 1.
+        const CONNECTIONS: &[(u8, u8, u32)] = &[
+            (1, 2, 16),
+            (1, 3, 13),
+            (2, 4, 12),
+            (3, 2, 4),
+            (3, 5, 14),
+            (4, 3, 9),
+            (4, 6, 20),
+            (5, 4, 7),
+            (5, 6, 4),
+        ];
+        let nodes = build_nodes(CONNECTIONS);
+        let mut network = FlowNetwork::new([&nodes[&1]], |key| nodes.get(key));
+
+        assert_eq!(network.len(), 6);
+        assert_eq!(network.max_flow(&1, &6).expect("Max flow failed"), 23);
+        assert_eq!(network.max_flow(&1, &6).expect("Max flow failed"), 0);
+
+        let mut cut = network.min_cut(&1);
+        cut.sort();
+        assert_eq!(cut, vec![&1, &2, &3, &5]);
+
+        // Flow is conserved at every node but the ends.
+        let mut balance: HashMap<u8, i64> = HashMap::new();
+        network.flows().for_each(|(from, to, flow)| {
+            *balance.entry(*from).or_default() -= flow as i64;
+            *balance.entry(*to).or_default() += flow as i64;
+        });
+        assert_eq!(balance[&1], -23);
+        assert_eq!(balance[&6], 23);
+        assert!((2..=5).all(|id| balance[&id] == 0));
+    }
+
+    #[test]
+    fn test_max_flow_errors() {
+        let nodes = build_nodes(CONNECTIONS);
+        let mut network = FlowNetwork::new([&nodes[&4]], |key| nodes.get(key));
+
+        assert!(matches!(
+            network.max_flow(&4, &4),
+            Err(SimpleGraphError::CannotPathToSelf { node: 4 })
+        ));
+        assert!(matches!(
+            network.max_flow(&1, &5),
+            Err(SimpleGraphError::NodeNotConnected { .. })
+        ));
+        assert_eq!(network.max_flow(&4, &1).expect("Max flow failed"), 0);
+    }
+
+    #[test]
+    fn test_edge_disjoint_paths() {
+        let nodes = build_nodes(CONNECTIONS);
+        let result =
+            edge_disjoint_paths(&nodes[&1], &5, |key| nodes.get(key)).expect("Search failed");
+
+        // Node 5 only has two incoming edges.
+        assert_eq!(result.count, 2);
+        assert_eq!(result.paths.len(), 2);
+
+        let mut used_edges = result
+            .paths
+            .iter()
+            .flat_map(|path| path.windows(2).map(|pair| (*pair[0], *pair[1])))
+            .collect::<Vec<_>>();
+        let edge_count = used_edges.len();
+        used_edges.sort();
+        used_edges.dedup();
+        assert_eq!(used_edges.len(), edge_count, "Paths share an edge");
+
+        result.paths.iter().for_each(|path| {
+            assert_eq!(path.first(), Some(&&1));
+            assert_eq!(path.last(), Some(&&5));
+            path.windows(2).for_each(|pair| {
+                assert!(
+                    CONNECTIONS
+                        .iter()
+                        .any(|(start, end, _)| start == pair[0] && end == pair[1]),
+                    "{pair:?} is not an edge"
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn test_edge_disjoint_paths_rerouted() {
+        // A search taking 1-2-3-4 first would have to re-route it to find a second path.
+        const CONNECTIONS: &[(u8, u8, u32)] =
+            &[(1, 2, 1), (2, 3, 1), (3, 4, 1), (1, 3, 1), (2, 4, 1)];
+        let nodes = build_nodes(CONNECTIONS);
+
+        let result =
+            edge_disjoint_paths(&nodes[&1], &4, |key| nodes.get(key)).expect("Search failed");
+        let mut paths = result.paths.clone();
+        paths.sort();
+        assert_eq!(result.count, 2);
+        assert_eq!(paths, vec![vec![&1, &2, &4], vec![&1, &3, &4]]);
+
+        let result =
+            edge_disjoint_paths(&nodes[&4], &1, |key| nodes.get(key)).expect("Search failed");
+        assert_eq!(result.count, 0);
+        assert!(result.paths.is_empty());
+    }
+}
@@ -0,0 +1,407 @@
+//! Iterative deepening searches, trading repeated work for a memory footprint that is
+//! linear in the depth of the search.
+//!
+//! Both searches here only ever hold the current path in memory, unlike [`crate::dijkstra`]
+//! which has to keep the entire frontier; this makes them suitable for searches where the
+//! frontier does not fit in RAM.
+
+use super::dfs::NodeInProgress;
+use crate::{SimpleGraphError, monitor::SearchMonitor, traits};
+use num_traits::Zero;
+use std::{cmp::Ord, fmt::Debug, hash::Hash};
+
+/// Outcome of a single bounded pass of an iterative deepening search.
+enum PassOutcome<'s, K, D> {
+    /// A path to the destination was found.
+    Found(Vec<&'s K>, D),
+    /// Some paths were cut off by the bound, so a deeper pass may find a solution.
+    CutOff,
+    /// The whole reachable graph was searched without being cut off.
+    Exhausted,
+}
+
+/// Iterative deepening depth-first search.
+///
+/// Runs a depth-limited DFS with a limit of 1, 2, 3... edges up to ``max_depth``, returning
+/// the first path found; this is therefore the path with the **fewest edges**, not
+/// necessarily the shortest distance.
+///
+/// Nodes already on the current path are never revisited, so cycles are safe.
+pub fn iddfs<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    max_depth: usize,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    iddfs_with_optional_monitor(start, destination, max_depth, None, get_node_by_key)
+}
+
+/// Same as [`iddfs`], but reports its progress to ``monitor`` as it goes, and stops with
+/// [`SimpleGraphError::Interrupted`] if ``monitor`` says so.
+///
+/// Expansions are counted across all passes, so nodes near the start are counted once
+/// for every pass that expands them.
+pub fn iddfs_monitored<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    max_depth: usize,
+    monitor: &mut SearchMonitor<'_, D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    iddfs_with_optional_monitor(
+        start,
+        destination,
+        max_depth,
+        Some(monitor),
+        get_node_by_key,
+    )
+}
+
+fn iddfs_with_optional_monitor<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    max_depth: usize,
+    mut monitor: Option<&mut SearchMonitor<'_, D>>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    if start.id() == destination {
+        return Err(SimpleGraphError::CannotPathToSelf {
+            node: start.id().clone(),
+        });
+    }
+
+    for depth_limit in 1..=max_depth {
+        #[cfg(feature = "trace")]
+        eprintln!("IDDFS pass with depth limit {depth_limit}");
+
+        match bounded_pass(
+            start,
+            destination,
+            |tracker_len, _| tracker_len > depth_limit,
+            monitor.as_deref_mut(),
+            None,
+            get_node_by_key.clone(),
+        )? {
+            PassOutcome::Found(path, distance) => return Ok((path, distance)),
+            PassOutcome::CutOff => continue,
+            PassOutcome::Exhausted => {
+                return Err(SimpleGraphError::NodeNotConnected {
+                    start: start.id().clone(),
+                    destination: destination.clone(),
+                });
+            }
+        }
+    }
+
+    Err(SimpleGraphError::DepthExceeded {
+        start: start.id().clone(),
+        destination: destination.clone(),
+        max_depth,
+    })
+}
+
+/// Iterative deepening A* search.
+///
+/// Runs depth-first passes bounded by the estimated total distance `g + h`, where `g` is
+/// the distance travelled so far and `h` is ``heuristic`` of the current node. Each pass
+/// raises the bound to the smallest estimate that exceeded the previous one.
+///
+/// As long as ``heuristic`` is admissible (i.e. never overestimates the remaining
+/// distance), the returned path is the shortest one. A heuristic returning
+/// [`Zero::zero`] for every node degrades this into iterative deepening on distance.
+pub fn ida_star<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    heuristic: impl Fn(&'s N) -> D,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    ida_star_with_optional_monitor(start, destination, heuristic, None, get_node_by_key)
+}
+
+/// Same as [`ida_star`], but reports its progress to ``monitor`` as it goes, and stops
+/// with [`SimpleGraphError::Interrupted`] if ``monitor`` says so.
+///
+/// The best distance reported is the bound of the current pass, which is a lower bound
+/// of the shortest distance as long as ``heuristic`` is admissible.
+pub fn ida_star_monitored<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    heuristic: impl Fn(&'s N) -> D,
+    monitor: &mut SearchMonitor<'_, D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    ida_star_with_optional_monitor(
+        start,
+        destination,
+        heuristic,
+        Some(monitor),
+        get_node_by_key,
+    )
+}
+
+fn ida_star_with_optional_monitor<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    heuristic: impl Fn(&'s N) -> D,
+    mut monitor: Option<&mut SearchMonitor<'_, D>>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    if start.id() == destination {
+        return Err(SimpleGraphError::CannotPathToSelf {
+            node: start.id().clone(),
+        });
+    }
+
+    let mut bound = heuristic(start);
+
+    loop {
+        #[cfg(feature = "trace")]
+        eprintln!("IDA* pass with bound {bound:?}");
+
+        let mut next_bound: Option<D> = None;
+        let outcome = bounded_pass(
+            start,
+            destination,
+            |_, next_node: &NodeInProgress<'s, K, D, N>| {
+                let estimate = next_node.distance.clone() + heuristic(next_node.node);
+                if estimate > bound {
+                    if next_bound
+                        .as_ref()
+                        .is_none_or(|current| estimate < *current)
+                    {
+                        next_bound = Some(estimate);
+                    }
+                    true
+                } else {
+                    false
+                }
+            },
+            monitor.as_deref_mut(),
+            Some(&bound),
+            get_node_by_key.clone(),
+        )?;
+
+        match outcome {
+            PassOutcome::Found(path, distance) => return Ok((path, distance)),
+            PassOutcome::CutOff if next_bound.is_some() => {
+                bound = next_bound.expect("Unreachable; checked above");
+            }
+            PassOutcome::CutOff | PassOutcome::Exhausted => {
+                return Err(SimpleGraphError::NodeNotConnected {
+                    start: start.id().clone(),
+                    destination: destination.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// A single depth-first pass that skips any node for which ``is_cut_off`` returns `true`.
+///
+/// ``is_cut_off`` is given the length the path would have including the new node, and
+/// the new node itself. Every expansion is recorded to ``monitor``, if any, along with
+/// ``best_distance``.
+fn bounded_pass<'s, K, D, N>(
+    start: &'s N,
+    destination: &'s K,
+    mut is_cut_off: impl FnMut(usize, &NodeInProgress<'s, K, D, N>) -> bool,
+    mut monitor: Option<&mut SearchMonitor<'_, D>>,
+    best_distance: Option<&D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<PassOutcome<'s, K, D>, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D>,
+{
+    let mut was_cut_off = false;
+    let mut tracker: Vec<NodeInProgress<'s, K, D, N>> = vec![NodeInProgress::new(start, D::zero())];
+
+    while let Some(last_node) = tracker.last_mut() {
+        match last_node.next_unvisited_neighbour(get_node_by_key.clone()) {
+            Some(next_node)
+                if tracker
+                    .iter()
+                    .any(|visited| visited.node.id() == next_node.node.id()) =>
+            {
+                // Never walk in circles.
+            }
+            Some(next_node) if is_cut_off(tracker.len(), &next_node) => {
+                was_cut_off = true;
+            }
+            Some(next_node) if next_node.node.id() == destination => {
+                let path = tracker
+                    .iter()
+                    .chain(std::iter::once(&next_node))
+                    .map(|n| n.node.id())
+                    .collect::<Vec<&'s K>>();
+
+                return Ok(PassOutcome::Found(path, next_node.distance));
+            }
+            Some(next_node) => {
+                tracker.push(next_node);
+                if let Some(monitor) = monitor.as_deref_mut() {
+                    monitor.record_expansion(tracker.len(), best_distance, 0)?;
+                }
+            }
+            None => {
+                // Backtrack
+                tracker.pop();
+            }
+        }
+    }
+
+    Ok(if was_cut_off {
+        PassOutcome::CutOff
+    } else {
+        PassOutcome::Exhausted
+    })
+}
+
+#[cfg(test)]
+mod tests_iterative_deepening {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use crate::traits::IsNode;
+    use std::collections::HashMap;
+
+    fn build_nodes() -> HashMap<u8, TestNode> {
+        (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect()
+    }
+
+    #[test]
+    fn test_iddfs() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&1).expect("Start node not found");
+
+        let (path, distance) =
+            iddfs(start_node, &5, 10, |key| nodes.get(key)).expect("IDDFS failed");
+        assert_eq!(path, vec![&1, &6, &5]);
+        assert_eq!(distance, 23);
+    }
+
+    #[test]
+    fn test_iddfs_depth_exceeded() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&1).expect("Start node not found");
+
+        assert!(matches!(
+            iddfs(start_node, &5, 1, |key| nodes.get(key)),
+            Err(SimpleGraphError::DepthExceeded { max_depth: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_iddfs_not_connected() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&5).expect("Start node not found");
+
+        assert!(matches!(
+            iddfs(start_node, &1, 10, |key| nodes.get(key)),
+            Err(SimpleGraphError::NodeNotConnected { .. })
+        ));
+    }
+
+    #[test]
+    fn test_ida_star_without_heuristic() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&1).expect("Start node not found");
+
+        let (path, distance) =
+            ida_star(start_node, &5, |_| 0, |key| nodes.get(key)).expect("IDA* failed");
+        assert_eq!(path, vec![&1, &3, &6, &5]);
+        assert_eq!(distance, 20);
+    }
+
+    #[test]
+    fn test_ida_star_with_heuristic() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&1).expect("Start node not found");
+
+        // Half of the true remaining distance, which is admissible.
+        let remaining: HashMap<u8, u32> =
+            HashMap::from([(1, 10), (2, 10), (3, 5), (4, 3), (5, 0), (6, 4)]);
+        let (path, distance) = ida_star(
+            start_node,
+            &5,
+            |node: &TestNode| remaining[node.id()],
+            |key| nodes.get(key),
+        )
+        .expect("IDA* failed");
+        assert_eq!(path, vec![&1, &3, &6, &5]);
+        assert_eq!(distance, 20);
+    }
+
+    #[test]
+    fn test_ida_star_not_connected() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&5).expect("Start node not found");
+
+        assert!(matches!(
+            ida_star(start_node, &1, |_| 0, |key| nodes.get(key)),
+            Err(SimpleGraphError::NodeNotConnected { .. })
+        ));
+    }
+
+    #[test]
+    fn test_iterative_deepening_monitored() {
+        let nodes = build_nodes();
+        let start_node = nodes.get(&1).expect("Start node not found");
+
+        let mut bounds = Vec::new();
+        let mut monitor = SearchMonitor::new().on_progress(1, |progress| {
+            bounds.push(progress.best_distance);
+        });
+        let (_, distance) =
+            ida_star_monitored(start_node, &5, |_| 0, &mut monitor, |key| nodes.get(key))
+                .expect("IDA* failed");
+        assert_eq!(distance, 20);
+        assert!(monitor.expanded() > 0);
+        drop(monitor);
+
+        // Bounds only ever increase between passes.
+        assert!(bounds.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(
+            bounds
+                .iter()
+                .all(|bound| bound.is_some_and(|bound| bound <= 20))
+        );
+
+        let mut monitor = SearchMonitor::new();
+        iddfs_monitored(start_node, &5, 10, &mut monitor, |key| nodes.get(key))
+            .expect("IDDFS failed");
+        assert!(monitor.expanded() > 0);
+    }
+}
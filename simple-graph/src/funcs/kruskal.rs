@@ -0,0 +1,250 @@
+//! Minimum spanning forests from a list of weighted edges, using Kruskal's algorithm.
+//!
+//! Unlike the other searches in this crate, these work on nodes numbered ``0..len``
+//! rather than on [`IsNode`](crate::traits::IsNode)s, as Kruskal's algorithm only ever
+//! needs every edge at once, sorted by weight.
+
+use std::{cmp::Ordering, fmt::Debug};
+
+/// A disjoint-set forest (union-find) over the nodes ``0..len``.
+///
+/// Finding the set of a node halves the path to its root along the way, and
+/// [`Self::union`] hangs the smaller tree under the larger, so that each operation is
+/// amortised ``O(α(n))``.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisjointSet {
+    /// The parent of each node; roots are their own parents.
+    parents: Vec<usize>,
+    /// The number of nodes under each root; only kept up to date for roots.
+    sizes: Vec<usize>,
+    /// The number of disjoint sets.
+    sets: usize,
+}
+
+impl DisjointSet {
+    /// Start with each of ``len`` nodes in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            sets: len,
+        }
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Whether there are no nodes at all.
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+
+    /// The root of the set containing ``node``, which stands for the whole set.
+    ///
+    /// Panics if ``node`` is out of bounds.
+    pub fn find(&mut self, mut node: usize) -> usize {
+        while self.parents[node] != node {
+            self.parents[node] = self.parents[self.parents[node]];
+            node = self.parents[node];
+        }
+        node
+    }
+
+    /// Whether ``a`` and ``b`` are in the same set.
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of nodes in the set containing ``node``.
+    pub fn size_of(&mut self, node: usize) -> usize {
+        let root = self.find(node);
+        self.sizes[root]
+    }
+
+    /// The sizes of every set, in no particular order.
+    pub fn set_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(|&(node, &parent)| node == parent)
+            .map(|(root, _)| self.sizes[root])
+    }
+
+    /// Merge the sets containing ``a`` and ``b``, returning ``false`` if they were
+    /// already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (root_a, root_b) = (self.find(a), self.find(b));
+        if root_a == root_b {
+            return false;
+        }
+
+        let (root, child) = if self.sizes[root_a] >= self.sizes[root_b] {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        self.sets -= 1;
+
+        true
+    }
+}
+
+/// An edge considered by [`Kruskal`], and whether it joined two trees of the forest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KruskalEdge<D> {
+    pub from: usize,
+    pub to: usize,
+    pub weight: D,
+    /// ``false`` if ``from`` and ``to`` were already in the same tree, i.e. the edge is
+    /// not part of the minimum spanning forest.
+    pub joined: bool,
+}
+
+/// Kruskal's algorithm, as an iterator over every edge in order of weight.
+///
+/// Each edge is yielded whether or not it joins two trees, so that the state of the
+/// forest after any number of edges can be inspected through
+/// [`disjoint_set`](Self::disjoint_set); filter on [`KruskalEdge::joined`], or use
+/// [`minimum_spanning_forest`], for the forest alone.
+///
+/// Edges of equal weight are considered in the order they were given. Weights that
+/// cannot be compared, such as ``NaN``, are treated as equal to everything.
+#[derive(Debug, Clone)]
+pub struct Kruskal<D> {
+    edges: std::vec::IntoIter<(usize, usize, D)>,
+    disjoint_set: DisjointSet,
+}
+
+impl<D: PartialOrd> Kruskal<D> {
+    /// Sort ``edges`` between the nodes ``0..len`` by weight.
+    ///
+    /// Panics on iteration if an edge refers to a node out of bounds.
+    pub fn new(len: usize, edges: impl IntoIterator<Item = (usize, usize, D)>) -> Self {
+        let mut edges = edges.into_iter().collect::<Vec<_>>();
+        edges.sort_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        Self {
+            edges: edges.into_iter(),
+            disjoint_set: DisjointSet::new(len),
+        }
+    }
+
+    /// The trees of the forest, as of the last edge yielded.
+    pub fn disjoint_set(&self) -> &DisjointSet {
+        &self.disjoint_set
+    }
+
+    /// The weight of the next edge, without considering it; e.g. to tell whether the
+    /// last edge yielded was the last of a run of equal weights.
+    pub fn peek_weight(&self) -> Option<&D> {
+        self.edges.as_slice().first().map(|(_, _, weight)| weight)
+    }
+}
+
+impl<D> Iterator for Kruskal<D> {
+    type Item = KruskalEdge<D>;
+
+    fn next(&mut self) -> Option<KruskalEdge<D>> {
+        let (from, to, weight) = self.edges.next()?;
+        let joined = self.disjoint_set.union(from, to);
+
+        Some(KruskalEdge {
+            from,
+            to,
+            weight,
+            joined,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.edges.size_hint()
+    }
+}
+
+/// The edges of a minimum spanning forest of the nodes ``0..len``, in order of weight;
+/// see [`Kruskal`].
+///
+/// If the graph is connected, this is a minimum spanning tree of ``len - 1`` edges.
+pub fn minimum_spanning_forest<D: PartialOrd>(
+    len: usize,
+    edges: impl IntoIterator<Item = (usize, usize, D)>,
+) -> Vec<KruskalEdge<D>> {
+    Kruskal::new(len, edges)
+        .filter(|edge| edge.joined)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests_kruskal {
+    use super::*;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut set = DisjointSet::new(6);
+        assert_eq!(set.sets(), 6);
+
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+
+        assert!(set.same_set(0, 3));
+        assert!(!set.same_set(0, 4));
+        assert_eq!(set.size_of(2), 4);
+        assert_eq!(set.sets(), 3);
+
+        let mut sizes = set.set_sizes().collect::<Vec<_>>();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 1, 4]);
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest() {
+        // Two components: a square with both diagonals, and a lone edge.
+        let edges = vec![
+            (0, 1, 1),
+            (1, 2, 2),
+            (2, 3, 1),
+            (3, 0, 3),
+            (0, 2, 5),
+            (1, 3, 4),
+            (4, 5, 7),
+        ];
+
+        let forest = minimum_spanning_forest(7, edges);
+        assert_eq!(
+            forest
+                .iter()
+                .map(|edge| (edge.from, edge.to, edge.weight))
+                .collect::<Vec<_>>(),
+            vec![(0, 1, 1), (2, 3, 1), (1, 2, 2), (4, 5, 7)]
+        );
+        assert_eq!(forest.iter().map(|edge| edge.weight).sum::<i32>(), 11);
+    }
+
+    #[test]
+    fn test_kruskal_steps() {
+        let mut kruskal = Kruskal::new(3, vec![(0, 1, 2.0), (1, 2, 1.0), (0, 2, 3.0)]);
+
+        assert!(
+            kruskal
+                .next()
+                .is_some_and(|edge| edge.joined && edge.weight == 1.0)
+        );
+        assert_eq!(kruskal.disjoint_set().sets(), 2);
+        assert_eq!(kruskal.peek_weight(), Some(&2.0));
+        assert!(kruskal.next().is_some_and(|edge| edge.joined));
+        assert!(kruskal.next().is_some_and(|edge| !edge.joined));
+        assert_eq!(kruskal.disjoint_set().sets(), 1);
+        assert_eq!(kruskal.next(), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Lowest common ancestor queries on trees, using binary lifting.
+
+use super::tree::RootedTree;
+use crate::{SimpleGraphError, traits};
+use num_traits::Zero;
+use std::{cmp::Ord, fmt::Debug, hash::Hash, ops::Sub};
+
+/// A lowest common ancestor (LCA) index over a [`RootedTree`].
+///
+/// Building the index takes ``O(n log n)`` time and memory; each query afterwards takes
+/// ``O(log n)``, regardless of how unbalanced the tree is.
+#[derive(Debug, Clone)]
+pub struct Lca<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    tree: RootedTree<'s, K, D>,
+    /// ``ancestors[j][i]`` is the index of the ``2^j``-th ancestor of the node at index
+    /// ``i``, or of the root if there are not that many ancestors.
+    ancestors: Vec<Vec<usize>>,
+}
+
+impl<'s, K, D> Lca<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+{
+    /// Build an LCA index for the tree of all nodes reachable from ``root``.
+    ///
+    /// Returns [`SimpleGraphError::NotATree`] if the reachable graph is not a tree.
+    pub fn new<N>(
+        root: &'s N,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Self, SimpleGraphError<K, D>>
+    where
+        N: traits::IsNode<'s, K, D> + 's,
+    {
+        RootedTree::new(root, get_node_by_key).map(Self::from_tree)
+    }
+}
+
+impl<'s, K, D> Lca<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// Build an LCA index for an already rooted tree.
+    pub fn from_tree(tree: RootedTree<'s, K, D>) -> Self {
+        let len = tree.len();
+        // Enough levels to jump from the deepest possible node straight to the root.
+        let levels = (usize::BITS - len.leading_zeros()).max(1) as usize;
+
+        let mut ancestors: Vec<Vec<usize>> = Vec::with_capacity(levels);
+        ancestors.push(
+            (0..len)
+                .map(|index| tree.parent_index(index).unwrap_or(0))
+                .collect(),
+        );
+
+        for level in 1..levels {
+            let previous = &ancestors[level - 1];
+            let next = (0..len)
+                .map(|index| previous[previous[index]])
+                .collect::<Vec<usize>>();
+            ancestors.push(next);
+        }
+
+        Self { tree, ancestors }
+    }
+
+    /// The underlying rooted tree.
+    pub fn tree(&self) -> &RootedTree<'s, K, D> {
+        &self.tree
+    }
+
+    /// Jump ``steps`` ancestors up from the node at ``index``.
+    fn lift(&self, mut index: usize, steps: usize) -> usize {
+        for (level, ancestors) in self.ancestors.iter().enumerate() {
+            if steps & (1 << level) != 0 {
+                index = ancestors[index];
+            }
+        }
+
+        index
+    }
+
+    /// The index of the lowest common ancestor of the nodes at ``a`` and ``b``.
+    fn lca_index(&self, a: usize, b: usize) -> usize {
+        let (depth_a, depth_b) = (self.tree.depth_at(a), self.tree.depth_at(b));
+        let (mut a, mut b) = if depth_a >= depth_b {
+            (self.lift(a, depth_a - depth_b), b)
+        } else {
+            (a, self.lift(b, depth_b - depth_a))
+        };
+
+        if a == b {
+            return a;
+        }
+
+        for ancestors in self.ancestors.iter().rev() {
+            if ancestors[a] != ancestors[b] {
+                a = ancestors[a];
+                b = ancestors[b];
+            }
+        }
+
+        self.ancestors[0][a]
+    }
+
+    /// The ID of the deepest node that is an ancestor of both ``a`` and ``b``.
+    ///
+    /// A node counts as its own ancestor, so if ``a`` is an ancestor of ``b`` the result
+    /// is ``a``. Returns [`None`] if either node is not part of the tree.
+    pub fn lca(&self, a: &K, b: &K) -> Option<&'s K> {
+        let a = self.tree.index_of(a)?;
+        let b = self.tree.index_of(b)?;
+
+        Some(self.tree.id_at(self.lca_index(a, b)))
+    }
+
+    /// The number of edges on the path between ``a`` and ``b``.
+    ///
+    /// Returns [`None`] if either node is not part of the tree.
+    pub fn distance(&self, a: &K, b: &K) -> Option<usize> {
+        let a = self.tree.index_of(a)?;
+        let b = self.tree.index_of(b)?;
+        let lca = self.lca_index(a, b);
+
+        Some(self.tree.depth_at(a) + self.tree.depth_at(b) - 2 * self.tree.depth_at(lca))
+    }
+
+    /// The total distance of the edges on the path between ``a`` and ``b``.
+    ///
+    /// Returns [`None`] if either node is not part of the tree.
+    pub fn weighted_distance(&self, a: &K, b: &K) -> Option<D>
+    where
+        D: Zero + Clone + Sub<Output = D>,
+    {
+        let a = self.tree.index_of(a)?;
+        let b = self.tree.index_of(b)?;
+        let lca_distance = self.tree.distance_at(self.lca_index(a, b)).clone();
+
+        // Subtract from each side separately, so that unsigned types never underflow.
+        Some(
+            (self.tree.distance_at(a).clone() - lca_distance.clone())
+                + (self.tree.distance_at(b).clone() - lca_distance),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests_lca {
+    use super::*;
+    use crate::funcs::tree::tests_tree::build_tree_nodes;
+
+    #[test]
+    fn test_lca() {
+        let nodes = build_tree_nodes();
+        let lca = Lca::new(&nodes[&1], |key| nodes.get(key)).expect("Failed to build LCA");
+
+        assert_eq!(lca.lca(&4, &5), Some(&2));
+        assert_eq!(lca.lca(&4, &7), Some(&1));
+        assert_eq!(lca.lca(&3, &7), Some(&3));
+        assert_eq!(lca.lca(&7, &7), Some(&7));
+        assert_eq!(lca.lca(&1, &8), None);
+    }
+
+    #[test]
+    fn test_distance() {
+        let nodes = build_tree_nodes();
+        let lca = Lca::new(&nodes[&1], |key| nodes.get(key)).expect("Failed to build LCA");
+
+        assert_eq!(lca.distance(&4, &5), Some(2));
+        assert_eq!(lca.distance(&4, &7), Some(5));
+        assert_eq!(lca.distance(&6, &6), Some(0));
+        assert_eq!(lca.weighted_distance(&4, &5), Some(6));
+        assert_eq!(lca.weighted_distance(&4, &7), Some(21));
+        assert_eq!(lca.weighted_distance(&3, &7), Some(9));
+    }
+
+    #[test]
+    fn test_lca_rooted_elsewhere() {
+        let nodes = build_tree_nodes();
+        let lca = Lca::new(&nodes[&6], |key| nodes.get(key)).expect("Failed to build LCA");
+
+        // Rooting changes ancestry, but not distances.
+        assert_eq!(lca.lca(&4, &5), Some(&2));
+        assert_eq!(lca.lca(&4, &7), Some(&6));
+        assert_eq!(lca.lca(&1, &4), Some(&1));
+        assert_eq!(lca.distance(&4, &7), Some(5));
+        assert_eq!(lca.weighted_distance(&4, &7), Some(21));
+    }
+}
@@ -1,8 +1,39 @@
 mod dijkstra;
-pub use dijkstra::dijkstra;
+pub use dijkstra::{dijkstra, dijkstra_monitored, dijkstra_within_budget};
 
 mod dfs;
 pub use dfs::*;
 
+mod multi_source;
+pub use multi_source::{NearestSources, multi_source_bfs, multi_source_dijkstra};
+
+mod topological;
+pub use topological::topological_sort;
+
+mod scc;
+pub use scc::strongly_connected_components;
+
+mod reachability;
+pub use reachability::Reachability;
+
+mod path_count;
+pub use path_count::{PathCountDirection, PathCountMatrix, PathCountOracle};
+
+mod iterative_deepening;
+pub use iterative_deepening::{ida_star, ida_star_monitored, iddfs, iddfs_monitored};
+
+mod branch_and_bound;
+pub use branch_and_bound::{BranchAndBoundSolution, branch_and_bound};
+
+mod flow;
+pub use flow::{EdgeDisjointPaths, FlowNetwork, edge_disjoint_paths};
+
+mod tree;
+pub use tree::{RootedTree, TreeDiameter, tree_center, tree_diameter};
+mod lca;
+pub use lca::Lca;
+
+mod kruskal;
+pub use kruskal::{DisjointSet, Kruskal, KruskalEdge, minimum_spanning_forest};
 #[cfg(test)]
 pub(crate) mod _tests;
@@ -0,0 +1,221 @@
+//! Searches from many start nodes at once, answering "how far is the nearest of these"
+//! for every reachable node in a single pass.
+
+use crate::{SimpleGraphError, traits, wrapper};
+use num_traits::Zero;
+use std::{
+    cmp::{Ord, Reverse},
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::Debug,
+    hash::Hash,
+};
+
+/// A map from each reachable node to the ID of its nearest source and the distance to it.
+pub type NearestSources<'s, K, D> = HashMap<&'s K, (&'s K, D)>;
+
+/// Frontier of nodes to visit, along with the source they were reached from.
+type SourcedFrontier<'s, K, D, N> =
+    BinaryHeap<(Reverse<D>, wrapper::UnorderedItem<(&'s N, &'s K)>)>;
+
+/// Run Dijkstra's algorithm from multiple sources simultaneously.
+///
+/// Each source comes with an initial distance, which acts as a head start (or a handicap)
+/// for paths leaving it; use [`Zero::zero`] for all of them if the sources are equal.
+///
+/// Returns a map from every reachable node to the ID of its nearest source and the
+/// distance to it. Sources themselves are included, possibly with a different nearest
+/// source if another one can reach them more cheaply than their initial distance.
+pub fn multi_source_dijkstra<'s, K, D, N>(
+    sources: impl IntoIterator<Item = (&'s N, D)>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<NearestSources<'s, K, D>, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D> + 's,
+{
+    let mut nearest_sources: NearestSources<'s, K, D> = HashMap::new();
+    let mut unvisited_distances: SourcedFrontier<'s, K, D, N> = BinaryHeap::new();
+
+    for (source, initial_distance) in sources {
+        unvisited_distances.push((
+            Reverse(initial_distance),
+            wrapper::UnorderedItem::new((source, source.id())),
+        ));
+    }
+
+    while let Some((Reverse(current_distance), wrapper::UnorderedItem((current_node, source_id)))) =
+        unvisited_distances.pop()
+    {
+        if nearest_sources.contains_key(current_node.id()) {
+            // Already reached from a nearer source; this entry is outdated.
+            continue;
+        }
+
+        #[cfg(feature = "trace")]
+        eprintln!(
+            "Visiting node {:?} from source {:?} with distance {:?}",
+            current_node.id(),
+            source_id,
+            current_distance
+        );
+
+        nearest_sources.insert(current_node.id(), (source_id, current_distance.clone()));
+
+        current_node
+            .neighbours(get_node_by_key.clone())
+            .try_for_each(|(neighbour_node, distance)| {
+                if distance < D::zero() {
+                    return Err(SimpleGraphError::NegativeDistance {
+                        start: current_node.id().clone(),
+                        destination: neighbour_node.id().clone(),
+                        distance,
+                    });
+                }
+
+                if !nearest_sources.contains_key(neighbour_node.id()) {
+                    unvisited_distances.push((
+                        Reverse(current_distance.clone() + distance),
+                        wrapper::UnorderedItem::new((neighbour_node, source_id)),
+                    ));
+                }
+
+                Ok(())
+            })?;
+    }
+
+    Ok(nearest_sources)
+}
+
+/// Run a breadth-first search from multiple sources simultaneously, ignoring edge
+/// distances and counting the number of edges instead.
+///
+/// Each source comes with an initial number of steps, with the same semantics as
+/// [`multi_source_dijkstra`]. Returns a map from every reachable node to the ID of its
+/// nearest source and the number of steps to it.
+pub fn multi_source_bfs<'s, K, D, N>(
+    sources: impl IntoIterator<Item = (&'s N, usize)>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> NearestSources<'s, K, usize>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D> + 's,
+{
+    let mut pending_sources = sources.into_iter().collect::<Vec<_>>();
+    // Sorted in descending order so that the nearest source can be popped off the end.
+    pending_sources.sort_by_key(|(_, initial_steps)| Reverse(*initial_steps));
+
+    let mut nearest_sources: NearestSources<'s, K, usize> = HashMap::new();
+    let mut queue: VecDeque<(&'s N, &'s K, usize)> = VecDeque::new();
+
+    loop {
+        // The queue is always sorted by steps, as is the list of pending sources; merge the
+        // two by taking whichever is nearer.
+        let next_source_is_nearer = match (pending_sources.last(), queue.front()) {
+            (Some((_, source_steps)), Some((_, _, queued_steps))) => source_steps <= queued_steps,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+
+        let (current_node, source_id, current_steps) = if next_source_is_nearer {
+            let (source, initial_steps) =
+                pending_sources.pop().expect("Unreachable; checked above");
+            (source, source.id(), initial_steps)
+        } else {
+            queue.pop_front().expect("Unreachable; checked above")
+        };
+
+        if nearest_sources.contains_key(current_node.id()) {
+            continue;
+        }
+
+        #[cfg(feature = "trace")]
+        eprintln!(
+            "Visiting node {:?} from source {:?} in {} steps",
+            current_node.id(),
+            source_id,
+            current_steps
+        );
+
+        nearest_sources.insert(current_node.id(), (source_id, current_steps));
+
+        current_node
+            .neighbours(get_node_by_key.clone())
+            .filter(|(neighbour_node, _)| !nearest_sources.contains_key(neighbour_node.id()))
+            .for_each(|(neighbour_node, _)| {
+                queue.push_back((neighbour_node, source_id, current_steps + 1));
+            });
+    }
+
+    nearest_sources
+}
+
+#[cfg(test)]
+mod tests_multi_source {
+    use super::*;
+    use crate::funcs::_tests::*;
+
+    fn build_nodes() -> HashMap<u8, TestNode> {
+        (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect()
+    }
+
+    #[test]
+    fn test_multi_source_dijkstra() {
+        let nodes = build_nodes();
+        let sources = [(&nodes[&2], 0), (&nodes[&6], 0)];
+
+        let nearest = multi_source_dijkstra(sources, |key| nodes.get(key))
+            .expect("Multi-source Dijkstra failed");
+
+        assert_eq!(nearest.get(&1), None);
+        assert_eq!(nearest[&2], (&2, 0));
+        assert_eq!(nearest[&3], (&2, 10));
+        assert_eq!(nearest[&4], (&2, 15));
+        assert_eq!(nearest[&5], (&6, 9));
+        assert_eq!(nearest[&6], (&6, 0));
+    }
+
+    #[test]
+    fn test_multi_source_dijkstra_with_initial_distances() {
+        let nodes = build_nodes();
+        let sources = [(&nodes[&2], 0), (&nodes[&6], 13)];
+
+        let nearest = multi_source_dijkstra(sources, |key| nodes.get(key))
+            .expect("Multi-source Dijkstra failed");
+
+        // 6 is now nearer to 2 (via 3) than its own head start.
+        assert_eq!(nearest[&6], (&2, 12));
+        assert_eq!(nearest[&5], (&2, 21));
+    }
+
+    #[test]
+    fn test_multi_source_bfs() {
+        let nodes = build_nodes();
+
+        let nearest = multi_source_bfs([(&nodes[&1], 0)], |key| nodes.get(key));
+        assert_eq!(nearest[&1], (&1, 0));
+        assert_eq!(nearest[&2], (&1, 1));
+        assert_eq!(nearest[&3], (&1, 1));
+        assert_eq!(nearest[&4], (&1, 2));
+        assert_eq!(nearest[&5], (&1, 2));
+        assert_eq!(nearest[&6], (&1, 1));
+
+        let nearest = multi_source_bfs([(&nodes[&1], 0), (&nodes[&4], 0)], |key| nodes.get(key));
+        assert_eq!(nearest[&4], (&4, 0));
+        assert_eq!(nearest[&5], (&4, 1));
+        assert_eq!(nearest[&6], (&1, 1));
+    }
+
+    #[test]
+    fn test_multi_source_bfs_with_initial_steps() {
+        let nodes = build_nodes();
+
+        let nearest = multi_source_bfs([(&nodes[&1], 0), (&nodes[&4], 3)], |key| nodes.get(key));
+        assert_eq!(nearest[&4], (&1, 2));
+        assert_eq!(nearest[&5], (&1, 2));
+    }
+}
@@ -0,0 +1,372 @@
+use super::topological::topological_sort;
+use crate::{SimpleGraphError, traits};
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, fmt::Debug, hash::Hash};
+
+/// Which end of the paths a [`PathCountOracle`] is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathCountDirection {
+    /// Counts are the number of paths from each node *to* the anchor.
+    ToDestination,
+    /// Counts are the number of paths *from* the anchor to each node.
+    FromSource,
+}
+
+/// A pre-computed table of the number of paths between one fixed node and every other
+/// node of a directed acyclic graph.
+///
+/// [`crate::dfs_count`] rebuilds its memoization every time it is called; when the same
+/// destination is queried from many different starts, this oracle builds the table in a
+/// single topological sweep, after which [`PathCountOracle::count`] is a hash lookup.
+///
+/// Both directions are supported without building an inverted graph:
+/// [`PathCountOracle::to_destination`] counts paths from anything into a fixed
+/// destination, while [`PathCountOracle::from_source`] counts paths from a fixed source
+/// to anything.
+#[derive(Debug, Clone)]
+pub struct PathCountOracle<'s, K>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    anchor: &'s K,
+    direction: PathCountDirection,
+    counts: FxHashMap<&'s K, usize>,
+}
+
+impl<'s, K> PathCountOracle<'s, K>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// Build an oracle counting the paths from each of ``nodes`` (and anything reachable
+    /// from them) to ``destination_id``.
+    pub fn to_destination<D, N>(
+        destination_id: &'s K,
+        nodes: impl IntoIterator<Item = &'s N>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Self, SimpleGraphError<K, D>>
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNodeWithIndexedNeighbours<'s, K, D> + 's,
+    {
+        let order = topological_sort(nodes, get_node_by_key.clone())?;
+        let mut counts: FxHashMap<&'s K, usize> =
+            FxHashMap::with_capacity_and_hasher(order.len(), Default::default());
+        counts.insert(destination_id, 1);
+
+        // Reverse topological order guarantees every neighbour is counted before the node.
+        for node in order.into_iter().rev() {
+            if node.id() == destination_id {
+                continue;
+            }
+
+            let count = node
+                .neighbours(get_node_by_key.clone())
+                .map(|(neighbour, _)| counts.get(neighbour.id()).copied().unwrap_or_default())
+                .sum::<usize>();
+
+            if count > 0 {
+                counts.insert(node.id(), count);
+            }
+        }
+
+        Ok(Self {
+            anchor: destination_id,
+            direction: PathCountDirection::ToDestination,
+            counts,
+        })
+    }
+
+    /// Build an oracle counting the paths from ``source`` to every node reachable from it.
+    ///
+    /// This is equivalent to [`PathCountOracle::to_destination`] on the inverted graph.
+    pub fn from_source<D, N>(
+        source: &'s N,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Self, SimpleGraphError<K, D>>
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNodeWithIndexedNeighbours<'s, K, D> + 's,
+    {
+        let order = topological_sort([source], get_node_by_key.clone())?;
+        let mut counts: FxHashMap<&'s K, usize> =
+            FxHashMap::with_capacity_and_hasher(order.len(), Default::default());
+        counts.insert(source.id(), 1);
+
+        // Topological order guarantees every node is final before it is propagated.
+        for node in order {
+            let count = counts.get(node.id()).copied().unwrap_or_default();
+            node.neighbours(get_node_by_key.clone())
+                .for_each(|(neighbour, _)| {
+                    *counts.entry(neighbour.id()).or_default() += count;
+                });
+        }
+
+        Ok(Self {
+            anchor: source.id(),
+            direction: PathCountDirection::FromSource,
+            counts,
+        })
+    }
+
+    /// The fixed end of every path counted by this oracle.
+    pub fn anchor(&self) -> &'s K {
+        self.anchor
+    }
+
+    /// Whether [`PathCountOracle::anchor`] is the destination or the source of the paths.
+    pub fn direction(&self) -> PathCountDirection {
+        self.direction
+    }
+
+    /// The number of paths between ``node_id`` and the anchor, in the direction of this
+    /// oracle.
+    ///
+    /// The anchor itself counts as having exactly one (empty) path, so that counts of
+    /// consecutive segments can be multiplied together. Nodes that are not connected to
+    /// the anchor, or were never seen while building the oracle, have a count of zero.
+    pub fn count(&self, node_id: &K) -> usize {
+        self.counts.get(node_id).copied().unwrap_or_default()
+    }
+}
+
+/// A pre-computed table of the number of paths between every ordered pair of nodes of a
+/// directed acyclic graph.
+///
+/// Where a [`PathCountOracle`] is anchored to a single node, this covers every pair at
+/// once, e.g. to chain the counts of several segments of a path through mandatory nodes.
+/// Nodes are indexed in topological order, so the count from a node to any node with a
+/// lower index is always zero. The table takes ``O(n^2)`` memory.
+#[derive(Debug, Clone)]
+pub struct PathCountMatrix<'s, K>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    ids: Vec<&'s K>,
+    indices: FxHashMap<&'s K, usize>,
+    /// Row ``i`` holds the number of paths from the node at index ``i`` to every node.
+    counts: Vec<usize>,
+}
+
+impl<'s, K> PathCountMatrix<'s, K>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// Count the paths between every pair of nodes reachable from ``nodes``.
+    pub fn new<D, N>(
+        nodes: impl IntoIterator<Item = &'s N>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Self, SimpleGraphError<K, D>>
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNodeWithIndexedNeighbours<'s, K, D> + 's,
+    {
+        let order = topological_sort(nodes, get_node_by_key.clone())?;
+        let len = order.len();
+        let ids = order.iter().map(|node| node.id()).collect::<Vec<&'s K>>();
+        let indices = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect::<FxHashMap<&'s K, usize>>();
+        let mut counts = vec![0usize; len * len];
+
+        // Reverse topological order guarantees every neighbour's row is complete first.
+        for (index, node) in order.iter().enumerate().rev() {
+            let (earlier_rows, later_rows) = counts.split_at_mut((index + 1) * len);
+            let row = &mut earlier_rows[index * len..];
+            row[index] = 1;
+
+            for (neighbour, _) in node.neighbours(get_node_by_key.clone()) {
+                let neighbour_index = indices[neighbour.id()];
+                let neighbour_row = &later_rows
+                    [(neighbour_index - index - 1) * len..(neighbour_index - index) * len];
+
+                // Only nodes after the neighbour can be reached from it.
+                row[neighbour_index..]
+                    .iter_mut()
+                    .zip(&neighbour_row[neighbour_index..])
+                    .for_each(|(count, neighbour_count)| *count += neighbour_count);
+            }
+        }
+
+        Ok(Self {
+            ids,
+            indices,
+            counts,
+        })
+    }
+
+    /// The number of nodes in the table.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the table has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The IDs of all nodes in the table, in topological order.
+    pub fn ids(&self) -> &[&'s K] {
+        &self.ids
+    }
+
+    /// The index of ``node_id`` in the table, or [`None`] if it was never seen.
+    pub fn index_of(&self, node_id: &K) -> Option<usize> {
+        self.indices.get(node_id).copied()
+    }
+
+    /// The number of paths from the node at index ``from`` to every node, by index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if ``from`` is out of bounds.
+    pub fn row(&self, from: usize) -> &[usize] {
+        &self.counts[from * self.len()..(from + 1) * self.len()]
+    }
+
+    /// The number of paths from the node at index ``from`` to the node at index ``to``.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn count_by_index(&self, from: usize, to: usize) -> usize {
+        self.row(from)[to]
+    }
+
+    /// The number of paths from ``from`` to ``to``.
+    ///
+    /// A node has exactly one (empty) path to itself, as in [`PathCountOracle::count`].
+    /// Nodes that were never seen have a count of zero.
+    pub fn count(&self, from: &K, to: &K) -> usize {
+        match (self.index_of(from), self.index_of(to)) {
+            (Some(from), Some(to)) => self.count_by_index(from, to),
+            _ => 0,
+        }
+    }
+
+    /// The number of paths visiting all of ``waypoints`` in the given order, i.e. the
+    /// product of the counts of each consecutive pair.
+    ///
+    /// Since the graph is acyclic, a path can never visit a node twice, so the segments
+    /// never overlap. Fewer than two waypoints count as a single path.
+    pub fn count_via(&self, waypoints: &[&K]) -> usize {
+        waypoints
+            .windows(2)
+            .map(|pair| self.count(pair[0], pair[1]))
+            .product()
+    }
+}
+
+#[cfg(test)]
+mod tests_path_count {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use std::collections::HashMap;
+
+    fn build_nodes() -> HashMap<u8, TestNode> {
+        (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect()
+    }
+
+    #[test]
+    fn test_to_destination() {
+        let nodes = build_nodes();
+        let oracle = PathCountOracle::to_destination(&5, nodes.values(), |key| nodes.get(key))
+            .expect("Failed to build oracle");
+
+        assert_eq!(oracle.direction(), PathCountDirection::ToDestination);
+        assert_eq!(oracle.count(&1), 6);
+        assert_eq!(oracle.count(&2), 3);
+        assert_eq!(oracle.count(&3), 2);
+        assert_eq!(oracle.count(&4), 1);
+        assert_eq!(oracle.count(&6), 1);
+        assert_eq!(oracle.count(&5), 1);
+        assert_eq!(oracle.count(&7), 0);
+    }
+
+    #[test]
+    fn test_to_intermediate_destination() {
+        let nodes = build_nodes();
+        let oracle = PathCountOracle::to_destination(&3, nodes.values(), |key| nodes.get(key))
+            .expect("Failed to build oracle");
+
+        assert_eq!(oracle.count(&1), 2);
+        assert_eq!(oracle.count(&2), 1);
+        assert_eq!(oracle.count(&4), 0);
+        assert_eq!(oracle.count(&5), 0);
+    }
+
+    #[test]
+    fn test_from_source() {
+        let nodes = build_nodes();
+        let oracle = PathCountOracle::from_source(&nodes[&1], |key| nodes.get(key))
+            .expect("Failed to build oracle");
+
+        assert_eq!(oracle.direction(), PathCountDirection::FromSource);
+        assert_eq!(oracle.anchor(), &1);
+        assert_eq!(oracle.count(&1), 1);
+        assert_eq!(oracle.count(&2), 1);
+        assert_eq!(oracle.count(&3), 2);
+        assert_eq!(oracle.count(&4), 3);
+        assert_eq!(oracle.count(&6), 3);
+        assert_eq!(oracle.count(&5), 6);
+    }
+
+    #[test]
+    fn test_matrix() {
+        let nodes = build_nodes();
+        let matrix = PathCountMatrix::new(nodes.values(), |key| nodes.get(key))
+            .expect("Failed to build matrix");
+
+        assert_eq!(matrix.len(), 6);
+        // Agrees with the oracles anchored at either end.
+        let to_five = PathCountOracle::to_destination(&5, nodes.values(), |key| nodes.get(key))
+            .expect("Failed to build oracle");
+        let from_one = PathCountOracle::from_source(&nodes[&1], |key| nodes.get(key))
+            .expect("Failed to build oracle");
+        (1..=6).for_each(|id| {
+            assert_eq!(matrix.count(&id, &5), to_five.count(&id));
+            assert_eq!(matrix.count(&1, &id), from_one.count(&id));
+        });
+
+        assert_eq!(matrix.count(&2, &6), 1);
+        assert_eq!(matrix.count(&3, &4), 1);
+        assert_eq!(matrix.count(&4, &3), 0);
+        assert_eq!(matrix.count(&1, &7), 0);
+
+        let (from, to) = (matrix.index_of(&1).unwrap(), matrix.index_of(&5).unwrap());
+        assert_eq!(matrix.count_by_index(from, to), 6);
+        assert_eq!(
+            matrix.row(from).iter().sum::<usize>(),
+            1 + 1 + 2 + 3 + 3 + 6
+        );
+    }
+
+    #[test]
+    fn test_matrix_count_via() {
+        let nodes = build_nodes();
+        let matrix = PathCountMatrix::new(nodes.values(), |key| nodes.get(key))
+            .expect("Failed to build matrix");
+
+        assert_eq!(matrix.count_via(&[&1, &3, &5]), 2 * 2);
+        assert_eq!(matrix.count_via(&[&1, &2, &3, &6, &5]), 1);
+        assert_eq!(matrix.count_via(&[&1, &6, &4]), 0);
+        assert_eq!(matrix.count_via(&[&1]), 1);
+    }
+
+    #[test]
+    fn test_cyclic_graph() {
+        const CYCLIC_CONNECTIONS: &[(u8, u8, u32)] = &[(1, 2, 1), (2, 3, 1), (3, 1, 1)];
+        let nodes: HashMap<u8, TestNode> = (1..=3)
+            .map(|id| (id, TestNode::new_with_connections(id, CYCLIC_CONNECTIONS)))
+            .collect();
+
+        assert!(matches!(
+            PathCountOracle::from_source(&nodes[&1], |key| nodes.get(key)),
+            Err(SimpleGraphError::CycleDetected { .. })
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+//! Pre-computed reachability between every pair of nodes, stored as bitsets.
+
+use super::scc::strongly_connected_components;
+use crate::traits;
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, fmt::Debug, hash::Hash};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// The transitive closure of a directed graph, answering whether one node can reach
+/// another in ``O(1)``.
+///
+/// Every strongly connected component shares a single row of ``n`` bits, so building the
+/// closure takes ``O(c * n / 64)`` memory for ``c`` components; this is intended for graphs
+/// of up to a few thousand nodes.
+///
+/// Every node is considered to reach itself, even if it is not on a cycle.
+#[derive(Debug, Clone)]
+pub struct Reachability<'s, K>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    ids: Vec<&'s K>,
+    /// The index of each node into ``ids``, and of its component into ``rows``.
+    indices: FxHashMap<&'s K, (usize, usize)>,
+    /// The number of [`u64`] words in each row.
+    words: usize,
+    /// The bits of all rows, one after another; bit ``i`` of a row is set if the node
+    /// at index ``i`` is reachable from the component.
+    rows: Vec<u64>,
+}
+
+impl<'s, K> Reachability<'s, K>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// Compute the reachability between all nodes reachable from ``starts``.
+    ///
+    /// The components are processed in reverse topological order, so that the row of
+    /// every component reached from another one is complete by the time it is merged.
+    pub fn new<D, N>(
+        starts: impl IntoIterator<Item = &'s N>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Self
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNodeWithIndexedNeighbours<'s, K, D> + 's,
+    {
+        let components = strongly_connected_components(starts, get_node_by_key.clone());
+
+        let mut ids: Vec<&'s K> = Vec::new();
+        let mut indices: FxHashMap<&'s K, (usize, usize)> = FxHashMap::default();
+        for (component_index, component) in components.iter().enumerate() {
+            for node in component {
+                indices.insert(node.id(), (ids.len(), component_index));
+                ids.push(node.id());
+            }
+        }
+
+        let words = ids.len().div_ceil(WORD_BITS);
+        let mut rows = vec![0u64; words * components.len()];
+
+        for (component_index, component) in components.iter().enumerate() {
+            // Components reachable from this one always come earlier.
+            let (earlier_rows, later_rows) = rows.split_at_mut(component_index * words);
+            let row = &mut later_rows[..words];
+
+            for node in component {
+                let (node_index, _) = indices[node.id()];
+                row[node_index / WORD_BITS] |= 1 << (node_index % WORD_BITS);
+
+                for (neighbour, _) in node.neighbours(get_node_by_key.clone()) {
+                    let (_, neighbour_component) = indices[neighbour.id()];
+                    if neighbour_component != component_index {
+                        let neighbour_row = &earlier_rows
+                            [neighbour_component * words..(neighbour_component + 1) * words];
+                        row.iter_mut()
+                            .zip(neighbour_row)
+                            .for_each(|(word, neighbour_word)| *word |= neighbour_word);
+                    }
+                }
+            }
+        }
+
+        Self {
+            ids,
+            indices,
+            words,
+            rows,
+        }
+    }
+
+    /// The row of the component ``key`` belongs to.
+    fn row(&self, key: &K) -> Option<&[u64]> {
+        let (_, component) = self.indices.get(key)?;
+        Some(&self.rows[component * self.words..(component + 1) * self.words])
+    }
+
+    /// The number of nodes covered.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether no nodes are covered, which is only the case if there were no starts.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Whether there is a path from ``a`` to ``b``.
+    ///
+    /// Returns `false` if either node is not covered.
+    pub fn can_reach(&self, a: &K, b: &K) -> bool {
+        match (self.row(a), self.indices.get(b)) {
+            (Some(row), Some(&(b_index, _))) => {
+                row[b_index / WORD_BITS] & (1 << (b_index % WORD_BITS)) != 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Iterate over the IDs of all nodes reachable from ``a``, including itself.
+    ///
+    /// Yields nothing if ``a`` is not covered.
+    pub fn reachable_from<'a>(&'a self, a: &K) -> impl Iterator<Item = &'s K> + 'a {
+        self.row(a)
+            .into_iter()
+            .flat_map(|row| row.iter().enumerate())
+            .flat_map(|(word_index, &word)| {
+                (0..WORD_BITS)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| word_index * WORD_BITS + bit)
+            })
+            .map(|index| self.ids[index])
+    }
+
+    /// The number of nodes reachable from ``a``, including itself.
+    pub fn count_reachable(&self, a: &K) -> usize {
+        self.row(a)
+            .map(|row| row.iter().map(|word| word.count_ones() as usize).sum())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests_reachability {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_acyclic() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let reachability = Reachability::new([&nodes[&3]], |key| nodes.get(key));
+
+        assert_eq!(reachability.len(), 4);
+        assert!(reachability.can_reach(&3, &5));
+        assert!(reachability.can_reach(&6, &5));
+        assert!(reachability.can_reach(&4, &4));
+        assert!(!reachability.can_reach(&5, &3));
+        assert!(!reachability.can_reach(&4, &6));
+        // Node 1 is never reached from node 3.
+        assert!(!reachability.can_reach(&1, &3));
+
+        let mut reachable = reachability.reachable_from(&3).copied().collect::<Vec<_>>();
+        reachable.sort();
+        assert_eq!(reachable, vec![3, 4, 5, 6]);
+        assert_eq!(reachability.count_reachable(&6), 2);
+        assert_eq!(reachability.count_reachable(&1), 0);
+    }
+
+    #[test]
+    fn test_cycles() {
+        const CYCLIC_CONNECTIONS: &[(u8, u8, u32)] = &[
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 4, 1),
+            (6, 5, 1),
+        ];
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CYCLIC_CONNECTIONS)))
+            .collect();
+
+        let reachability = Reachability::new(nodes.values(), |key| nodes.get(key));
+
+        assert_eq!(reachability.len(), 6);
+        assert!(reachability.can_reach(&3, &2));
+        assert!(reachability.can_reach(&1, &5));
+        assert!(reachability.can_reach(&5, &4));
+        assert!(!reachability.can_reach(&4, &1));
+        assert!(!reachability.can_reach(&1, &6));
+        assert_eq!(reachability.count_reachable(&2), 5);
+        assert_eq!(reachability.count_reachable(&6), 3);
+    }
+
+    #[test]
+    fn test_many_words() {
+        let connections = (0..200u8)
+            .map(|id| (id, id + 1, 1))
+            .collect::<Vec<(u8, u8, u32)>>();
+        let nodes: HashMap<u8, TestNode> = (0..=200)
+            .map(|id| (id, TestNode::new_with_connections(id, &connections)))
+            .collect();
+
+        let reachability = Reachability::new([&nodes[&0]], |key| nodes.get(key));
+
+        assert_eq!(reachability.len(), 201);
+        assert!(reachability.can_reach(&0, &200));
+        assert!(reachability.can_reach(&70, &130));
+        assert!(!reachability.can_reach(&130, &70));
+        assert_eq!(reachability.count_reachable(&100), 101);
+        assert_eq!(reachability.reachable_from(&199).count(), 2);
+    }
+}
@@ -0,0 +1,183 @@
+use super::dfs::NodeInProgress;
+use crate::traits;
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, fmt::Debug, hash::Hash};
+
+/// The bookkeeping of Tarjan's algorithm for every node visited so far.
+struct Tarjan<'s, K, N> {
+    /// The order each node was first visited in; also the index into the vectors below.
+    indices: FxHashMap<&'s K, usize>,
+    lowlinks: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<&'s N>,
+}
+
+impl<'s, K, N> Tarjan<'s, K, N>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// Give ``node`` the next index and put it on the stack.
+    fn visit<D>(&mut self, node: &'s N)
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNode<'s, K, D>,
+    {
+        let index = self.lowlinks.len();
+        self.indices.insert(node.id(), index);
+        self.lowlinks.push(index);
+        self.on_stack.push(true);
+        self.stack.push(node);
+    }
+
+    /// Pop the component rooted at the node at ``root_index`` off the stack.
+    fn pop_component<D>(&mut self, root_index: usize) -> Vec<&'s N>
+    where
+        D: Zero + Ord + Clone + Debug,
+        N: traits::IsNode<'s, K, D>,
+    {
+        let split_at = self
+            .stack
+            .iter()
+            .rposition(|node| self.indices[node.id()] == root_index)
+            .expect("Unreachable; the root is always on the stack");
+        let component = self.stack.split_off(split_at);
+        component
+            .iter()
+            .for_each(|node| self.on_stack[self.indices[node.id()]] = false);
+        component
+    }
+}
+
+/// Find the strongly connected components of all nodes reachable from ``starts``, using
+/// Tarjan's algorithm.
+///
+/// Every reachable node belongs to exactly one component, possibly on its own. The
+/// components are returned in reverse topological order: no component has an edge to
+/// a component that comes after it.
+pub fn strongly_connected_components<'s, K, D, N>(
+    starts: impl IntoIterator<Item = &'s N>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Vec<Vec<&'s N>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D> + 's,
+{
+    let mut tarjan: Tarjan<'s, K, N> = Tarjan {
+        indices: FxHashMap::default(),
+        lowlinks: Vec::new(),
+        on_stack: Vec::new(),
+        stack: Vec::new(),
+    };
+    let mut components: Vec<Vec<&'s N>> = Vec::new();
+
+    for start in starts {
+        if tarjan.indices.contains_key(start.id()) {
+            continue;
+        }
+
+        tarjan.visit(start);
+        let mut tracker: Vec<NodeInProgress<'s, K, D, N>> =
+            vec![NodeInProgress::new(start, D::zero())];
+
+        while let Some(last_node) = tracker.last_mut() {
+            let last_index = tarjan.indices[last_node.node.id()];
+
+            match last_node.next_unvisited_neighbour(get_node_by_key.clone()) {
+                Some(next_node) => match tarjan.indices.get(next_node.node.id()) {
+                    Some(&next_index) => {
+                        if tarjan.on_stack[next_index] {
+                            tarjan.lowlinks[last_index] =
+                                tarjan.lowlinks[last_index].min(next_index);
+                        }
+                    }
+                    None => {
+                        tarjan.visit(next_node.node);
+                        tracker.push(next_node);
+                    }
+                },
+                None => {
+                    tracker.pop();
+
+                    // This node is the root of a component; everything above it on the
+                    // stack belongs to the same component.
+                    if tarjan.lowlinks[last_index] == last_index {
+                        components.push(tarjan.pop_component(last_index));
+                    }
+
+                    if let Some(parent) = tracker.last() {
+                        let parent_index = tarjan.indices[parent.node.id()];
+                        tarjan.lowlinks[parent_index] =
+                            tarjan.lowlinks[parent_index].min(tarjan.lowlinks[last_index]);
+                    }
+                }
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests_scc {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use crate::traits::IsNode;
+    use std::collections::HashMap;
+
+    fn component_ids(components: Vec<Vec<&TestNode>>) -> Vec<Vec<u8>> {
+        components
+            .into_iter()
+            .map(|component| {
+                let mut ids = component
+                    .into_iter()
+                    .map(|node| *node.id())
+                    .collect::<Vec<u8>>();
+                ids.sort();
+                ids
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_acyclic() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let components = strongly_connected_components([&nodes[&1]], |key| nodes.get(key));
+
+        // Every node is on its own, in reverse topological order.
+        assert_eq!(
+            component_ids(components),
+            vec![vec![5], vec![4], vec![6], vec![3], vec![2], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        const CYCLIC_CONNECTIONS: &[(u8, u8, u32)] = &[
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+            (3, 4, 1),
+            (4, 5, 1),
+            (5, 6, 1),
+            (6, 4, 1),
+            (6, 7, 1),
+            (8, 7, 1),
+        ];
+        let nodes: HashMap<u8, TestNode> = (1..=8)
+            .map(|id| (id, TestNode::new_with_connections(id, CYCLIC_CONNECTIONS)))
+            .collect();
+
+        let components =
+            strongly_connected_components([&nodes[&1], &nodes[&8]], |key| nodes.get(key));
+
+        assert_eq!(
+            component_ids(components),
+            vec![vec![7], vec![4, 5, 6], vec![1, 2, 3], vec![8]]
+        );
+    }
+}
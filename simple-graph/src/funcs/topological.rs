@@ -0,0 +1,117 @@
+use super::dfs::NodeInProgress;
+use crate::{SimpleGraphError, traits};
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, fmt::Debug, hash::Hash};
+
+/// Marks for nodes during a topological sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    /// The node is on the current path; reaching it again means there is a cycle.
+    InProgress,
+    /// All descendants of the node have been sorted.
+    Done,
+}
+
+/// Sort all nodes reachable from ``starts`` topologically, such that every node comes
+/// before all of its neighbours.
+///
+/// Returns [`SimpleGraphError::CycleDetected`] with the IDs of the nodes forming the
+/// cycle, in the order they are connected, if the reachable graph is not acyclic.
+pub fn topological_sort<'s, K, D, N>(
+    starts: impl IntoIterator<Item = &'s N>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<Vec<&'s N>, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNodeWithIndexedNeighbours<'s, K, D> + 's,
+{
+    let mut marks: FxHashMap<&'s K, Mark> = FxHashMap::default();
+    let mut post_order: Vec<&'s N> = Vec::new();
+
+    for start in starts {
+        if marks.contains_key(start.id()) {
+            continue;
+        }
+
+        marks.insert(start.id(), Mark::InProgress);
+        let mut tracker: Vec<NodeInProgress<'s, K, D, N>> =
+            vec![NodeInProgress::new(start, D::zero())];
+
+        while let Some(last_node) = tracker.last_mut() {
+            match last_node.next_unvisited_neighbour(get_node_by_key.clone()) {
+                Some(next_node) => match marks.get(next_node.node.id()) {
+                    Some(Mark::InProgress) => {
+                        let cycle = tracker
+                            .iter()
+                            .map(|n| n.node.id())
+                            .skip_while(|id| *id != next_node.node.id())
+                            .cloned()
+                            .collect::<Vec<K>>();
+
+                        return Err(SimpleGraphError::CycleDetected { cycle });
+                    }
+                    Some(Mark::Done) => {}
+                    None => {
+                        marks.insert(next_node.node.id(), Mark::InProgress);
+                        tracker.push(next_node);
+                    }
+                },
+                None => {
+                    let finished = tracker.pop().expect("Unreachable; checked above");
+                    marks.insert(finished.node.id(), Mark::Done);
+                    post_order.push(finished.node);
+                }
+            }
+        }
+    }
+
+    post_order.reverse();
+    Ok(post_order)
+}
+
+#[cfg(test)]
+mod tests_topological {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use crate::traits::IsNode;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_topological_sort() {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+
+        let order = topological_sort([&nodes[&1]], |key| nodes.get(key))
+            .expect("Topological sort failed")
+            .into_iter()
+            .map(|node| *node.id())
+            .collect::<Vec<u8>>();
+
+        assert_eq!(order.len(), 6);
+        let position = |id: u8| order.iter().position(|other| *other == id).unwrap();
+        CONNECTIONS.iter().for_each(|(start, end, _)| {
+            assert!(
+                position(*start) < position(*end),
+                "{start} should come before {end}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_cycle_detected() {
+        const CYCLIC_CONNECTIONS: &[(u8, u8, u32)] =
+            &[(1, 2, 1), (2, 3, 1), (3, 4, 1), (4, 2, 1), (3, 5, 1)];
+        let nodes: HashMap<u8, TestNode> = (1..=5)
+            .map(|id| (id, TestNode::new_with_connections(id, CYCLIC_CONNECTIONS)))
+            .collect();
+
+        let result = topological_sort([&nodes[&1]], |key| nodes.get(key));
+        assert!(matches!(
+            result,
+            Err(SimpleGraphError::CycleDetected { cycle }) if cycle == vec![2, 3, 4]
+        ));
+    }
+}
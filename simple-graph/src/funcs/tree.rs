@@ -0,0 +1,464 @@
+//! Rooted views of tree-shaped graphs.
+//!
+//! Puzzle inputs that are trees rarely say which way is "up"; [`RootedTree`] picks a root
+//! and works out the parent, depth and distance from the root of every node, validating
+//! that the graph actually is a tree along the way.
+
+use crate::{SimpleGraphError, traits};
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, collections::VecDeque, fmt::Debug, hash::Hash};
+
+/// A tree-shaped graph, rooted at a chosen node.
+///
+/// Edges may be given in either direction or both: an edge from a node back to its own
+/// parent is ignored, so undirected trees (where every edge appears twice) work just as
+/// well as trees whose edges all point away from the root.
+#[derive(Debug, Clone)]
+pub struct RootedTree<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// Node IDs in breadth-first order; the root is always first.
+    ids: Vec<&'s K>,
+    indices: FxHashMap<&'s K, usize>,
+    parents: Vec<Option<usize>>,
+    depths: Vec<usize>,
+    distances: Vec<D>,
+}
+
+impl<'s, K, D> RootedTree<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+{
+    /// Build a rooted tree from every node reachable from ``root``.
+    ///
+    /// Returns [`SimpleGraphError::NotATree`] if any node can be reached in more than one
+    /// way, i.e. the reachable graph contains a cycle or two nodes sharing a child.
+    pub fn new<N>(
+        root: &'s N,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Result<Self, SimpleGraphError<K, D>>
+    where
+        N: traits::IsNode<'s, K, D> + 's,
+    {
+        let mut tree = Self {
+            ids: vec![root.id()],
+            indices: FxHashMap::default(),
+            parents: vec![None],
+            depths: vec![0],
+            distances: vec![D::zero()],
+        };
+        tree.indices.insert(root.id(), 0);
+
+        let mut queue: VecDeque<(&'s N, usize)> = VecDeque::from([(root, 0)]);
+
+        while let Some((current_node, current_index)) = queue.pop_front() {
+            let parent_index = tree.parents[current_index];
+
+            for (neighbour_node, distance) in current_node.neighbours(get_node_by_key.clone()) {
+                let neighbour_id = neighbour_node.id();
+
+                if parent_index.is_some_and(|parent_index| tree.ids[parent_index] == neighbour_id) {
+                    // The way back up an undirected edge.
+                    continue;
+                }
+
+                if tree.indices.contains_key(neighbour_id) {
+                    return Err(SimpleGraphError::NotATree {
+                        node: neighbour_id.clone(),
+                    });
+                }
+
+                let neighbour_index = tree.ids.len();
+                tree.ids.push(neighbour_id);
+                tree.indices.insert(neighbour_id, neighbour_index);
+                tree.parents.push(Some(current_index));
+                tree.depths.push(tree.depths[current_index] + 1);
+                tree.distances
+                    .push(tree.distances[current_index].clone() + distance);
+                queue.push_back((neighbour_node, neighbour_index));
+            }
+        }
+
+        Ok(tree)
+    }
+}
+
+impl<'s, K, D> RootedTree<'s, K, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+{
+    /// The ID of the root node.
+    pub fn root(&self) -> &'s K {
+        self.ids[0]
+    }
+
+    /// The number of nodes in the tree, including the root.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the tree has no nodes, which is never the case as it always has a root.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Whether ``node_id`` is part of this tree.
+    pub fn contains(&self, node_id: &K) -> bool {
+        self.indices.contains_key(node_id)
+    }
+
+    /// Iterate over the IDs of all nodes in breadth-first order, starting from the root.
+    pub fn ids(&self) -> impl Iterator<Item = &'s K> {
+        self.ids.iter().copied()
+    }
+
+    /// The ID of the parent of ``node_id``, or [`None`] for the root and unknown nodes.
+    pub fn parent(&self, node_id: &K) -> Option<&'s K> {
+        self.index_of(node_id)
+            .and_then(|index| self.parents[index])
+            .map(|parent_index| self.ids[parent_index])
+    }
+
+    /// The number of edges between the root and ``node_id``.
+    pub fn depth(&self, node_id: &K) -> Option<usize> {
+        self.index_of(node_id).map(|index| self.depths[index])
+    }
+
+    /// The total distance of the edges between the root and ``node_id``.
+    pub fn distance_from_root(&self, node_id: &K) -> Option<&D> {
+        self.index_of(node_id).map(|index| &self.distances[index])
+    }
+
+    /// The ID of the node furthest from the root by distance, and that distance.
+    ///
+    /// Ties are broken in favour of the node nearest the root in breadth-first order.
+    pub fn furthest_from_root(&self) -> (&'s K, &D)
+    where
+        D: Ord,
+    {
+        let index = (1..self.len()).fold(0, |furthest, index| {
+            if self.distances[index] > self.distances[furthest] {
+                index
+            } else {
+                furthest
+            }
+        });
+
+        (self.ids[index], &self.distances[index])
+    }
+
+    /// The IDs of the nodes on the path from ``node_id`` up to the root, inclusive.
+    pub fn path_to_root(&self, node_id: &K) -> Option<Vec<&'s K>> {
+        let mut index = self.index_of(node_id)?;
+        let mut path = vec![self.ids[index]];
+
+        while let Some(parent_index) = self.parents[index] {
+            path.push(self.ids[parent_index]);
+            index = parent_index;
+        }
+
+        Some(path)
+    }
+
+    /// The position of ``node_id`` in breadth-first order.
+    pub(crate) fn index_of(&self, node_id: &K) -> Option<usize> {
+        self.indices.get(node_id).copied()
+    }
+
+    /// The position of the parent of the node at ``index``.
+    pub(crate) fn parent_index(&self, index: usize) -> Option<usize> {
+        self.parents[index]
+    }
+
+    /// The ID of the node at ``index``.
+    pub(crate) fn id_at(&self, index: usize) -> &'s K {
+        self.ids[index]
+    }
+
+    /// The depth of the node at ``index``.
+    pub(crate) fn depth_at(&self, index: usize) -> usize {
+        self.depths[index]
+    }
+
+    /// The distance from the root of the node at ``index``.
+    pub(crate) fn distance_at(&self, index: usize) -> &D {
+        &self.distances[index]
+    }
+}
+
+/// The longest path in a tree, as found by [`tree_diameter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeDiameter<'s, K, D> {
+    /// The two ends of the diameter, in the same order as [`TreeDiameter::path`].
+    pub endpoints: (&'s K, &'s K),
+    /// The total distance of the edges on the diameter.
+    pub length: D,
+    /// The IDs of the nodes on the diameter, from one end to the other.
+    pub path: Vec<&'s K>,
+}
+
+/// Re-root ``tree`` at the node furthest from its current root.
+///
+/// Returns [`SimpleGraphError::NodeNotConnected`] if the new root cannot reach every
+/// node of ``tree``, which happens when the edges are not given in both directions.
+fn reroot_at_furthest<'s, K, D, N>(
+    tree: &RootedTree<'s, K, D>,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<RootedTree<'s, K, D>, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D> + 's,
+{
+    let (furthest_id, _) = tree.furthest_from_root();
+    let furthest_node =
+        get_node_by_key(furthest_id).expect("Unreachable; node was found while rooting the tree");
+    let rerooted = RootedTree::new(furthest_node, get_node_by_key)?;
+
+    match tree.ids().find(|id| !rerooted.contains(id)) {
+        Some(missing_id) => Err(SimpleGraphError::NodeNotConnected {
+            start: furthest_id.clone(),
+            destination: missing_id.clone(),
+        }),
+        None => Ok(rerooted),
+    }
+}
+
+/// Find the longest path in the tree containing ``start``, by distance.
+///
+/// This roots the tree at ``start``, then again at the node furthest from it; the node
+/// furthest from *that* is the other end of the diameter. Since every edge needs to be
+/// walkable both ways for this to work, the tree must be undirected, i.e. list every
+/// edge in both directions.
+///
+/// Returns [`SimpleGraphError::NotATree`] if the graph contains a cycle, or
+/// [`SimpleGraphError::NodeNotConnected`] if its edges are one-directional.
+pub fn tree_diameter<'s, K, D, N>(
+    start: &'s N,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<TreeDiameter<'s, K, D>, SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D> + 's,
+{
+    let tree = RootedTree::new(start, get_node_by_key.clone())?;
+    let from_one_end = reroot_at_furthest(&tree, get_node_by_key)?;
+    let (other_end, length) = from_one_end.furthest_from_root();
+
+    Ok(TreeDiameter {
+        endpoints: (other_end, from_one_end.root()),
+        length: length.clone(),
+        path: from_one_end
+            .path_to_root(other_end)
+            .expect("Unreachable; node was found in the tree"),
+    })
+}
+
+/// Find the center of the tree containing ``start``, i.e. the node(s) whose distance to
+/// the furthest node in the tree is the smallest.
+///
+/// Returns the IDs of the center nodes along with that distance, known as the radius of
+/// the tree. There is usually one center, but there are two if they are equally central;
+/// in that case they are adjacent to each other on the diameter.
+///
+/// The same requirements and errors as [`tree_diameter`] apply.
+pub fn tree_center<'s, K, D, N>(
+    start: &'s N,
+    get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+) -> Result<(Vec<&'s K>, D), SimpleGraphError<K, D>>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug,
+    N: traits::IsNode<'s, K, D> + 's,
+{
+    let tree = RootedTree::new(start, get_node_by_key.clone())?;
+    let from_one_end = reroot_at_furthest(&tree, get_node_by_key.clone())?;
+    let from_other_end = reroot_at_furthest(&from_one_end, get_node_by_key)?;
+
+    // The furthest node from anything in a tree is always one of the diameter's ends, so
+    // the eccentricity of a node is the further of its distances to the two.
+    let eccentricity = |id: &K| {
+        let to_one_end = from_one_end
+            .distance_from_root(id)
+            .expect("Unreachable; both rootings span the whole tree");
+        let to_other_end = from_other_end
+            .distance_from_root(id)
+            .expect("Unreachable; both rootings span the whole tree");
+
+        to_one_end.max(to_other_end).clone()
+    };
+
+    let mut centers: Vec<&'s K> = Vec::with_capacity(2);
+    let mut radius: Option<D> = None;
+
+    for id in from_other_end
+        .path_to_root(from_one_end.root())
+        .expect("Unreachable; node was found in the tree")
+    {
+        let candidate = eccentricity(id);
+        match radius.as_ref().map(|radius| candidate.cmp(radius)) {
+            Some(std::cmp::Ordering::Greater) => {}
+            Some(std::cmp::Ordering::Equal) => centers.push(id),
+            Some(std::cmp::Ordering::Less) | None => {
+                centers.clear();
+                centers.push(id);
+                radius = Some(candidate);
+            }
+        }
+    }
+
+    Ok((
+        centers,
+        radius.expect("Unreachable; the diameter has at least one node"),
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod tests_tree {
+    use super::*;
+    use crate::funcs::_tests::*;
+    use std::collections::HashMap;
+
+    /// An undirected tree, with every edge listed in both directions:
+    ///
+    /// ```text
+    ///         1
+    ///       /   \
+    ///      2     3
+    ///     / \     \
+    ///    4   5     6
+    ///               \
+    ///                7
+    /// ```
+    pub(crate) const TREE_CONNECTIONS: &[(u8, u8, u32)] = &[
+        (1, 2, 3),
+        (2, 1, 3),
+        (1, 3, 4),
+        (3, 1, 4),
+        (2, 4, 5),
+        (4, 2, 5),
+        (2, 5, 1),
+        (5, 2, 1),
+        (3, 6, 2),
+        (6, 3, 2),
+        (6, 7, 7),
+        (7, 6, 7),
+    ];
+
+    pub(crate) fn build_tree_nodes() -> HashMap<u8, TestNode> {
+        (1..=7)
+            .map(|id| (id, TestNode::new_with_connections(id, TREE_CONNECTIONS)))
+            .collect()
+    }
+
+    #[test]
+    fn test_rooted_tree() {
+        let nodes = build_tree_nodes();
+        let tree = RootedTree::new(&nodes[&1], |key| nodes.get(key)).expect("Failed to root tree");
+
+        assert_eq!(tree.root(), &1);
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.parent(&1), None);
+        assert_eq!(tree.parent(&5), Some(&2));
+        assert_eq!(tree.parent(&7), Some(&6));
+        assert_eq!(tree.depth(&7), Some(3));
+        assert_eq!(tree.distance_from_root(&7), Some(&13));
+        assert_eq!(tree.depth(&8), None);
+    }
+
+    #[test]
+    fn test_rooted_tree_at_leaf() {
+        let nodes = build_tree_nodes();
+        let tree = RootedTree::new(&nodes[&7], |key| nodes.get(key)).expect("Failed to root tree");
+
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.parent(&1), Some(&3));
+        assert_eq!(tree.depth(&4), Some(5));
+        assert_eq!(tree.distance_from_root(&4), Some(&21));
+    }
+
+    #[test]
+    fn test_tree_diameter() {
+        let nodes = build_tree_nodes();
+
+        // Any start should find the same diameter, 4-2-1-3-6-7.
+        for start in [1, 4, 7] {
+            let diameter =
+                tree_diameter(&nodes[&start], |key| nodes.get(key)).expect("Diameter failed");
+
+            assert_eq!(diameter.length, 21);
+            let mut path = diameter.path.iter().map(|id| **id).collect::<Vec<u8>>();
+            if path[0] != 4 {
+                path.reverse();
+            }
+            assert_eq!(path, vec![4, 2, 1, 3, 6, 7]);
+            assert_eq!(
+                (diameter.path[0], diameter.path[diameter.path.len() - 1]),
+                diameter.endpoints
+            );
+        }
+    }
+
+    #[test]
+    fn test_tree_center() {
+        let nodes = build_tree_nodes();
+
+        let (centers, radius) =
+            tree_center(&nodes[&5], |key| nodes.get(key)).expect("Center failed");
+        assert_eq!(centers, vec![&3]);
+        assert_eq!(radius, 12);
+    }
+
+    #[test]
+    fn test_tree_center_with_two_centers() {
+        // A path 1-2-3-4 with equal weights has two centers.
+        const PATH: &[(u8, u8, u32)] = &[
+            (1, 2, 1),
+            (2, 1, 1),
+            (2, 3, 1),
+            (3, 2, 1),
+            (3, 4, 1),
+            (4, 3, 1),
+        ];
+        let nodes: HashMap<u8, TestNode> = (1..=4)
+            .map(|id| (id, TestNode::new_with_connections(id, PATH)))
+            .collect();
+
+        let (mut centers, radius) =
+            tree_center(&nodes[&1], |key| nodes.get(key)).expect("Center failed");
+        centers.sort();
+        assert_eq!(centers, vec![&2, &3]);
+        assert_eq!(radius, 2);
+    }
+
+    #[test]
+    fn test_tree_diameter_one_directional() {
+        const DIRECTED: &[(u8, u8, u32)] = &[(1, 2, 1), (1, 3, 1)];
+        let nodes: HashMap<u8, TestNode> = (1..=3)
+            .map(|id| (id, TestNode::new_with_connections(id, DIRECTED)))
+            .collect();
+
+        assert!(matches!(
+            tree_diameter(&nodes[&1], |key| nodes.get(key)),
+            Err(SimpleGraphError::NodeNotConnected { .. })
+        ));
+    }
+
+    #[test]
+    fn test_not_a_tree() {
+        // 1 -> 2 -> 4 and 1 -> 3 -> 4
+        const DIAMOND: &[(u8, u8, u32)] = &[(1, 2, 1), (1, 3, 1), (2, 4, 1), (3, 4, 1)];
+        let nodes: HashMap<u8, TestNode> = (1..=4)
+            .map(|id| (id, TestNode::new_with_connections(id, DIAMOND)))
+            .collect();
+
+        assert!(matches!(
+            RootedTree::new(&nodes[&1], |key| nodes.get(key)),
+            Err(SimpleGraphError::NotATree { node: 4 })
+        ));
+    }
+}
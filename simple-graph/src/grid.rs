@@ -0,0 +1,342 @@
+//! A 2D grid adapter that exposes a `width × height` cell array as a graph.
+//!
+//! Grid puzzles are the most common graph problems in Advent of Code, but their cells
+//! rarely come with an adjacency list. [`GridGraph`] builds one from a passability and
+//! a weight closure, so that the cells implement [`IsNode`] and
+//! [`IsNodeWithIndexedNeighbours`] and can be handed straight to [`crate::dijkstra`],
+//! [`crate::Dfs`] and friends.
+
+use crate::traits::{IsNode, IsNodeWithIndexedNeighbours};
+use num_traits::Zero;
+use std::fmt::Debug;
+
+/// The coordinates of a cell in a [`GridGraph`], as `(x, y)`.
+pub type GridCoord = (usize, usize);
+
+/// Which cells are considered adjacent to each other in a [`GridGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Up, down, left and right only; also known as the von Neumann neighbourhood.
+    FourWay,
+    /// All eight surrounding cells including diagonals; also known as the Moore neighbourhood.
+    EightWay,
+}
+
+impl Neighbourhood {
+    const FOUR_WAY_OFFSETS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    const EIGHT_WAY_OFFSETS: [(isize, isize); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+
+    /// The `(dx, dy)` offsets of the adjacent cells, in clockwise order starting from
+    /// the cell above.
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Self::FourWay => &Self::FOUR_WAY_OFFSETS,
+            Self::EightWay => &Self::EIGHT_WAY_OFFSETS,
+        }
+    }
+}
+
+/// A single passable cell in a [`GridGraph`], with its neighbours pre-computed.
+#[derive(Debug, Clone)]
+pub struct GridCell<D> {
+    id: GridCoord,
+    neighbours: Vec<(GridCoord, D)>,
+}
+
+impl<D> GridCell<D> {
+    /// The `(x, y)` coordinates of this cell.
+    pub fn coord(&self) -> GridCoord {
+        self.id
+    }
+
+    /// The number of passable cells adjacent to this one.
+    pub fn degree(&self) -> usize {
+        self.neighbours.len()
+    }
+}
+
+impl<'s, D> IsNode<'s, GridCoord, D> for GridCell<D>
+where
+    D: Zero + Ord + Clone + Debug + 's,
+{
+    fn id(&self) -> &GridCoord {
+        &self.id
+    }
+
+    fn neighbours(
+        &'s self,
+        get_node_by_key: impl Fn(&GridCoord) -> Option<&'s Self>,
+    ) -> impl Iterator<Item = (&'s Self, D)> {
+        self.neighbours.iter().filter_map(move |(coord, distance)| {
+            get_node_by_key(coord).map(|node| (node, distance.clone()))
+        })
+    }
+}
+
+impl<'s, D> IsNodeWithIndexedNeighbours<'s, GridCoord, D> for GridCell<D>
+where
+    D: Zero + Ord + Clone + Debug + 's,
+{
+    fn get_neighbour(
+        &'s self,
+        index: usize,
+        get_node_by_key: impl Fn(&GridCoord) -> Option<&'s Self>,
+    ) -> Option<(&'s Self, D)> {
+        self.neighbours.get(index).and_then(|(coord, distance)| {
+            get_node_by_key(coord).map(|node| (node, distance.clone()))
+        })
+    }
+}
+
+/// A `width × height` grid of cells, where each passable cell is a node connected to
+/// its passable neighbours.
+///
+/// The passability and weight closures are evaluated once per cell at construction;
+/// the resulting [`GridCell`]s can then be looked up with [`GridGraph::get`], which is
+/// exactly the ``get_node_by_key`` closure the search functions expect:
+///
+/// ```
+/// use simple_graph::grid::{GridGraph, Neighbourhood};
+///
+/// let maze = ["..#", ".##", "..."];
+/// let grid = GridGraph::new(
+///     3,
+///     3,
+///     Neighbourhood::FourWay,
+///     |&(x, y)| maze[y].as_bytes()[x] == b'.',
+///     |_| 1_u32,
+/// );
+///
+/// let start = grid.get(&(0, 0)).unwrap();
+/// let (path, distance) = simple_graph::dijkstra(start, &(2, 2), |key| grid.get(key)).unwrap();
+/// assert_eq!(distance, 4);
+/// assert_eq!(path.len(), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GridGraph<D> {
+    width: usize,
+    height: usize,
+    neighbourhood: Neighbourhood,
+    cells: Vec<Option<GridCell<D>>>,
+}
+
+impl<D> GridGraph<D>
+where
+    D: Clone,
+{
+    /// Build a new grid graph.
+    ///
+    /// ``is_passable`` decides whether a cell exists as a node at all; ``weight`` is the
+    /// cost of stepping *into* a cell, which becomes the distance of every edge leading
+    /// to it.
+    pub fn new(
+        width: usize,
+        height: usize,
+        neighbourhood: Neighbourhood,
+        is_passable: impl Fn(&GridCoord) -> bool,
+        weight: impl Fn(&GridCoord) -> D,
+    ) -> Self {
+        let passable = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|coord| is_passable(&coord))
+            .collect::<Vec<bool>>();
+        let weights = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .zip(passable.iter())
+            .map(|(coord, is_passable)| is_passable.then(|| weight(&coord)))
+            .collect::<Vec<Option<D>>>();
+
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .zip(passable.iter())
+            .map(|(coord, is_passable)| {
+                is_passable.then(|| {
+                    let neighbours = neighbourhood
+                        .offsets()
+                        .iter()
+                        .filter_map(|offset| {
+                            Self::offset_coord(width, height, &coord, offset).and_then(
+                                |neighbour| {
+                                    weights[neighbour.1 * width + neighbour.0]
+                                        .clone()
+                                        .map(|distance| (neighbour, distance))
+                                },
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    GridCell {
+                        id: coord,
+                        neighbours,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            width,
+            height,
+            neighbourhood,
+            cells,
+        }
+    }
+}
+
+impl<D> GridGraph<D> {
+    fn offset_coord(
+        width: usize,
+        height: usize,
+        coord: &GridCoord,
+        offset: &(isize, isize),
+    ) -> Option<GridCoord> {
+        let x = coord.0.checked_add_signed(offset.0)?;
+        let y = coord.1.checked_add_signed(offset.1)?;
+
+        (x < width && y < height).then_some((x, y))
+    }
+
+    /// The width of the grid, in cells.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the grid, in cells.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The neighbourhood used to connect the cells.
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// Get the passable cell at the given coordinates, if any.
+    ///
+    /// Out of bounds and impassable coordinates both return [`None`].
+    pub fn get(&self, coord: &GridCoord) -> Option<&GridCell<D>> {
+        if coord.0 >= self.width || coord.1 >= self.height {
+            return None;
+        }
+
+        self.cells[coord.1 * self.width + coord.0].as_ref()
+    }
+
+    /// Iterate over all passable cells, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = &GridCell<D>> {
+        self.cells.iter().flatten()
+    }
+
+    /// The number of passable cells in the grid.
+    pub fn len(&self) -> usize {
+        self.cells().count()
+    }
+
+    /// Whether the grid has no passable cells at all.
+    pub fn is_empty(&self) -> bool {
+        self.cells().next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests_grid {
+    use super::*;
+
+    const MAZE: &[&str] = &[
+        "S.#.....", //
+        ".##.###.", //
+        "....#...", //
+        "##.##.#.", //
+        "......#E", //
+    ];
+
+    fn build_maze(neighbourhood: Neighbourhood) -> GridGraph<u32> {
+        GridGraph::new(
+            MAZE[0].len(),
+            MAZE.len(),
+            neighbourhood,
+            |&(x, y)| MAZE[y].as_bytes()[x] != b'#',
+            |_| 1,
+        )
+    }
+
+    #[test]
+    fn test_impassable_cells() {
+        let grid = build_maze(Neighbourhood::FourWay);
+
+        assert!(grid.get(&(0, 0)).is_some());
+        assert!(grid.get(&(2, 0)).is_none());
+        assert!(grid.get(&(8, 0)).is_none());
+        assert!(grid.get(&(0, 5)).is_none());
+        assert_eq!(grid.len(), 27);
+        assert_eq!(grid.get(&(0, 0)).map(GridCell::degree), Some(2));
+    }
+
+    #[test]
+    fn test_four_way_dijkstra() {
+        let grid = build_maze(Neighbourhood::FourWay);
+
+        let start = grid.get(&(0, 0)).expect("Start cell not found");
+        let (path, distance) =
+            crate::dijkstra(start, &(7, 4), |key| grid.get(key)).expect("Dijkstra failed");
+
+        assert_eq!(distance, 15);
+        assert_eq!(path.first(), Some(&&(0, 0)));
+        assert_eq!(path.last(), Some(&&(7, 4)));
+        path.windows(2).for_each(|pair| {
+            assert_eq!(
+                pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1),
+                1
+            );
+        });
+    }
+
+    #[test]
+    fn test_eight_way_dijkstra() {
+        let grid = build_maze(Neighbourhood::EightWay);
+
+        let start = grid.get(&(0, 0)).expect("Start cell not found");
+        let (_, distance) =
+            crate::dijkstra(start, &(7, 4), |key| grid.get(key)).expect("Dijkstra failed");
+
+        assert_eq!(distance, 9);
+    }
+
+    #[test]
+    fn test_weighted_cells() {
+        // Stepping into the middle row is expensive, so the path should go around it.
+        let grid = GridGraph::new(
+            3,
+            3,
+            Neighbourhood::FourWay,
+            |_| true,
+            |&(x, y)| if y == 1 && x < 2 { 10_u32 } else { 1 },
+        );
+
+        let start = grid.get(&(0, 0)).expect("Start cell not found");
+        let (path, distance) =
+            crate::dijkstra(start, &(0, 2), |key| grid.get(key)).expect("Dijkstra failed");
+
+        assert_eq!(distance, 6);
+        assert_eq!(
+            path,
+            vec![
+                &(0, 0),
+                &(1, 0),
+                &(2, 0),
+                &(2, 1),
+                &(2, 2),
+                &(1, 2),
+                &(0, 2)
+            ]
+        );
+    }
+}
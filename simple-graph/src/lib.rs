@@ -6,9 +6,14 @@
 //! representation.
 
 mod errors;
+pub mod grid;
+pub mod monitor;
+pub mod result;
+pub mod state;
 pub mod traits;
 pub mod wrapper;
 pub use errors::*;
+pub use result::SearchResult;
 
 mod funcs;
 pub use funcs::*;
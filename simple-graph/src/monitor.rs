@@ -0,0 +1,269 @@
+//! Hooks for observing long-running searches while they run.
+//!
+//! The ``trace`` feature prints every step of every search, which is far too much for
+//! anything but the smallest graphs. A [`SearchMonitor`] instead calls back every so many
+//! expansions with a [`Progress`] snapshot, which binaries can use to drive a progress bar
+//! or a log line. Searches that accept one have a ``_monitored`` variant.
+//!
+//! The same monitor can also stop a search that is running for too long, either when a
+//! shared [`AtomicBool`] is set or when a deadline passes; the search then returns
+//! [`SimpleGraphError::Interrupted`] with the progress it made so far.
+
+use crate::SimpleGraphError;
+use std::{
+    fmt::Debug,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Checking the time is much more expensive than checking a flag, so the deadline is
+/// only checked on the first expansion and every this many expansions after that.
+const DEADLINE_CHECK_INTERVAL: usize = 64;
+
+/// A snapshot of how far a search has progressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress<D> {
+    /// The number of nodes expanded so far.
+    pub expanded: usize,
+    /// The number of entries waiting to be explored: the priority queue for Dijkstra, or
+    /// the length of the current path for depth-first searches.
+    pub frontier: usize,
+    /// The best distance known so far, if the search has one: the distance of the latest
+    /// node settled by Dijkstra, or the shortest solution found by a depth-first search.
+    pub best_distance: Option<D>,
+    /// The number of solutions found so far, for searches that find more than one.
+    pub solutions: usize,
+}
+
+/// Why a search was stopped by its [`SearchMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterruptReason {
+    /// The flag passed to [`SearchMonitor::cancel_on`] was set.
+    Cancelled,
+    /// The deadline passed to [`SearchMonitor::deadline`] has passed.
+    DeadlineExceeded,
+}
+
+/// A callback receiving [`Progress`] reports.
+pub type ProgressCallback<'m, D> = Box<dyn FnMut(&Progress<D>) + 'm>;
+
+/// Observes a search, calling ``on_progress`` every ``report_every`` expansions.
+///
+/// A monitor counts expansions across every search it is passed to, so the same monitor
+/// can be reused for a sequence of searches to report on all of them as a whole; this
+/// also means a deadline applies to the sequence as a whole.
+pub struct SearchMonitor<'m, D> {
+    report_every: usize,
+    expanded: usize,
+    on_progress: Option<ProgressCallback<'m, D>>,
+    cancelled: Option<&'m AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl<'m, D> Debug for SearchMonitor<'m, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchMonitor")
+            .field("report_every", &self.report_every)
+            .field("expanded", &self.expanded)
+            .field("has_on_progress", &self.on_progress.is_some())
+            .field("cancelled", &self.cancelled)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+impl<'m, D> Default for SearchMonitor<'m, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'m, D> SearchMonitor<'m, D> {
+    /// Create a monitor that does not report anything.
+    pub fn new() -> Self {
+        Self {
+            report_every: 0,
+            expanded: 0,
+            on_progress: None,
+            cancelled: None,
+            deadline: None,
+        }
+    }
+
+    /// Call ``on_progress`` every ``report_every`` expansions.
+    ///
+    /// A ``report_every`` of zero disables reporting.
+    pub fn on_progress(
+        mut self,
+        report_every: usize,
+        on_progress: impl FnMut(&Progress<D>) + 'm,
+    ) -> Self {
+        self.report_every = report_every;
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Stop the search as soon as ``cancelled`` is set to `true`, e.g. from another thread
+    /// or a signal handler.
+    pub fn cancel_on(mut self, cancelled: &'m AtomicBool) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// Stop the search once ``deadline`` has passed.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stop the search once ``time_limit`` has elapsed from now.
+    pub fn time_limit(self, time_limit: Duration) -> Self {
+        self.deadline(Instant::now() + time_limit)
+    }
+
+    /// The number of expansions recorded by this monitor so far.
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// Record a single expansion, reporting progress if it is due.
+    ///
+    /// Returns [`SimpleGraphError::Interrupted`] if the search should stop; searches
+    /// should leave themselves in a consistent state before calling this.
+    ///
+    /// ``best_distance`` is only cloned when a report is actually made.
+    pub(crate) fn record_expansion<K>(
+        &mut self,
+        frontier: usize,
+        best_distance: Option<&D>,
+        solutions: usize,
+    ) -> Result<(), SimpleGraphError<K, D>>
+    where
+        K: Debug,
+        D: Clone + Debug,
+    {
+        self.expanded += 1;
+
+        if self.report_every > 0
+            && self.expanded.is_multiple_of(self.report_every)
+            && let Some(on_progress) = self.on_progress.as_mut()
+        {
+            on_progress(&Progress {
+                expanded: self.expanded,
+                frontier,
+                best_distance: best_distance.cloned(),
+                solutions,
+            });
+        }
+
+        match self.interruption() {
+            Some(reason) => Err(SimpleGraphError::Interrupted {
+                reason,
+                progress: Progress {
+                    expanded: self.expanded,
+                    frontier,
+                    best_distance: best_distance.cloned(),
+                    solutions,
+                },
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the search should stop now, and why.
+    fn interruption(&self) -> Option<InterruptReason> {
+        if self
+            .cancelled
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+        {
+            return Some(InterruptReason::Cancelled);
+        }
+
+        if (self.expanded - 1).is_multiple_of(DEADLINE_CHECK_INTERVAL)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Some(InterruptReason::DeadlineExceeded);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests_monitor {
+    use super::*;
+
+    #[test]
+    fn test_report_every() {
+        let mut reports = Vec::new();
+        {
+            let mut monitor = SearchMonitor::new().on_progress(3, |progress: &Progress<u32>| {
+                reports.push(progress.clone());
+            });
+            (0..10).for_each(|i| {
+                monitor
+                    .record_expansion::<u8>(i, Some(&(i as u32)), 0)
+                    .expect("Monitor should not interrupt")
+            });
+            assert_eq!(monitor.expanded(), 10);
+        }
+
+        assert_eq!(
+            reports
+                .iter()
+                .map(|progress| (progress.expanded, progress.best_distance))
+                .collect::<Vec<_>>(),
+            vec![(3, Some(2)), (6, Some(5)), (9, Some(8))]
+        );
+    }
+
+    #[test]
+    fn test_never_report() {
+        let mut monitor = SearchMonitor::<u32>::new();
+        (0..10).for_each(|i| {
+            monitor
+                .record_expansion::<u8>(i, None, 0)
+                .expect("Monitor should not interrupt")
+        });
+        assert_eq!(monitor.expanded(), 10);
+    }
+
+    #[test]
+    fn test_cancel_on() {
+        let cancelled = AtomicBool::new(false);
+        let mut monitor = SearchMonitor::<u32>::new().cancel_on(&cancelled);
+
+        assert!(monitor.record_expansion::<u8>(1, None, 0).is_ok());
+        cancelled.store(true, Ordering::Relaxed);
+        assert!(matches!(
+            monitor.record_expansion::<u8>(2, Some(&5), 1),
+            Err(SimpleGraphError::Interrupted {
+                reason: InterruptReason::Cancelled,
+                progress: Progress {
+                    expanded: 2,
+                    frontier: 2,
+                    best_distance: Some(5),
+                    solutions: 1,
+                },
+            })
+        ));
+    }
+
+    #[test]
+    fn test_deadline() {
+        let mut monitor = SearchMonitor::<u32>::new().deadline(Instant::now());
+
+        assert!(matches!(
+            monitor.record_expansion::<u8>(1, None, 0),
+            Err(SimpleGraphError::Interrupted {
+                reason: InterruptReason::DeadlineExceeded,
+                ..
+            })
+        ));
+
+        let mut monitor = SearchMonitor::<u32>::new().time_limit(Duration::from_secs(3600));
+        assert!(monitor.record_expansion::<u8>(1, None, 0).is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+//! An owned form of the paths returned by the search functions.
+//!
+//! The searches return paths as references into the graph, which cannot outlive it. A
+//! [`SearchResult`] clones the keys instead, so that it can be kept around after the
+//! graph is dropped; with the ``serde`` feature it can also be cached to disk between
+//! runs.
+
+/// A path found by a search, and its total distance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchResult<K, D> {
+    /// The keys of the nodes on the path, from the start to the destination inclusive.
+    pub path: Vec<K>,
+    /// The total distance of the path.
+    pub cost: D,
+}
+
+impl<K, D> SearchResult<K, D> {
+    /// Create a new [`SearchResult`] from an owned path and its cost.
+    pub fn new(path: Vec<K>, cost: D) -> Self {
+        Self { path, cost }
+    }
+
+    /// The number of edges on the path.
+    pub fn hops(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+
+    /// Consume the result and return the path and cost as a tuple.
+    pub fn into_inner(self) -> (Vec<K>, D) {
+        (self.path, self.cost)
+    }
+}
+
+impl<'s, K, D> From<(Vec<&'s K>, D)> for SearchResult<K, D>
+where
+    K: Clone,
+{
+    fn from((path, cost): (Vec<&'s K>, D)) -> Self {
+        Self {
+            path: path.into_iter().cloned().collect(),
+            cost,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_result {
+    use super::*;
+    use crate::funcs::_tests::{CONNECTIONS, TestNode};
+    use std::collections::HashMap;
+
+    fn wiki_example() -> SearchResult<u8, u32> {
+        let nodes: HashMap<u8, TestNode> = (1..=6)
+            .map(|id| (id, TestNode::new_with_connections(id, CONNECTIONS)))
+            .collect();
+        crate::dijkstra(&nodes[&1], &5, |key| nodes.get(key))
+            .expect("Failed to find path")
+            .into()
+    }
+
+    #[test]
+    fn test_from_search() {
+        let result = wiki_example();
+
+        assert_eq!(result.path, vec![1, 3, 6, 5]);
+        assert_eq!(result.cost, 20);
+        assert_eq!(result.hops(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use crate::wrapper::UnorderedItem;
+
+        let result = wiki_example();
+        let serialized = serde_json::to_string(&result).expect("Failed to serialize");
+        assert_eq!(serialized, r#"{"path":[1,3,6,5],"cost":20}"#);
+        let deserialized: SearchResult<u8, u32> =
+            serde_json::from_str(&serialized).expect("Failed to deserialize");
+        assert_eq!(deserialized, result);
+
+        let item = UnorderedItem::new(result);
+        let serialized = serde_json::to_string(&item).expect("Failed to serialize");
+        assert_eq!(serialized, r#"{"path":[1,3,6,5],"cost":20}"#);
+        let deserialized: UnorderedItem<SearchResult<u8, u32>> =
+            serde_json::from_str(&serialized).expect("Failed to deserialize");
+        assert_eq!(deserialized.into_inner(), item.into_inner());
+    }
+}
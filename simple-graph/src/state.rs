@@ -0,0 +1,350 @@
+//! A wrapper that expands every node of a graph into `(node, state)` pairs.
+//!
+//! Many puzzles need shortest paths where the cost of a move depends on more than the
+//! node: the direction being faced for turn penalties, the keys collected so far, the
+//! number of steps taken in a straight line... [`StateGraph`] takes a transition closure
+//! describing how the state changes along each edge, and builds the augmented graph of
+//! all reachable `(node, state)` pairs, so that [`crate::dijkstra`] and friends can be
+//! used on it unchanged.
+
+use crate::{
+    SimpleGraphError,
+    traits::{IsNode, IsNodeWithIndexedNeighbours},
+};
+use fxhash::FxHashMap;
+use num_traits::Zero;
+use std::{cmp::Ord, collections::VecDeque, fmt::Debug, hash::Hash};
+
+/// The key of a node in a [`StateGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AugmentedKey<K, S> {
+    /// The underlying node ``node``, reached with state ``state``.
+    State { node: K, state: S },
+    /// The underlying node ``node`` in any state.
+    ///
+    /// Every [`AugmentedKey::State`] node has an edge of zero distance to the
+    /// corresponding [`AugmentedKey::Any`] node, which has no neighbours itself; use this
+    /// as the destination when it does not matter which state the destination is
+    /// reached in.
+    Any(K),
+}
+
+impl<K, S> AugmentedKey<K, S> {
+    /// The key of the underlying node.
+    pub fn node(&self) -> &K {
+        match self {
+            Self::State { node, .. } | Self::Any(node) => node,
+        }
+    }
+
+    /// The state, or [`None`] for an [`AugmentedKey::Any`] key.
+    pub fn state(&self) -> Option<&S> {
+        match self {
+            Self::State { state, .. } => Some(state),
+            Self::Any(_) => None,
+        }
+    }
+}
+
+/// The error type of searches on a [`StateGraph`].
+pub type StateGraphError<K, S, D> = SimpleGraphError<AugmentedKey<K, S>, D>;
+
+/// The path and distance found by [`StateGraph::shortest_path`].
+pub type StateSearchResult<'s, K, S, D> =
+    Result<(Vec<&'s AugmentedKey<K, S>>, D), StateGraphError<K, S, D>>;
+
+/// A single node of a [`StateGraph`], with its neighbours pre-computed.
+#[derive(Debug, Clone)]
+pub struct StateNode<K, S, D> {
+    id: AugmentedKey<K, S>,
+    neighbours: Vec<(AugmentedKey<K, S>, D)>,
+}
+
+impl<'s, K, S, D> IsNode<'s, AugmentedKey<K, S>, D> for StateNode<K, S, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    S: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug + 's,
+{
+    fn id(&self) -> &AugmentedKey<K, S> {
+        &self.id
+    }
+
+    fn neighbours(
+        &'s self,
+        get_node_by_key: impl Fn(&AugmentedKey<K, S>) -> Option<&'s Self>,
+    ) -> impl Iterator<Item = (&'s Self, D)> {
+        self.neighbours.iter().filter_map(move |(key, distance)| {
+            get_node_by_key(key).map(|node| (node, distance.clone()))
+        })
+    }
+}
+
+impl<'s, K, S, D> IsNodeWithIndexedNeighbours<'s, AugmentedKey<K, S>, D> for StateNode<K, S, D>
+where
+    K: Debug + Clone + Eq + Hash + 's,
+    S: Debug + Clone + Eq + Hash + 's,
+    D: Zero + Ord + Clone + Debug + 's,
+{
+    fn get_neighbour(
+        &'s self,
+        index: usize,
+        get_node_by_key: impl Fn(&AugmentedKey<K, S>) -> Option<&'s Self>,
+    ) -> Option<(&'s Self, D)> {
+        self.neighbours
+            .get(index)
+            .and_then(|(key, distance)| get_node_by_key(key).map(|node| (node, distance.clone())))
+    }
+}
+
+/// The graph of all `(node, state)` pairs reachable from a starting node and state.
+///
+/// The augmented graph is built eagerly at construction, so the number of reachable
+/// states needs to fit in memory. Nodes are looked up with [`StateGraph::get`], which is
+/// the ``get_node_by_key`` closure the search functions expect.
+#[derive(Debug, Clone)]
+pub struct StateGraph<K, S, D>
+where
+    K: Debug + Clone + Eq + Hash,
+    S: Debug + Clone + Eq + Hash,
+{
+    start: AugmentedKey<K, S>,
+    nodes: FxHashMap<AugmentedKey<K, S>, StateNode<K, S, D>>,
+}
+
+impl<K, S, D> StateGraph<K, S, D>
+where
+    K: Debug + Clone + Eq + Hash,
+    S: Debug + Clone + Eq + Hash,
+    D: Zero + Ord + Clone + Debug,
+{
+    /// Build the augmented graph reachable from ``start`` in ``initial_state``.
+    ///
+    /// For every edge of the underlying graph, ``transition`` is called with the current
+    /// node and state, the neighbour and the distance of the edge. It returns the state
+    /// after moving to the neighbour along with the distance of the move, which may differ
+    /// from the original; or [`None`] if the move is not allowed in the current state.
+    pub fn new<'s, N>(
+        start: &'s N,
+        initial_state: S,
+        transition: impl Fn(&'s N, &S, &'s N, D) -> Option<(S, D)>,
+        get_node_by_key: impl Fn(&K) -> Option<&'s N> + Clone,
+    ) -> Self
+    where
+        K: 's,
+        N: IsNode<'s, K, D> + 's,
+    {
+        let start_key = AugmentedKey::State {
+            node: start.id().clone(),
+            state: initial_state.clone(),
+        };
+        let mut nodes: FxHashMap<AugmentedKey<K, S>, StateNode<K, S, D>> = FxHashMap::default();
+        nodes.insert(
+            start_key.clone(),
+            StateNode {
+                id: start_key.clone(),
+                neighbours: Vec::new(),
+            },
+        );
+
+        let mut queue: VecDeque<(&'s N, S)> = VecDeque::from([(start, initial_state)]);
+
+        while let Some((current_node, current_state)) = queue.pop_front() {
+            let mut neighbours = current_node
+                .neighbours(get_node_by_key.clone())
+                .filter_map(|(neighbour_node, distance)| {
+                    let (next_state, next_distance) =
+                        transition(current_node, &current_state, neighbour_node, distance)?;
+                    let next_key = AugmentedKey::State {
+                        node: neighbour_node.id().clone(),
+                        state: next_state.clone(),
+                    };
+
+                    if !nodes.contains_key(&next_key) {
+                        nodes.insert(
+                            next_key.clone(),
+                            StateNode {
+                                id: next_key.clone(),
+                                neighbours: Vec::new(),
+                            },
+                        );
+                        queue.push_back((neighbour_node, next_state));
+                    }
+
+                    Some((next_key, next_distance))
+                })
+                .collect::<Vec<_>>();
+
+            let any_key = AugmentedKey::Any(current_node.id().clone());
+            nodes.entry(any_key.clone()).or_insert_with(|| StateNode {
+                id: any_key.clone(),
+                neighbours: Vec::new(),
+            });
+            neighbours.push((any_key, D::zero()));
+
+            let current_key = AugmentedKey::State {
+                node: current_node.id().clone(),
+                state: current_state,
+            };
+            nodes
+                .get_mut(&current_key)
+                .expect("Unreachable; inserted when queued")
+                .neighbours = neighbours;
+        }
+
+        Self {
+            start: start_key,
+            nodes,
+        }
+    }
+
+    /// The node for the starting node in its initial state.
+    pub fn start(&self) -> &StateNode<K, S, D> {
+        self.nodes
+            .get(&self.start)
+            .expect("Unreachable; start is always inserted")
+    }
+
+    /// Get a node of the augmented graph by its key.
+    pub fn get(&self, key: &AugmentedKey<K, S>) -> Option<&StateNode<K, S, D>> {
+        self.nodes.get(key)
+    }
+
+    /// The number of nodes in the augmented graph, including [`AugmentedKey::Any`] nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the augmented graph has no nodes, which is never the case as it always
+    /// has a start.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Iterate over all the states ``node_id`` can be reached in.
+    pub fn states_of<'a>(&'a self, node_id: &'a K) -> impl Iterator<Item = &'a S> {
+        self.nodes
+            .keys()
+            .filter(move |key| key.node() == node_id)
+            .filter_map(AugmentedKey::state)
+    }
+
+    /// Find the shortest path from the start to ``destination`` in any state, using
+    /// [`crate::dijkstra`].
+    ///
+    /// The returned path consists of [`AugmentedKey::State`] keys only, ending with the
+    /// state ``destination`` was reached in.
+    pub fn shortest_path<'s>(&'s self, destination: &K) -> StateSearchResult<'s, K, S, D> {
+        let any_key = AugmentedKey::Any(destination.clone());
+        let Some((destination_key, _)) = self.nodes.get_key_value(&any_key) else {
+            return Err(SimpleGraphError::NodeNotConnected {
+                start: self.start.clone(),
+                destination: any_key,
+            });
+        };
+
+        crate::dijkstra(self.start(), destination_key, |key| self.get(key)).map(
+            |(mut path, distance)| {
+                // Drop the final hop into the `Any` node.
+                path.pop();
+                (path, distance)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests_state {
+    use super::*;
+    use crate::grid::{GridCell, GridCoord, GridGraph, Neighbourhood};
+
+    type Direction = (isize, isize);
+
+    const MAZE: &[&str] = &[
+        "#######", //
+        "#....E#", //
+        "#.###.#", //
+        "#S....#", //
+        "#######", //
+    ];
+
+    fn build_maze() -> GridGraph<u32> {
+        GridGraph::new(
+            MAZE[0].len(),
+            MAZE.len(),
+            Neighbourhood::FourWay,
+            |&(x, y)| MAZE[y].as_bytes()[x] != b'#',
+            |_| 1,
+        )
+    }
+
+    /// Turning costs 1000 on top of the step itself.
+    fn turn_penalty<'s>(
+        current: &'s GridCell<u32>,
+        facing: &Direction,
+        neighbour: &'s GridCell<u32>,
+        distance: u32,
+    ) -> Option<(Direction, u32)> {
+        let (from, to): (GridCoord, GridCoord) = (current.coord(), neighbour.coord());
+        let heading = (
+            to.0 as isize - from.0 as isize,
+            to.1 as isize - from.1 as isize,
+        );
+
+        match heading {
+            _ if heading == *facing => Some((heading, distance)),
+            _ if heading == (-facing.0, -facing.1) => None,
+            _ => Some((heading, distance + 1000)),
+        }
+    }
+
+    #[test]
+    fn test_turn_penalties() {
+        let grid = build_maze();
+        let start = grid.get(&(1, 3)).expect("Start cell not found");
+        let graph = StateGraph::new(start, (1, 0), turn_penalty, |key| grid.get(key));
+
+        let (path, distance) = graph.shortest_path(&(5, 1)).expect("No path found");
+
+        assert_eq!(distance, 1006);
+        assert_eq!(
+            path.iter().map(|key| *key.node()).collect::<Vec<_>>(),
+            vec![(1, 3), (2, 3), (3, 3), (4, 3), (5, 3), (5, 2), (5, 1)]
+        );
+        assert_eq!(path.last().and_then(|key| key.state()), Some(&(0, -1)));
+    }
+
+    #[test]
+    fn test_states_of() {
+        let grid = build_maze();
+        let start = grid.get(&(1, 3)).expect("Start cell not found");
+        let graph = StateGraph::new(start, (1, 0), turn_penalty, |key| grid.get(key));
+
+        // The top left corner can only be entered heading north or west.
+        let mut states = graph.states_of(&(1, 1)).copied().collect::<Vec<_>>();
+        states.sort();
+        assert_eq!(states, vec![(-1, 0), (0, -1)]);
+    }
+
+    #[test]
+    fn test_unreachable_in_any_state() {
+        let grid = build_maze();
+        let start = grid.get(&(1, 3)).expect("Start cell not found");
+        // Never allowed to move west or north.
+        let graph = StateGraph::new(
+            start,
+            (),
+            |current: &GridCell<u32>, _, neighbour: &GridCell<u32>, distance| {
+                (neighbour.coord() > current.coord() && neighbour.coord().1 >= current.coord().1)
+                    .then_some(((), distance))
+            },
+            |key| grid.get(key),
+        );
+
+        assert!(matches!(
+            graph.shortest_path(&(5, 1)),
+            Err(SimpleGraphError::NodeNotConnected { .. })
+        ));
+        assert!(graph.shortest_path(&(5, 3)).is_ok());
+    }
+}
@@ -3,6 +3,13 @@ use std::ops::Deref;
 /// A wrapper struct that indicates the contained item does not have a specific order,
 /// but the wrapper itself will always be [`Eq`] and [`Ord`], returning [`std::cmp::Ordering::Equal`]
 /// for all comparisons.
+///
+/// With the ``serde`` feature, this (de)serializes as the contained item.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct UnorderedItem<T>(pub T);
 
 impl<T> UnorderedItem<T> {
@@ -34,8 +41,8 @@ impl<T> PartialEq for UnorderedItem<T> {
 impl<T> Eq for UnorderedItem<T> {}
 
 impl<T> PartialOrd for UnorderedItem<T> {
-    fn partial_cmp(&self, _other: &Self) -> Option<std::cmp::Ordering> {
-        Some(std::cmp::Ordering::Equal)
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
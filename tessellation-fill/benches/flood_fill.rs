@@ -0,0 +1,148 @@
+//! Benchmarks of the tessellation fill against the breadth-first flood fill it replaced in
+//! [Day 9 of 2025](../../aoc-2025-09/README.md).
+//!
+//! The day is a binary, so its flood fill is reproduced here as it stands there: the
+//! outline is drawn onto a grid of colours, then every cell reachable from a seed inside
+//! it is visited through a queue, remembering each one in a [`HashSet`]. Its cost grows
+//! with the area of the polygon, while that of the tessellation fill grows with its
+//! perimeter, so the gap widens with every doubling of ``size``.
+//!
+//! Run with ``cargo bench -p tessellation-fill --bench flood_fill``.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::{
+    collections::{HashSet, VecDeque},
+    hint::black_box,
+};
+use tessellation_fill::{
+    Coord, Point, SimplePolygon, filled_area, tessellation_fill, traits::Polygon,
+};
+
+/// A grid of cells, each either uncoloured or coloured, as in Day 9.
+struct Grid {
+    width: Coord,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    /// A grid just large enough for ``polygon``, with its outline drawn on.
+    fn with_outline(polygon: &SimplePolygon) -> Self {
+        let bounds = polygon.bounds();
+        let mut grid = Self {
+            width: bounds.x + bounds.width,
+            cells: vec![false; ((bounds.x + bounds.width) * (bounds.y + bounds.height)) as usize],
+        };
+        for (from, to) in polygon.edges() {
+            for x in from[0].min(to[0])..=from[0].max(to[0]) {
+                for y in from[1].min(to[1])..=from[1].max(to[1]) {
+                    grid.set([x, y]);
+                }
+            }
+        }
+
+        grid
+    }
+
+    fn index(&self, [x, y]: Point) -> Option<usize> {
+        let height = self.cells.len() as Coord / self.width;
+        ((0..self.width).contains(&x) && (0..height).contains(&y))
+            .then_some((y * self.width + x) as usize)
+    }
+
+    fn is_uncoloured(&self, point: Point) -> bool {
+        self.index(point).is_some_and(|index| !self.cells[index])
+    }
+
+    fn set(&mut self, point: Point) {
+        if let Some(index) = self.index(point) {
+            self.cells[index] = true;
+        }
+    }
+
+    /// Colour every uncoloured cell reachable from ``seed``, returning how many there were.
+    fn flood_fill_from(&mut self, seed: Point) -> usize {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([seed]);
+        let mut filled = 0;
+
+        while let Some(point) = queue.pop_front() {
+            if !self.is_uncoloured(point) {
+                continue;
+            }
+            self.set(point);
+            filled += 1;
+
+            let [x, y] = point;
+            for next in [[x - 1, y], [x + 1, y], [x, y - 1], [x, y + 1]] {
+                if self.is_uncoloured(next) && seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        filled
+    }
+}
+
+/// A comb of ``teeth`` teeth standing on a solid bar, spanning ``size`` cells each way;
+/// the same shape as in the ``large`` benchmarks.
+fn comb(size: i64, teeth: i64) -> SimplePolygon {
+    let pitch = size / teeth;
+    let bar = size / 4;
+    let mut vertices: Vec<Point> = vec![[0, 0], [size, 0]];
+
+    for tooth in 0..teeth {
+        let right = size - tooth * pitch;
+        vertices.extend([
+            [right, size],
+            [right - pitch / 2, size],
+            [right - pitch / 2, bar],
+            [right - pitch, bar],
+        ]);
+    }
+
+    SimplePolygon::new(vertices).expect("Invalid comb")
+}
+
+fn bench_flood_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flood_fill");
+    group.sample_size(10);
+
+    for size in [256, 512, 1_024, 2_048] {
+        let polygon = comb(size, 8);
+        let grid = Grid::with_outline(&polygon);
+
+        // Both fills must agree on what they fill, outline included.
+        let outline = grid.cells.iter().filter(|&&cell| cell).count();
+        let mut check = Grid {
+            width: grid.width,
+            cells: grid.cells.clone(),
+        };
+        assert_eq!(
+            (check.flood_fill_from([1, 1]) + outline) as u128,
+            filled_area(&polygon)
+        );
+
+        group.bench_function(BenchmarkId::new("bfs", size), |b| {
+            b.iter_batched_ref(
+                || Grid {
+                    width: grid.width,
+                    cells: grid.cells.clone(),
+                },
+                |grid| grid.flood_fill_from(black_box([1, 1])),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(BenchmarkId::new("tessellation", size), |b| {
+            b.iter(|| tessellation_fill(black_box(&polygon)))
+        });
+        group.bench_function(BenchmarkId::new("area", size), |b| {
+            b.iter(|| filled_area(black_box(&polygon)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_flood_fill);
+criterion_main!(benches);
@@ -0,0 +1,93 @@
+//! Benchmarks of filling large, sparse polygons.
+//!
+//! Run with and without the ``rayon`` feature to compare the parallel subdivision:
+//! ``cargo bench -p tessellation-fill [--features rayon]``.
+//!
+//! The ``orthogonal`` group compares the general classification of quads against
+//! [`OrthogonalEdges`](tessellation_fill::OrthogonalEdges) on combs of more and more
+//! teeth, where checking every edge for every quad costs the most.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use tessellation_fill::{
+    Point, SimplePolygon, filled_area, tessellation_fill, tessellation_fill_orthogonal,
+};
+
+/// A comb of ``teeth`` teeth standing on a solid bar, spanning ``size`` cells each way.
+fn comb(size: i64, teeth: i64) -> SimplePolygon {
+    let pitch = size / teeth;
+    let bar = size / 4;
+    let mut vertices: Vec<Point> = vec![[0, 0], [size, 0]];
+
+    for tooth in 0..teeth {
+        let right = size - tooth * pitch;
+        vertices.extend([
+            [right, size],
+            [right - pitch / 2, size],
+            [right - pitch / 2, bar],
+            [right - pitch, bar],
+        ]);
+    }
+
+    SimplePolygon::new(vertices).expect("Invalid comb")
+}
+
+/// A staircase of ``steps`` steps, spanning ``size`` cells each way.
+fn staircase(size: i64, steps: i64) -> SimplePolygon {
+    let step = size / steps;
+    let mut vertices: Vec<Point> = vec![[0, 0]];
+
+    for index in 0..steps {
+        vertices.extend([
+            [(index + 1) * step, index * step],
+            [(index + 1) * step, (index + 1) * step],
+        ]);
+    }
+    vertices.push([0, steps * step]);
+
+    SimplePolygon::new(vertices).expect("Invalid staircase")
+}
+
+fn bench_fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tessellation_fill");
+    group.sample_size(10);
+
+    for (name, polygon) in [
+        ("comb/20000x16", comb(20_000, 16)),
+        ("staircase/20000x64", staircase(20_000, 64)),
+    ] {
+        group.bench_function(BenchmarkId::new("tree", name), |b| {
+            b.iter(|| tessellation_fill(black_box(&polygon)))
+        });
+        group.bench_function(BenchmarkId::new("area", name), |b| {
+            b.iter(|| filled_area(black_box(&polygon)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_orthogonal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("orthogonal");
+    group.sample_size(10);
+
+    for teeth in [16, 64] {
+        let polygon = comb(4_096, teeth);
+        assert_eq!(
+            tessellation_fill_orthogonal(&polygon).as_ref(),
+            Ok(&tessellation_fill(&polygon))
+        );
+
+        group.bench_function(BenchmarkId::new("general", teeth), |b| {
+            b.iter(|| tessellation_fill(black_box(&polygon)))
+        });
+        group.bench_function(BenchmarkId::new("sorted_edges", teeth), |b| {
+            b.iter(|| tessellation_fill_orthogonal(black_box(&polygon)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_fill, bench_orthogonal);
+criterion_main!(benches);
@@ -0,0 +1,160 @@
+//! Filling within a budget of quadtree nodes, for polygons whose exact tree would not fit
+//! in memory.
+
+use crate::{
+    FilledRegion, Quad, QuadClass, QuadNode, Scalar, SimplePolygon, classify, traits::Polygon,
+};
+use std::ops::Range;
+
+/// A region filled by [`tessellation_fill_with_budget`], which may be coarser than the
+/// exact fill.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApproximateFill {
+    /// The region, in which every boundary quad left unsubdivided counts as filled, so
+    /// its area is never less than the exact area.
+    pub region: FilledRegion,
+    /// The number of cells in the boundary quads left unsubdivided; the exact area is at
+    /// least ``region.area() - area_error``.
+    pub area_error: u64,
+}
+
+impl ApproximateFill {
+    /// Whether the budget was large enough for the exact fill.
+    pub const fn is_exact(&self) -> bool {
+        self.area_error == 0
+    }
+}
+
+/// Fill ``polygon`` like [`tessellation_fill`](crate::tessellation_fill), building no more
+/// than ``max_nodes`` nodes of the quadtree.
+///
+/// The tree is built breadth-first, so that when the budget runs out, the outline is
+/// resolved to the same depth all the way around, rather than exactly along one part
+/// and not at all along the rest. Boundary quads that would take the tree over budget
+/// are left as leaves, and their area reported as the error.
+///
+/// The root is always built, however small ``max_nodes`` is.
+pub fn tessellation_fill_with_budget<T: Scalar>(
+    polygon: &SimplePolygon<T>,
+    max_nodes: usize,
+) -> ApproximateFill {
+    fill_breadth_first(polygon, max_nodes, |_, _| true).0
+}
+
+/// Build the quadtree of ``polygon`` breadth-first, with no more than ``max_nodes`` nodes.
+///
+/// ``proceed`` is called before each quad is processed, with the number of quads
+/// processed so far and the number still queued; if it returns `false`, every quad still
+/// queued is left as a leaf, and the fill is returned unfinished alongside `false`.
+pub(crate) fn fill_breadth_first<T: Scalar>(
+    polygon: &SimplePolygon<T>,
+    max_nodes: usize,
+    mut proceed: impl FnMut(usize, usize) -> bool,
+) -> (ApproximateFill, bool) {
+    let bounds = polygon.bounds();
+    // Each node, in breadth-first order, with the range of its children.
+    let mut nodes: Vec<(Quad, QuadClass, Range<usize>)> =
+        vec![(bounds, classify(polygon, bounds), 0..0)];
+    let mut area_error = 0;
+    let mut finished = true;
+
+    let mut index = 0;
+    while let Some(&(quad, class, _)) = nodes.get(index) {
+        let is_unresolved = class == QuadClass::Boundary && !quad.is_cell();
+        if finished && !proceed(index, nodes.len() - index) {
+            finished = false;
+        }
+
+        if is_unresolved && !finished {
+            area_error += quad.area();
+        } else if is_unresolved {
+            let children = quad.split().collect::<Vec<_>>();
+            if nodes.len() + children.len() <= max_nodes {
+                let start = nodes.len();
+                nodes.extend(
+                    children
+                        .into_iter()
+                        .map(|child| (child, classify(polygon, child), 0..0)),
+                );
+                nodes[index].2 = start..nodes.len();
+            } else {
+                area_error += quad.area();
+            }
+        }
+        index += 1;
+    }
+
+    let fill = ApproximateFill {
+        region: FilledRegion::new(assemble(&nodes, 0)),
+        area_error,
+    };
+    (fill, finished)
+}
+
+/// Build the subtree of the node at ``index`` out of the flat list of nodes.
+fn assemble(nodes: &[(Quad, QuadClass, Range<usize>)], index: usize) -> QuadNode {
+    let (quad, class, children) = nodes[index].clone();
+    QuadNode {
+        quad,
+        class,
+        children: children.map(|child| assemble(nodes, child)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filled_area, tessellation_fill};
+
+    fn polygon() -> SimplePolygon {
+        SimplePolygon::new(vec![
+            [0, 0],
+            [1_000, 0],
+            [1_000, 1_000],
+            [500, 1_000],
+            [500, 300],
+            [0, 700],
+        ])
+        .expect("Invalid polygon")
+    }
+
+    #[test]
+    fn test_exact() {
+        let polygon = polygon();
+        let exact = tessellation_fill(&polygon);
+
+        let fill = tessellation_fill_with_budget(&polygon, usize::MAX);
+        assert!(fill.is_exact());
+        assert_eq!(fill.region, exact);
+
+        // Exactly enough nodes.
+        let fill = tessellation_fill_with_budget(&polygon, exact.nodes().count());
+        assert!(fill.is_exact());
+        assert_eq!(fill.region, exact);
+    }
+
+    #[test]
+    fn test_budget() {
+        let polygon = polygon();
+        let exact_area = filled_area(&polygon) as u64;
+
+        let mut last_error = u64::MAX;
+        for max_nodes in [1, 5, 50, 500, 5_000] {
+            let fill = tessellation_fill_with_budget(&polygon, max_nodes);
+            let area = fill.region.area();
+
+            assert!(fill.region.nodes().count() <= max_nodes);
+            assert!(!fill.is_exact());
+            assert!(area >= exact_area);
+            assert!(area - fill.area_error <= exact_area);
+            // A larger budget never makes the bound worse.
+            assert!(fill.area_error <= last_error);
+            last_error = fill.area_error;
+        }
+
+        // Only the root fits, so the whole bounding box is unresolved.
+        let fill = tessellation_fill_with_budget(&polygon, 0);
+        assert!(fill.region.root().is_some_and(|root| root.is_leaf()));
+        assert_eq!(fill.area_error, 1_001 * 1_001);
+    }
+}
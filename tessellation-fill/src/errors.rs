@@ -0,0 +1,30 @@
+use crate::{ApproximateFill, Coord, Point, Scalar};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TessellationFillError<T: Scalar = Coord> {
+    #[error("a polygon needs at least {minimum} vertices, found {count}")]
+    TooFewVertices { count: usize, minimum: usize },
+
+    #[error("edge {index} from {from:?} to {to:?} is not axis-aligned")]
+    NonOrthogonalEdge {
+        index: usize,
+        from: Point<T>,
+        to: Point<T>,
+    },
+
+    #[error("edge {index} has zero length, as it starts and ends at {vertex:?}")]
+    DegenerateEdge { index: usize, vertex: Point<T> },
+
+    #[error("all {count} vertices are on one line, so the ring does not enclose any area")]
+    OpenRing { count: usize },
+
+    #[error("edges {first} and {second} intersect other than at a shared vertex")]
+    SelfIntersection { first: usize, second: usize },
+
+    #[error("the fill was cancelled after {processed} quads")]
+    Cancelled {
+        processed: usize,
+        partial: Box<ApproximateFill>,
+    },
+}
@@ -0,0 +1,151 @@
+//! Rendering a [`FilledRegion`] to an image, one pixel per cell, to see what went wrong
+//! when a polygon does not fill the way it should.
+
+use crate::{FilledRegion, Quad, QuadClass};
+use image::{ImageFormat, ImageResult, Rgb, RgbImage};
+use std::{io, path::Path};
+
+/// The colours of the cells in an exported image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageColours {
+    /// Cells of [`QuadClass::Inside`] quads, and all filled cells of a region that is not
+    /// stored as a quadtree.
+    pub inside: [u8; 3],
+    /// Cells that are not filled.
+    pub outside: [u8; 3],
+    /// Cells that the outline passes through.
+    pub boundary: [u8; 3],
+}
+
+impl Default for ImageColours {
+    fn default() -> Self {
+        Self {
+            inside: [0x2e, 0xa0, 0x43],
+            outside: [0x16, 0x1b, 0x22],
+            boundary: [0xf8, 0x51, 0x49],
+        }
+    }
+}
+
+impl ImageColours {
+    /// The colour of the cells of a leaf quad of ``class``.
+    pub const fn of(&self, class: QuadClass) -> [u8; 3] {
+        match class {
+            QuadClass::Inside => self.inside,
+            QuadClass::Outside => self.outside,
+            QuadClass::Boundary => self.boundary,
+        }
+    }
+}
+
+impl FilledRegion {
+    /// Render the cells within ``view`` into an image, with the cell at the top left of
+    /// ``view`` as its first pixel.
+    ///
+    /// The image is as large as ``view``, so for regions as large as those of Day 9,
+    /// ``view`` should only be a small window onto the part in question.
+    pub fn to_image(&self, view: Quad, colours: &ImageColours) -> RgbImage {
+        let mut image =
+            RgbImage::from_pixel(view.width as u32, view.height as u32, Rgb(colours.outside));
+        let mut paint = |quad: Quad, colour: [u8; 3]| {
+            quad.intersection(&view)
+                .into_iter()
+                .flat_map(|quad| quad.cells())
+                .for_each(|[x, y]| {
+                    image.put_pixel((x - view.x) as u32, (y - view.y) as u32, Rgb(colour))
+                })
+        };
+
+        if self.root().is_some() {
+            self.nodes()
+                .filter(|node| node.is_leaf())
+                .for_each(|node| paint(node.quad, colours.of(node.class)));
+        } else {
+            self.filled_quads()
+                .for_each(|quad| paint(quad, colours.inside));
+        }
+
+        image
+    }
+
+    /// Write the cells within ``view`` to ``writer`` as a binary PPM image; see
+    /// [`to_image`](Self::to_image).
+    pub fn write_ppm(
+        &self,
+        mut writer: impl io::Write,
+        view: Quad,
+        colours: &ImageColours,
+    ) -> io::Result<()> {
+        let image = self.to_image(view, colours);
+        write!(writer, "P6\n{} {}\n255\n", image.width(), image.height())?;
+        writer.write_all(image.as_raw())
+    }
+
+    /// Save the cells within ``view`` to ``path`` as a PNG image; see
+    /// [`to_image`](Self::to_image).
+    pub fn save_png(
+        &self,
+        path: impl AsRef<Path>,
+        view: Quad,
+        colours: &ImageColours,
+    ) -> ImageResult<()> {
+        self.to_image(view, colours)
+            .save_with_format(path, ImageFormat::Png)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FillStrategy, SimplePolygon, tessellation_fill};
+
+    fn polygon() -> SimplePolygon {
+        SimplePolygon::new(vec![[0, 0], [7, 0], [7, 7], [0, 7]]).expect("Invalid polygon")
+    }
+
+    #[test]
+    fn test_to_image() {
+        let colours = ImageColours::default();
+        let image = tessellation_fill(&polygon()).to_image(Quad::new(-1, -1, 10, 10), &colours);
+
+        assert_eq!(image.dimensions(), (10, 10));
+        assert_eq!(image.get_pixel(0, 0).0, colours.outside);
+        assert_eq!(image.get_pixel(1, 1).0, colours.boundary);
+        assert_eq!(image.get_pixel(8, 4).0, colours.boundary);
+        assert_eq!(image.get_pixel(4, 4).0, colours.inside);
+        assert_eq!(image.get_pixel(9, 9).0, colours.outside);
+
+        // Without a quadtree, every filled cell is inside.
+        let region = FillStrategy::Scanline.fill(&polygon());
+        let image = region.to_image(Quad::new(0, 0, 3, 3), &colours);
+        assert_eq!(image.get_pixel(0, 0).0, colours.inside);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut ppm = Vec::new();
+        tessellation_fill(&polygon())
+            .write_ppm(&mut ppm, Quad::new(0, 0, 4, 2), &ImageColours::default())
+            .expect("Failed to write PPM");
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 4 * 2 * 3);
+    }
+
+    #[test]
+    fn test_save_png() {
+        let path = std::env::temp_dir().join("tessellation-fill-test-save-png.png");
+        let region = tessellation_fill(&polygon());
+        let view = region.bounds();
+        let colours = ImageColours::default();
+
+        region
+            .save_png(&path, view, &colours)
+            .expect("Failed to save PNG");
+        let loaded = image::open(&path).expect("Failed to open PNG").to_rgb8();
+        std::fs::remove_file(&path).expect("Failed to remove PNG");
+
+        assert_eq!(loaded, region.to_image(view, &colours));
+    }
+}
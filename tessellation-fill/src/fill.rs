@@ -0,0 +1,446 @@
+//! The quadtree tessellation fill itself.
+
+use crate::{
+    FilledRegion, Quad, QuadClass, QuadNode, Scalar, SimplePolygon, contains_even_odd, from_cell,
+    traits::{Polygon, RasterTarget},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// The depth of the tree down to which the children of a quad are subdivided in parallel;
+/// below it, each subtree is small enough that spawning more tasks costs more than it
+/// saves.
+#[cfg(feature = "rayon")]
+const PARALLEL_DEPTH: usize = 6;
+
+/// Classify ``quad`` against the outline of ``polygon``.
+///
+/// A quad not touched by any edge lies entirely on one side of the outline, so a single
+/// cell is enough to tell which. A single cell touched by an edge is filled if the
+/// outline passes through it, or if its centre is inside.
+pub(crate) fn classify<T: Scalar>(polygon: &SimplePolygon<T>, quad: Quad) -> QuadClass {
+    let centre = || quad.min().map(from_cell);
+
+    if quad.is_empty() {
+        QuadClass::Outside
+    } else if polygon
+        .edges()
+        .any(|(from, to)| quad.touches_segment(from, to))
+    {
+        if !quad.is_cell()
+            || polygon
+                .edges()
+                .any(|(from, to)| quad.crosses_segment(from, to))
+        {
+            QuadClass::Boundary
+        } else if contains_even_odd(polygon, centre()) {
+            QuadClass::Inside
+        } else {
+            QuadClass::Outside
+        }
+    } else if contains_even_odd(polygon, centre()) {
+        QuadClass::Inside
+    } else {
+        QuadClass::Outside
+    }
+}
+
+/// Build the subtree of ``quad``, only recursing into quads on the outline.
+pub(crate) fn subdivide<T: Scalar>(
+    polygon: &SimplePolygon<T>,
+    quad: Quad,
+    depth: usize,
+) -> QuadNode {
+    subdivide_by(&|quad| classify(polygon, quad), quad, depth)
+}
+
+/// Build the subtree of ``quad`` as [`subdivide`] does, classifying each quad with
+/// ``classify``.
+///
+/// With the ``rayon`` feature, the children of quads shallower than [`PARALLEL_DEPTH`] are
+/// subdivided in parallel.
+pub(crate) fn subdivide_by(
+    classify: &(impl Fn(Quad) -> QuadClass + Sync),
+    quad: Quad,
+    depth: usize,
+) -> QuadNode {
+    match classify(quad) {
+        QuadClass::Boundary if !quad.is_cell() => QuadNode {
+            quad,
+            class: QuadClass::Boundary,
+            children: map_children(quad, depth, |child| {
+                subdivide_by(classify, child, depth + 1)
+            }),
+        },
+        class => QuadNode::leaf(quad, class),
+    }
+}
+
+/// The area of the filled cells in the subtree of ``quad``, without building it.
+fn subdivided_area<T: Scalar>(polygon: &SimplePolygon<T>, quad: Quad, depth: usize) -> u128 {
+    match classify(polygon, quad) {
+        QuadClass::Boundary if !quad.is_cell() => map_children(quad, depth, |child| {
+            subdivided_area(polygon, child, depth + 1)
+        })
+        .into_iter()
+        .sum(),
+        QuadClass::Outside => 0,
+        _ => quad.width as u128 * quad.height as u128,
+    }
+}
+
+/// Apply ``f`` to each of the children of ``quad``, in parallel if ``depth`` is shallow
+/// enough.
+#[cfg(feature = "rayon")]
+fn map_children<T: Send>(quad: Quad, depth: usize, f: impl Fn(Quad) -> T + Sync + Send) -> Vec<T> {
+    if depth < PARALLEL_DEPTH {
+        quad.split()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(f)
+            .collect()
+    } else {
+        quad.split().map(f).collect()
+    }
+}
+
+/// Apply ``f`` to each of the children of ``quad``.
+#[cfg(not(feature = "rayon"))]
+fn map_children<T>(quad: Quad, _depth: usize, f: impl Fn(Quad) -> T) -> Vec<T> {
+    quad.split().map(f).collect()
+}
+
+/// Call ``visit`` with every filled leaf quad of the subtree of ``quad``, without building
+/// the tree.
+fn for_each_filled_quad<T: Scalar>(
+    polygon: &SimplePolygon<T>,
+    quad: Quad,
+    visit: &mut impl FnMut(Quad),
+) {
+    match classify(polygon, quad) {
+        QuadClass::Boundary if !quad.is_cell() => quad
+            .split()
+            .for_each(|child| for_each_filled_quad(polygon, child, visit)),
+        QuadClass::Outside => {}
+        _ => visit(quad),
+    }
+}
+
+/// Fill ``polygon`` straight into ``target``, without allocating a [`FilledRegion`].
+///
+/// Only the part of the polygon within the bounds of ``target`` is subdivided, and every
+/// filled cell within them is set exactly once; cells that are not filled are left as
+/// they were.
+pub fn fill_into<T: Scalar>(polygon: &SimplePolygon<T>, target: &mut impl RasterTarget) {
+    let Some(quad) = polygon.bounds().intersection(&target.bounds()) else {
+        return;
+    };
+
+    for_each_filled_quad(polygon, quad, &mut |quad| {
+        quad.cells().for_each(|[x, y]| target.set(x, y))
+    });
+}
+
+/// The number of cells filled by ``polygon``, including those on its outline.
+///
+/// The areas of the filled quads are summed as the bounding box is subdivided, so no
+/// [`FilledRegion`] is ever allocated; this is the same as
+/// ``tessellation_fill(polygon).area()``, without the memory.
+pub fn filled_area<T: Scalar>(polygon: &SimplePolygon<T>) -> u128 {
+    subdivided_area(polygon, polygon.bounds(), 0)
+}
+
+/// Fill ``polygon`` by recursively subdividing its bounding box into quads.
+///
+/// Quads entirely inside or outside of the polygon become leaves straight away, so the
+/// work done grows with the length of the outline rather than with the area enclosed.
+/// Cells on the outline are filled.
+pub fn tessellation_fill<T: Scalar>(polygon: &SimplePolygon<T>) -> FilledRegion {
+    FilledRegion::new(subdivide(polygon, polygon.bounds(), 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    /// The example from Day 9 of 2025.
+    const EXAMPLE: &[Point] = &[
+        [7, 1],
+        [11, 1],
+        [11, 7],
+        [9, 7],
+        [9, 5],
+        [2, 5],
+        [2, 3],
+        [7, 3],
+    ];
+
+    fn assert_matches_brute_force(polygon: &SimplePolygon) {
+        let region = tessellation_fill(polygon);
+        let bounds = region.bounds();
+        assert_eq!(bounds, polygon.bounds());
+
+        let mut expected_area = 0;
+        for y in bounds.y - 1..=bounds.max()[1] + 1 {
+            for x in bounds.x - 1..=bounds.max()[0] + 1 {
+                let expected = contains_even_odd(polygon, [x, y]);
+                assert_eq!(
+                    region.contains([x, y]),
+                    expected,
+                    "Mismatch at {:?}",
+                    [x, y]
+                );
+                expected_area += expected as u64;
+            }
+        }
+
+        assert_eq!(region.area(), expected_area);
+
+        assert_eq!(region.iter_filled_cells().count() as u64, expected_area);
+        assert!(
+            region
+                .iter_filled_cells()
+                .all(|point| contains_even_odd(polygon, point))
+        );
+
+        let rects = region.to_rect_list();
+        assert_eq!(rects.area(), expected_area);
+        assert!(rects.len() <= region.filled_quads().count());
+        for y in bounds.y..=bounds.max()[1] {
+            for x in bounds.x..=bounds.max()[0] {
+                assert_eq!(rects.contains([x, y]), region.contains([x, y]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_example() {
+        let polygon = SimplePolygon::new(EXAMPLE.to_vec()).expect("Invalid polygon");
+        assert_matches_brute_force(&polygon);
+
+        let region = tessellation_fill(&polygon);
+        assert_eq!(region.area(), 46);
+        assert_eq!(
+            region.to_rect_list().rects(),
+            &[
+                Quad::new(7, 1, 5, 2),
+                Quad::new(2, 3, 10, 3),
+                Quad::new(9, 6, 3, 2),
+            ]
+        );
+        assert!(region.contains([3, 4]));
+        assert!(!region.contains([3, 2]));
+    }
+
+    /// A window onto the grid, recording every cell set.
+    struct Window {
+        bounds: Quad,
+        cells: Vec<Point>,
+    }
+
+    impl RasterTarget for Window {
+        fn bounds(&self) -> Quad {
+            self.bounds
+        }
+
+        fn set(&mut self, x: i64, y: i64) {
+            assert!(self.bounds.contains([x, y]), "Set outside of bounds");
+            self.cells.push([x, y]);
+        }
+    }
+
+    #[test]
+    fn test_fill_into() {
+        let polygon = SimplePolygon::new(EXAMPLE.to_vec()).expect("Invalid polygon");
+        let region = tessellation_fill(&polygon);
+
+        for bounds in [
+            Quad::new(0, 0, 20, 20),
+            Quad::new(8, 2, 3, 5),
+            Quad::new(-5, -5, 3, 3),
+        ] {
+            let mut window = Window {
+                bounds,
+                cells: Vec::new(),
+            };
+            fill_into(&polygon, &mut window);
+
+            let mut expected = (bounds.y..=bounds.max()[1])
+                .flat_map(|y| (bounds.x..=bounds.max()[0]).map(move |x| [x, y]))
+                .filter(|&point| region.contains(point))
+                .collect::<Vec<_>>();
+            window.cells.sort_by_key(|&[x, y]| (y, x));
+            expected.sort_by_key(|&[x, y]| (y, x));
+
+            assert_eq!(window.cells, expected);
+        }
+    }
+
+    /// The number of cells on or inside the outline, from the shoelace formula and Pick's
+    /// theorem: ``A = I + B / 2 - 1``, so ``I + B = A + B / 2 + 1``.
+    fn shoelace_area(polygon: &SimplePolygon) -> u128 {
+        let (twice_area, perimeter) =
+            polygon
+                .edges()
+                .fold((0i128, 0i128), |(twice_area, perimeter), (from, to)| {
+                    (
+                        twice_area + from[0] as i128 * to[1] as i128
+                            - to[0] as i128 * from[1] as i128,
+                        perimeter
+                            + (to[0] - from[0]).abs() as i128
+                            + (to[1] - from[1]).abs() as i128,
+                    )
+                });
+
+        ((twice_area.abs() + perimeter) / 2 + 1) as u128
+    }
+
+    #[test]
+    fn test_filled_area() {
+        let polygon = SimplePolygon::new(EXAMPLE.to_vec()).expect("Invalid polygon");
+        assert_eq!(filled_area(&polygon), 46);
+        assert_eq!(filled_area(&polygon), shoelace_area(&polygon));
+
+        // Coordinates of the size of the real Day 9 input.
+        let polygon = SimplePolygon::new(vec![
+            [1_000, 2_000],
+            [98_000, 2_000],
+            [98_000, 97_000],
+            [50_000, 97_000],
+            [50_000, 48_000],
+            [1_000, 48_000],
+        ])
+        .expect("Invalid polygon");
+        assert_eq!(filled_area(&polygon), shoelace_area(&polygon));
+    }
+
+    /// Whether the cell at ``point`` is filled, checked against every edge.
+    fn brute_force<T: Scalar>(polygon: &SimplePolygon<T>, point: Point) -> bool {
+        polygon
+            .edges()
+            .any(|(from, to)| Quad::new(point[0], point[1], 1, 1).crosses_segment(from, to))
+            || contains_even_odd(polygon, point.map(from_cell))
+    }
+
+    fn assert_matches_supercover<T: Scalar>(polygon: &SimplePolygon<T>) -> FilledRegion {
+        let region = tessellation_fill(polygon);
+        let bounds = region.bounds();
+
+        let mut expected_area = 0;
+        for y in bounds.y..=bounds.max()[1] {
+            for x in bounds.x..=bounds.max()[0] {
+                let expected = brute_force(polygon, [x, y]);
+                assert_eq!(
+                    region.contains([x, y]),
+                    expected,
+                    "Mismatch at {:?}",
+                    [x, y]
+                );
+                expected_area += expected as u64;
+            }
+        }
+        assert_eq!(region.area(), expected_area);
+        assert_eq!(filled_area(polygon), expected_area as u128);
+
+        region
+    }
+
+    #[test]
+    fn test_triangle() {
+        let polygon = SimplePolygon::new(vec![[0, 0], [6, 0], [0, 6]]).expect("Invalid polygon");
+        let region = assert_matches_supercover(&polygon);
+
+        // Every cell on or below the hypotenuse, and those it clips above it.
+        assert_eq!(region.area(), 28);
+        assert!(region.contains([3, 3]));
+        assert!(region.contains([1, 5]));
+        assert!(!region.contains([4, 3]));
+        assert!(!region.contains([6, 1]));
+
+        // A shallower slope, which clips the corners of more cells.
+        let polygon = SimplePolygon::new(vec![[0, 0], [7, 2], [0, 4]]).expect("Invalid polygon");
+        let region = assert_matches_supercover(&polygon);
+        assert!(region.contains([4, 1]));
+        assert!(region.contains([4, 3]));
+        assert!(!region.contains([6, 0]));
+        assert!(!region.contains([6, 4]));
+    }
+
+    #[test]
+    fn test_irregular() {
+        let polygons = [
+            vec![[0, 0], [9, 2], [12, 11], [5, 7], [1, 12], [3, 5]],
+            vec![
+                [2, 0],
+                [8, 0],
+                [10, 3],
+                [10, 9],
+                [4, 12],
+                [0, 6],
+                [4, 6],
+                [4, 3],
+            ],
+        ];
+        for vertices in polygons {
+            let polygon = SimplePolygon::new(vertices).expect("Invalid polygon");
+            assert_matches_supercover(&polygon);
+        }
+
+        let polygon =
+            SimplePolygon::new(vec![[0.3, 0.2], [7.7, 1.4], [4.1, 6.9]]).expect("Invalid polygon");
+        let region = assert_matches_supercover(&polygon);
+        // The first vertex clips cell zero, even though its centre is outside.
+        assert!(region.contains([0, 0]));
+    }
+
+    #[test]
+    fn test_spiral() {
+        let polygon = SimplePolygon::new(vec![
+            [0, 0],
+            [20, 0],
+            [20, 20],
+            [4, 20],
+            [4, 8],
+            [12, 8],
+            [12, 12],
+            [8, 12],
+            [8, 16],
+            [16, 16],
+            [16, 4],
+            [0, 4],
+        ])
+        .expect("Invalid polygon");
+
+        assert_matches_brute_force(&polygon);
+        assert_eq!(filled_area(&polygon), shoelace_area(&polygon));
+    }
+
+    #[test]
+    fn test_large() {
+        // Far too large to fill cell by cell.
+        let polygon = SimplePolygon::new(vec![
+            [0, 0],
+            [10_000, 0],
+            [10_000, 10_000],
+            [5_000, 10_000],
+            [5_000, 5_000],
+            [0, 5_000],
+        ])
+        .expect("Invalid polygon");
+
+        let region = tessellation_fill(&polygon);
+        assert_eq!(region.area(), 10_001 * 5_001 + 5_001 * 5_000);
+        assert!(region.contains([2_500, 2_500]));
+        assert_eq!(
+            region.to_rect_list().rects(),
+            &[
+                Quad::new(0, 0, 10_001, 5_001),
+                Quad::new(5_000, 5_001, 5_001, 5_000)
+            ]
+        );
+        assert!(!region.contains([2_500, 7_500]));
+        assert!(region.nodes().count() < 1_000_000);
+    }
+}
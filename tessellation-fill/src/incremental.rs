@@ -0,0 +1,195 @@
+//! Refilling a polygon as it is edited, only where it changed.
+
+use crate::{
+    Coord, FilledRegion, Point, Quad, QuadClass, QuadNode, Scalar, SimplePolygon, classify,
+    subdivide, tessellation_fill, traits::Polygon,
+};
+
+/// A polygon along with its fill, kept up to date as the polygon is edited.
+///
+/// An edit only changes the edges either side of one vertex, and cells can only change
+/// sides between the old edges and the new ones; so only the quads overlapping the
+/// bounding box of both are classified again, and the rest of the tree is kept as it is.
+/// If the edit changes the bounds of the polygon, the whole of it is refilled.
+///
+/// Edits are not checked; run [`is_simple`](crate::is_simple) on
+/// [`polygon`](Self::polygon) if they could make the outline cross itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalFill<T: Scalar = Coord> {
+    polygon: SimplePolygon<T>,
+    region: FilledRegion,
+}
+
+impl<T: Scalar> IncrementalFill<T> {
+    /// Fill ``polygon`` in full, ready to be edited.
+    pub fn new(polygon: SimplePolygon<T>) -> Self {
+        let region = tessellation_fill(&polygon);
+        Self { polygon, region }
+    }
+
+    /// The polygon, with every edit so far.
+    pub const fn polygon(&self) -> &SimplePolygon<T> {
+        &self.polygon
+    }
+
+    /// The region filled by [`polygon`](Self::polygon), exactly as
+    /// [`tessellation_fill`] would fill it.
+    pub const fn region(&self) -> &FilledRegion {
+        &self.region
+    }
+
+    /// Take the region, with no more edits to be made.
+    pub fn into_region(self) -> FilledRegion {
+        self.region
+    }
+
+    /// Move vertex ``index`` to ``to``, returning the quad that was refilled.
+    ///
+    /// Panics if ``index`` is out of bounds.
+    pub fn move_vertex(&mut self, index: usize, to: Point<T>) -> Quad {
+        let vertices = self.polygon.vertices_mut();
+        let len = vertices.len();
+        let (previous, next) = (
+            vertices[(index + len - 1) % len],
+            vertices[(index + 1) % len],
+        );
+        let from = std::mem::replace(&mut vertices[index], to);
+
+        self.refill(&[previous, from, next, to])
+    }
+
+    /// Split edge ``index`` in two at ``point``, which becomes vertex ``index + 1``,
+    /// returning the quad that was refilled.
+    ///
+    /// Panics if ``index`` is out of bounds.
+    pub fn insert_vertex(&mut self, index: usize, point: Point<T>) -> Quad {
+        let vertices = self.polygon.vertices_mut();
+        let (from, to) = (vertices[index], vertices[(index + 1) % vertices.len()]);
+        vertices.insert(index + 1, point);
+
+        self.refill(&[from, to, point])
+    }
+
+    /// Refill the quads overlapping any edge between ``changed``, the ends of every edge
+    /// before and after the edit, unless the bounds changed.
+    fn refill(&mut self, changed: &[Point<T>]) -> Quad {
+        let bounds = self.polygon.bounds();
+        if bounds != self.region.bounds() {
+            self.region = tessellation_fill(&self.polygon);
+            return bounds;
+        }
+
+        // Widened by a cell each way, for edges running along the sides of cells.
+        let dirty = changed.bounds().grow(1);
+        let root = self
+            .region
+            .root_mut()
+            .expect("Unreachable; incremental fills are always stored as quadtrees");
+        refresh(&self.polygon, root, &dirty, 0);
+
+        dirty
+            .intersection(&bounds)
+            .expect("Unreachable; the changed edges are within the bounds")
+    }
+}
+
+/// Classify ``node`` and its descendants again where they overlap ``dirty``, subdividing
+/// any leaf that is now on the outline.
+fn refresh<T: Scalar>(polygon: &SimplePolygon<T>, node: &mut QuadNode, dirty: &Quad, depth: usize) {
+    if node.quad.intersection(dirty).is_none() {
+        return;
+    }
+
+    match classify(polygon, node.quad) {
+        QuadClass::Boundary if !node.quad.is_cell() && !node.is_leaf() => node
+            .children
+            .iter_mut()
+            .for_each(|child| refresh(polygon, child, dirty, depth + 1)),
+        QuadClass::Boundary if !node.quad.is_cell() => {
+            *node = subdivide(polygon, node.quad, depth);
+        }
+        class => *node = QuadNode::leaf(node.quad, class),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matches_full_fill(fill: &IncrementalFill) {
+        assert_eq!(fill.region(), &tessellation_fill(fill.polygon()));
+    }
+
+    #[test]
+    fn test_move_vertex() {
+        // A square with a notch cut into its left side; the corners keep the bounds fixed
+        // while the notch is reshaped.
+        let polygon = SimplePolygon::new(vec![
+            [0, 0],
+            [20, 0],
+            [20, 20],
+            [0, 20],
+            [0, 12],
+            [9, 12],
+            [9, 9],
+            [2, 9],
+            [2, 4],
+            [0, 4],
+        ])
+        .expect("Invalid polygon");
+        let mut fill = IncrementalFill::new(polygon);
+
+        for (index, to) in [
+            (6, [14, 9]),
+            (7, [2, 6]),
+            (5, [14, 12]),
+            (6, [14, 11]),
+            (6, [9, 9]),
+            (5, [9, 12]),
+            (7, [2, 9]),
+            // Diagonal edges.
+            (6, [17, 16]),
+            (8, [5, 2]),
+        ] {
+            let dirty = fill.move_vertex(index, to);
+            assert_matches_full_fill(&fill);
+            assert_eq!(fill.region().bounds().intersection(&dirty), Some(dirty));
+            assert!(dirty.area() < fill.region().bounds().area());
+        }
+    }
+
+    #[test]
+    fn test_insert_vertex() {
+        let polygon =
+            SimplePolygon::new(vec![[0, 0], [16, 0], [16, 16], [0, 16]]).expect("Invalid polygon");
+        let mut fill = IncrementalFill::new(polygon);
+
+        // Notch the top edge, then the closing edge.
+        fill.insert_vertex(0, [8, 5]);
+        assert_matches_full_fill(&fill);
+        fill.insert_vertex(4, [6, 8]);
+        assert_matches_full_fill(&fill);
+        assert_eq!(
+            fill.polygon().vertices(),
+            &[[0, 0], [8, 5], [16, 0], [16, 16], [0, 16], [6, 8]]
+        );
+
+        fill.move_vertex(1, [8, 12]);
+        assert_matches_full_fill(&fill);
+    }
+
+    #[test]
+    fn test_bounds_changed() {
+        let polygon =
+            SimplePolygon::new(vec![[0, 0], [8, 0], [8, 8], [0, 8]]).expect("Invalid polygon");
+        let mut fill = IncrementalFill::new(polygon);
+
+        let dirty = fill.move_vertex(2, [12, 10]);
+        assert_eq!(dirty, Quad::new(0, 0, 13, 11));
+        assert_matches_full_fill(&fill);
+
+        let dirty = fill.insert_vertex(3, [-4, 4]);
+        assert_eq!(dirty, fill.region().bounds());
+        assert_matches_full_fill(&fill);
+    }
+}
@@ -0,0 +1,132 @@
+//! The largest rectangle that fits inside a polygon, found from its tessellation.
+
+use crate::{Quad, Scalar, SimplePolygon, first_cell_reaching, tessellation_fill, traits::Polygon};
+
+/// The largest axis-aligned rectangle with two vertices of ``polygon`` at its opposite
+/// corners, every cell of which is filled by ``polygon``; this is part 2 of Day 9 of 2025.
+///
+/// Rather than checking every pair of vertices against every edge, the polygon is filled
+/// and merged into a [`RectList`](crate::RectList), against which each candidate is
+/// checked with a summed area table; see [`RectList::largest_rect_between`]. Each vertex
+/// counts as the cell it lies in.
+///
+/// Returns [`None`] only if no rectangle fits, which never happens for a polygon that
+/// fills the cells of its own vertices.
+///
+/// [`RectList::largest_rect_between`]: crate::RectList::largest_rect_between
+pub fn largest_inscribed_rect<T: Scalar>(polygon: &SimplePolygon<T>) -> Option<Quad> {
+    let corners = polygon
+        .vertices()
+        .iter()
+        .map(|vertex| vertex.map(first_cell_reaching))
+        .collect::<Vec<_>>();
+
+    tessellation_fill(polygon)
+        .to_rect_list()
+        .largest_rect_between(&corners)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, contains_even_odd};
+
+    /// Try every pair of vertices, checking every cell of each rectangle.
+    fn brute_force(polygon: &SimplePolygon) -> u64 {
+        let region = tessellation_fill(polygon);
+        let vertices = polygon.vertices();
+
+        vertices
+            .iter()
+            .flat_map(|a| vertices.iter().map(move |b| (a, b)))
+            .map(|(a, b)| {
+                Quad::from_corners(
+                    [a[0].min(b[0]), a[1].min(b[1])],
+                    [a[0].max(b[0]), a[1].max(b[1])],
+                )
+            })
+            .filter(|quad| quad.cells().all(|cell| region.contains(cell)))
+            .map(|quad| quad.area())
+            .max()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_example() {
+        let polygon = SimplePolygon::new(vec![
+            [7, 1],
+            [11, 1],
+            [11, 7],
+            [9, 7],
+            [9, 5],
+            [2, 5],
+            [2, 3],
+            [7, 3],
+        ])
+        .expect("Invalid polygon");
+
+        let rect = largest_inscribed_rect(&polygon).expect("No rectangle found");
+        assert_eq!(rect, Quad::new(2, 3, 8, 3));
+        assert_eq!(rect.area(), 24);
+        assert!(rect.cells().all(|cell| contains_even_odd(&polygon, cell)));
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let polygons: [&[Point]; 3] = [
+            // A spiral, where the largest rectangle is one of its arms.
+            &[
+                [0, 0],
+                [20, 0],
+                [20, 20],
+                [4, 20],
+                [4, 8],
+                [12, 8],
+                [12, 12],
+                [8, 12],
+                [8, 16],
+                [16, 16],
+                [16, 4],
+                [0, 4],
+            ],
+            // A notch cut out of the middle of the widest candidate.
+            &[
+                [0, 0],
+                [5, 0],
+                [5, 4],
+                [6, 4],
+                [6, 0],
+                [12, 0],
+                [12, 9],
+                [0, 9],
+            ],
+            // Diagonal edges, whose clipped cells count as filled.
+            &[[0, 0], [9, 2], [12, 11], [5, 7], [1, 12], [3, 5]],
+        ];
+
+        for vertices in polygons {
+            let polygon = SimplePolygon::new(vertices.to_vec()).expect("Invalid polygon");
+            let rect = largest_inscribed_rect(&polygon).expect("No rectangle found");
+            assert_eq!(rect.area(), brute_force(&polygon), "{vertices:?}");
+        }
+    }
+
+    #[test]
+    fn test_scalars() {
+        let polygon =
+            SimplePolygon::<u32>::new(vec![[0, 0], [6, 0], [6, 2], [2, 2], [2, 5], [0, 5]])
+                .expect("Invalid polygon");
+        assert_eq!(
+            largest_inscribed_rect(&polygon),
+            Some(Quad::new(0, 0, 7, 3))
+        );
+
+        // Each vertex counts as the cell it lies in.
+        let polygon = SimplePolygon::new(vec![[0.2, 0.1], [5.8, 0.3], [6.1, 3.9], [-0.1, 4.2]])
+            .expect("Invalid polygon");
+        assert_eq!(
+            largest_inscribed_rect(&polygon),
+            Some(Quad::new(0, 0, 7, 5))
+        );
+    }
+}
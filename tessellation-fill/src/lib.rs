@@ -0,0 +1,83 @@
+//! # Tessellation Fill
+//!
+//! `tessellation_fill` fills the area enclosed by a polygon on an integer grid, without
+//! visiting every cell of it.
+//!
+//! A flood fill visits each cell it fills, so its cost grows with the *area* of the
+//! polygon, which is prohibitive for large, sparse outlines. Instead, the bounding box of
+//! the polygon is recursively subdivided into quadrants:
+//!
+//! * a quadrant that no edge passes through is entirely inside or entirely outside the
+//!   polygon, which a single point-in-polygon test decides; it is not subdivided further;
+//! * a quadrant that an edge passes through is split into four, until the quadrants are
+//!   single cells.
+//!
+//! The cost therefore grows with the *perimeter* of the polygon instead. For small or dense
+//! polygons, [`FillStrategy::Scanline`] fills row by row without the overhead of the tree.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use tessellation_fill::{SimplePolygon, tessellation_fill, traits::Polygon};
+//!
+//! // An L-shaped outline.
+//! let polygon = SimplePolygon::new(vec![[0, 0], [4, 0], [4, 2], [2, 2], [2, 4], [0, 4]])
+//!     .expect("Invalid polygon");
+//! let region = tessellation_fill(&polygon);
+//!
+//! assert_eq!(region.area(), 5 * 3 + 2 * 3);
+//! assert!(region.contains([1, 3]));
+//! assert!(!region.contains([3, 3]));
+//! assert_eq!(region.bounds(), polygon.bounds());
+//! ```
+//!
+//! ## Conventions
+//!
+//! * Vertices are listed in order around the ring; the last vertex connects back to the
+//!   first.
+//! * Coordinates can be any [`Scalar`], such as [`i64`], [`u32`] or [`f64`]; each cell of
+//!   the grid is centred on its integer coordinates.
+//! * Edges can be at any angle, although [`SimplePolygon::new_orthogonal`] checks that
+//!   they are all axis-aligned, as in all Advent of Code inputs; such polygons fill
+//!   faster with [`tessellation_fill_orthogonal`].
+//! * The ring must not cross or touch itself, which is not checked when filling; run
+//!   [`validate`] or [`is_simple`] first on outlines that could.
+//! * Cells that the outline passes through count as filled, along with every cell whose
+//!   centre is inside it.
+
+mod errors;
+pub use errors::*;
+
+pub mod traits;
+
+mod models;
+pub use models::*;
+
+mod fill;
+pub use fill::*;
+
+mod orthogonal;
+pub use orthogonal::*;
+
+mod budget;
+pub use budget::*;
+
+mod progress;
+pub use progress::*;
+
+mod incremental;
+pub use incremental::*;
+
+mod scanline;
+pub use scanline::*;
+
+mod inscribed;
+pub use inscribed::*;
+
+mod validate;
+pub use validate::*;
+
+#[cfg(feature = "image")]
+mod export;
+#[cfg(feature = "image")]
+pub use export::*;
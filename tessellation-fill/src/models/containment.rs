@@ -0,0 +1,176 @@
+//! Point-in-polygon tests for cells on the grid.
+//!
+//! The tests are exact for integer coordinates, as long as the products of differences
+//! between coordinates fit in the [`Scalar`] type.
+
+use super::{Point, Scalar, orientation};
+use crate::traits::Polygon;
+use std::cmp::Ordering;
+
+/// Whether ``value`` is between ``a`` and ``b`` inclusive, in either order.
+fn is_between<T: Scalar>(value: T, a: T, b: T) -> bool {
+    (a <= value && value <= b) || (b <= value && value <= a)
+}
+
+/// Whether ``point`` lies on the edge from ``from`` to ``to``, inclusive of both ends.
+pub(crate) fn is_on_edge<T: Scalar>(from: Point<T>, to: Point<T>, point: Point<T>) -> bool {
+    orientation(from, to, point) == Ordering::Equal
+        && is_between(point[0], from[0], to[0])
+        && is_between(point[1], from[1], to[1])
+}
+
+/// The signed crossing of a ray cast from ``point`` towards positive ``x`` with the edge
+/// from ``from`` to ``to``: ``1`` for an upward edge passing to its right, ``-1`` for a
+/// downward one, and ``0`` if the ray misses it.
+///
+/// Edges are treated as half-open in ``y``, so that a ray through a vertex shared by two
+/// edges is only counted once.
+fn crossing<T: Scalar>(from: Point<T>, to: Point<T>, point: Point<T>) -> i64 {
+    if from[1] <= point[1] && point[1] < to[1] && orientation(from, to, point) == Ordering::Greater
+    {
+        1
+    } else if to[1] <= point[1]
+        && point[1] < from[1]
+        && orientation(from, to, point) == Ordering::Less
+    {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Whether ``point`` lies on the outline of ``polygon``.
+pub fn is_on_outline<P: Polygon + ?Sized>(polygon: &P, point: Point<P::Scalar>) -> bool {
+    polygon
+        .edges()
+        .any(|(from, to)| is_on_edge(from, to, point))
+}
+
+/// The number of times the outline of ``polygon`` winds around ``point``, positive if
+/// counter-clockwise with ``y`` pointing up.
+///
+/// Only meaningful for points that are not on the outline itself.
+pub fn winding_number<P: Polygon + ?Sized>(polygon: &P, point: Point<P::Scalar>) -> i64 {
+    polygon
+        .edges()
+        .map(|(from, to)| crossing(from, to, point))
+        .sum()
+}
+
+/// Whether ``point`` is on the outline of ``polygon`` or enclosed by it under the
+/// even-odd rule: a ray cast from it crosses the outline an odd number of times.
+pub fn contains_even_odd<P: Polygon + ?Sized>(polygon: &P, point: Point<P::Scalar>) -> bool {
+    is_on_outline(polygon, point)
+        || polygon
+            .edges()
+            .filter(|&(from, to)| crossing(from, to, point) != 0)
+            .count()
+            % 2
+            == 1
+}
+
+/// Whether ``point`` is on the outline of ``polygon`` or enclosed by it under the
+/// non-zero rule: the outline winds around it at least once.
+///
+/// Agrees with [`contains_even_odd`] for simple polygons; they only differ where a
+/// self-intersecting outline winds around a point more than once.
+pub fn contains_nonzero<P: Polygon + ?Sized>(polygon: &P, point: Point<P::Scalar>) -> bool {
+    is_on_outline(polygon, point) || winding_number(polygon, point) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A U shape, open at the top.
+    const U_SHAPE: &[Point] = &[
+        [0, 0],
+        [2, 0],
+        [2, 4],
+        [4, 4],
+        [4, 0],
+        [6, 0],
+        [6, 6],
+        [0, 6],
+    ];
+
+    #[test]
+    fn test_even_odd() {
+        assert!(contains_even_odd(U_SHAPE, [1, 1]));
+        assert!(contains_even_odd(U_SHAPE, [3, 5]));
+        assert!(contains_even_odd(U_SHAPE, [5, 1]));
+        assert!(!contains_even_odd(U_SHAPE, [3, 1]));
+        assert!(!contains_even_odd(U_SHAPE, [7, 3]));
+        assert!(!contains_even_odd(U_SHAPE, [-1, 5]));
+
+        // On the outline, including the vertices.
+        assert!(contains_even_odd(U_SHAPE, [3, 4]));
+        assert!(contains_even_odd(U_SHAPE, [4, 0]));
+        assert!(contains_even_odd(U_SHAPE, [6, 6]));
+    }
+
+    #[test]
+    fn test_winding_number() {
+        assert_eq!(winding_number(U_SHAPE, [1, 1]), 1);
+        assert_eq!(winding_number(U_SHAPE, [3, 1]), 0);
+
+        let reversed = U_SHAPE.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(winding_number(reversed.as_slice(), [1, 1]), -1);
+        assert!(contains_nonzero(reversed.as_slice(), [1, 1]));
+    }
+
+    #[test]
+    fn test_overlapping() {
+        // The same square traced twice, which only the non-zero rule fills.
+        let twice: &[Point] = &[
+            [0, 0],
+            [4, 0],
+            [4, 4],
+            [0, 4],
+            [0, 0],
+            [4, 0],
+            [4, 4],
+            [0, 4],
+        ];
+
+        assert_eq!(winding_number(twice, [2, 2]), 2);
+        assert!(contains_nonzero(twice, [2, 2]));
+        assert!(!contains_even_odd(twice, [2, 2]));
+        assert!(contains_even_odd(twice, [0, 2]));
+    }
+
+    #[test]
+    fn test_scalars() {
+        let unsigned: &[Point<u32>] = &[
+            [0, 0],
+            [2, 0],
+            [2, 4],
+            [4, 4],
+            [4, 0],
+            [6, 0],
+            [6, 6],
+            [0, 6],
+        ];
+        assert!(contains_even_odd(unsigned, [1, 1]));
+        assert!(!contains_even_odd(unsigned, [3, 1]));
+        assert!(contains_nonzero(unsigned, [3, 4]));
+        assert!(!contains_nonzero(unsigned, [7, 3]));
+
+        let float: &[Point<f64>] = &[[0.5, 0.5], [3.5, 0.5], [3.5, 2.5], [0.5, 2.5]];
+        assert!(contains_even_odd(float, [1.0, 1.0]));
+        assert!(contains_even_odd(float, [3.5, 1.0]));
+        assert!(!contains_even_odd(float, [4.0, 1.0]));
+        assert_eq!(winding_number(float, [2.0, 2.0]), 1);
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let triangle: &[Point] = &[[0, 0], [6, 0], [0, 6]];
+
+        assert!(contains_even_odd(triangle, [1, 1]));
+        assert!(contains_nonzero(triangle, [2, 3]));
+        assert!(is_on_outline(triangle, [3, 3]));
+        assert!(!contains_even_odd(triangle, [4, 3]));
+        assert!(!contains_nonzero(triangle, [5, 5]));
+    }
+}
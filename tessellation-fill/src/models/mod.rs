@@ -0,0 +1,20 @@
+//! Models for polygons and the quadtrees filling them.
+
+mod scalar;
+pub use scalar::Scalar;
+pub(crate) use scalar::{first_cell_reaching, from_cell, last_cell_reaching, orientation};
+
+mod containment;
+pub use containment::*;
+
+mod polygon;
+pub use polygon::*;
+
+mod quad;
+pub use quad::*;
+
+mod rects;
+pub use rects::*;
+
+mod region;
+pub use region::*;
@@ -0,0 +1,187 @@
+use super::Scalar;
+use crate::{TessellationFillError, traits::Polygon};
+
+/// A single coordinate of a cell on the grid.
+pub type Coord = i64;
+
+/// The ``[x, y]`` coordinates of a point, which defaults to the centre of a cell.
+pub type Point<T = Coord> = [T; 2];
+
+/// A polygon made of a single closed ring of edges, with coordinates of any [`Scalar`]
+/// type.
+///
+/// The ring is closed implicitly: the last vertex connects back to the first, so it should
+/// not be repeated.
+///
+/// With the ``serde`` feature, this (de)serializes as its list of vertices, which are
+/// checked by [`SimplePolygon::new`] when deserializing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<Point<T>>", into = "Vec<Point<T>>")
+)]
+pub struct SimplePolygon<T: Scalar = Coord> {
+    vertices: Vec<Point<T>>,
+}
+
+impl<T: Scalar> SimplePolygon<T> {
+    /// Create a polygon from its vertices, in order around the ring.
+    ///
+    /// Returns [`TessellationFillError::TooFewVertices`] if there are not enough vertices
+    /// to enclose any area.
+    pub fn new(vertices: Vec<Point<T>>) -> Result<Self, TessellationFillError<T>> {
+        if vertices.len() < 3 {
+            return Err(TessellationFillError::TooFewVertices {
+                count: vertices.len(),
+                minimum: 3,
+            });
+        }
+
+        Ok(Self { vertices })
+    }
+
+    /// Create a polygon from its vertices like [`SimplePolygon::new`], additionally
+    /// requiring every edge to be horizontal or vertical.
+    ///
+    /// Returns [`TessellationFillError::NonOrthogonalEdge`] for the first edge that is
+    /// not.
+    pub fn new_orthogonal(vertices: Vec<Point<T>>) -> Result<Self, TessellationFillError<T>> {
+        if vertices.len() < 4 {
+            return Err(TessellationFillError::TooFewVertices {
+                count: vertices.len(),
+                minimum: 4,
+            });
+        }
+
+        let polygon = Self { vertices };
+        if let Some((index, (from, to))) = polygon
+            .edges()
+            .enumerate()
+            .find(|(_, (from, to))| from[0] != to[0] && from[1] != to[1])
+        {
+            return Err(TessellationFillError::NonOrthogonalEdge { index, from, to });
+        }
+
+        Ok(polygon)
+    }
+
+    /// The vertices, for editing in place; nothing is checked.
+    pub(crate) fn vertices_mut(&mut self) -> &mut Vec<Point<T>> {
+        &mut self.vertices
+    }
+
+    /// Whether every edge is horizontal or vertical.
+    pub fn is_orthogonal(&self) -> bool {
+        self.edges()
+            .all(|(from, to)| from[0] == to[0] || from[1] == to[1])
+    }
+}
+
+impl<T: Scalar> TryFrom<Vec<Point<T>>> for SimplePolygon<T> {
+    type Error = TessellationFillError<T>;
+
+    /// See [`SimplePolygon::new`].
+    fn try_from(vertices: Vec<Point<T>>) -> Result<Self, Self::Error> {
+        Self::new(vertices)
+    }
+}
+
+impl<T: Scalar> From<SimplePolygon<T>> for Vec<Point<T>> {
+    fn from(polygon: SimplePolygon<T>) -> Self {
+        polygon.vertices
+    }
+}
+
+impl<T: Scalar> Polygon for SimplePolygon<T> {
+    type Scalar = T;
+
+    fn vertices(&self) -> &[Point<T>] {
+        &self.vertices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Quad;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            SimplePolygon::new(vec![[0, 0], [1, 0]]),
+            Err(TessellationFillError::TooFewVertices {
+                count: 2,
+                minimum: 3
+            })
+        );
+        assert_eq!(
+            SimplePolygon::new_orthogonal(vec![[0, 0], [1, 0], [1, 1]]),
+            Err(TessellationFillError::TooFewVertices {
+                count: 3,
+                minimum: 4
+            })
+        );
+        assert!(
+            SimplePolygon::new(vec![[0, 0], [2, 0], [2, 2], [1, 3]])
+                .is_ok_and(|polygon| !polygon.is_orthogonal())
+        );
+        assert_eq!(
+            SimplePolygon::new_orthogonal(vec![[0, 0], [2, 0], [2, 2], [1, 3]]),
+            Err(TessellationFillError::NonOrthogonalEdge {
+                index: 2,
+                from: [2, 2],
+                to: [1, 3],
+            })
+        );
+        // The closing edge is checked too.
+        assert!(matches!(
+            SimplePolygon::new_orthogonal(vec![[0, 0], [2, 0], [2, 2], [1, 2]]),
+            Err(TessellationFillError::NonOrthogonalEdge { index: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_edges_and_bounds() {
+        let polygon =
+            SimplePolygon::new(vec![[1, 2], [5, 2], [5, 7], [1, 7]]).expect("Invalid polygon");
+
+        assert_eq!(
+            polygon.edges().collect::<Vec<_>>(),
+            vec![
+                ([1, 2], [5, 2]),
+                ([5, 2], [5, 7]),
+                ([5, 7], [1, 7]),
+                ([1, 7], [1, 2]),
+            ]
+        );
+        assert_eq!(polygon.bounds(), Quad::new(1, 2, 5, 6));
+    }
+
+    #[test]
+    fn test_bounds_between_cells() {
+        // Only the cells whose centres are within the vertices are covered.
+        let polygon = SimplePolygon::new(vec![[0.5, -1.5], [4.5, -1.5], [4.5, 2.0], [0.5, 2.0]])
+            .expect("Invalid polygon");
+        assert_eq!(polygon.bounds(), Quad::new(1, -1, 4, 4));
+
+        let polygon = SimplePolygon::<u32>::new(vec![[3, 0], [5, 0], [5, 9], [3, 9]])
+            .expect("Invalid polygon");
+        assert_eq!(polygon.bounds(), Quad::new(3, 0, 3, 10));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let polygon =
+            SimplePolygon::new(vec![[1, 2], [5, 2], [5, 7], [1, 7]]).expect("Invalid polygon");
+        let serialized = serde_json::to_string(&polygon).expect("Failed to serialize");
+        assert_eq!(serialized, "[[1,2],[5,2],[5,7],[1,7]]");
+        let deserialized: SimplePolygon =
+            serde_json::from_str(&serialized).expect("Failed to deserialize");
+        assert_eq!(deserialized, polygon);
+
+        // The vertices are checked as if the polygon was created from them.
+        assert!(serde_json::from_str::<SimplePolygon>("[[1,2],[5,2]]").is_err());
+    }
+}
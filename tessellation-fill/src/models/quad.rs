@@ -0,0 +1,309 @@
+use super::{Coord, Point, Scalar, from_cell, orientation};
+use std::cmp::Ordering;
+
+/// A rectangle of whole cells, from ``[x, y]`` to ``[x + width - 1, y + height - 1]``
+/// inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quad {
+    pub x: Coord,
+    pub y: Coord,
+    pub width: Coord,
+    pub height: Coord,
+}
+
+impl Quad {
+    /// Create a quad from its top left cell and its size in cells.
+    pub const fn new(x: Coord, y: Coord, width: Coord, height: Coord) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Create the smallest quad covering both ``min`` and ``max``, inclusive.
+    pub fn from_corners(min: Point, max: Point) -> Self {
+        Self::new(min[0], min[1], max[0] - min[0] + 1, max[1] - min[1] + 1)
+    }
+
+    /// The top left cell.
+    pub const fn min(&self) -> Point {
+        [self.x, self.y]
+    }
+
+    /// The bottom right cell, inclusive.
+    pub const fn max(&self) -> Point {
+        [self.x + self.width - 1, self.y + self.height - 1]
+    }
+
+    /// Whether this quad covers no cells at all.
+    pub const fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// The number of cells covered.
+    pub const fn area(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.width as u64 * self.height as u64
+        }
+    }
+
+    /// Whether this quad is a single cell, and so cannot be split any further.
+    pub const fn is_cell(&self) -> bool {
+        self.width == 1 && self.height == 1
+    }
+
+    /// Whether the cell at ``point`` is covered by this quad.
+    pub const fn contains(&self, point: Point) -> bool {
+        let max = self.max();
+        point[0] >= self.x && point[0] <= max[0] && point[1] >= self.y && point[1] <= max[1]
+    }
+
+    /// Iterate over every cell covered, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Point> + use<> {
+        let [max_x, max_y] = self.max();
+        let min_x = self.x;
+        (self.y..=max_y).flat_map(move |y| (min_x..=max_x).map(move |x| [x, y]))
+    }
+
+    /// This quad with ``by`` more cells on every side.
+    pub const fn grow(&self, by: Coord) -> Self {
+        Self::new(
+            self.x - by,
+            self.y - by,
+            self.width + 2 * by,
+            self.height + 2 * by,
+        )
+    }
+
+    /// The cells covered by both this quad and ``other``, if any.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let (max, other_max) = (self.max(), other.max());
+        let min = [self.x.max(other.x), self.y.max(other.y)];
+        let max = [max[0].min(other_max[0]), max[1].min(other_max[1])];
+
+        (min[0] <= max[0] && min[1] <= max[1]).then(|| Self::from_corners(min, max))
+    }
+
+    /// Whether the range of cell centres covered by this quad overlaps the range of the
+    /// axis-aligned segment from ``from`` to ``to`` inclusive; for a single cell, whether
+    /// its centre is on the segment.
+    ///
+    /// Panics if the cells of this quad cannot be represented in ``T``.
+    pub fn intersects_segment<T: Scalar>(&self, from: Point<T>, to: Point<T>) -> bool {
+        let (min, max) = (self.min(), self.max());
+        let overlaps = |axis: usize| {
+            let (low, high) = if from[axis] <= to[axis] {
+                (from[axis], to[axis])
+            } else {
+                (to[axis], from[axis])
+            };
+            low <= from_cell(max[axis]) && high >= from_cell(min[axis])
+        };
+
+        overlaps(0) && overlaps(1)
+    }
+
+    /// The segment from ``from`` to ``to`` and the corners of the cells covered by this
+    /// quad, in doubled coordinates shifted by one, so that the edges of the cells fall on
+    /// whole numbers without ever going negative.
+    fn doubled<T: Scalar>(&self, from: Point<T>, to: Point<T>) -> ([Point<T>; 2], [Point<T>; 4]) {
+        let double = |value: T| value + value + T::one();
+        let (min, max) = (self.min(), self.max());
+        let (low, high) = (
+            min.map(|coord| from_cell::<T>(coord * 2)),
+            max.map(|coord| from_cell::<T>(coord * 2 + 2)),
+        );
+
+        (
+            [from.map(double), to.map(double)],
+            [low, [high[0], low[1]], high, [low[0], high[1]]],
+        )
+    }
+
+    /// Whether the segment from ``from`` to ``to`` touches any of the squares of the cells
+    /// covered by this quad, including their edges.
+    ///
+    /// Panics if the cells of this quad cannot be represented in ``T``.
+    pub fn touches_segment<T: Scalar>(&self, from: Point<T>, to: Point<T>) -> bool {
+        let ([from, to], corners) = self.doubled(from, to);
+        let (low, high) = (corners[0], corners[2]);
+        let overlaps = |axis: usize| {
+            let (start, end) = ordered(from[axis], to[axis]);
+            start <= high[axis] && end >= low[axis]
+        };
+
+        // Separated along either axis, or along the normal of the segment.
+        overlaps(0) && overlaps(1) && {
+            let sides = corners.map(|corner| orientation(from, to, corner));
+            !sides.iter().all(|&side| side == Ordering::Greater)
+                && !sides.iter().all(|&side| side == Ordering::Less)
+        }
+    }
+
+    /// Whether the segment from ``from`` to ``to`` passes through the inside of the square
+    /// of any cell covered by this quad, rather than just along its edges.
+    ///
+    /// This is the supercover of the segment: every cell it passes through, including
+    /// those it only clips the corner of.
+    ///
+    /// Panics if the cells of this quad cannot be represented in ``T``.
+    pub fn crosses_segment<T: Scalar>(&self, from: Point<T>, to: Point<T>) -> bool {
+        let ([from, to], corners) = self.doubled(from, to);
+        let (low, high) = (corners[0], corners[2]);
+        let overlaps = |axis: usize| {
+            let (start, end) = ordered(from[axis], to[axis]);
+            start < high[axis] && end > low[axis]
+        };
+
+        overlaps(0) && overlaps(1) && {
+            let sides = corners.map(|corner| orientation(from, to, corner));
+            // A single point has no sides, but is inside the squares.
+            from == to || (sides.contains(&Ordering::Greater) && sides.contains(&Ordering::Less))
+        }
+    }
+
+    /// Split into up to four quadrants, halving each side; sides of a single cell are not
+    /// split, so a row or a column of cells is only split in two.
+    pub fn split(&self) -> impl Iterator<Item = Self> + use<> {
+        let (left, right) = (self.width - self.width / 2, self.width / 2);
+        let (top, bottom) = (self.height - self.height / 2, self.height / 2);
+        let (x, y) = (self.x, self.y);
+
+        [
+            Self::new(x, y, left, top),
+            Self::new(x + left, y, right, top),
+            Self::new(x, y + top, left, bottom),
+            Self::new(x + left, y + top, right, bottom),
+        ]
+        .into_iter()
+        .filter(|quad| !quad.is_empty())
+    }
+}
+
+/// ``a`` and ``b``, in ascending order.
+fn ordered<T: PartialOrd>(a: T, b: T) -> (T, T) {
+    if b < a { (b, a) } else { (a, b) }
+}
+
+/// How a [`Quad`] lies relative to the outline of a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuadClass {
+    /// Every cell is inside the polygon, and none is on its outline.
+    Inside,
+    /// Every cell is outside the polygon.
+    Outside,
+    /// The outline of the polygon touches at least one cell; for a single cell, the
+    /// outline passes through it, and so it is filled.
+    Boundary,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corners() {
+        let quad = Quad::from_corners([2, 3], [5, 3]);
+        assert_eq!(quad, Quad::new(2, 3, 4, 1));
+        assert_eq!(quad.max(), [5, 3]);
+        assert_eq!(quad.area(), 4);
+        assert!(quad.contains([5, 3]));
+        assert!(!quad.contains([6, 3]));
+        assert!(!quad.contains([2, 4]));
+        assert_eq!(quad.grow(2), Quad::new(0, 1, 8, 5));
+        assert_eq!(
+            Quad::new(1, 1, 2, 2).cells().collect::<Vec<_>>(),
+            vec![[1, 1], [2, 1], [1, 2], [2, 2]]
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        let quad = Quad::new(0, 0, 4, 4);
+        assert_eq!(
+            quad.intersection(&Quad::new(2, -1, 5, 2)),
+            Some(Quad::new(2, 0, 2, 1))
+        );
+        assert_eq!(
+            quad.intersection(&Quad::new(1, 1, 1, 1)),
+            Some(Quad::new(1, 1, 1, 1))
+        );
+        assert_eq!(quad.intersection(&Quad::new(4, 0, 1, 1)), None);
+    }
+
+    #[test]
+    fn test_touches_and_crosses_segment() {
+        let quad = Quad::new(2, 2, 3, 3);
+
+        // Diagonals through the quad, and just past its corner.
+        assert!(quad.crosses_segment([0, 0], [6, 6]));
+        assert!(quad.crosses_segment([0, 6], [6, 0]));
+        assert!(quad.crosses_segment([0, 2], [2, 4]));
+        assert!(quad.touches_segment([0, 3], [2, 5]));
+        assert!(!quad.crosses_segment([0, 3], [2, 5]));
+        assert!(!quad.crosses_segment([0, 5], [2, 7]));
+        assert!(!quad.touches_segment([0, 6], [2, 8]));
+
+        // Along the edge of the squares: touching, but not crossing.
+        assert!(quad.touches_segment([4.5, 0.0], [4.5, 9.0]));
+        assert!(!quad.crosses_segment([4.5, 0.0], [4.5, 9.0]));
+        assert!(quad.crosses_segment([4.4, 0.0], [4.4, 9.0]));
+
+        // Clipping the corner of a single cell.
+        let cell = Quad::new(0, 0, 1, 1);
+        assert!(cell.crosses_segment([0.2, 0.7], [0.7, 0.2]));
+        assert!(cell.touches_segment([0.0, 1.0], [1.0, 0.0]));
+        assert!(!cell.crosses_segment([0.0, 1.0], [1.0, 0.0]));
+        assert!(!cell.touches_segment([0.25, 1.5], [1.5, 0.25]));
+
+        // Unsigned coordinates next to cell zero.
+        assert!(cell.crosses_segment::<u32>([0, 0], [3, 1]));
+        assert!(!cell.crosses_segment::<u32>([1, 0], [1, 3]));
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            Quad::new(0, 0, 5, 4).split().collect::<Vec<_>>(),
+            vec![
+                Quad::new(0, 0, 3, 2),
+                Quad::new(3, 0, 2, 2),
+                Quad::new(0, 2, 3, 2),
+                Quad::new(3, 2, 2, 2),
+            ]
+        );
+        assert_eq!(
+            Quad::new(4, 7, 1, 3).split().collect::<Vec<_>>(),
+            vec![Quad::new(4, 7, 1, 2), Quad::new(4, 9, 1, 1)]
+        );
+        assert_eq!(Quad::new(4, 7, 1, 1).split().count(), 1);
+
+        // The quadrants always cover the original exactly.
+        let quad = Quad::new(-3, 5, 7, 9);
+        assert_eq!(
+            quad.split().map(|child| child.area()).sum::<u64>(),
+            quad.area()
+        );
+    }
+
+    #[test]
+    fn test_intersects_segment() {
+        let quad = Quad::new(2, 2, 3, 3);
+        assert!(quad.intersects_segment([0, 3], [9, 3]));
+        assert!(quad.intersects_segment([4, 4], [4, 9]));
+        assert!(quad.intersects_segment([4, 0], [4, 2]));
+        assert!(!quad.intersects_segment([5, 0], [5, 9]));
+        assert!(!quad.intersects_segment([0, 1], [9, 1]));
+        assert!(!quad.intersects_segment([0, 3], [1, 3]));
+
+        // Between the centres of two columns of cells.
+        assert!(!quad.intersects_segment([4.5, 0.0], [4.5, 9.0]));
+        assert!(quad.intersects_segment([3.5, 0.0], [3.5, 9.0]));
+    }
+}
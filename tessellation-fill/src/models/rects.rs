@@ -0,0 +1,490 @@
+use super::{Coord, Point, Quad};
+use std::collections::BTreeMap;
+
+/// The cells of a [`FilledRegion`](crate::FilledRegion) as a short list of disjoint
+/// rectangles, which stays small however many cells they cover.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RectList {
+    bounds: Quad,
+    rects: Vec<Quad>,
+}
+
+/// A run of adjacent filled cells along one row, from ``[x_start, y]`` to ``[x_end, y]``
+/// inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RowSpan {
+    pub y: Coord,
+    pub x_start: Coord,
+    pub x_end: Coord,
+}
+
+impl RowSpan {
+    /// The number of cells in the run.
+    pub const fn width(&self) -> Coord {
+        self.x_end - self.x_start + 1
+    }
+}
+
+impl RectList {
+    /// Merge ``quads``, which may overlap, into as few rectangles as a single sweep down
+    /// the rows can find.
+    ///
+    /// The rows are split into bands wherever a quad starts or ends; within a band, each
+    /// run of adjacent filled cells becomes one rectangle as wide as the run, which is
+    /// extended downwards for as long as the same run continues in the next band.
+    pub fn from_quads(bounds: Quad, quads: impl IntoIterator<Item = Quad>) -> Self {
+        let mut rects = Vec::new();
+        // The runs of the previous band, and the row their rectangles started on.
+        let mut open: BTreeMap<(Coord, Coord), Coord> = BTreeMap::new();
+
+        for (y, runs) in bands(quads) {
+            let mut next_open = BTreeMap::new();
+            for run in runs {
+                let top = open.remove(&run).unwrap_or(y);
+                next_open.insert(run, top);
+            }
+            rects.extend(
+                open.into_iter()
+                    .map(|((start, end), top)| Quad::new(start, top, end - start, y - top)),
+            );
+            open = next_open;
+        }
+
+        rects.sort_unstable_by_key(|rect| (rect.y, rect.x));
+        Self { bounds, rects }
+    }
+
+    /// The quad covering the whole region; no cell outside of it is filled.
+    pub const fn bounds(&self) -> Quad {
+        self.bounds
+    }
+
+    /// The rectangles, sorted by their top left cell, ``y`` first.
+    pub fn rects(&self) -> &[Quad] {
+        &self.rects
+    }
+
+    /// The number of rectangles.
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    /// Whether there are no filled cells at all.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// The number of filled cells.
+    pub fn area(&self) -> u64 {
+        self.rects.iter().map(|rect| rect.area()).sum()
+    }
+
+    /// Whether the cell at ``point`` is covered by any of the rectangles.
+    pub fn contains(&self, point: Point) -> bool {
+        let after = self.rects.partition_point(|rect| rect.y <= point[1]);
+        self.rects[..after].iter().any(|rect| rect.contains(point))
+    }
+
+    /// Lazily iterate over the runs of covered cells along each row, top to bottom and
+    /// left to right, with runs that touch merged into one.
+    ///
+    /// The rows are split into bands as in [`from_quads`](Self::from_quads), and the runs
+    /// of each band repeated for each of its rows; only the bands are held in memory.
+    pub fn row_spans(&self) -> impl Iterator<Item = RowSpan> + use<> {
+        let bands = bands(self.rects.iter().copied());
+        let ends = bands.iter().skip(1).map(|band| band.0).collect::<Vec<_>>();
+
+        bands.into_iter().zip(ends).flat_map(|((top, runs), end)| {
+            (top..end).flat_map(move |y| {
+                runs.clone().into_iter().map(move |(start, stop)| RowSpan {
+                    y,
+                    x_start: start,
+                    x_end: stop - 1,
+                })
+            })
+        })
+    }
+
+    /// The cells within ``within`` that are not covered by any of the rectangles.
+    pub fn complement(&self, within: Quad) -> Self {
+        let bands = bands(
+            self.rects
+                .iter()
+                .filter_map(|rect| rect.intersection(&within)),
+        );
+        let (right, bottom) = (within.x + within.width, within.y + within.height);
+
+        let first = bands.first().map_or(bottom, |band| band.0);
+        let mut gaps = vec![Quad::new(
+            within.x,
+            within.y,
+            within.width,
+            first - within.y,
+        )];
+        for (index, (top, runs)) in bands.iter().enumerate() {
+            let end = bands.get(index + 1).map_or(bottom, |band| band.0);
+            let mut x = within.x;
+            for &(start, stop) in runs.iter().chain(&[(right, right)]) {
+                gaps.push(Quad::new(x, *top, start - x, end - top));
+                x = stop;
+            }
+        }
+
+        Self::from_quads(within, gaps)
+    }
+
+    /// Every cell within ``cells`` of a covered cell in either direction, which is the
+    /// same as each cell being at most ``cells`` away by
+    /// [`distance_to_boundary`](crate::FilledRegion::distance_to_boundary); so every
+    /// rectangle simply grows by ``cells`` on each side, and is merged again.
+    ///
+    /// ``cells`` must not be negative; the bounds grow along with the rectangles.
+    pub fn dilate(&self, cells: Coord) -> Self {
+        Self::from_quads(
+            self.bounds.grow(cells),
+            self.rects.iter().map(|rect| rect.grow(cells)),
+        )
+    }
+
+    /// Only the covered cells with no uncovered cell within ``cells`` of them in either
+    /// direction, counting every cell outside of [`bounds`](Self::bounds) as uncovered.
+    ///
+    /// This is the complement of the uncovered cells [dilated](Self::dilate) by
+    /// ``cells``; only uncovered cells up to ``cells`` outside of the bounds can reach
+    /// back into them, so the complement is only taken that far out.
+    ///
+    /// ``cells`` must not be negative; the bounds are kept as they are.
+    pub fn erode(&self, cells: Coord) -> Self {
+        self.complement(self.bounds.grow(cells))
+            .dilate(cells)
+            .complement(self.bounds)
+    }
+
+    /// The largest rectangle with two of ``corners`` at its opposite corners, and every
+    /// cell of which is covered by these rectangles.
+    ///
+    /// The grid is compressed to the rows and columns where a rectangle or a corner starts
+    /// or ends, and the covered area of every compressed block summed up into a table, so
+    /// that the coverage of each candidate is found in ``O(log n)``; candidates are tried
+    /// from the largest down, so only those larger than the answer are checked at all.
+    ///
+    /// Returns [`None`] if no such rectangle exists, including if ``corners`` is empty.
+    pub fn largest_rect_between(&self, corners: &[Point]) -> Option<Quad> {
+        let xs = compress(
+            self.rects
+                .iter()
+                .flat_map(|rect| [rect.x, rect.x + rect.width])
+                .chain(corners.iter().flat_map(|corner| [corner[0], corner[0] + 1])),
+        );
+        let ys = compress(
+            self.rects
+                .iter()
+                .flat_map(|rect| [rect.y, rect.y + rect.height])
+                .chain(corners.iter().flat_map(|corner| [corner[1], corner[1] + 1])),
+        );
+        let index = |coords: &[Coord], value: Coord| {
+            coords
+                .binary_search(&value)
+                .expect("Unreachable; every boundary was compressed")
+        };
+
+        // ``covered[j * columns + i]`` is the covered area above row ``ys[j]`` and left of
+        // column ``xs[i]``.
+        let columns = xs.len();
+        let mut covered = vec![0u64; columns * ys.len()];
+        for rect in &self.rects {
+            let [left, top] = [index(&xs, rect.x), index(&ys, rect.y)];
+            let [right, bottom] = [
+                index(&xs, rect.x + rect.width),
+                index(&ys, rect.y + rect.height),
+            ];
+            for j in top..bottom {
+                for i in left..right {
+                    covered[(j + 1) * columns + i + 1] =
+                        ((xs[i + 1] - xs[i]) * (ys[j + 1] - ys[j])) as u64;
+                }
+            }
+        }
+        for j in 1..ys.len() {
+            for i in 1..columns {
+                covered[j * columns + i] += covered[(j - 1) * columns + i]
+                    + covered[j * columns + i - 1]
+                    - covered[(j - 1) * columns + i - 1];
+            }
+        }
+
+        let is_covered = |candidate: &Quad| {
+            let [left, top] = [index(&xs, candidate.x), index(&ys, candidate.y)];
+            let [right, bottom] = [
+                index(&xs, candidate.x + candidate.width),
+                index(&ys, candidate.y + candidate.height),
+            ];
+            covered[bottom * columns + right] + covered[top * columns + left]
+                - covered[top * columns + right]
+                - covered[bottom * columns + left]
+                == candidate.area()
+        };
+
+        let mut candidates = corners
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &a)| {
+                corners[index..].iter().map(move |&b| {
+                    Quad::from_corners(
+                        [a[0].min(b[0]), a[1].min(b[1])],
+                        [a[0].max(b[0]), a[1].max(b[1])],
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_unstable_by_key(|candidate| std::cmp::Reverse(candidate.area()));
+        candidates.into_iter().find(is_covered)
+    }
+}
+
+/// The rows covered by ``quads``, which may overlap, split into bands wherever a quad
+/// starts or ends: the first row of each band, and its runs of covered cells as
+/// ``(x_start, x_end)``, sorted, merged and with ``x_end`` exclusive. Each band ends
+/// where the next starts, and the last has no runs.
+fn bands(quads: impl IntoIterator<Item = Quad>) -> Vec<(Coord, Vec<(Coord, Coord)>)> {
+    // ``(y, is_start, x_start, x_end)``, where ``x_end`` is exclusive; ends sort first.
+    let mut events = quads
+        .into_iter()
+        .filter(|quad| !quad.is_empty())
+        .flat_map(|quad| {
+            let (start, end) = (quad.x, quad.x + quad.width);
+            [
+                (quad.y, true, start, end),
+                (quad.y + quad.height, false, start, end),
+            ]
+        })
+        .collect::<Vec<_>>();
+    events.sort_unstable();
+
+    let mut bands = Vec::new();
+    // How many quads cover each ``(x_start, x_end)`` in the current band.
+    let mut active: BTreeMap<(Coord, Coord), usize> = BTreeMap::new();
+    let mut events = events.into_iter().peekable();
+
+    while let Some(&(y, ..)) = events.peek() {
+        while let Some((_, is_start, start, end)) = events.next_if(|event| event.0 == y) {
+            if is_start {
+                *active.entry((start, end)).or_default() += 1;
+            } else if let Some(count) = active.get_mut(&(start, end)) {
+                *count -= 1;
+                if *count == 0 {
+                    active.remove(&(start, end));
+                }
+            }
+        }
+
+        let mut runs: Vec<(Coord, Coord)> = Vec::new();
+        for &(start, end) in active.keys() {
+            match runs.last_mut() {
+                Some(run) if run.1 >= start => run.1 = run.1.max(end),
+                _ => runs.push((start, end)),
+            }
+        }
+        bands.push((y, runs));
+    }
+
+    bands
+}
+
+/// The sorted, distinct values of ``bounds``.
+fn compress(bounds: impl Iterator<Item = Coord>) -> Vec<Coord> {
+    let mut coords = bounds.collect::<Vec<_>>();
+    coords.sort_unstable();
+    coords.dedup();
+    coords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        // Four cells of a 2x2 square, and a row of two cells below it.
+        let rects = RectList::from_quads(
+            Quad::new(0, 0, 3, 3),
+            [
+                Quad::new(0, 0, 1, 1),
+                Quad::new(1, 0, 1, 1),
+                Quad::new(0, 1, 1, 1),
+                Quad::new(1, 1, 1, 1),
+                Quad::new(1, 2, 2, 1),
+            ],
+        );
+
+        assert_eq!(
+            rects.rects(),
+            &[Quad::new(0, 0, 2, 2), Quad::new(1, 2, 2, 1)]
+        );
+        assert_eq!(rects.area(), 6);
+        assert!(rects.contains([0, 1]));
+        assert!(rects.contains([2, 2]));
+        assert!(!rects.contains([0, 2]));
+        assert!(!rects.contains([2, 0]));
+    }
+
+    #[test]
+    fn test_gaps() {
+        // A gap between two bands, and between two runs of the same band.
+        let rects = RectList::from_quads(
+            Quad::new(0, 0, 5, 5),
+            [
+                Quad::new(0, 0, 2, 2),
+                Quad::new(3, 0, 2, 2),
+                Quad::new(0, 3, 5, 2),
+            ],
+        );
+
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects.area(), 18);
+        assert!(!rects.contains([2, 1]));
+        assert!(!rects.contains([1, 2]));
+
+        assert!(RectList::from_quads(Quad::new(0, 0, 1, 1), []).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping() {
+        // A cross of two overlapping bars, with a copy of one of them.
+        let rects = RectList::from_quads(
+            Quad::new(0, 0, 5, 5),
+            [
+                Quad::new(0, 2, 5, 1),
+                Quad::new(2, 0, 1, 5),
+                Quad::new(2, 0, 1, 5),
+            ],
+        );
+
+        assert_eq!(
+            rects.rects(),
+            &[
+                Quad::new(2, 0, 1, 2),
+                Quad::new(0, 2, 5, 1),
+                Quad::new(2, 3, 1, 2)
+            ]
+        );
+        assert_eq!(rects.area(), 9);
+    }
+
+    /// A ring with a hole of 3x3 cells, and a single cell in the middle of the hole.
+    fn ring() -> RectList {
+        RectList::from_quads(
+            Quad::new(0, 0, 9, 9),
+            [
+                Quad::new(0, 0, 9, 3),
+                Quad::new(0, 3, 3, 3),
+                Quad::new(6, 3, 3, 3),
+                Quad::new(0, 6, 9, 3),
+                Quad::new(4, 4, 1, 1),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_complement() {
+        let rects = ring();
+        let within = Quad::new(-1, 2, 11, 5);
+        let complement = rects.complement(within);
+
+        assert_eq!(complement.bounds(), within);
+        for cell in within.grow(1).cells() {
+            assert_eq!(
+                complement.contains(cell),
+                within.contains(cell) && !rects.contains(cell),
+                "{cell:?}"
+            );
+        }
+        assert_eq!(rects.complement(Quad::new(3, 3, 3, 3)).area(), 8);
+        assert!(rects.complement(Quad::new(0, 0, 3, 3)).is_empty());
+    }
+
+    #[test]
+    fn test_dilate_and_erode() {
+        let rects = ring();
+        let within = |cell: Point, cells: Coord, covered: bool| {
+            Quad::new(cell[0], cell[1], 1, 1)
+                .grow(cells)
+                .cells()
+                .any(|other| rects.contains(other) == covered)
+        };
+
+        for cells in 0..4 {
+            let dilated = rects.dilate(cells);
+            let eroded = rects.erode(cells);
+            assert_eq!(dilated.bounds(), rects.bounds().grow(cells));
+            assert_eq!(eroded.bounds(), rects.bounds());
+
+            for cell in rects.bounds().grow(cells + 1).cells() {
+                assert_eq!(
+                    dilated.contains(cell),
+                    within(cell, cells, true),
+                    "{cell:?}"
+                );
+                assert_eq!(
+                    eroded.contains(cell),
+                    !within(cell, cells, false),
+                    "{cell:?}"
+                );
+            }
+        }
+
+        // The hole closes up, and the ring is worn away down to the middle of its sides.
+        assert_eq!(rects.dilate(1).complement(rects.bounds()).area(), 0);
+        assert_eq!(rects.erode(1).area(), 7 * 7 - 5 * 5);
+        assert!(rects.erode(2).is_empty());
+    }
+
+    #[test]
+    fn test_row_spans() {
+        let spans = ring().row_spans().collect::<Vec<_>>();
+
+        assert_eq!(spans.len(), 3 + 2 * 3 + 1 + 3);
+        assert_eq!(
+            spans[2..6],
+            [
+                RowSpan {
+                    y: 2,
+                    x_start: 0,
+                    x_end: 8
+                },
+                RowSpan {
+                    y: 3,
+                    x_start: 0,
+                    x_end: 2
+                },
+                RowSpan {
+                    y: 3,
+                    x_start: 6,
+                    x_end: 8
+                },
+                RowSpan {
+                    y: 4,
+                    x_start: 0,
+                    x_end: 2
+                },
+            ]
+        );
+        assert_eq!(
+            spans.iter().map(|span| span.width() as u64).sum::<u64>(),
+            ring().area()
+        );
+        assert!(
+            spans
+                .iter()
+                .all(|span| (span.x_start..=span.x_end).all(|x| ring().contains([x, span.y])))
+        );
+        assert_eq!(
+            RectList::from_quads(Quad::new(0, 0, 1, 1), [])
+                .row_spans()
+                .count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use super::{Coord, Point, Quad, QuadClass, RectList, RowSpan};
+
+/// A node of the quadtree built by [`tessellation_fill`](crate::tessellation_fill).
+///
+/// Only [`QuadClass::Boundary`] quads larger than a single cell have children; every other
+/// node is a leaf. A larger boundary quad is only a leaf if its fill ran out of budget, in
+/// which case all of its cells count as filled; see
+/// [`tessellation_fill_with_budget`](crate::tessellation_fill_with_budget).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuadNode {
+    pub quad: Quad,
+    pub class: QuadClass,
+    pub children: Vec<QuadNode>,
+}
+
+impl QuadNode {
+    /// Create a node without children.
+    pub const fn leaf(quad: Quad, class: QuadClass) -> Self {
+        Self {
+            quad,
+            class,
+            children: Vec::new(),
+        }
+    }
+
+    /// Whether this node has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Whether this node is a leaf covering only filled cells.
+    pub fn is_filled(&self) -> bool {
+        self.is_leaf() && self.class != QuadClass::Outside
+    }
+}
+
+/// How the cells of a [`FilledRegion`] are stored, depending on the
+/// [`FillStrategy`](crate::FillStrategy) that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Cells {
+    /// A quadtree, whose root covers the bounds of the region.
+    Quadtree(QuadNode),
+    /// Rows of filled cells, sorted by ``y`` then ``x``, none of which overlap.
+    Spans(Vec<Quad>),
+}
+
+/// The cells filled by a polygon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilledRegion {
+    bounds: Quad,
+    cells: Cells,
+}
+
+impl FilledRegion {
+    /// Wrap the root of a quadtree.
+    pub const fn new(root: QuadNode) -> Self {
+        Self {
+            bounds: root.quad,
+            cells: Cells::Quadtree(root),
+        }
+    }
+
+    /// Wrap spans of filled cells one row high within ``bounds``, as found by a scanline
+    /// fill.
+    ///
+    /// The spans are sorted here, but must not overlap each other.
+    pub fn from_spans(bounds: Quad, mut spans: Vec<Quad>) -> Self {
+        debug_assert!(spans.iter().all(|span| span.height == 1));
+        spans.sort_by_key(|span| (span.y, span.x));
+
+        Self {
+            bounds,
+            cells: Cells::Spans(spans),
+        }
+    }
+
+    /// The root of the quadtree, if this region is stored as one.
+    pub const fn root(&self) -> Option<&QuadNode> {
+        match &self.cells {
+            Cells::Quadtree(root) => Some(root),
+            Cells::Spans(_) => None,
+        }
+    }
+
+    /// The root of the quadtree, for refilling in place.
+    pub(crate) fn root_mut(&mut self) -> Option<&mut QuadNode> {
+        match &mut self.cells {
+            Cells::Quadtree(root) => Some(root),
+            Cells::Spans(_) => None,
+        }
+    }
+
+    /// The quad covering the whole region; no cell outside of it is filled.
+    pub const fn bounds(&self) -> Quad {
+        self.bounds
+    }
+
+    /// Iterate over every node of the quadtree, parents before their children.
+    ///
+    /// Yields nothing if this region is not stored as a quadtree.
+    pub fn nodes(&self) -> impl Iterator<Item = &QuadNode> + '_ {
+        let mut stack = self.root().into_iter().collect::<Vec<_>>();
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+            Some(node)
+        })
+    }
+
+    /// Iterate over quads covering only filled cells; together they cover every filled
+    /// cell exactly once.
+    pub fn filled_quads(&self) -> Box<dyn Iterator<Item = Quad> + '_> {
+        match &self.cells {
+            Cells::Quadtree(_) => Box::new(
+                self.nodes()
+                    .filter(|node| node.is_filled())
+                    .map(|node| node.quad),
+            ),
+            Cells::Spans(spans) => Box::new(spans.iter().copied()),
+        }
+    }
+
+    /// Lazily iterate over the coordinates of every filled cell, quad by quad and row by
+    /// row within each quad.
+    ///
+    /// Only the tree walk and the current quad are held in memory, however many cells
+    /// there are.
+    pub fn iter_filled_cells(&self) -> impl Iterator<Item = Point> + '_ {
+        self.filled_quads().flat_map(|quad| quad.cells())
+    }
+
+    /// Merge the filled quads into a [`RectList`], which is far smaller than the tree for
+    /// large regions.
+    pub fn to_rect_list(&self) -> RectList {
+        RectList::from_quads(self.bounds, self.filled_quads())
+    }
+
+    /// Lazily iterate over the runs of filled cells along each row, top to bottom and left
+    /// to right; see [`RectList::row_spans`].
+    ///
+    /// This is far more compact than [`iter_filled_cells`](Self::iter_filled_cells) for
+    /// anything drawing the region a row at a time.
+    pub fn row_spans(&self) -> impl Iterator<Item = RowSpan> + use<> {
+        self.to_rect_list().row_spans()
+    }
+
+    /// The number of filled cells, including those on the outline.
+    pub fn area(&self) -> u64 {
+        self.filled_quads().map(|quad| quad.area()).sum()
+    }
+
+    /// The leaf of the quadtree covering the cell at ``point``, walking down from the root.
+    ///
+    /// Returns [`None`] if ``point`` is out of bounds, or if this region is not stored as a
+    /// quadtree.
+    fn leaf_at(&self, point: Point) -> Option<&QuadNode> {
+        let mut node = self.root().filter(|root| root.quad.contains(point))?;
+        while !node.is_leaf() {
+            node = node
+                .children
+                .iter()
+                .find(|child| child.quad.contains(point))
+                .expect("Unreachable; the children of a node cover it exactly");
+        }
+
+        Some(node)
+    }
+
+    /// Whether the cell at ``point`` is filled, walking down the quadtree from the root,
+    /// or searching the spans of its row.
+    pub fn contains(&self, point: Point) -> bool {
+        match &self.cells {
+            Cells::Quadtree(_) => self.leaf_at(point).is_some_and(QuadNode::is_filled),
+            Cells::Spans(spans) => {
+                let after = spans.partition_point(|span| (span.y, span.x) <= (point[1], point[0]));
+                after > 0 && spans[after - 1].contains(point)
+            }
+        }
+    }
+
+    /// Whether the cell at ``point`` is filled without the outline passing through it,
+    /// walking down the quadtree from the root in ``O(log n)``.
+    ///
+    /// Regions not stored as a quadtree do not know where the outline is, so for them
+    /// this is the same as [`contains`](Self::contains).
+    pub fn is_inside(&self, point: Point) -> bool {
+        match &self.cells {
+            Cells::Quadtree(_) => self
+                .leaf_at(point)
+                .is_some_and(|leaf| leaf.class == QuadClass::Inside),
+            Cells::Spans(_) => self.contains(point),
+        }
+    }
+
+    /// The distance from the cell at ``point`` to the nearest cell on the outline, counted
+    /// in steps to any of the eight neighbouring cells; ``0`` for cells on the outline.
+    ///
+    /// Subtrees are searched nearest first, skipping any that cannot be closer than the
+    /// nearest outline cell found so far, so only the few leaves around ``point`` are
+    /// visited. ``point`` can be anywhere, inside, outside or out of bounds.
+    ///
+    /// Returns [`None`] if this region is not stored as a quadtree, or has no outline.
+    pub fn distance_to_boundary(&self, point: Point) -> Option<Coord> {
+        let mut nearest = None;
+        nearest_boundary(self.root()?, point, &mut nearest);
+        nearest
+    }
+}
+
+/// The distance from ``point`` to the nearest cell of ``quad``, in steps to any of the
+/// eight neighbouring cells.
+fn distance_to_quad(quad: &Quad, point: Point) -> Coord {
+    let [max_x, max_y] = quad.max();
+    let dx = (quad.x - point[0]).max(point[0] - max_x).max(0);
+    let dy = (quad.y - point[1]).max(point[1] - max_y).max(0);
+    dx.max(dy)
+}
+
+/// Update ``nearest`` with the distance from ``point`` to any outline cell in the subtree of
+/// ``node`` that is nearer.
+///
+/// A boundary leaf larger than a cell, left by a fill that ran out of budget, counts as
+/// outline throughout.
+fn nearest_boundary(node: &QuadNode, point: Point, nearest: &mut Option<Coord>) {
+    let distance = distance_to_quad(&node.quad, point);
+    if node.class != QuadClass::Boundary || nearest.is_some_and(|nearest| nearest <= distance) {
+        return;
+    }
+
+    if node.is_leaf() {
+        *nearest = Some(distance);
+    } else {
+        let mut children = node.children.iter().collect::<Vec<_>>();
+        children.sort_by_key(|child| distance_to_quad(&child.quad, point));
+        children
+            .into_iter()
+            .for_each(|child| nearest_boundary(child, point, nearest));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region() -> FilledRegion {
+        // A 2x2 quad with one cell outside.
+        FilledRegion::new(QuadNode {
+            quad: Quad::new(0, 0, 2, 2),
+            class: QuadClass::Boundary,
+            children: vec![
+                QuadNode::leaf(Quad::new(0, 0, 1, 1), QuadClass::Boundary),
+                QuadNode::leaf(Quad::new(1, 0, 1, 1), QuadClass::Boundary),
+                QuadNode::leaf(Quad::new(0, 1, 1, 1), QuadClass::Boundary),
+                QuadNode::leaf(Quad::new(1, 1, 1, 1), QuadClass::Outside),
+            ],
+        })
+    }
+
+    #[test]
+    fn test_area() {
+        let region = region();
+        assert_eq!(region.nodes().count(), 5);
+        assert_eq!(region.filled_quads().count(), 3);
+        assert_eq!(region.area(), 3);
+        assert_eq!(
+            region.iter_filled_cells().collect::<Vec<_>>(),
+            vec![[0, 0], [1, 0], [0, 1]]
+        );
+    }
+
+    #[test]
+    fn test_spans() {
+        let region = FilledRegion::from_spans(
+            Quad::new(0, 0, 6, 2),
+            vec![
+                Quad::new(3, 1, 3, 1),
+                Quad::new(4, 0, 2, 1),
+                Quad::new(0, 1, 2, 1),
+                Quad::new(0, 0, 1, 1),
+            ],
+        );
+
+        assert!(region.root().is_none());
+        assert_eq!(region.nodes().count(), 0);
+        assert_eq!(region.area(), 8);
+        assert_eq!(region.iter_filled_cells().count(), 8);
+        assert!(region.contains([0, 0]));
+        assert!(region.contains([5, 0]));
+        assert!(region.contains([1, 1]));
+        assert!(region.contains([3, 1]));
+        assert!(!region.contains([2, 1]));
+        assert!(!region.contains([3, 0]));
+        assert!(!region.contains([6, 1]));
+    }
+
+    #[test]
+    fn test_contains() {
+        let region = region();
+        assert!(region.contains([0, 0]));
+        assert!(region.contains([0, 1]));
+        assert!(!region.contains([1, 1]));
+        assert!(!region.contains([2, 0]));
+        assert!(!region.contains([-1, 0]));
+    }
+
+    #[test]
+    fn test_row_spans() {
+        use crate::{FillStrategy, SimplePolygon, tessellation_fill};
+
+        let polygon = SimplePolygon::new(vec![[0, 0], [9, 2], [12, 11], [5, 7], [1, 12], [3, 5]])
+            .expect("Invalid polygon");
+        let region = tessellation_fill(&polygon);
+        let spans = region.row_spans().collect::<Vec<_>>();
+
+        let mut cells = spans
+            .iter()
+            .flat_map(|span| (span.x_start..=span.x_end).map(|x| [x, span.y]))
+            .collect::<Vec<_>>();
+        let mut expected = region.iter_filled_cells().collect::<Vec<_>>();
+        cells.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(cells, expected);
+
+        // Sorted, and never touching within a row; the same however the region is stored.
+        assert!(
+            spans
+                .windows(2)
+                .all(|pair| { pair[0].y < pair[1].y || pair[0].x_end + 1 < pair[1].x_start })
+        );
+        assert!(
+            spans
+                .iter()
+                .copied()
+                .eq(FillStrategy::Scanline.fill(&polygon).row_spans())
+        );
+    }
+
+    #[test]
+    fn test_is_inside() {
+        use crate::{SimplePolygon, tessellation_fill};
+
+        let polygon =
+            SimplePolygon::new(vec![[0, 0], [6, 0], [6, 6], [0, 6]]).expect("Invalid polygon");
+        let region = tessellation_fill(&polygon);
+
+        assert!(region.is_inside([3, 3]));
+        assert!(region.is_inside([1, 5]));
+        // On the outline, and outside.
+        assert!(!region.is_inside([0, 3]));
+        assert!(region.contains([0, 3]));
+        assert!(!region.is_inside([7, 3]));
+
+        // The outline of a region of spans is unknown.
+        let region = FilledRegion::from_spans(Quad::new(0, 0, 3, 1), vec![Quad::new(0, 0, 3, 1)]);
+        assert!(region.is_inside([0, 0]));
+        assert_eq!(region.distance_to_boundary([0, 0]), None);
+    }
+
+    #[test]
+    fn test_distance_to_boundary() {
+        use crate::{SimplePolygon, tessellation_fill};
+
+        let polygon = SimplePolygon::new(vec![
+            [0, 0],
+            [20, 0],
+            [20, 20],
+            [4, 20],
+            [4, 8],
+            [12, 8],
+            [12, 12],
+            [8, 12],
+            [8, 16],
+            [16, 16],
+            [16, 4],
+            [0, 4],
+            [3, 1],
+        ])
+        .expect("Invalid polygon");
+        let region = tessellation_fill(&polygon);
+        let outline = region
+            .nodes()
+            .filter(|node| node.is_leaf() && node.class == QuadClass::Boundary)
+            .map(|node| node.quad.min())
+            .collect::<Vec<_>>();
+
+        for y in -3..24 {
+            for x in -3..24 {
+                let expected = outline
+                    .iter()
+                    .map(|cell| (cell[0] - x).abs().max((cell[1] - y).abs()))
+                    .min();
+                assert_eq!(
+                    region.distance_to_boundary([x, y]),
+                    expected,
+                    "{:?}",
+                    [x, y]
+                );
+            }
+        }
+        assert_eq!(region.distance_to_boundary([18, 10]), Some(2));
+        assert_eq!(region.distance_to_boundary([100, 10]), Some(80));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use crate::{SimplePolygon, tessellation_fill};
+
+        let polygon = SimplePolygon::new(vec![[0, 0], [9, 0], [9, 4], [4, 4], [4, 9], [0, 9]])
+            .expect("Invalid polygon");
+        let region = tessellation_fill(&polygon);
+
+        let serialized = serde_json::to_string(&region).expect("Failed to serialize");
+        let deserialized: FilledRegion =
+            serde_json::from_str(&serialized).expect("Failed to deserialize");
+        assert_eq!(deserialized, region);
+        assert_eq!(deserialized.area(), region.area());
+
+        let serialized =
+            serde_json::to_string(&region.to_rect_list()).expect("Failed to serialize");
+        let deserialized: RectList =
+            serde_json::from_str(&serialized).expect("Failed to deserialize");
+        assert_eq!(deserialized, region.to_rect_list());
+
+        let node = QuadNode::leaf(Quad::new(1, 2, 3, 4), QuadClass::Inside);
+        let serialized = serde_json::to_string(&node).expect("Failed to serialize");
+        assert_eq!(
+            serialized,
+            r#"{"quad":{"x":1,"y":2,"width":3,"height":4},"class":"Inside","children":[]}"#
+        );
+    }
+}
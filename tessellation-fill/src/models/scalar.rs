@@ -0,0 +1,141 @@
+//! The scalar types that the coordinates of a polygon can be made of.
+
+use super::{Coord, Point};
+use num_traits::{Num, NumCast};
+use std::{cmp::Ordering, fmt::Debug};
+
+/// A type that the coordinates of a polygon can be made of, such as [`i64`], [`u32`] or
+/// [`f64`].
+///
+/// Whatever the coordinates of the polygon, the grid being filled is always made of cells
+/// with [`Coord`] coordinates, each centred on its integer coordinates; a cell is filled
+/// if its centre is on or inside the outline.
+pub trait Scalar: Num + NumCast + Copy + PartialOrd + Debug + Send + Sync {}
+
+impl<T> Scalar for T where T: Num + NumCast + Copy + PartialOrd + Debug + Send + Sync {}
+
+/// The difference ``a - b`` as a magnitude, and whether it is negative; this never
+/// underflows, even for unsigned types.
+fn difference<T: Scalar>(a: T, b: T) -> (T, bool) {
+    if a < b { (b - a, true) } else { (a - b, false) }
+}
+
+/// The product of two [`difference`]s, as a magnitude and whether it is negative.
+fn product<T: Scalar>(a: (T, bool), b: (T, bool)) -> (T, bool) {
+    let magnitude = a.0 * b.0;
+    (magnitude, a.1 != b.1 && magnitude != T::zero())
+}
+
+/// Which side of the line through ``from`` and ``to`` ``point`` is on: [`Ordering::Greater`]
+/// if it is to the left, looking from ``from`` towards ``to``, [`Ordering::Less`] if it is
+/// to the right, and [`Ordering::Equal`] if it is on the line.
+///
+/// This is the sign of the cross product of the edge and the vector to ``point``, found
+/// without any negative intermediate values; the products must fit in ``T``.
+pub(crate) fn orientation<T: Scalar>(from: Point<T>, to: Point<T>, point: Point<T>) -> Ordering {
+    let lhs = product(difference(to[0], from[0]), difference(point[1], from[1]));
+    let rhs = product(difference(point[0], from[0]), difference(to[1], from[1]));
+
+    match (lhs.1, rhs.1) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => lhs.0.partial_cmp(&rhs.0).unwrap_or(Ordering::Equal),
+        (true, true) => rhs.0.partial_cmp(&lhs.0).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// The coordinate of a cell as a scalar.
+///
+/// Panics if the cell cannot be represented in ``T``, which never happens for cells
+/// within the bounds of a polygon made of ``T``.
+pub(crate) fn from_cell<T: Scalar>(coord: Coord) -> T {
+    T::from(coord).expect("Unreachable; cells within the bounds of a polygon fit in its scalar")
+}
+
+/// The first cell whose centre is at or after ``value``.
+fn cell_at_or_after<T: Scalar>(value: T) -> Coord {
+    let cell: Coord = NumCast::from(value).expect("Coordinate out of the range of the grid");
+    if from_cell::<T>(cell) < value {
+        cell + 1
+    } else {
+        cell
+    }
+}
+
+/// The last cell whose centre is at or before ``value``.
+fn cell_at_or_before<T: Scalar>(value: T) -> Coord {
+    let cell: Coord = NumCast::from(value).expect("Coordinate out of the range of the grid");
+    if from_cell::<T>(cell) > value {
+        cell - 1
+    } else {
+        cell
+    }
+}
+
+/// The first cell whose square reaches past ``value``, so that a segment ending at
+/// ``value`` could pass through it.
+pub(crate) fn first_cell_reaching<T: Scalar>(value: T) -> Coord {
+    let cell = cell_at_or_before(value);
+    let offset = value - from_cell(cell);
+    if offset + offset < T::one() {
+        cell
+    } else {
+        cell + 1
+    }
+}
+
+/// The last cell whose square reaches before ``value``, so that a segment ending at
+/// ``value`` could pass through it.
+pub(crate) fn last_cell_reaching<T: Scalar>(value: T) -> Coord {
+    let cell = cell_at_or_after(value);
+    let offset = from_cell::<T>(cell) - value;
+    if offset + offset < T::one() {
+        cell
+    } else {
+        cell - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orientation() {
+        assert_eq!(orientation([0, 0], [4, 0], [2, 3]), Ordering::Greater);
+        assert_eq!(orientation([0, 0], [4, 0], [2, -3]), Ordering::Less);
+        assert_eq!(orientation([0, 0], [4, 4], [7, 7]), Ordering::Equal);
+
+        // Unsigned, where the edge runs backwards.
+        assert_eq!(
+            orientation::<u32>([4, 2], [0, 2], [1, 1]),
+            Ordering::Greater
+        );
+        assert_eq!(orientation::<u32>([4, 2], [0, 2], [1, 3]), Ordering::Less);
+
+        assert_eq!(
+            orientation([0.0, 0.0], [1.0, 1.0], [0.25, 0.5]),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_cells() {
+        assert_eq!(cell_at_or_after(2.5), 3);
+        assert_eq!(cell_at_or_before(2.5), 2);
+        assert_eq!(cell_at_or_after(-2.5), -2);
+        assert_eq!(cell_at_or_before(-2.5), -3);
+        assert_eq!(cell_at_or_after(3.0), 3);
+        assert_eq!(cell_at_or_before(3.0), 3);
+        assert_eq!(cell_at_or_after(7u32), 7);
+
+        assert_eq!(first_cell_reaching(2.3), 2);
+        assert_eq!(first_cell_reaching(2.5), 3);
+        assert_eq!(last_cell_reaching(2.3), 2);
+        assert_eq!(last_cell_reaching(2.5), 2);
+        assert_eq!(last_cell_reaching(2.7), 3);
+        assert_eq!(first_cell_reaching(-0.3), 0);
+        assert_eq!(first_cell_reaching(0u32), 0);
+        assert_eq!(last_cell_reaching(4i64), 4);
+    }
+}
@@ -0,0 +1,266 @@
+//! A faster classification of quads for polygons whose edges are all axis-aligned, as in
+//! every Advent of Code input.
+
+use crate::{
+    Coord, FilledRegion, Point, Quad, QuadClass, Scalar, SimplePolygon, TessellationFillError,
+    from_cell, subdivide_by, traits::Polygon,
+};
+
+/// An axis-aligned edge lying along ``at`` on one axis, from ``low`` to ``high`` on the
+/// other, in the doubled coordinates of [`OrthogonalEdges`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AxisEdge<T> {
+    at: T,
+    low: T,
+    high: T,
+}
+
+/// The edges of a polygon whose edges are all horizontal or vertical, kept in two lists
+/// each sorted by the coordinate the edges lie along.
+///
+/// A quad is only classified against the vertical edges within its columns and the
+/// horizontal edges within its rows, which a binary search finds, rather than against
+/// every edge at every angle; and whether its centre is inside only needs the vertical
+/// edges to its right. Coordinates are doubled and shifted by one, as in
+/// [`Quad::touches_segment`], so that the sides of cells fall on whole numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrthogonalEdges<T: Scalar = Coord> {
+    /// Sorted by ``x``.
+    vertical: Vec<AxisEdge<T>>,
+    /// Sorted by ``y``.
+    horizontal: Vec<AxisEdge<T>>,
+}
+
+impl<T: Scalar> OrthogonalEdges<T> {
+    /// Sort the edges of ``polygon``.
+    ///
+    /// Returns [`TessellationFillError::NonOrthogonalEdge`] for the first edge that is
+    /// neither horizontal nor vertical.
+    pub fn new(polygon: &SimplePolygon<T>) -> Result<Self, TessellationFillError<T>> {
+        let double = |value: T| value + value + T::one();
+        let ordered = |a: T, b: T| if b < a { (b, a) } else { (a, b) };
+        let (mut vertical, mut horizontal) = (Vec::new(), Vec::new());
+
+        for (index, (from, to)) in polygon.edges().enumerate() {
+            if from[0] != to[0] && from[1] != to[1] {
+                return Err(TessellationFillError::NonOrthogonalEdge { index, from, to });
+            }
+
+            let [from, to] = [from.map(double), to.map(double)];
+            if from[0] == to[0] {
+                let (low, high) = ordered(from[1], to[1]);
+                vertical.push(AxisEdge {
+                    at: from[0],
+                    low,
+                    high,
+                });
+            } else {
+                let (low, high) = ordered(from[0], to[0]);
+                horizontal.push(AxisEdge {
+                    at: from[1],
+                    low,
+                    high,
+                });
+            }
+        }
+
+        let by_position = |a: &AxisEdge<T>, b: &AxisEdge<T>| {
+            a.at.partial_cmp(&b.at).unwrap_or(std::cmp::Ordering::Equal)
+        };
+        vertical.sort_unstable_by(by_position);
+        horizontal.sort_unstable_by(by_position);
+
+        Ok(Self {
+            vertical,
+            horizontal,
+        })
+    }
+
+    /// Classify ``quad`` against the outline, exactly as
+    /// [`tessellation_fill`](crate::tessellation_fill) would.
+    ///
+    /// Panics if the cells of ``quad`` cannot be represented in ``T``.
+    pub fn classify(&self, quad: Quad) -> QuadClass {
+        if quad.is_empty() {
+            return QuadClass::Outside;
+        }
+
+        let (min, max) = (quad.min(), quad.max());
+        let low = min.map(|coord| from_cell::<T>(coord * 2));
+        let high = max.map(|coord| from_cell::<T>(coord * 2 + 2));
+
+        // A single cell is only on the outline if an edge passes through it; one that an
+        // edge only runs along the side of is entirely on one side or the other.
+        if self.any_edge(low, high, quad.is_cell()) {
+            QuadClass::Boundary
+        } else if self.encloses(min.map(|coord| from_cell(coord * 2 + 1))) {
+            QuadClass::Inside
+        } else {
+            QuadClass::Outside
+        }
+    }
+
+    /// Whether any edge overlaps the box from ``low`` to ``high`` along both axes; if
+    /// ``strict``, it must pass through the inside of the box rather than just touch it.
+    fn any_edge(&self, low: Point<T>, high: Point<T>, strict: bool) -> bool {
+        let overlaps = |start: T, end: T, low: T, high: T| {
+            if strict {
+                start < high && end > low
+            } else {
+                start <= high && end >= low
+            }
+        };
+
+        within(&self.vertical, low[0], high[0]).iter().any(|edge| {
+            overlaps(edge.at, edge.at, low[0], high[0])
+                && overlaps(edge.low, edge.high, low[1], high[1])
+        }) || within(&self.horizontal, low[1], high[1])
+            .iter()
+            .any(|edge| {
+                overlaps(edge.at, edge.at, low[1], high[1])
+                    && overlaps(edge.low, edge.high, low[0], high[0])
+            })
+    }
+
+    /// Whether ``point``, in doubled coordinates and not on the outline, is enclosed under
+    /// the even-odd rule: a ray cast from it towards positive ``x`` crosses an odd number
+    /// of vertical edges, each half-open in ``y`` as in
+    /// [`contains_even_odd`](crate::contains_even_odd).
+    fn encloses(&self, point: Point<T>) -> bool {
+        let start = self.vertical.partition_point(|edge| edge.at <= point[0]);
+        self.vertical[start..]
+            .iter()
+            .filter(|edge| edge.low <= point[1] && point[1] < edge.high)
+            .count()
+            % 2
+            == 1
+    }
+}
+
+/// The edges lying along anywhere from ``low`` to ``high`` inclusive.
+fn within<T: Scalar>(edges: &[AxisEdge<T>], low: T, high: T) -> &[AxisEdge<T>] {
+    let start = edges.partition_point(|edge| edge.at < low);
+    let end = edges.partition_point(|edge| edge.at <= high);
+    &edges[start..end]
+}
+
+/// Fill ``polygon`` exactly as [`tessellation_fill`](crate::tessellation_fill) does, but
+/// classifying each quad through [`OrthogonalEdges`] rather than against every edge.
+///
+/// Returns [`TessellationFillError::NonOrthogonalEdge`] for the first edge that is
+/// neither horizontal nor vertical.
+pub fn tessellation_fill_orthogonal<T: Scalar>(
+    polygon: &SimplePolygon<T>,
+) -> Result<FilledRegion, TessellationFillError<T>> {
+    let edges = OrthogonalEdges::new(polygon)?;
+    Ok(FilledRegion::new(subdivide_by(
+        &|quad| edges.classify(quad),
+        polygon.bounds(),
+        0,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tessellation_fill;
+
+    fn assert_matches_general<T: Scalar>(vertices: Vec<Point<T>>) {
+        let polygon = SimplePolygon::new(vertices).expect("Invalid polygon");
+        assert_eq!(
+            tessellation_fill_orthogonal(&polygon),
+            Ok(tessellation_fill(&polygon)),
+            "{:?}",
+            polygon.vertices()
+        );
+    }
+
+    #[test]
+    fn test_matches_general() {
+        // The example from Day 9 of 2025.
+        assert_matches_general(vec![
+            [7, 1],
+            [11, 1],
+            [11, 7],
+            [9, 7],
+            [9, 5],
+            [2, 5],
+            [2, 3],
+            [7, 3],
+        ]);
+        // A spiral, whose arms are a cell apart.
+        assert_matches_general(vec![
+            [0, 0],
+            [20, 0],
+            [20, 20],
+            [4, 20],
+            [4, 8],
+            [12, 8],
+            [12, 12],
+            [8, 12],
+            [8, 16],
+            [16, 16],
+            [16, 4],
+            [0, 4],
+        ]);
+        // Teeth along the bottom and the top, one cell wide, and away from the origin.
+        assert_matches_general(vec![
+            [-5, 3],
+            [-4, 3],
+            [-4, 6],
+            [-3, 6],
+            [-3, 3],
+            [1, 3],
+            [1, 12],
+            [0, 12],
+            [0, 8],
+            [-1, 8],
+            [-1, 12],
+            [-5, 12],
+        ]);
+        assert_matches_general::<u32>(vec![[0, 0], [6, 0], [6, 2], [2, 2], [2, 5], [0, 5]]);
+        // Edges along the sides of cells, and through their middles.
+        assert_matches_general(vec![
+            [0.5, 0.5],
+            [6.5, 0.5],
+            [6.5, 3.0],
+            [3.0, 3.0],
+            [3.0, 5.5],
+            [0.5, 5.5],
+        ]);
+    }
+
+    #[test]
+    fn test_classify() {
+        let polygon =
+            SimplePolygon::new(vec![[0, 0], [4, 0], [4, 4], [0, 4]]).expect("Invalid polygon");
+        let edges = OrthogonalEdges::new(&polygon).expect("Not orthogonal");
+
+        assert_eq!(edges.classify(Quad::new(1, 1, 3, 3)), QuadClass::Inside);
+        assert_eq!(edges.classify(Quad::new(5, 0, 2, 2)), QuadClass::Outside);
+        assert_eq!(edges.classify(Quad::new(3, 3, 2, 2)), QuadClass::Boundary);
+        assert_eq!(edges.classify(Quad::new(4, 2, 1, 1)), QuadClass::Boundary);
+
+        // Cells that the outline only runs along the sides of.
+        let polygon = SimplePolygon::new(vec![[0.5, 0.5], [4.5, 0.5], [4.5, 4.5], [0.5, 4.5]])
+            .expect("Invalid polygon");
+        let edges = OrthogonalEdges::new(&polygon).expect("Not orthogonal");
+        assert_eq!(edges.classify(Quad::new(4, 2, 1, 1)), QuadClass::Inside);
+        assert_eq!(edges.classify(Quad::new(5, 2, 1, 1)), QuadClass::Outside);
+        assert_eq!(edges.classify(Quad::new(4, 2, 2, 1)), QuadClass::Boundary);
+    }
+
+    #[test]
+    fn test_not_orthogonal() {
+        let polygon =
+            SimplePolygon::new(vec![[0, 0], [2, 0], [2, 2], [1, 3]]).expect("Invalid polygon");
+        assert_eq!(
+            tessellation_fill_orthogonal(&polygon),
+            Err(TessellationFillError::NonOrthogonalEdge {
+                index: 2,
+                from: [2, 2],
+                to: [1, 3],
+            })
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Reporting the progress of fills that take a long time, and cancelling them.
+
+use crate::{FilledRegion, Scalar, SimplePolygon, TessellationFillError, fill_breadth_first};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// How many quads are processed between each report of progress.
+pub const PROGRESS_INTERVAL: usize = 4096;
+
+/// A flag shared between a fill and whoever may want to stop it, possibly from another
+/// thread; clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every fill holding this token to stop as soon as it can.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called on this token or any clone of it.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a fill has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of quads classified and, if on the outline, subdivided.
+    pub processed: usize,
+    /// The number of quads queued to be processed. Each quad on the outline among them
+    /// adds more, so this is only a lower bound on the work left, which is exact once
+    /// the smallest quads are reached.
+    pub remaining: usize,
+}
+
+/// Fill ``polygon`` like [`tessellation_fill`](crate::tessellation_fill), reporting its
+/// progress to ``on_progress`` and stopping early if ``cancellation`` is cancelled.
+///
+/// Progress is reported every [`PROGRESS_INTERVAL`] quads, and once more when the fill
+/// is finished, with nothing remaining. The quadtree is built breadth-first, as in
+/// [`tessellation_fill_with_budget`](crate::tessellation_fill_with_budget), so
+/// ``remaining`` grows level by level before it shrinks.
+///
+/// Returns [`TessellationFillError::Cancelled`] if cancelled, with the tree as it stood:
+/// every quad on the outline not yet subdivided is left as a filled leaf, and the error
+/// on its area is bounded in the same way as for a fill that ran out of budget.
+pub fn tessellation_fill_with_progress<T: Scalar>(
+    polygon: &SimplePolygon<T>,
+    mut on_progress: impl FnMut(Progress),
+    cancellation: &CancellationToken,
+) -> Result<FilledRegion, TessellationFillError<T>> {
+    let mut processed = 0;
+    let (fill, finished) = fill_breadth_first(polygon, usize::MAX, |index, remaining| {
+        processed = index;
+        if index % PROGRESS_INTERVAL == 0 {
+            on_progress(Progress {
+                processed: index,
+                remaining,
+            });
+        }
+        !cancellation.is_cancelled()
+    });
+
+    if finished {
+        on_progress(Progress {
+            processed: processed + 1,
+            remaining: 0,
+        });
+        Ok(fill.region)
+    } else {
+        Err(TessellationFillError::Cancelled {
+            processed,
+            partial: Box::new(fill),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filled_area, tessellation_fill};
+
+    /// A triangle with a long diagonal edge, for enough quads to report progress on.
+    fn polygon() -> SimplePolygon {
+        SimplePolygon::new(vec![[0, 0], [3_000, 0], [0, 2_000]]).expect("Invalid polygon")
+    }
+
+    #[test]
+    fn test_progress() {
+        let polygon = polygon();
+        let mut reports = Vec::new();
+        let region = tessellation_fill_with_progress(
+            &polygon,
+            |progress| reports.push(progress),
+            &CancellationToken::new(),
+        )
+        .expect("Fill was cancelled");
+
+        assert_eq!(region, tessellation_fill(&polygon));
+        assert!(reports.len() > 2);
+        assert!(
+            reports
+                .windows(2)
+                .all(|pair| pair[0].processed < pair[1].processed)
+        );
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                processed: region.nodes().count(),
+                remaining: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let polygon = polygon();
+        let exact_area = filled_area(&polygon) as u64;
+        let cancellation = CancellationToken::new();
+
+        let result = tessellation_fill_with_progress(
+            &polygon,
+            |progress| {
+                if progress.processed >= PROGRESS_INTERVAL {
+                    cancellation.cancel();
+                }
+            },
+            &cancellation,
+        );
+        let Err(TessellationFillError::Cancelled { processed, partial }) = result else {
+            panic!("Fill was not cancelled: {result:?}");
+        };
+
+        assert_eq!(processed, PROGRESS_INTERVAL);
+        assert!(!partial.is_exact());
+        let area = partial.region.area();
+        assert!(area >= exact_area);
+        assert!(area - partial.area_error <= exact_area);
+
+        // Cancelled before it started, so only the root is built.
+        let result = tessellation_fill_with_progress(&polygon, |_| {}, &cancellation);
+        assert!(matches!(
+            result,
+            Err(TessellationFillError::Cancelled { processed: 0, partial })
+                if partial.region.nodes().count() == 1
+        ));
+    }
+}
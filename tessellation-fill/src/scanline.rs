@@ -0,0 +1,342 @@
+//! A classic scanline fill, visiting every row of the polygon once.
+
+use crate::{Coord, FilledRegion, Quad, Scalar, SimplePolygon, traits::Polygon};
+use std::cmp::Ordering;
+
+/// The span of cells from ``start`` to ``end`` inclusive, on row ``y``.
+fn span(y: Coord, start: Coord, end: Coord) -> Quad {
+    Quad::new(start, y, end - start + 1, 1)
+}
+
+fn partial_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// An edge of the polygon, from its lower end to its upper end.
+struct ScanEdge {
+    bottom: [f64; 2],
+    top: [f64; 2],
+}
+
+impl ScanEdge {
+    fn new(from: [f64; 2], to: [f64; 2]) -> Self {
+        if from[1] <= to[1] {
+            Self {
+                bottom: from,
+                top: to,
+            }
+        } else {
+            Self {
+                bottom: to,
+                top: from,
+            }
+        }
+    }
+
+    /// The ``x`` of the point on this edge at ``y``, which must not be horizontal.
+    fn x_at(&self, y: f64) -> f64 {
+        self.bottom[0]
+            + (y - self.bottom[1]) * (self.top[0] - self.bottom[0]) / (self.top[1] - self.bottom[1])
+    }
+
+    /// The range of ``x`` covered by this edge between ``low`` and ``high``.
+    fn x_range(&self, low: f64, high: f64) -> (f64, f64) {
+        let (start, end) = if self.bottom[1] == self.top[1] {
+            (self.bottom[0], self.top[0])
+        } else {
+            (
+                self.x_at(low.max(self.bottom[1])),
+                self.x_at(high.min(self.top[1])),
+            )
+        };
+
+        (start.min(end), start.max(end))
+    }
+}
+
+/// Fill ``polygon`` row by row, keeping a list of the edges spanning the current row.
+///
+/// On each row, the cells whose centres lie between pairs of crossings of the edges are
+/// inside, and the cells any edge passes through within the height of the row are on the
+/// outline.
+///
+/// Every filled cell is visited, so the work done grows with the area enclosed; on small
+/// or dense polygons this avoids the overhead of building a quadtree. The crossings are
+/// found in [`f64`], which is exact for integer coordinates below ``2^53``.
+pub fn scanline_fill<T: Scalar>(polygon: &SimplePolygon<T>) -> FilledRegion {
+    let bounds = polygon.bounds();
+    let to_f64 = |value: T| {
+        value
+            .to_f64()
+            .expect("Unreachable; every scalar can be approximated as a float")
+    };
+
+    let mut edges = polygon
+        .edges()
+        .map(|(from, to)| ScanEdge::new(from.map(to_f64), to.map(to_f64)))
+        .collect::<Vec<_>>();
+    edges.sort_unstable_by(|a, b| partial_cmp(&a.bottom[1], &b.bottom[1]));
+
+    let mut spans = Vec::new();
+    let mut active: Vec<&ScanEdge> = Vec::new();
+    let mut next_edge = 0;
+    let mut ranges: Vec<(Coord, Coord)> = Vec::new();
+
+    for y in bounds.y..=bounds.max()[1] {
+        let row = y as f64;
+        let (band_low, band_high) = (row - 0.5, row + 0.5);
+
+        while let Some(edge) = edges
+            .get(next_edge)
+            .filter(|edge| edge.bottom[1] < band_high)
+        {
+            active.push(edge);
+            next_edge += 1;
+        }
+        active.retain(|edge| edge.top[1] > band_low);
+
+        // Cells between pairs of crossings of the centre line of the row are inside; the
+        // edges are half-open in ``y``, so that a vertex is only crossed once.
+        let mut crossings = active
+            .iter()
+            .filter(|edge| edge.bottom[1] <= row && row < edge.top[1])
+            .map(|edge| edge.x_at(row))
+            .collect::<Vec<_>>();
+        crossings.sort_unstable_by(partial_cmp);
+        ranges.clear();
+        ranges.extend(
+            crossings
+                .chunks_exact(2)
+                .map(|pair| (pair[0].ceil() as Coord, pair[1].floor() as Coord))
+                .filter(|(start, end)| start <= end),
+        );
+
+        // Cells that the outline passes through within the row, even if their centres
+        // are outside; horizontal edges between two rows pass through neither.
+        ranges.extend(
+            active
+                .iter()
+                .filter(|edge| edge.bottom[1] != edge.top[1] || edge.bottom[1] > band_low)
+                .map(|edge| {
+                    let (start, end) = edge.x_range(band_low, band_high);
+                    (
+                        (start - 0.5).floor() as Coord + 1,
+                        (end + 0.5).ceil() as Coord - 1,
+                    )
+                })
+                .filter(|(start, end)| start <= end),
+        );
+
+        ranges.sort_unstable();
+        let mut ranges = ranges.iter().copied();
+        if let Some(first) = ranges.next() {
+            let (start, end) = ranges.fold(first, |(start, end), (next_start, next_end)| {
+                if next_start <= end + 1 {
+                    (start, end.max(next_end))
+                } else {
+                    spans.push(span(y, start, end));
+                    (next_start, next_end)
+                }
+            });
+            spans.push(span(y, start, end));
+        }
+    }
+
+    FilledRegion::from_spans(bounds, spans)
+}
+
+/// The algorithm used to fill a polygon.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FillStrategy {
+    /// Recursively subdivide the bounding box, only refining quads on the outline; see
+    /// [`tessellation_fill`](crate::tessellation_fill).
+    #[default]
+    Quadtree,
+    /// Visit every row of the bounding box; see [`scanline_fill`].
+    Scanline,
+}
+
+impl FillStrategy {
+    /// Fill ``polygon`` using this strategy.
+    ///
+    /// Both strategies fill exactly the same cells, and only differ in how the resulting
+    /// [`FilledRegion`] stores them.
+    pub fn fill<T: Scalar>(&self, polygon: &SimplePolygon<T>) -> FilledRegion {
+        match self {
+            Self::Quadtree => crate::tessellation_fill(polygon),
+            Self::Scanline => scanline_fill(polygon),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contains_even_odd;
+
+    #[test]
+    fn test_strategies_agree() {
+        let polygons = [
+            vec![[0, 0], [4, 0], [4, 2], [2, 2], [2, 4], [0, 4]],
+            vec![
+                [7, 1],
+                [11, 1],
+                [11, 7],
+                [9, 7],
+                [9, 5],
+                [2, 5],
+                [2, 3],
+                [7, 3],
+            ],
+            // Teeth along the bottom and the top, so that rows cross many edges.
+            vec![
+                [0, 0],
+                [2, 0],
+                [2, 3],
+                [4, 3],
+                [4, 0],
+                [6, 0],
+                [6, 3],
+                [8, 3],
+                [8, 0],
+                [10, 0],
+                [10, 9],
+                [7, 9],
+                [7, 5],
+                [5, 5],
+                [5, 9],
+                [0, 9],
+            ],
+        ];
+
+        for vertices in polygons {
+            let polygon = SimplePolygon::new(vertices).expect("Invalid polygon");
+            let quadtree = FillStrategy::Quadtree.fill(&polygon);
+            let scanline = FillStrategy::Scanline.fill(&polygon);
+
+            assert_eq!(quadtree.area(), scanline.area());
+            assert_eq!(quadtree.bounds(), scanline.bounds());
+
+            let bounds = polygon.bounds();
+            for y in bounds.y - 1..=bounds.max()[1] + 1 {
+                for x in bounds.x - 1..=bounds.max()[0] + 1 {
+                    assert_eq!(
+                        scanline.contains([x, y]),
+                        quadtree.contains([x, y]),
+                        "Mismatch at {:?}",
+                        [x, y]
+                    );
+                    assert_eq!(
+                        scanline.contains([x, y]),
+                        contains_even_odd(&polygon, [x, y])
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_scalars() {
+        let polygon =
+            SimplePolygon::<u32>::new(vec![[3, 1], [9, 1], [9, 6], [6, 6], [6, 3], [3, 3]])
+                .expect("Invalid polygon");
+        let float = SimplePolygon::<f64>::new(vec![
+            [2.5, 0.5],
+            [9.0, 0.5],
+            [9.0, 6.5],
+            [5.5, 6.5],
+            [5.5, 3.0],
+            [2.5, 3.0],
+        ])
+        .expect("Invalid polygon");
+
+        for (quadtree, scanline, contains) in [
+            (
+                FillStrategy::Quadtree.fill(&polygon),
+                FillStrategy::Scanline.fill(&polygon),
+                Box::new(|[x, y]: [i64; 2]| contains_even_odd(&polygon, [x as u32, y as u32]))
+                    as Box<dyn Fn([i64; 2]) -> bool>,
+            ),
+            (
+                FillStrategy::Quadtree.fill(&float),
+                FillStrategy::Scanline.fill(&float),
+                Box::new(|[x, y]: [i64; 2]| contains_even_odd(&float, [x as f64, y as f64])),
+            ),
+        ] {
+            assert_eq!(quadtree.area(), scanline.area());
+            for y in 0..10 {
+                for x in 0..12 {
+                    assert_eq!(
+                        quadtree.contains([x, y]),
+                        contains([x, y]),
+                        "Mismatch at {:?}",
+                        [x, y]
+                    );
+                    assert_eq!(
+                        scanline.contains([x, y]),
+                        contains([x, y]),
+                        "Mismatch at {:?}",
+                        [x, y]
+                    );
+                }
+            }
+        }
+
+        // Cells 3 to 9 of rows 1 to 3, and 6 to 9 of rows 4 to 6.
+        assert_eq!(FillStrategy::Scanline.fill(&float).area(), 7 * 3 + 4 * 3);
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let polygons = [
+            vec![[0.0, 0.0], [6.0, 0.0], [0.0, 6.0]],
+            vec![[0.0, 0.0], [7.0, 2.0], [0.0, 4.0]],
+            vec![
+                [0.0, 0.0],
+                [9.0, 2.0],
+                [12.0, 11.0],
+                [5.0, 7.0],
+                [1.0, 12.0],
+                [3.0, 5.0],
+            ],
+            vec![[0.3, 0.2], [7.7, 1.4], [4.1, 6.9]],
+            vec![[1.5, 0.5], [6.5, 0.5], [6.5, 3.0], [4.0, 5.5], [1.5, 3.0]],
+        ];
+
+        for vertices in polygons {
+            let polygon = SimplePolygon::new(vertices).expect("Invalid polygon");
+            let quadtree = FillStrategy::Quadtree.fill(&polygon);
+            let scanline = FillStrategy::Scanline.fill(&polygon);
+
+            assert_eq!(quadtree.area(), scanline.area());
+            let bounds = polygon.bounds();
+            for y in bounds.y - 1..=bounds.max()[1] + 1 {
+                for x in bounds.x - 1..=bounds.max()[0] + 1 {
+                    assert_eq!(
+                        scanline.contains([x, y]),
+                        quadtree.contains([x, y]),
+                        "Mismatch at {:?}",
+                        [x, y]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        let polygon = SimplePolygon::new(vec![[0, 0], [4, 0], [4, 2], [2, 2], [2, 4], [0, 4]])
+            .expect("Invalid polygon");
+
+        assert_eq!(
+            scanline_fill(&polygon).filled_quads().collect::<Vec<_>>(),
+            vec![
+                Quad::new(0, 0, 5, 1),
+                Quad::new(0, 1, 5, 1),
+                Quad::new(0, 2, 5, 1),
+                Quad::new(0, 3, 3, 1),
+                Quad::new(0, 4, 3, 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Coord, Point, Quad, Scalar, first_cell_reaching, last_cell_reaching};
+
+/// A closed ring of vertices, which can be filled onto the grid.
+///
+/// The ring is closed implicitly: the last vertex connects back to the first.
+pub trait Polygon {
+    /// The type of the coordinates of the vertices.
+    type Scalar: Scalar;
+
+    /// The vertices of the ring, in order; there must be at least one.
+    fn vertices(&self) -> &[Point<Self::Scalar>];
+
+    /// Iterate over the edges of the ring as ``(from, to)`` pairs, including the closing
+    /// edge from the last vertex back to the first.
+    fn edges(&self) -> impl Iterator<Item = (Point<Self::Scalar>, Point<Self::Scalar>)> + '_ {
+        let vertices = self.vertices();
+        vertices
+            .iter()
+            .copied()
+            .zip(vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// The smallest [`Quad`] covering every cell that the outline could pass through.
+    ///
+    /// If the vertices are too close together for any cell to be filled, the quad is
+    /// empty.
+    fn bounds(&self) -> Quad {
+        let vertices = self.vertices();
+        let (min, max) = vertices
+            .iter()
+            .fold((vertices[0], vertices[0]), |(min, max), vertex| {
+                (
+                    [
+                        partial_min(min[0], vertex[0]),
+                        partial_min(min[1], vertex[1]),
+                    ],
+                    [
+                        partial_max(max[0], vertex[0]),
+                        partial_max(max[1], vertex[1]),
+                    ],
+                )
+            });
+
+        Quad::from_corners(
+            [first_cell_reaching(min[0]), first_cell_reaching(min[1])],
+            [last_cell_reaching(max[0]), last_cell_reaching(max[1])],
+        )
+    }
+}
+
+fn partial_min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+fn partial_max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+/// A bare slice of vertices, without any validation.
+impl<T: Scalar> Polygon for [Point<T>] {
+    type Scalar = T;
+
+    fn vertices(&self) -> &[Point<T>] {
+        self
+    }
+}
+
+/// A grid of cells that a fill can be written into directly, such as a user's own grid
+/// or an image buffer.
+pub trait RasterTarget {
+    /// The cells that can be written to; cells outside of it are never set.
+    fn bounds(&self) -> Quad;
+
+    /// Mark the cell at ``(x, y)`` as filled.
+    fn set(&mut self, x: Coord, y: Coord);
+}
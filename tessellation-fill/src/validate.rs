@@ -0,0 +1,360 @@
+//! Checks that a polygon is one that the fills in this crate can make sense of.
+//!
+//! Edges are indexed by the vertex they start from: edge ``i`` runs from vertex ``i`` to
+//! vertex ``i + 1``, and the last edge back to vertex ``0``.
+
+use crate::{Point, Scalar, TessellationFillError, is_on_edge, orientation, traits::Polygon};
+use std::cmp::Ordering;
+
+/// Whether the closed segments from ``a`` to ``b`` and from ``c`` to ``d`` have any point
+/// in common.
+fn segments_touch<T: Scalar>(a: Point<T>, b: Point<T>, c: Point<T>, d: Point<T>) -> bool {
+    let sides = [
+        orientation(a, b, c),
+        orientation(a, b, d),
+        orientation(c, d, a),
+        orientation(c, d, b),
+    ];
+
+    if sides.contains(&Ordering::Equal) {
+        is_on_edge(a, b, c) || is_on_edge(a, b, d) || is_on_edge(c, d, a) || is_on_edge(c, d, b)
+    } else {
+        sides[0] != sides[1] && sides[2] != sides[3]
+    }
+}
+
+/// Whether edges ``first`` and ``second`` of ``polygon``, where ``first < second``, meet
+/// anywhere other than at a vertex they share.
+///
+/// Adjacent edges always share a vertex, so they only intersect if one doubles back
+/// along the other.
+fn edges_intersect<P: Polygon + ?Sized>(polygon: &P, first: usize, second: usize) -> bool {
+    let vertices = polygon.vertices();
+    let len = vertices.len();
+    let edge = |index: usize| (vertices[index], vertices[(index + 1) % len]);
+    let ((a, b), (c, d)) = (edge(first), edge(second));
+
+    if second == first + 1 {
+        // ``b`` is shared.
+        orientation(a, b, d) == Ordering::Equal && (is_on_edge(a, b, d) || is_on_edge(c, d, a))
+    } else if first == 0 && second == len - 1 {
+        // ``a`` is shared.
+        orientation(a, b, c) == Ordering::Equal && (is_on_edge(a, b, c) || is_on_edge(c, d, b))
+    } else {
+        segments_touch(a, b, c, d)
+    }
+}
+
+/// Whether point ``a`` comes before point ``b`` along the sweep, ``x`` first.
+fn sweep_order<T: Scalar>(a: &Point<T>, b: &Point<T>) -> Ordering {
+    a[0].partial_cmp(&b[0])
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a[1].partial_cmp(&b[1]).unwrap_or(Ordering::Equal))
+}
+
+/// Check that no two edges of ``polygon`` meet anywhere other than at a vertex they
+/// share, returning [`TessellationFillError::SelfIntersection`] for a pair that does.
+///
+/// The fills in this crate silently produce nonsense for outlines that cross themselves,
+/// so this should be run on any polygon that is not known to be simple.
+///
+/// This is a Shamos-Hoey sweep: a line is swept across the polygon in ``x``, keeping the
+/// edges it crosses sorted by where they cross it. Two edges can only intersect after
+/// having been next to each other in that order, so each edge is only compared with its
+/// neighbours as it is inserted, and the edges either side of it when it is removed; the
+/// first intersection found is returned.
+///
+/// Each edge is placed by binary search, for ``O(n log n)`` comparisons between edges in
+/// all; but the sweep is kept in a [`Vec`], which each edge is found in by a linear scan
+/// when it is removed, and which shifts the edges after it whenever one is inserted or
+/// removed. That is ``O(n)`` moves of edge indices for each edge, so ``O(n²)`` in all at
+/// worst, when most edges cross the sweep line at once.
+pub fn is_simple<P: Polygon + ?Sized>(polygon: &P) -> Result<(), TessellationFillError<P::Scalar>> {
+    let vertices = polygon.vertices();
+    let len = vertices.len();
+    // Each edge from its first endpoint along the sweep to its last.
+    let segments = polygon
+        .edges()
+        .map(|(from, to)| match sweep_order(&from, &to) {
+            Ordering::Greater => (to, from),
+            _ => (from, to),
+        })
+        .collect::<Vec<_>>();
+    let intersection = |a: usize, b: usize| {
+        let (first, second) = (a.min(b), a.max(b));
+        edges_intersect(polygon, first, second)
+            .then_some(TessellationFillError::SelfIntersection { first, second })
+    };
+
+    // ``(point, is_removal, edge)``; at the same point, edges are inserted before any are
+    // removed, so that edges meeting there are all in the sweep at once.
+    let mut events = segments
+        .iter()
+        .enumerate()
+        .flat_map(|(index, &(start, end))| [(start, false, index), (end, true, index)])
+        .collect::<Vec<_>>();
+    events.sort_by(|a, b| sweep_order(&a.0, &b.0).then(a.1.cmp(&b.1)));
+
+    // The edges crossing the sweep line, from the right of it to the left, looking along
+    // the edges.
+    let mut sweep: Vec<usize> = Vec::with_capacity(len);
+
+    for (point, is_removal, index) in events {
+        if is_removal {
+            let position = sweep
+                .iter()
+                .position(|&edge| edge == index)
+                .expect("Unreachable; every edge is inserted before it is removed");
+            sweep.remove(position);
+
+            if let Some(error) = position
+                .checked_sub(1)
+                .zip(sweep.get(position))
+                .and_then(|(below, &above)| intersection(sweep[below], above))
+            {
+                return Err(error);
+            }
+        } else {
+            let (_, end) = segments[index];
+            // Where ``point`` is relative to each edge, or failing that, where the rest of
+            // the new edge is; every edge in the sweep spans ``point``, so it is on any
+            // edge it is level with.
+            let side = |edge: usize| {
+                let (from, to) = segments[edge];
+                (orientation(from, to, point), orientation(from, to, end))
+            };
+            let position = sweep.partition_point(|&edge| match side(edge) {
+                (Ordering::Equal, side) => side == Ordering::Greater,
+                (side, _) => side == Ordering::Greater,
+            });
+
+            // The edges that ``point`` is on, which are all next to where it goes.
+            let touching = sweep[..position]
+                .iter()
+                .rev()
+                .take_while(|&&edge| side(edge).0 == Ordering::Equal)
+                .chain(
+                    sweep[position..]
+                        .iter()
+                        .take_while(|&&edge| side(edge).0 == Ordering::Equal),
+                );
+            let neighbours = position
+                .checked_sub(1)
+                .map(|below| &sweep[below])
+                .into_iter()
+                .chain(sweep.get(position));
+
+            if let Some(error) = touching
+                .chain(neighbours)
+                .find_map(|&edge| intersection(edge, index))
+            {
+                return Err(error);
+            }
+
+            sweep.insert(position, index);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that ``polygon`` is a simple polygon that encloses some area, before filling it.
+///
+/// The checks are done in this order, returning the first problem found:
+///
+/// * [`TessellationFillError::TooFewVertices`] if there are fewer than 3 vertices;
+/// * [`TessellationFillError::DegenerateEdge`] for the first edge of zero length, which
+///   includes repeating the first vertex at the end of the ring;
+/// * [`TessellationFillError::OpenRing`] if all vertices are on one line, so that the
+///   ring is a path traced back onto itself;
+/// * [`TessellationFillError::SelfIntersection`] for a pair of edges that meet anywhere
+///   other than at a vertex they share; see [`is_simple`].
+pub fn validate<P: Polygon + ?Sized>(polygon: &P) -> Result<(), TessellationFillError<P::Scalar>> {
+    let vertices = polygon.vertices();
+    if vertices.len() < 3 {
+        return Err(TessellationFillError::TooFewVertices {
+            count: vertices.len(),
+            minimum: 3,
+        });
+    }
+
+    if let Some((index, (vertex, _))) = polygon
+        .edges()
+        .enumerate()
+        .find(|(_, (from, to))| from == to)
+    {
+        return Err(TessellationFillError::DegenerateEdge { index, vertex });
+    }
+
+    if vertices
+        .iter()
+        .all(|&vertex| orientation(vertices[0], vertices[1], vertex) == Ordering::Equal)
+    {
+        return Err(TessellationFillError::OpenRing {
+            count: vertices.len(),
+        });
+    }
+
+    is_simple(polygon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimplePolygon;
+
+    #[test]
+    fn test_valid() {
+        let polygons: [&[Point]; 3] = [
+            &[[0, 0], [4, 0], [0, 3]],
+            &[[0, 0], [4, 0], [4, 2], [2, 2], [2, 4], [0, 4]],
+            // A vertex in the middle of a straight edge.
+            &[[0, 0], [2, 0], [4, 0], [4, 4], [0, 4]],
+        ];
+        for vertices in polygons {
+            assert_eq!(validate(vertices), Ok(()), "{vertices:?}");
+        }
+
+        let polygon = SimplePolygon::<u32>::new(vec![[5, 0], [9, 4], [5, 8], [1, 4]])
+            .expect("Invalid polygon");
+        assert_eq!(validate(&polygon), Ok(()));
+        assert_eq!(validate(&[[0.0, 0.0], [1.5, 0.2], [0.7, 2.9]][..]), Ok(()));
+    }
+
+    #[test]
+    fn test_too_few_vertices() {
+        assert_eq!(
+            validate(&[[0, 0], [1, 1]][..]),
+            Err(TessellationFillError::TooFewVertices {
+                count: 2,
+                minimum: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_degenerate_edge() {
+        assert_eq!(
+            validate(&[[0, 0], [4, 0], [4, 0], [4, 4]][..]),
+            Err(TessellationFillError::DegenerateEdge {
+                index: 1,
+                vertex: [4, 0]
+            })
+        );
+        // The ring is closed implicitly, so repeating the first vertex is an edge too.
+        assert_eq!(
+            validate(&[[0, 0], [4, 0], [4, 4], [0, 0]][..]),
+            Err(TessellationFillError::DegenerateEdge {
+                index: 3,
+                vertex: [0, 0]
+            })
+        );
+    }
+
+    #[test]
+    fn test_open_ring() {
+        assert_eq!(
+            validate(&[[0, 0], [2, 2], [5, 5], [1, 1]][..]),
+            Err(TessellationFillError::OpenRing { count: 4 })
+        );
+        assert_eq!(
+            validate(&[[3u32, 0], [3, 7], [3, 2]][..]),
+            Err(TessellationFillError::OpenRing { count: 3 })
+        );
+    }
+
+    #[test]
+    fn test_self_intersection() {
+        // A bow tie, whose middle edges cross.
+        assert_eq!(
+            validate(&[[0, 0], [4, 0], [0, 4], [4, 4]][..]),
+            Err(TessellationFillError::SelfIntersection {
+                first: 1,
+                second: 3
+            })
+        );
+        // A vertex touching a non-adjacent edge.
+        assert_eq!(
+            validate(&[[0, 0], [6, 0], [6, 4], [3, 0], [0, 4]][..]),
+            Err(TessellationFillError::SelfIntersection {
+                first: 0,
+                second: 3
+            })
+        );
+        // A spike doubling back along the edge before it, whose tip then lies on that edge
+        // too.
+        assert!(matches!(
+            validate(&[[0, 0], [6, 0], [6, 4], [6, 2], [0, 2]][..]),
+            Err(TessellationFillError::SelfIntersection {
+                first: 1,
+                second: 2 | 3
+            })
+        ));
+        // The same, across the closing vertex.
+        assert!(matches!(
+            validate(&[[0, 0], [3, 0], [3, 4], [6, 4], [6, 0]][..]),
+            Err(TessellationFillError::SelfIntersection {
+                first: 0 | 1,
+                second: 4
+            })
+        ));
+    }
+
+    /// Whether any pair of edges intersects, comparing every pair.
+    fn brute_force(polygon: &[Point]) -> bool {
+        let len = polygon.len();
+        (0..len).any(|first| (first + 1..len).any(|second| edges_intersect(polygon, first, second)))
+    }
+
+    #[test]
+    fn test_is_simple_matches_brute_force() {
+        // A fixed-seed xorshift generator, with coordinates from a small range so that
+        // vertices often land on other edges.
+        let mut state = 0x5eed_u64;
+        let mut below = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound) as i64
+        };
+
+        for _ in 0..5_000 {
+            let len = below(8) as usize + 3;
+            let polygon = (0..len).map(|_| [below(6), below(6)]).collect::<Vec<_>>();
+
+            match is_simple(&polygon[..]) {
+                Ok(()) => assert!(!brute_force(&polygon), "Missed in {polygon:?}"),
+                Err(TessellationFillError::SelfIntersection { first, second }) => {
+                    assert!(edges_intersect(&polygon[..], first, second), "{polygon:?}")
+                }
+                Err(error) => panic!("Unexpected error {error:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_simple_large() {
+        // A comb of thin teeth, which keeps many edges in the sweep at once.
+        let teeth = 500;
+        let mut polygon = vec![[0, 0]];
+        for tooth in 0..teeth {
+            polygon.extend([
+                [tooth * 4 + 1, 0],
+                [tooth * 4 + 1, 1000],
+                [tooth * 4 + 3, 1000],
+            ]);
+            polygon.push([tooth * 4 + 3, 0]);
+        }
+        polygon.extend([[teeth * 4, 0], [teeth * 4, -10], [0, -10]]);
+
+        assert_eq!(is_simple(&polygon[..]), Ok(()));
+
+        // Bend the last tooth back across the one before it.
+        let len = polygon.len();
+        polygon[len - 5][0] = (teeth - 2) * 4 + 2;
+        assert!(matches!(
+            is_simple(&polygon[..]),
+            Err(TessellationFillError::SelfIntersection { .. })
+        ));
+    }
+}